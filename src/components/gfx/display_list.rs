@@ -15,6 +15,8 @@
 /// low-level drawing primitives.
 
 use color::Color;
+#[cfg(test)]
+use color::rgb;
 use geometry::Au;
 use render_context::RenderContext;
 use text::SendableTextRun;
@@ -55,6 +57,17 @@ impl<E> DisplayList<E> {
         }
         debug!("Ending display list.")
     }
+
+    /// Returns the topmost item, which is the last one drawn, whose bounds contain the given
+    /// point.
+    pub fn item_at<'a>(&'a self, point: Point2D<Au>) -> Option<&'a DisplayItem<E>> {
+        for self.list.rev_iter().advance |item| {
+            if item.contains_point(point) {
+                return Some(item)
+            }
+        }
+        None
+    }
 }
 
 /// One drawing command in the list.
@@ -88,6 +101,8 @@ pub struct TextDisplayItem<E> {
     text_run: ~SendableTextRun,
     range: Range,
     color: Color,
+    /// Whether the text ends a line by breaking a word, so that a hyphen is drawn after it.
+    hyphenated: bool,
 }
 
 /// Renders an image.
@@ -116,18 +131,54 @@ impl<E> DisplayItem<E> {
             TextDisplayItemClass(ref text) => {
                 debug!("Drawing text at %?.", text.base.bounds);
 
-                // FIXME(pcwalton): Allocating? Why?
-                let new_run = @text.text_run.deserialize(render_context.font_ctx);
+                let new_run = text.text_run.deserialize_cached(render_context.font_ctx);
 
                 let font = new_run.font;
                 let origin = text.base.bounds.origin;
-                let baseline_origin = Point2D(origin.x, origin.y + font.metrics.ascent);
+                let ascent = new_run.metrics_for_range(&text.range).ascent;
+                let baseline_origin = Point2D(origin.x, origin.y + ascent);
+                let advance = new_run.metrics_for_range(&text.range).advance_width;
 
-                font.draw_text_into_context(render_context,
-                                            new_run,
-                                            &text.range,
-                                            baseline_origin,
-                                            text.color);
+                // The hyphen goes at the end of the text, which is its left if it is
+                // right-to-left; the text is then drawn to the right of the hyphen.
+                let mut text_origin = baseline_origin;
+                if text.hyphenated {
+                    let hyphen_run = new_run.hyphen_run();
+                    let hyphen_range = Range::new(0, hyphen_run.char_len());
+                    let hyphen_origin = if new_run.rtl {
+                        let hyphen_width = new_run.hyphen_width();
+                        text_origin = Point2D(baseline_origin.x + hyphen_width, baseline_origin.y);
+                        baseline_origin
+                    } else {
+                        Point2D(baseline_origin.x + advance, baseline_origin.y)
+                    };
+                    font.draw_text_into_context(render_context,
+                                                &hyphen_run,
+                                                &hyphen_range,
+                                                hyphen_origin,
+                                                text.color);
+                }
+
+                // Draw each part of the run that uses a different font separately. The parts of
+                // a right-to-left run are laid out from the right edge of the run.
+                let mut font_origin = text_origin;
+                if new_run.rtl {
+                    font_origin = Point2D(font_origin.x + advance, font_origin.y);
+                }
+                for new_run.iter_font_ranges_for_range(&text.range) |run_font, font_range| {
+                    let advance = run_font.measure_text(new_run, font_range).advance_width;
+                    if new_run.rtl {
+                        font_origin = Point2D(font_origin.x - advance, font_origin.y);
+                    }
+                    run_font.draw_text_into_context(render_context,
+                                                    new_run,
+                                                    font_range,
+                                                    font_origin,
+                                                    text.color);
+                    if !new_run.rtl {
+                        font_origin = Point2D(font_origin.x + advance, font_origin.y);
+                    }
+                }
 
                 if new_run.underline {
                     // TODO(eatkinson): Use the font metrics to properly position the underline
@@ -167,5 +218,44 @@ impl<E> DisplayItem<E> {
     fn bounds(&self) -> Rect<Au> {
         self.base().bounds
     }
+
+    /// Returns whether the given point is within the bounds of this item.
+    pub fn contains_point(&self, point: Point2D<Au>) -> bool {
+        let bounds = self.bounds();
+        point.x <= bounds.origin.x + bounds.size.width &&
+            bounds.origin.x <= point.x &&
+            point.y < bounds.origin.y + bounds.size.height &&
+            bounds.origin.y < point.y
+    }
+}
+
+#[test]
+fn test_item_at() {
+    let solid_color = |x: int, y: int, width: int, height: int, extra: uint| {
+        SolidColorDisplayItemClass(~SolidColorDisplayItem {
+            base: BaseDisplayItem {
+                bounds: Rect(Point2D(Au(x), Au(y)), Size2D(Au(width), Au(height))),
+                extra: extra,
+            },
+            color: rgb(0, 0, 0),
+        })
+    };
+    let extra_at = |list: &DisplayList<uint>, x: int, y: int| {
+        list.item_at(Point2D(Au(x), Au(y))).map(|item| item.base().extra)
+    };
+
+    let mut list = DisplayList::new();
+    list.append_item(solid_color(0, 0, 100, 100, 1));
+    list.append_item(solid_color(50, 50, 100, 100, 2));
+
+    // The item drawn last is on top.
+    assert!(extra_at(&list, 10, 10) == Some(1));
+    assert!(extra_at(&list, 60, 60) == Some(2));
+    assert!(extra_at(&list, 120, 120) == Some(2));
+    assert!(extra_at(&list, 200, 10) == None);
+
+    // Points on the left and right edges are inside, and those on the top and bottom outside.
+    assert!(extra_at(&list, 150, 60) == Some(2));
+    assert!(extra_at(&list, 120, 150) == None);
 }
 
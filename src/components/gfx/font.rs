@@ -2,28 +2,33 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use color::Color;
+use color::{Color, rgba};
 use font_context::FontContext;
-use geometry::Au;
+use geometry::{Au, to_frac_px};
+use glyph_atlas::{GlyphAtlas, GlyphRaster};
 use platform::font_context::FontContextHandle;
 use platform::font::{FontHandle, FontTable};
 use render_context::RenderContext;
 use servo_util::range::Range;
 use std::cast;
+use std::hashmap::HashMap;
+use std::managed;
 use std::result;
 use std::ptr;
 use std::str;
 use std::vec;
-use servo_util::cache::{Cache, HashCache};
-use text::glyph::{GlyphStore, GlyphIndex};
-use text::shaping::ShaperMethods;
+use servo_util::cache::{Cache, WeightedLRUCache};
+use text::glyph::{BreakTypeNone, GlyphStore, GlyphIndex};
+use text::shaping::{FontFeature, ShaperMethods, ShapingOptions};
 use text::{Shaper, TextRun};
 use extra::arc::ARC;
 
 use azure::{AzFloat, AzScaledFontRef};
 use azure::scaled_font::ScaledFont;
-use azure::azure_hl::{BackendType, ColorPattern};
+use azure::azure_hl::{BackendType, ColorPattern, DrawOptions, DrawSurfaceOptions, DrawTarget};
+use azure::azure_hl::Linear;
 use geom::{Point2D, Rect, Size2D};
+use geom::matrix2d::Matrix2D;
 
 use servo_util::time;
 use servo_util::time::profile;
@@ -35,7 +40,7 @@ use servo_util::time::ProfilerChan;
 // resources needed by the graphics layer to draw glyphs.
 
 pub trait FontHandleMethods {
-    fn new_from_buffer(fctx: &FontContextHandle, buf: ~[u8], style: &SpecifiedFontStyle)
+    fn new_from_buffer(fctx: &FontContextHandle, buf: ARC<~[u8]>, style: &SpecifiedFontStyle)
                     -> Result<Self,()>;
 
     // an identifier usable by FontContextHandle to recreate this FontHandle.
@@ -110,6 +115,21 @@ impl CSSFontWeight {
             _ => false
         }
     }
+
+    /// Returns the numeric value of the weight, from 100 to 900.
+    pub fn to_number(self) -> uint {
+        match self {
+            FontWeight100 => 100,
+            FontWeight200 => 200,
+            FontWeight300 => 300,
+            FontWeight400 => 400,
+            FontWeight500 => 500,
+            FontWeight600 => 600,
+            FontWeight700 => 700,
+            FontWeight800 => 800,
+            FontWeight900 => 900,
+        }
+    }
 }
 
 // TODO(Issue #179): eventually this will be split into the specified
@@ -125,6 +145,8 @@ pub struct FontStyle {
     italic: bool,
     oblique: bool,
     families: ~str,
+    /// The language of the text, which can change the fonts that generic families resolve to.
+    lang: Option<~str>,
     // TODO(Issue #198): font-stretch, text-decoration, font-variant, size-adjust
 }
 
@@ -159,9 +181,25 @@ impl FontDescriptor {
 }
 
 // A FontSelector is a platform-specific strategy for serializing face names.
-#[deriving(Clone, Eq)]
+#[deriving(Clone)]
 pub enum FontSelector {
     SelectorPlatformIdentifier(~str),
+    /// A font downloaded for an `@font-face` rule: the URL it came from, and its data.
+    SelectorWebFont(~str, ARC<~[u8]>),
+}
+
+impl Eq for FontSelector {
+    fn eq(&self, other: &FontSelector) -> bool {
+        match (self, other) {
+            (&SelectorPlatformIdentifier(ref a), &SelectorPlatformIdentifier(ref b)) => a == b,
+            // Web fonts are identified by their URLs, so their data needn't be compared.
+            (&SelectorWebFont(ref a, _), &SelectorWebFont(ref b, _)) => a == b,
+            (_, _) => false,
+        }
+    }
+    fn ne(&self, other: &FontSelector) -> bool {
+        !(*self).eq(other)
+    }
 }
 
 // This struct is the result of mapping a specified FontStyle into the
@@ -192,11 +230,73 @@ impl FontGroup {
         self.fonts = ~[];
     }
 
-    pub fn create_textrun(&self, text: ~str, underline: bool) -> TextRun {
+    /// Creates a text run for the given text, drawing each character in the first font of the
+    /// group that has a glyph for it. Characters that no font in the group can render are drawn
+    /// in a system font that can, if there is one. If `rtl` is set, the text runs from right to
+    /// left. `features` are the OpenType features to shape the text with, and `hyphen_breaks` the
+    /// character indices, in increasing order, before which a word may be hyphenated.
+    pub fn create_textrun(&self,
+                          fctx: &mut FontContext,
+                          text: ~str,
+                          underline: bool,
+                          rtl: bool,
+                          features: &[FontFeature],
+                          hyphen_breaks: &[uint])
+                          -> TextRun {
         assert!(self.fonts.len() > 0);
 
-        // TODO(Issue #177): Actually fall back through the FontGroup when a font is unsuitable.
-        return TextRun::new(self.fonts[0], text, underline);
+        let mut fonts = ~[self.fonts[0]];
+        let mut font_segments = ~[(0u, 0u)];
+        let mut byte_i = 0;
+        while byte_i < text.len() {
+            let range = text.char_range_at(byte_i);
+
+            // Whitespace and control characters stay in whatever font we're already using, so
+            // that they don't needlessly split the run.
+            if !range.ch.is_whitespace() && range.ch >= ' ' {
+                let font = self.font_for_char(fctx, range.ch);
+                let font_i = match fonts.position(|f| managed::mut_ptr_eq(*f, font)) {
+                    Some(font_i) => font_i,
+                    None => {
+                        fonts.push(font);
+                        fonts.len() - 1
+                    }
+                };
+                let (start, current_font_i) = font_segments[font_segments.len() - 1];
+                if font_i != current_font_i {
+                    if start == byte_i {
+                        font_segments[font_segments.len() - 1] = (byte_i, font_i);
+                    } else {
+                        font_segments.push((byte_i, font_i));
+                    }
+                }
+            }
+
+            byte_i = range.next;
+        }
+
+        TextRun::new_with_fonts(fonts,
+                                font_segments,
+                                text,
+                                underline,
+                                rtl,
+                                features,
+                                hyphen_breaks)
+    }
+
+    /// Returns the font that should be used to draw the given character: the first font in the
+    /// group that has a glyph for it, or else a system font that does. If no font has a glyph
+    /// for it, returns the primary font, which will draw a missing-glyph box.
+    pub fn font_for_char(&self, fctx: &mut FontContext, codepoint: char) -> @mut Font {
+        for self.fonts.iter().advance |font| {
+            if font.glyph_index(codepoint).is_some() {
+                return *font;
+            }
+        }
+        match fctx.get_fallback_font_for_char(&self.style, codepoint) {
+            Some(font) => font,
+            None => self.fonts[0],
+        }
     }
 }
 
@@ -228,6 +328,56 @@ impl RunMetrics {
     }
 }
 
+/// The key of a font's shaping cache: some text and the options it was shaped with.
+#[deriving(Clone, Eq, IterBytes)]
+pub struct ShapeCacheKey {
+    text: ~str,
+    options: ShapingOptions,
+}
+
+/// The most memory, in bytes, that each font's shaping cache may use.
+static SHAPE_CACHE_BUDGET: uint = 1024 * 1024;
+
+/// How far a synthesized oblique face leans: the horizontal shift per unit of height.
+static SYNTHETIC_OBLIQUE_SKEW: AzFloat = 0.2;
+
+/// The number of positions, evenly spaced across a pixel, that glyphs are rasterized at.
+static SUBPIXEL_POSITIONS: uint = 4;
+
+/// The device scales that glyphs are rasterized at are distinguished to this many parts in one.
+static SCALE_KEY_PRECISION: AzFloat = 1000.0;
+
+/// A glyph to draw, positioned in the coordinate space of the draw target.
+#[deriving(Clone)]
+struct PositionedGlyph {
+    index: GlyphIndex,
+    position: Point2D<AzFloat>,
+    advance: Au,
+}
+
+/// Identifies a rasterization of one of a font's glyphs.
+#[deriving(Clone, Eq, IterBytes)]
+struct GlyphRasterKey {
+    index: GlyphIndex,
+    /// The device scale, in `SCALE_KEY_PRECISION`ths.
+    scale: uint,
+    /// The position within the pixel, in `SUBPIXEL_POSITIONS`ths.
+    subpixel: uint,
+    /// The red, green, blue and alpha of the color.
+    color: (u8, u8, u8, u8),
+}
+
+/// The character drawn at the end of a line that breaks a word.
+static HYPHEN: char = '\u2010';
+
+/// How far apart, as a fraction of the font size, the two copies of a glyph are drawn to
+/// synthesize a bold face.
+static SYNTHETIC_BOLD_OFFSET_RATIO: float = 1.0 / 16.0;
+
+fn shape_cache_entry_size(key: &ShapeCacheKey, glyphs: &ARC<GlyphStore>) -> uint {
+    key.text.len() + glyphs.get().heap_size()
+}
+
 /**
 A font instance. Layout can use this to calculate glyph metrics
 and the renderer can use it to render text.
@@ -236,17 +386,33 @@ pub struct Font {
     priv handle: FontHandle,
     priv azure_font: Option<ScaledFont>,
     priv shaper: Option<@Shaper>,
+    /// How to find this font again in another task.
+    priv selector: FontSelector,
+    /// True if the style is bold but the face isn't, so the font draws each glyph twice, a
+    /// little apart, to embolden it.
+    synthetic_bold: bool,
+    /// True if the style is italic or oblique but the face is upright, so the font shears its
+    /// glyphs as it draws them.
+    synthetic_oblique: bool,
     style: UsedFontStyle,
     metrics: FontMetrics,
     backend: BackendType,
     profiler_chan: ProfilerChan,
-    shape_cache: HashCache<~str, ARC<GlyphStore>>,
+    shape_cache: WeightedLRUCache<ShapeCacheKey, ARC<GlyphStore>>,
+    /// Where this font's glyphs are in the glyph atlas of the render task's font context.
+    priv glyph_rasters: HashMap<GlyphRasterKey, GlyphRaster>,
+    /// The generation of the atlas that `glyph_rasters` refers to.
+    priv glyph_raster_generation: uint,
+    /// The text, glyphs and advance of the hyphen drawn at the end of a line that breaks a word,
+    /// once it has been shaped.
+    priv hyphen: Option<(~str, ARC<GlyphStore>, Au)>,
 }
 
 impl Font {
     pub fn new_from_buffer(ctx: &FontContext,
-                       buffer: ~[u8],
+                       buffer: ARC<~[u8]>,
                        style: &SpecifiedFontStyle,
+                       selector: FontSelector,
                        backend: BackendType,
                        profiler_chan: ProfilerChan)
             -> Result<@mut Font, ()> {
@@ -257,35 +423,50 @@ impl Font {
             return Err(handle.get_err());
         };
         
-        let metrics = handle.get_metrics();
+        let (synthetic_bold, synthetic_oblique) = Font::synthesis_needed(&handle, style);
+        let metrics = Font::adjust_metrics(handle.get_metrics(), style, synthetic_bold);
         // TODO(Issue #179): convert between specified and used font style here?
 
         return Ok(@mut Font {
             handle: handle,
             azure_font: None,
             shaper: None,
+            selector: selector,
+            synthetic_bold: synthetic_bold,
+            synthetic_oblique: synthetic_oblique,
             style: copy *style,
             metrics: metrics,
             backend: backend,
             profiler_chan: profiler_chan,
-            shape_cache: HashCache::new(),
+            shape_cache: WeightedLRUCache::new(SHAPE_CACHE_BUDGET, shape_cache_entry_size),
+            glyph_rasters: HashMap::new(),
+            glyph_raster_generation: 0,
+            hyphen: None,
         });
     }
 
     pub fn new_from_adopted_handle(_fctx: &FontContext, handle: FontHandle,
                                style: &SpecifiedFontStyle, backend: BackendType,
                                profiler_chan: ProfilerChan) -> @mut Font {
-        let metrics = handle.get_metrics();
+        let (synthetic_bold, synthetic_oblique) = Font::synthesis_needed(&handle, style);
+        let metrics = Font::adjust_metrics(handle.get_metrics(), style, synthetic_bold);
+        let selector = SelectorPlatformIdentifier(handle.face_identifier());
 
         @mut Font {
             handle: handle,
             azure_font: None,
             shaper: None,
+            selector: selector,
+            synthetic_bold: synthetic_bold,
+            synthetic_oblique: synthetic_oblique,
             style: copy *style,
             metrics: metrics,
             backend: backend,
             profiler_chan: profiler_chan,
-            shape_cache: HashCache::new(),
+            shape_cache: WeightedLRUCache::new(SHAPE_CACHE_BUDGET, shape_cache_entry_size),
+            glyph_rasters: HashMap::new(),
+            glyph_raster_generation: 0,
+            hyphen: None,
         }
     }
 
@@ -302,6 +483,26 @@ impl Font {
         return Ok(Font::new_from_adopted_handle(fctx, styled_handle, style, backend, profiler_chan));
     }
 
+    /// Returns whether a font drawn from the given face in the given style must synthesize
+    /// emboldening and slant, respectively, because the face lacks them.
+    fn synthesis_needed(handle: &FontHandle, style: &UsedFontStyle) -> (bool, bool) {
+        let synthetic_bold = style.weight.is_bold() && !handle.boldness().is_bold();
+        let synthetic_oblique = (style.italic || style.oblique) && !handle.is_italic();
+        (synthetic_bold, synthetic_oblique)
+    }
+
+    /// Widens the face's metrics to cover the extra advance of synthesized bold glyphs.
+    fn adjust_metrics(metrics: FontMetrics, style: &UsedFontStyle, synthetic_bold: bool)
+                      -> FontMetrics {
+        if !synthetic_bold {
+            return metrics;
+        }
+        FontMetrics {
+            max_advance: metrics.max_advance + Au::from_frac_px(synthetic_bold_offset(style)),
+            .. metrics
+        }
+    }
+
     priv fn get_shaper(@mut self) -> @Shaper {
         // fast path: already created a shaper
         match self.shaper {
@@ -363,12 +564,189 @@ impl Font {
 
 
 impl Font {
+    /// Draws the given range of a run. `baseline_origin` is the left end of the range on the
+    /// baseline; right-to-left text is drawn leftward from the right end.
+    ///
+    /// Glyphs are copied out of the render context's glyph atlas, rasterizing them into it first
+    /// if need be, unless the draw target is rotated or skewed.
     pub fn draw_text_into_context(&mut self,
                               rctx: &RenderContext,
                               run: &TextRun,
                               range: &Range,
                               baseline_origin: Point2D<Au>,
                               color: Color) {
+        let mut origin = copy baseline_origin;
+        if run.rtl {
+            let advance = self.measure_text(run, range).advance_width;
+            origin = Point2D(origin.x + advance, origin.y);
+        }
+        let mut positioned_glyphs = ~[];
+        vec::reserve(&mut positioned_glyphs, range.length());
+
+        for run.iter_slices_for_range(range) |glyphs, _offset, slice_range| {
+            for glyphs.iter_glyphs_for_char_range(slice_range) |_i, glyph| {
+                let glyph_advance = glyph.advance_();
+                let glyph_offset = glyph.offset().get_or_default(Au::zero_point());
+                if run.rtl {
+                    origin = Point2D(origin.x - glyph_advance, origin.y);
+                }
+
+                positioned_glyphs.push(PositionedGlyph {
+                    index: glyph.index(),
+                    position: Point2D(to_frac_px(origin.x + glyph_offset.x) as AzFloat,
+                                      to_frac_px(origin.y + glyph_offset.y) as AzFloat),
+                    advance: glyph_advance,
+                });
+                if !run.rtl {
+                    origin = Point2D(origin.x + glyph_advance, origin.y);
+                }
+            };
+        }
+
+        let target = rctx.get_draw_target();
+        let baseline_y = to_frac_px(baseline_origin.y) as AzFloat;
+        let transform = target.get_transform();
+        let unblitted_glyphs = if transform.m12 == 0 as AzFloat &&
+                transform.m21 == 0 as AzFloat && transform.m11 == transform.m22 {
+            self.blit_glyphs_from_atlas(rctx, positioned_glyphs, &transform, color)
+        } else {
+            positioned_glyphs
+        };
+        self.fill_glyphs(target, unblitted_glyphs, baseline_y, color);
+    }
+
+    /// Draws the given glyphs by copying them out of the glyph atlas, rasterizing those that
+    /// aren't in it yet. Returns the glyphs that it couldn't draw this way: those too big for the
+    /// atlas, and in the rare case that the atlas filled up and was cleared in the middle of the
+    /// run, those that were rasterized before that.
+    priv fn blit_glyphs_from_atlas(&mut self,
+                                   rctx: &RenderContext,
+                                   glyphs: &[PositionedGlyph],
+                                   transform: &Matrix2D<AzFloat>,
+                                   color: Color)
+                                   -> ~[PositionedGlyph] {
+        let atlas = rctx.font_ctx.get_glyph_atlas();
+        if self.glyph_raster_generation != atlas.generation {
+            self.glyph_rasters.clear();
+            self.glyph_raster_generation = atlas.generation;
+        }
+
+        // Find or rasterize every glyph before taking a snapshot of the atlas to copy them from.
+        let mut blits = ~[];
+        let mut unblitted_glyphs = ~[];
+        for glyphs.iter().advance |glyph| {
+            // Snap the glyph to the device pixel grid, except for a fraction of a pixel
+            // horizontally.
+            let x = glyph.position.x * transform.m11 + transform.m31;
+            let y = glyph.position.y * transform.m22 + transform.m32;
+            let pixel_x = x.floor();
+            let subpixel = ((x - pixel_x) * SUBPIXEL_POSITIONS as AzFloat) as uint;
+            let key = GlyphRasterKey {
+                index: glyph.index,
+                scale: (transform.m11 * SCALE_KEY_PRECISION).round() as uint,
+                subpixel: subpixel,
+                color: (color_component(color.r), color_component(color.g),
+                        color_component(color.b), color_component(color.a)),
+            };
+            let pen = Point2D(pixel_x as i32, y.round() as i32);
+            match self.get_glyph_raster(atlas, &key, glyph.advance) {
+                Some(raster) => blits.push((raster, pen, *glyph)),
+                None => unblitted_glyphs.push(*glyph),
+            }
+        }
+
+        let generation = atlas.generation;
+        let surface = atlas.surface();
+        let target = rctx.get_draw_target();
+        let transform = target.get_transform();
+        target.set_transform(&Matrix2D::identity());
+        for blits.iter().advance |&(ref raster, ref pen, ref glyph)| {
+            if raster.generation != generation {
+                unblitted_glyphs.push(*glyph);
+                loop;
+            }
+            let source = Rect(Point2D(raster.rect.origin.x as AzFloat,
+                                      raster.rect.origin.y as AzFloat),
+                              Size2D(raster.rect.size.width as AzFloat,
+                                     raster.rect.size.height as AzFloat));
+            let dest = Rect(Point2D((pen.x - raster.origin.x) as AzFloat,
+                                    (pen.y - raster.origin.y) as AzFloat),
+                            copy source.size);
+            target.draw_surface(surface,
+                                dest,
+                                source,
+                                DrawSurfaceOptions(Linear, true),
+                                DrawOptions(1 as AzFloat, 0));
+        }
+        target.set_transform(&transform);
+        unblitted_glyphs
+    }
+
+    /// Returns the glyph with the given key in the atlas, rasterizing it into the atlas first if
+    /// it isn't there. Returns None if the glyph is too big for the atlas.
+    priv fn get_glyph_raster(&mut self,
+                             atlas: &mut GlyphAtlas,
+                             key: &GlyphRasterKey,
+                             advance: Au)
+                             -> Option<GlyphRaster> {
+        match self.glyph_rasters.find(key) {
+            Some(raster) if atlas.contains(raster) => return Some(*raster),
+            _ => {}
+        }
+
+        // Leave room for the parts of the glyph outside its advance and above or below the font's
+        // ascent and descent, and for synthesized emboldening and slant.
+        let scale = (key.scale as float) / (SCALE_KEY_PRECISION as float);
+        let ascent = (to_frac_px(self.metrics.ascent) * scale).ceil() as i32;
+        let descent = (to_frac_px(self.metrics.descent).abs() * scale).ceil() as i32;
+        let padding = (to_frac_px(self.metrics.em_size) * scale / 4f).ceil() as i32 + 1;
+        let mut width = to_frac_px(advance) * scale;
+        if self.synthetic_bold {
+            width += synthetic_bold_offset(&self.style) * scale;
+        }
+        if self.synthetic_oblique {
+            width += (ascent as float) * (SYNTHETIC_OBLIQUE_SKEW as float);
+        }
+        let size = Size2D(width.ceil() as i32 + padding * 2, ascent + descent + padding * 2);
+        let rect = match atlas.allocate(&size) {
+            Some(rect) => rect,
+            None => return None,
+        };
+        let origin = Point2D(padding, padding + ascent);
+
+        // Draw the glyph into its part of the atlas at the device scale, with its origin the
+        // given fraction of a pixel to the right of the raster's.
+        let subpixel_x = (key.subpixel as AzFloat) / (SUBPIXEL_POSITIONS as AzFloat);
+        let pen_x = (rect.origin.x + origin.x) as AzFloat + subpixel_x;
+        let pen_y = (rect.origin.y + origin.y) as AzFloat;
+        let matrix: Matrix2D<AzFloat> = Matrix2D::identity();
+        let matrix = matrix.scale(scale as AzFloat, scale as AzFloat).translate(pen_x, pen_y);
+        atlas.draw_target.set_transform(&matrix);
+        let glyph = PositionedGlyph {
+            index: key.index,
+            position: Point2D(0 as AzFloat, 0 as AzFloat),
+            advance: advance,
+        };
+        let (r, g, b, a) = key.color;
+        let color = rgba(r, g, b, (a as float) / 255f);
+        self.fill_glyphs(&atlas.draw_target, [glyph], 0 as AzFloat, color);
+
+        let raster = GlyphRaster {
+            generation: atlas.generation,
+            rect: rect,
+            origin: origin,
+        };
+        self.glyph_rasters.insert(copy *key, raster);
+        Some(raster)
+    }
+
+    /// Has Azure rasterize and draw the given glyphs, which sit on a baseline at the given height,
+    /// synthesizing emboldening and slant if need be.
+    priv fn fill_glyphs(&mut self,
+                        target: &DrawTarget,
+                        glyphs: &[PositionedGlyph],
+                        baseline_y: AzFloat,
+                        color: Color) {
         use std::libc::types::common::c99::{uint16_t, uint32_t};
         use azure::{struct__AzDrawOptions,
                     struct__AzGlyph,
@@ -376,7 +754,8 @@ impl Font {
                     struct__AzPoint};
         use azure::azure::{AzDrawTargetFillGlyphs};
 
-        let target = rctx.get_draw_target();
+        if glyphs.len() == 0 { return; } // Otherwise the Quartz backend will assert.
+
         let azfontref = self.get_azure_font();
         let pattern = ColorPattern(color);
         let azure_pattern = pattern.azure_color_pattern;
@@ -387,33 +766,49 @@ impl Font {
             fields: 0x0200 as uint16_t
         };
 
-        let mut origin = copy baseline_origin;
-        let mut azglyphs = ~[];
-        vec::reserve(&mut azglyphs, range.length());
+        // Shear the glyphs about the baseline to synthesize an oblique face. The shear is applied
+        // before, and so in the coordinate space of, the target's own transform.
+        let old_transform = target.get_transform();
+        if self.synthetic_oblique {
+            let shear = Matrix2D::new(1 as AzFloat, 0 as AzFloat,
+                                      -SYNTHETIC_OBLIQUE_SKEW, 1 as AzFloat,
+                                      SYNTHETIC_OBLIQUE_SKEW * baseline_y, 0 as AzFloat);
+            target.set_transform(&shear.mul(&old_transform));
+        }
 
-        for run.iter_slices_for_range(range) |glyphs, _offset, slice_range| {
-            for glyphs.iter_glyphs_for_char_range(slice_range) |_i, glyph| {
-                let glyph_advance = glyph.advance_();
-                let glyph_offset = glyph.offset().get_or_default(Au::zero_point());
+        // Glyph advances already include the extra width of synthesized bold glyphs, each of
+        // which is drawn a second time at this offset.
+        let bold_offset = if self.synthetic_bold {
+            synthetic_bold_offset(&self.style) as AzFloat
+        } else {
+            0 as AzFloat
+        };
 
-                let azglyph = struct__AzGlyph {
-                    mIndex: glyph.index() as uint32_t,
+        let mut azglyphs = ~[];
+        vec::reserve(&mut azglyphs, glyphs.len());
+        for glyphs.iter().advance |glyph| {
+            let azglyph = struct__AzGlyph {
+                mIndex: glyph.index as uint32_t,
+                mPosition: struct__AzPoint {
+                    x: glyph.position.x,
+                    y: glyph.position.y
+                }
+            };
+            if self.synthetic_bold {
+                azglyphs.push(struct__AzGlyph {
+                    mIndex: azglyph.mIndex,
                     mPosition: struct__AzPoint {
-                        x: (origin.x + glyph_offset.x).to_px() as AzFloat,
-                        y: (origin.y + glyph_offset.y).to_px() as AzFloat
+                        x: azglyph.mPosition.x + bold_offset,
+                        y: azglyph.mPosition.y
                     }
-                };
-                origin = Point2D(origin.x + glyph_advance, origin.y);
-                azglyphs.push(azglyph)
-            };
+                });
+            }
+            azglyphs.push(azglyph)
         }
 
-        let azglyph_buf_len = azglyphs.len();
-        if azglyph_buf_len == 0 { return; } // Otherwise the Quartz backend will assert.
-
         let glyphbuf = struct__AzGlyphBuffer {
             mGlyphs: vec::raw::to_ptr(azglyphs),
-            mNumGlyphs: azglyph_buf_len as uint32_t            
+            mNumGlyphs: azglyphs.len() as uint32_t
         };
 
         unsafe {
@@ -425,10 +820,11 @@ impl Font {
                                    ptr::to_unsafe_ptr(&options),
                                    ptr::null());
         }
+
+        target.set_transform(&old_transform);
     }
 
     pub fn measure_text(&self, run: &TextRun, range: &Range) -> RunMetrics {
-        // TODO(Issue #199): alter advance direction for RTL
         // TODO(Issue #98): using inter-char and inter-word spacing settings  when measuring text
         let mut advance = Au(0);
         for run.iter_slices_for_range(range) |glyphs, _offset, slice_range| {
@@ -450,19 +846,54 @@ impl Font {
         RunMetrics::new(advance, self.metrics.ascent, self.metrics.descent)
     }
 
-    pub fn shape_text(@mut self, text: ~str, is_whitespace: bool) -> ARC<GlyphStore> {
-        do profile(time::LayoutShapingCategory, self.profiler_chan.clone()) {
-            let shaper = self.get_shaper();
-            do self.shape_cache.find_or_create(&text) |txt| {
-                let mut glyphs = GlyphStore::new(text.char_len(), is_whitespace);
-                shaper.shape_text(*txt, &mut glyphs);
-                ARC(glyphs)
+    /// Shapes the given text, reusing the glyphs from an earlier call with the same text and
+    /// options if they are still in the shaping cache.
+    pub fn shape_text(@mut self, text: ~str, options: &ShapingOptions) -> ARC<GlyphStore> {
+        let key = ShapeCacheKey {
+            text: text,
+            options: copy *options,
+        };
+        let cached = self.shape_cache.find(&key);
+        self.profiler_chan.send(time::CacheLookupMsg(time::LayoutShapeCacheCategory,
+                                                     cached.is_some()));
+        match cached {
+            Some(glyphs) => glyphs,
+            None => {
+                do profile(time::LayoutShapingCategory, self.profiler_chan.clone()) {
+                    let shaper = self.get_shaper();
+                    let mut glyphs = GlyphStore::new(key.text.char_len(), options.is_whitespace);
+                    shaper.shape_text(key.text, options, &mut glyphs);
+                    if options.break_before != BreakTypeNone && glyphs.char_len() > 0 {
+                        glyphs.set_can_break_before(0, options.break_before);
+                    }
+                    let glyphs = ARC(glyphs);
+                    self.shape_cache.insert(key.clone(), glyphs.clone());
+                    glyphs
+                }
             }
         }
     }
 
     pub fn get_descriptor(&self) -> FontDescriptor {
-        FontDescriptor::new(copy self.style, SelectorPlatformIdentifier(self.handle.face_identifier()))
+        FontDescriptor::new(copy self.style, self.selector.clone())
+    }
+
+    /// Returns the text and glyphs of the hyphen drawn at the end of a line that breaks a word of
+    /// text in this font, and its advance. It is shaped the first time it is needed.
+    pub fn hyphen(@mut self) -> (~str, ARC<GlyphStore>, Au) {
+        match self.hyphen {
+            Some((ref text, ref glyphs, advance)) => return (copy *text, glyphs.clone(), advance),
+            None => {}
+        }
+
+        let hyphen = if self.glyph_index(HYPHEN).is_some() { HYPHEN } else { '-' };
+        let text = str::from_char(hyphen);
+        let options = ShapingOptions::new(false, BreakTypeNone, false, []);
+        let glyphs = self.shape_text(copy text, &options);
+        let char_range = Range::new(0, glyphs.get().char_len());
+        let advance = self.measure_text_for_slice(glyphs.get(), &char_range).advance_width;
+        self.hyphen = Some((copy text, glyphs.clone(), advance));
+        (text, glyphs, advance)
     }
 
     pub fn glyph_index(&self, codepoint: char) -> Option<GlyphIndex> {
@@ -470,13 +901,30 @@ impl Font {
     }
 
     pub fn glyph_h_advance(&self, glyph: GlyphIndex) -> FractionalPixel {
-        match self.handle.glyph_h_advance(glyph) {
+        let advance = match self.handle.glyph_h_advance(glyph) {
           Some(adv) => adv,
           None => /* FIXME: Need fallback strategy */ 10f as FractionalPixel
+        };
+        if self.synthetic_bold {
+            advance + synthetic_bold_offset(&self.style)
+        } else {
+            advance
         }
     }
 }
 
+/// Converts a color component to the byte it is stored as in a `GlyphRasterKey`.
+fn color_component(value: AzFloat) -> u8 {
+    (value * (255 as AzFloat)).round() as u8
+}
+
+/// Returns how far apart, in pixels, the two copies of each glyph of a synthesized bold face are
+/// drawn. This is also the extra advance of each glyph.
+fn synthetic_bold_offset(style: &UsedFontStyle) -> FractionalPixel {
+    let offset = style.pt_size * SYNTHETIC_BOLD_OFFSET_RATIO;
+    if offset < 1f { 1f } else { offset }
+}
+
 /*fn should_destruct_on_fail_without_leaking() {
     #[test];
     #[should_fail];
@@ -2,19 +2,25 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use font::{Font, FontDescriptor, FontGroup, FontHandleMethods, FontStyle,
-           SelectorPlatformIdentifier};
+use font::{Font, FontDescriptor, FontGroup, FontHandleMethods, FontSelector, FontStyle,
+           SelectorPlatformIdentifier, SelectorWebFont};
 use font::{SpecifiedFontStyle, UsedFontStyle};
-use font_list::FontList;
-use servo_util::cache::{Cache, LRUCache};
+use font_list::{FallbackKey, FontEntry, FontList};
+use font_prefs::{FontPrefs, is_generic_family};
+use glyph_atlas::GlyphAtlas;
+use servo_util::cache::{Cache, LRUCache, WeightedLRUCache, unit_weight};
 use servo_util::time::ProfilerChan;
 
 use platform::font::FontHandle;
 use platform::font_context::FontContextHandle;
+use text::TextRun;
+use text::text_run::TextRunCacheKey;
 
 use azure::azure_hl::BackendType;
+use extra::arc::ARC;
 use std::hashmap::HashMap;
 use std::result;
+use std::uint;
 
 // TODO(Rust #3934): creating lots of new dummy styles is a workaround
 // for not being able to store symbolic enums in top-level constants.
@@ -26,6 +32,34 @@ pub fn dummy_style() -> FontStyle {
         italic: false,
         oblique: false,
         families: ~"serif, sans-serif",
+        lang: None,
+    }
+}
+
+/// The family, weight and style that an `@font-face` rule declares for its font.
+#[deriving(Clone)]
+pub struct WebFontFace {
+    family: ~str,
+    /// The weight, from 100 to 900.
+    weight: uint,
+    italic: bool,
+}
+
+/// Where the font for an `@font-face` rule comes from.
+#[deriving(Clone)]
+enum WebFontSource {
+    /// An installed font, by family name.
+    LocalWebFont(~str),
+    /// A downloaded font: the URL it came from, and its data.
+    DownloadedWebFont(~str, ARC<~[u8]>),
+}
+
+/// Returns true if fonts in the given `@font-face` format can be loaded. A source without a
+/// format hint is always worth trying.
+pub fn is_supported_web_font_format(format: Option<&str>) -> bool {
+    match format {
+        None | Some("truetype") | Some("opentype") | Some("truetype-aat") => true,
+        Some(_) => false,
     }
 }
 
@@ -41,14 +75,30 @@ pub struct FontContext {
     group_cache: LRUCache<SpecifiedFontStyle, @FontGroup>,
     handle: FontContextHandle,
     backend: BackendType,
-    generic_fonts: HashMap<~str,~str>,
+    /// The user's choices of the fonts that generic families resolve to.
+    font_prefs: FontPrefs,
+    /// The system font, if any, chosen to draw each character that a font group couldn't, in
+    /// each style it was asked for in.
+    fallback_cache: HashMap<FallbackKey, Option<FontSelector>>,
+    /// The fonts declared by `@font-face` rules that have been loaded, by family name.
+    web_fonts: HashMap<~str, ~[(WebFontFace, WebFontSource)]>,
+    /// The text runs deserialized from the display lists drawn with this context, so that
+    /// drawing a display list again doesn't redo the work.
+    run_cache: WeightedLRUCache<TextRunCacheKey, @TextRun>,
+    /// The glyphs rasterized for drawing text with this context. Created on first use, since
+    /// layout never draws.
+    glyph_atlas: Option<GlyphAtlas>,
     profiler_chan: ProfilerChan,
 }
 
+/// The most text runs a font context keeps deserialized.
+static RUN_CACHE_SIZE: uint = 1024;
+
 #[allow(non_implicitly_copyable_typarams)]
 impl<'self> FontContext {
     pub fn new(backend: BackendType,
            needs_font_list: bool,
+           font_prefs: FontPrefs,
            profiler_chan: ProfilerChan)
            -> FontContext {
         let handle = FontContextHandle::new();
@@ -56,21 +106,17 @@ impl<'self> FontContext {
                             Some(FontList::new(&handle, profiler_chan.clone())) }
                         else { None };
 
-        // TODO: Allow users to specify these.
-        let mut generic_fonts = HashMap::with_capacity(5);
-        generic_fonts.insert(~"serif", ~"Times New Roman");
-        generic_fonts.insert(~"sans-serif", ~"Arial");
-        generic_fonts.insert(~"cursive", ~"Apple Chancery");
-        generic_fonts.insert(~"fantasy", ~"Papyrus");
-        generic_fonts.insert(~"monospace", ~"Menlo");
-
         FontContext { 
             instance_cache: LRUCache::new(10),
             font_list: font_list,
             group_cache: LRUCache::new(10),
             handle: handle,
             backend: backend,
-            generic_fonts: generic_fonts,
+            font_prefs: font_prefs,
+            fallback_cache: HashMap::new(),
+            web_fonts: HashMap::new(),
+            run_cache: WeightedLRUCache::new(RUN_CACHE_SIZE, unit_weight),
+            glyph_atlas: None,
             profiler_chan: profiler_chan,
         }
     }
@@ -79,6 +125,14 @@ impl<'self> FontContext {
         self.font_list.get_ref()
     }
 
+    /// Returns the atlas that glyphs are rasterized into for drawing, creating it if need be.
+    pub fn get_glyph_atlas(&'self mut self) -> &'self mut GlyphAtlas {
+        if self.glyph_atlas.is_none() {
+            self.glyph_atlas = Some(GlyphAtlas::new(self.backend));
+        }
+        self.glyph_atlas.get_mut_ref()
+    }
+
     pub fn get_resolved_font_for_style(&mut self, style: &SpecifiedFontStyle) -> @FontGroup {
         match self.group_cache.find(style) {
             Some(fg) => {
@@ -113,14 +167,126 @@ impl<'self> FontContext {
         }
     }
 
-    priv fn transform_family(&self, family: &str) -> ~str {
-        // FIXME: Need a find_like() in HashMap.
-        let family = family.to_str();
-        debug!("(transform family) searching for `%s`", family);
-        match self.generic_fonts.find(&family) {
-            None => family,
-            Some(mapped_family) => copy *mapped_family
+    /// Finds a system font with the given style that can draw the given character.
+    pub fn get_fallback_font_for_char(&mut self, style: &SpecifiedFontStyle, codepoint: char)
+                                      -> Option<@mut Font> {
+        let key = FallbackKey::new(style, codepoint);
+        let selector = match self.fallback_cache.find(&key) {
+            Some(selector) => copy *selector,
+            None => {
+                let selector = match self.font_list {
+                    Some(ref mut fl) => {
+                        do fl.find_font_for_codepoint(style, codepoint).map |entry| {
+                            SelectorPlatformIdentifier(entry.handle.face_identifier())
+                        }
+                    }
+                    None => None,
+                };
+                self.fallback_cache.insert(key, copy selector);
+                selector
+            }
+        };
+
+        match selector {
+            None => None,
+            Some(selector) => {
+                let desc = FontDescriptor::new(copy *style, selector);
+                match self.get_font_by_descriptor(&desc) {
+                    Ok(font) => Some(font),
+                    Err(()) => None,
+                }
+            }
+        }
+    }
+
+    /// Registers an installed font as the font of an `@font-face` rule, if there is a family
+    /// with the given name. Returns true if there is.
+    pub fn add_local_web_font(&mut self, face: &WebFontFace, local_family: &str) -> bool {
+        let found = match self.font_list {
+            Some(ref fl) => fl.has_family(local_family),
+            None => false,
+        };
+        if found {
+            self.add_web_font_source(face, LocalWebFont(local_family.to_owned()));
+        }
+        found
+    }
+
+    /// Registers a downloaded font as the font of an `@font-face` rule.
+    pub fn add_downloaded_web_font(&mut self, face: &WebFontFace, url: ~str, data: ~[u8]) {
+        self.add_web_font_source(face, DownloadedWebFont(url, ARC(data)));
+    }
+
+    priv fn add_web_font_source(&mut self, face: &WebFontFace, source: WebFontSource) {
+        debug!("registering web font for family `%s`", face.family);
+        let faces = self.web_fonts.find_or_insert(face.family.clone(), ~[]);
+        faces.push((face.clone(), source));
+
+        // Font groups created before the font arrived must be created again to include it.
+        self.group_cache.evict_all();
+    }
+
+    /// Finds the `@font-face` font of the given family that best matches the given style. A face
+    /// of the wrong style is used only if none has the right one; then the nearest weight wins.
+    priv fn find_web_font(&self, family: &str, style: &SpecifiedFontStyle)
+                          -> Option<WebFontSource> {
+        let faces = match self.web_fonts.find_equiv(&family) {
+            Some(faces) => faces,
+            None => return None,
+        };
+
+        let italic = style.italic || style.oblique;
+        let weight = style.weight.to_number();
+        let mut best = None;
+        let mut best_distance = uint::max_value;
+        for faces.iter().advance |&(ref face, ref source)| {
+            let weight_distance = if face.weight > weight {
+                face.weight - weight
+            } else {
+                weight - face.weight
+            };
+            let style_distance = if face.italic == italic { 0 } else { 1000 };
+            let distance = style_distance + weight_distance;
+            if distance < best_distance {
+                best = Some(source.clone());
+                best_distance = distance;
+            }
+        }
+        best
+    }
+
+    /// Returns the families, in order of preference, to look for in place of the given family
+    /// for text in the given language. A generic family resolves to the families the font
+    /// preferences choose for it, followed by those the platform does; any other family stands
+    /// for itself.
+    priv fn resolve_family(&self, family: &str, lang: Option<&str>) -> ~[~str] {
+        if !is_generic_family(family) {
+            return ~[family.to_owned()];
+        }
+        let mut families = self.font_prefs.families_for(family, lang);
+        match self.font_list {
+            Some(ref fl) => families.push_all_move(fl.get_generic_font_families(family, lang)),
+            None => {}
+        }
+        debug!("(resolve family) `%s` resolves to %?", family, families);
+        families
+    }
+
+    /// Returns the first of the given families that is installed, with the face in it that best
+    /// matches the given style.
+    priv fn find_font_in_families(&self, families: &[~str], style: &SpecifiedFontStyle)
+                                  -> Option<@FontEntry> {
+        let fl = match self.font_list {
+            Some(ref fl) => fl,
+            None => return None,
+        };
+        for families.iter().advance |family| {
+            match fl.find_font_in_family(*family, style) {
+                Some(entry) => return Some(entry),
+                None => {}
+            }
         }
+        None
     }
 
     priv fn create_font_group(&mut self, style: &SpecifiedFontStyle) -> @FontGroup {
@@ -128,19 +294,29 @@ impl<'self> FontContext {
 
         debug!("(create font group) --- starting ---");
 
+        let lang = style.lang.map(|lang| lang.as_slice());
+
         // TODO(Issue #193): make iteration over 'font-family' more robust.
         for style.families.split_iter(',').advance |family| {
             let family_name = family.trim();
-            let transformed_family_name = self.transform_family(family_name);
-            debug!("(create font group) transformed family is `%s`", transformed_family_name);
-
-            let result = match self.font_list {
-                Some(ref fl) => {
-                    fl.find_font_in_family(transformed_family_name, style)
-                },
-                None => None,
+
+            // Families declared by `@font-face` rules hide installed families of the same name.
+            let families = match self.find_web_font(family_name, style) {
+                Some(DownloadedWebFont(url, data)) => {
+                    let font_desc = FontDescriptor::new(copy *style, SelectorWebFont(url, data));
+                    match self.get_font_by_descriptor(&font_desc) {
+                        Ok(font) => fonts.push(font),
+                        Err(()) => debug!("(create font group) couldn't load web font for `%s`",
+                                          family_name),
+                    }
+                    loop
+                }
+                Some(LocalWebFont(local_family)) => ~[local_family],
+                None => self.resolve_family(family_name, lang),
             };
 
+            let result = self.find_font_in_families(families, style);
+
             let mut found = false;
             for result.iter().advance |font_entry| {
                 found = true;
@@ -155,30 +331,24 @@ impl<'self> FontContext {
             };
 
             if !found {
-                debug!("(create font group) didn't find `%s`", transformed_family_name);
+                debug!("(create font group) didn't find `%s`", family_name);
             }
         }
 
-        let last_resort = FontList::get_last_resort_font_families();
-
-        for last_resort.iter().advance |family| {
-            let result = match self.font_list {
-                Some(ref fl) => {
-                    fl.find_font_in_family(*family, style)
-                },
-                None => None,
-            };
-
-            for result.iter().advance |font_entry| {
-                let font_id =
-                  SelectorPlatformIdentifier(font_entry.handle.face_identifier());
-                let font_desc = FontDescriptor::new(copy *style, font_id);
+        // The last resort is the first installed family of those that the preferences and the
+        // platform choose.
+        let mut last_resort = self.font_prefs.families_for("sans-serif", lang);
+        last_resort.push_all_move(FontList::get_last_resort_font_families());
+        let result = self.find_font_in_families(last_resort, style);
+        for result.iter().advance |font_entry| {
+            let font_id =
+              SelectorPlatformIdentifier(font_entry.handle.face_identifier());
+            let font_desc = FontDescriptor::new(copy *style, font_id);
 
-                let instance = self.get_font_by_descriptor(&font_desc);
+            let instance = self.get_font_by_descriptor(&font_desc);
 
-                do result::iter(&instance) |font: &@mut Font| {
-                    fonts.push(*font);
-                }
+            do result::iter(&instance) |font: &@mut Font| {
+                fonts.push(*font);
             }
         }
 
@@ -205,6 +375,14 @@ impl<'self> FontContext {
                                                      self.profiler_chan.clone()))
                 })
             }
+            &SelectorWebFont(_, ref data) => {
+                Font::new_from_buffer(self,
+                                      data.clone(),
+                                      &desc.style,
+                                      desc.selector.clone(),
+                                      self.backend,
+                                      self.profiler_chan.clone())
+            }
         };
     }
 }
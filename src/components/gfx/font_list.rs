@@ -11,13 +11,49 @@ use servo_util::time;
 use servo_util::time::profile;
 use servo_util::time::ProfilerChan;
 
-use std::hashmap::HashMap;
+use std::hashmap::{HashMap, HashSet};
 
 pub type FontFamilyMap = HashMap<~str, @mut FontFamily>;
 
+/// Families with wide coverage of Unicode, in the order they are tried when none of the fonts a
+/// page asked for can draw a character. The platform's last resort families are tried after
+/// these.
+static FALLBACK_FAMILIES: [&'static str, ..6] = [
+    "DejaVu Sans", "Noto Sans", "Droid Sans Fallback", "Arial Unicode MS", "Lucida Grande",
+    "Symbola"
+];
+
+/// The most families to load while searching for a font that can draw a character.
+static MAX_FALLBACK_FAMILIES: uint = 12;
+
+/// Identifies a search for a fallback font: the character to draw, and the style of the text it
+/// is in, which decides which face of each fallback family is tried.
+#[deriving(Clone, Eq, IterBytes)]
+pub struct FallbackKey {
+    codepoint: char,
+    /// The numeric value of the font weight.
+    weight: uint,
+    italic: bool,
+    oblique: bool,
+    families: ~str,
+}
+
+impl FallbackKey {
+    pub fn new(style: &SpecifiedFontStyle, codepoint: char) -> FallbackKey {
+        FallbackKey {
+            codepoint: codepoint,
+            weight: style.weight.to_number(),
+            italic: style.italic,
+            oblique: style.oblique,
+            families: copy style.families,
+        }
+    }
+}
+
 trait FontListHandleMethods {
     fn get_available_families(&self, fctx: &FontContextHandle) -> FontFamilyMap;
     fn load_variations_for_family(&self, family: @mut FontFamily);
+    fn get_generic_font_families(&self, generic: &str, lang: Option<&str>) -> ~[~str];
     fn get_last_resort_font_families() -> ~[~str];
 }
 
@@ -26,6 +62,9 @@ pub struct FontList {
     family_map: FontFamilyMap,
     handle: FontListHandle,
     prof_chan: ProfilerChan,
+    /// The characters, in the styles they were searched for in, that no fallback family could
+    /// draw.
+    fallback_misses: HashSet<FallbackKey>,
 }
 
 impl FontList {
@@ -37,6 +76,7 @@ impl FontList {
             handle: handle,
             family_map: HashMap::new(),
             prof_chan: prof_chan.clone(),
+            fallback_misses: HashSet::new(),
         };
         list.refresh(fctx);
         list
@@ -76,6 +116,11 @@ impl FontList {
         result
     }
 
+    /// Returns true if a family with the given name is installed.
+    pub fn has_family(&self, family_name: &str) -> bool {
+        self.find_family(family_name).is_some()
+    }
+
     priv fn find_family(&self, family_name: &str) -> Option<@mut FontFamily> {
         // look up canonical name
         let family = self.family_map.find_equiv(&family_name);
@@ -87,6 +132,54 @@ impl FontList {
         family.map(|f| **f)
     }
 
+    /// Searches the installed fallback families, in order, for a face of the given style with a
+    /// glyph for the given character, for use when none of the fonts the page asked for can
+    /// render it. Only the first `MAX_FALLBACK_FAMILIES` installed ones are loaded, and characters
+    /// that none of them can draw are remembered so that they are not searched for again.
+    pub fn find_font_for_codepoint(&mut self,
+                                   style: &SpecifiedFontStyle,
+                                   codepoint: char)
+                                   -> Option<@FontEntry> {
+        let key = FallbackKey::new(style, codepoint);
+        if self.fallback_misses.contains(&key) {
+            return None;
+        }
+
+        let mut names: ~[~str] = FALLBACK_FAMILIES.iter().transform(|name| name.to_str()).collect();
+        names.push_all_move(FontList::get_last_resort_font_families());
+        let mut searched: ~[~str] = ~[];
+        for names.iter().advance |name| {
+            if searched.len() == MAX_FALLBACK_FAMILIES {
+                break;
+            }
+            if searched.contains(name) {
+                loop
+            }
+            let family = match self.find_family(*name) {
+                None => loop,
+                Some(family) => family,
+            };
+            searched.push(copy *name);
+            match family.find_font_for_style(&self.handle, style) {
+                Some(entry) if entry.handle.glyph_index(codepoint).is_some() => {
+                    debug!("FontList: found fallback font for U+%04X in family[%s]",
+                           codepoint as uint, family.family_name);
+                    return Some(entry);
+                }
+                _ => {}
+            }
+        }
+
+        self.fallback_misses.insert(key);
+        None
+    }
+
+    /// Returns the families, in order of preference, that the platform resolves the given
+    /// generic family to for text in the given language. Not all of them need be installed.
+    pub fn get_generic_font_families(&self, generic: &str, lang: Option<&str>) -> ~[~str] {
+        self.handle.get_generic_font_families(generic, lang)
+    }
+
     pub fn get_last_resort_font_families() -> ~[~str] {
         let last_resort = FontListHandle::get_last_resort_font_families();
         last_resort
@@ -134,7 +227,32 @@ impl FontFamily {
             }
         }
 
-        None
+        // The family lacks the face we want, so use the nearest one it has; the font will
+        // synthesize the missing emboldening or slant when it draws. Keeping the right slant
+        // matters more than keeping the right weight.
+        let mut fallback: Option<@FontEntry> = None;
+        for this.entries.each |entry| {
+            if style.italic == entry.is_italic() {
+                fallback = Some(*entry);
+                break;
+            }
+        }
+        if fallback.is_none() {
+            for this.entries.each |entry| {
+                if style.weight.is_bold() == entry.is_bold() {
+                    fallback = Some(*entry);
+                    break;
+                }
+            }
+        }
+        if fallback.is_none() && this.entries.len() > 0 {
+            fallback = Some(this.entries[0]);
+        }
+
+        let decision = if fallback.is_some() { "Falling back to" } else { "Couldn't find" };
+        debug!("FontFamily: %s a face of family[%s] of the wrong style", decision,
+               this.family_name);
+        fallback
     }
 }
 
@@ -0,0 +1,173 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Font preferences, which choose the fonts that generic families like `serif` and `monospace`
+//! resolve to, overriding the platform's choices. They are read from a file in which each line
+//! maps a generic family, for all languages or for one, to a list of families in order of
+//! preference:
+//!
+//!     # Comments start with a hash.
+//!     serif = DejaVu Serif, Times New Roman
+//!     sans-serif[ja] = IPAGothic
+
+use std::ascii::StrAsciiExt;
+use std::io;
+use std::path::Path;
+
+/// The generic font families of CSS.
+static GENERIC_FAMILIES: &'static [&'static str] = &[
+    "serif", "sans-serif", "cursive", "fantasy", "monospace"
+];
+
+/// Returns true if the given family name is one of the generic families of CSS.
+pub fn is_generic_family(family: &str) -> bool {
+    GENERIC_FAMILIES.iter().any_(|generic| generic.eq_ignore_ascii_case(family))
+}
+
+/// The families that a generic family resolves to, for all languages or for one.
+#[deriving(Clone)]
+struct GenericFamilyMapping {
+    generic: ~str,
+    /// The language, in lower case, or None if the mapping is for every language.
+    lang: Option<~str>,
+    families: ~[~str],
+}
+
+#[deriving(Clone)]
+pub struct FontPrefs {
+    priv mappings: ~[GenericFamilyMapping],
+}
+
+impl FontPrefs {
+    /// Creates preferences that leave every generic family to the platform.
+    pub fn new() -> FontPrefs {
+        FontPrefs {
+            mappings: ~[],
+        }
+    }
+
+    /// Reads preferences from the given file.
+    pub fn load(path: &str) -> Result<FontPrefs, ~str> {
+        match io::read_whole_file_str(&Path(path)) {
+            Ok(source) => FontPrefs::parse(source),
+            Err(message) => Err(fmt!("couldn't read font preferences from %s: %s",
+                                     path, message)),
+        }
+    }
+
+    /// Parses preferences in the format of a preference file.
+    pub fn parse(source: &str) -> Result<FontPrefs, ~str> {
+        let mut mappings = ~[];
+        let mut line_number = 0;
+        for source.any_line_iter().advance |line| {
+            line_number += 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("#") {
+                loop;
+            }
+            match parse_mapping(line) {
+                Ok(mapping) => mappings.push(mapping),
+                Err(message) => {
+                    return Err(fmt!("font preferences line %u: %s", line_number, message));
+                }
+            }
+        }
+        Ok(FontPrefs {
+            mappings: mappings,
+        })
+    }
+
+    /// Returns the families, in order of preference, that the given generic family resolves to
+    /// for text in the given language. A mapping for the language is preferred to one for its
+    /// primary language (`zh` for `zh-TW`), which is preferred to one for every language. Returns
+    /// an empty list if there are no mappings for the generic family.
+    pub fn families_for(&self, generic: &str, lang: Option<&str>) -> ~[~str] {
+        let lang = lang.map(|lang| lang.to_ascii_lower());
+        let primary_lang = lang.map(|lang| lang.split_iter('-').next().get().to_owned());
+
+        let mut best: Option<(uint, &GenericFamilyMapping)> = None;
+        for self.mappings.iter().advance |mapping| {
+            if !mapping.generic.eq_ignore_ascii_case(generic) {
+                loop;
+            }
+            let rank = match mapping.lang {
+                None => 0,
+                Some(ref mapping_lang) if Some(copy *mapping_lang) == lang => 2,
+                Some(ref mapping_lang) if Some(copy *mapping_lang) == primary_lang => 1,
+                Some(_) => loop,
+            };
+            // A later mapping of the same rank overrides an earlier one.
+            match best {
+                Some((best_rank, _)) if best_rank > rank => {}
+                _ => best = Some((rank, mapping)),
+            }
+        }
+
+        match best {
+            Some((_, mapping)) => copy mapping.families,
+            None => ~[],
+        }
+    }
+}
+
+/// Parses one line of a preference file, such as `sans-serif[ja] = IPAGothic, Arial`.
+fn parse_mapping(line: &str) -> Result<GenericFamilyMapping, ~str> {
+    let (key, value) = match line.find('=') {
+        Some(i) => (line.slice_to(i).trim(), line.slice_from(i + 1).trim()),
+        None => return Err(~"expected `generic-family = family, ...`"),
+    };
+
+    let (generic, lang) = match key.find('[') {
+        Some(i) if key.ends_with("]") => {
+            let lang = key.slice(i + 1, key.len() - 1).trim();
+            if lang.is_empty() {
+                return Err(~"empty language");
+            }
+            (key.slice_to(i).trim(), Some(lang.to_ascii_lower()))
+        }
+        Some(_) => return Err(~"expected `]` after the language"),
+        None => (key, None),
+    };
+    if !is_generic_family(generic) {
+        return Err(fmt!("`%s` isn't a generic font family", generic));
+    }
+
+    let mut families = ~[];
+    for value.split_iter(',').advance |family| {
+        let family = family.trim();
+        if family.is_empty() {
+            return Err(~"empty family name");
+        }
+        families.push(family.to_owned());
+    }
+
+    Ok(GenericFamilyMapping {
+        generic: generic.to_ascii_lower(),
+        lang: lang,
+        families: families,
+    })
+}
+
+#[test]
+fn test_families_for_language() {
+    let prefs = FontPrefs::parse("# Test preferences\n\
+                                  serif = Serif A, Serif B\n\
+                                  \n\
+                                  serif[zh] = Serif Zh\n\
+                                  SERIF[zh-TW] = Serif Zh-TW\n").unwrap();
+    assert_eq!(prefs.families_for("serif", None), ~[~"Serif A", ~"Serif B"]);
+    assert_eq!(prefs.families_for("serif", Some("en")), ~[~"Serif A", ~"Serif B"]);
+    assert_eq!(prefs.families_for("serif", Some("zh-CN")), ~[~"Serif Zh"]);
+    assert_eq!(prefs.families_for("Serif", Some("zh-tw")), ~[~"Serif Zh-TW"]);
+    assert!(prefs.families_for("monospace", None).is_empty());
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(FontPrefs::parse("serif Times").is_err());
+    assert!(FontPrefs::parse("Times = Arial").is_err());
+    assert!(FontPrefs::parse("serif[] = Times").is_err());
+    assert!(FontPrefs::parse("serif[ja = Times").is_err());
+    assert!(FontPrefs::parse("serif = Times,,Arial").is_err());
+}
@@ -0,0 +1,148 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! An atlas of rasterized glyphs. Fonts rasterize each glyph they draw into the atlas once, at
+//! the size and subpixel offset it is drawn at, and afterwards draw it by copying it out, which
+//! is much cheaper than having Azure rasterize its outline every time a tile is painted.
+
+use azure::azure_hl::{B8G8R8A8, BackendType, DrawTarget, SourceSurface};
+use geom::{Point2D, Rect, Size2D};
+
+/// The width and height of the atlas, in pixels.
+static ATLAS_SIZE: i32 = 1024;
+
+/// A glyph rasterized into the atlas.
+#[deriving(Clone)]
+pub struct GlyphRaster {
+    /// The generation of the atlas the glyph was rasterized into. The glyph is gone once the
+    /// atlas has been cleared since.
+    generation: uint,
+    /// The part of the atlas holding the glyph.
+    rect: Rect<i32>,
+    /// Where the glyph's origin on the baseline is, relative to the top left of `rect`.
+    origin: Point2D<i32>,
+}
+
+/// Packs rectangles into a square from the top down, in rows ("shelves") filled from left to
+/// right. A shelf is as tall as the tallest rectangle in it.
+struct ShelfPacker {
+    size: i32,
+    shelf_top: i32,
+    shelf_height: i32,
+    shelf_x: i32,
+}
+
+impl ShelfPacker {
+    fn new(size: i32) -> ShelfPacker {
+        ShelfPacker {
+            size: size,
+            shelf_top: 0,
+            shelf_height: 0,
+            shelf_x: 0,
+        }
+    }
+
+    /// Finds room for a rectangle of the given size, returning its top left, or None if there is
+    /// no room left.
+    fn pack(&mut self, size: &Size2D<i32>) -> Option<Point2D<i32>> {
+        if size.width > self.size {
+            return None;
+        }
+        if self.shelf_x + size.width > self.size {
+            self.shelf_top += self.shelf_height;
+            self.shelf_height = 0;
+            self.shelf_x = 0;
+        }
+        if self.shelf_top + size.height > self.size {
+            return None;
+        }
+
+        let position = Point2D(self.shelf_x, self.shelf_top);
+        self.shelf_x += size.width;
+        if size.height > self.shelf_height {
+            self.shelf_height = size.height;
+        }
+        Some(position)
+    }
+}
+
+pub struct GlyphAtlas {
+    priv backend: BackendType,
+    /// The surface glyphs are rasterized into.
+    draw_target: DrawTarget,
+    /// A snapshot of the draw target to copy glyphs from, unless glyphs have been rasterized
+    /// since it was taken.
+    priv snapshot: Option<SourceSurface>,
+    priv packer: ShelfPacker,
+    /// Incremented each time the atlas fills up and is cleared.
+    generation: uint,
+}
+
+impl GlyphAtlas {
+    pub fn new(backend: BackendType) -> GlyphAtlas {
+        GlyphAtlas {
+            backend: backend,
+            draw_target: new_draw_target(backend),
+            snapshot: None,
+            packer: ShelfPacker::new(ATLAS_SIZE),
+            generation: 0,
+        }
+    }
+
+    /// Reserves the part of the atlas that a glyph of the given size is about to be rasterized
+    /// into, clearing the atlas first if it is full. Returns None if the glyph is too big for even
+    /// an empty atlas.
+    pub fn allocate(&mut self, size: &Size2D<i32>) -> Option<Rect<i32>> {
+        let position = match self.packer.pack(size) {
+            Some(position) => position,
+            None => {
+                self.clear();
+                match self.packer.pack(size) {
+                    Some(position) => position,
+                    None => return None,
+                }
+            }
+        };
+        self.snapshot = None;
+        Some(Rect(position, *size))
+    }
+
+    /// Returns true if the given glyph is still in the atlas.
+    pub fn contains(&self, raster: &GlyphRaster) -> bool {
+        raster.generation == self.generation
+    }
+
+    /// Returns a surface holding the glyphs, to copy them out of.
+    pub fn surface<'a>(&'a mut self) -> &'a SourceSurface {
+        if self.snapshot.is_none() {
+            self.snapshot = Some(self.draw_target.snapshot());
+        }
+        self.snapshot.get_ref()
+    }
+
+    priv fn clear(&mut self) {
+        debug!("glyph atlas is full; clearing it");
+        self.draw_target = new_draw_target(self.backend);
+        self.snapshot = None;
+        self.packer = ShelfPacker::new(ATLAS_SIZE);
+        self.generation += 1;
+    }
+}
+
+/// Creates the draw target of an atlas. It starts out transparent.
+fn new_draw_target(backend: BackendType) -> DrawTarget {
+    DrawTarget::new(backend, Size2D(ATLAS_SIZE, ATLAS_SIZE), B8G8R8A8)
+}
+
+#[test]
+fn test_shelf_packer() {
+    let mut packer = ShelfPacker::new(10);
+    assert!(packer.pack(&Size2D(4, 3)) == Some(Point2D(0, 0)));
+    assert!(packer.pack(&Size2D(4, 5)) == Some(Point2D(4, 0)));
+    // This doesn't fit beside the others, so it starts a shelf below the tallest of them.
+    assert!(packer.pack(&Size2D(4, 2)) == Some(Point2D(0, 5)));
+    assert!(packer.pack(&Size2D(6, 4)) == Some(Point2D(4, 5)));
+    assert!(packer.pack(&Size2D(1, 2)) == None);
+    assert!(packer.pack(&Size2D(11, 1)) == None);
+}
@@ -7,6 +7,7 @@
 
 use azure::azure_hl::{BackendType, CairoBackend, CoreGraphicsBackend};
 use azure::azure_hl::{CoreGraphicsAcceleratedBackend, Direct2DBackend, SkiaBackend};
+use font_prefs::FontPrefs;
 
 use std::f64;
 use std::result;
@@ -19,9 +20,28 @@ pub struct Opts {
     tile_size: uint,
     profiler_period: Option<f64>,
 
+    /// A file to write a Chrome trace-event log of every profiled span to on exit.
+    trace_file: Option<~str>,
+
+    /// A file to write per-category profiler statistics to on exit. The report is written as CSV
+    /// if the file name ends in `.csv` and as JSON otherwise.
+    report_file: Option<~str>,
+
     /// A scale factor to apply to tiles, to allow rendering tiles at higher resolutions for
     /// testing pan and zoom code.
     zoom: uint,
+
+    /// The fonts to use for generic font families, read from the file given on the command line,
+    /// if any.
+    font_prefs: FontPrefs,
+
+    /// A directory of hyphenation dictionaries, one for each language, to hyphenate text styled
+    /// `hyphens: auto` with.
+    hyphenation_dir: Option<~str>,
+
+    /// A file holding the user's stylesheet, which is cascaded between the user agent's
+    /// stylesheets and the document's.
+    user_stylesheet: Option<~str>,
 }
 
 #[allow(non_implicitly_copyable_typarams)]
@@ -37,6 +57,11 @@ pub fn from_cmdline_args(args: &[~str]) -> Opts {
         getopts::optopt("t"),  // threads to render with
         getopts::optflagopt("p"),  // profiler flag and output interval
         getopts::optopt("z"),  // zoom level
+        getopts::optopt("T"),  // trace-event output file
+        getopts::optopt("R"),  // profiler report output file
+        getopts::optopt("F"),  // font preferences file
+        getopts::optopt("H"),  // hyphenation dictionary directory
+        getopts::optopt("u"),  // user stylesheet
     ];
 
     let opt_match = match getopts::getopts(args, opts) {
@@ -94,12 +119,31 @@ pub fn from_cmdline_args(args: &[~str]) -> Opts {
         None => 1,
     };
 
+    let trace_file = getopts::opt_maybe_str(&opt_match, "T");
+    let report_file = getopts::opt_maybe_str(&opt_match, "R");
+
+    let font_prefs = match getopts::opt_maybe_str(&opt_match, "F") {
+        Some(font_prefs_file) => match FontPrefs::load(font_prefs_file) {
+            Ok(font_prefs) => font_prefs,
+            Err(message) => fail!(message),
+        },
+        None => FontPrefs::new(),
+    };
+
+    let hyphenation_dir = getopts::opt_maybe_str(&opt_match, "H");
+    let user_stylesheet = getopts::opt_maybe_str(&opt_match, "u");
+
     Opts {
         urls: urls,
         render_backend: render_backend,
         n_render_threads: n_render_threads,
         tile_size: tile_size,
         profiler_period: profiler_period,
+        trace_file: trace_file,
+        report_file: report_file,
         zoom: zoom,
+        font_prefs: font_prefs,
+        hyphenation_dir: hyphenation_dir,
+        user_stylesheet: user_stylesheet,
     }
 }
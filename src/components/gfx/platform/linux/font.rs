@@ -25,6 +25,7 @@ use freetype::freetype::{FT_SizeRec, FT_UInt, FT_Size_Metrics};
 use freetype::freetype::{ft_sfnt_os2};
 use freetype::tt_os2::TT_OS2;
 
+use extra::arc::ARC;
 use std::cast;
 use std::ptr;
 use std::str;
@@ -49,7 +50,8 @@ impl FontTableMethods for FontTable {
 }
 
 enum FontSource {
-    FontSourceMem(~[u8]),
+    /// The font's data, which is shared by every instance of the font.
+    FontSourceMem(ARC<~[u8]>),
     FontSourceFile(~str)
 }
 
@@ -75,13 +77,13 @@ impl Drop for FontHandle {
 
 impl FontHandleMethods for FontHandle {
     pub fn new_from_buffer(fctx: &FontContextHandle,
-                           buf: ~[u8],
+                           buf: ARC<~[u8]>,
                            style: &SpecifiedFontStyle)
                         -> Result<FontHandle, ()> {
         let ft_ctx: FT_Library = fctx.ctx.ctx;
         if ft_ctx.is_null() { return Err(()); }
 
-        let face_result = do vec::as_imm_buf(buf) |bytes: *u8, len: uint| {
+        let face_result = do vec::as_imm_buf(*buf.get()) |bytes: *u8, len: uint| {
             create_face_from_buffer(ft_ctx, bytes, len, style.pt_size)
         };
 
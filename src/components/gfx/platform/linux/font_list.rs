@@ -129,8 +129,66 @@ impl FontListHandle {
         }
     }
 
+    /// Returns the families that the given generic family stands for in the given language,
+    /// according to the fontconfig configuration's aliases.
+    pub fn get_generic_font_families(&self, generic: &str, lang: Option<&str>) -> ~[~str] {
+        generic_font_families(generic, lang)
+    }
+
     pub fn get_last_resort_font_families() -> ~[~str] {
-        ~[~"Arial"]
+        let mut families = generic_font_families("sans-serif", None);
+        families.push(~"Arial");
+        families
+    }
+}
+
+/// Returns the families, in order of preference, that fontconfig substitutes for the given
+/// generic family. Not all of them need be installed.
+fn generic_font_families(generic: &str, lang: Option<&str>) -> ~[~str] {
+    unsafe {
+        let config = FcConfigGetCurrent();
+        let wrapper = AutoPattern { pattern: FcPatternCreate() };
+        let pattern = wrapper.pattern;
+        let res = do str::as_c_str("family") |FC_FAMILY| {
+            do str::as_c_str(generic) |family| {
+                FcPatternAddString(pattern, FC_FAMILY, family as *FcChar8)
+            }
+        };
+        if res != 1 {
+            debug!("adding family to pattern failed");
+            return ~[];
+        }
+        for lang.iter().advance |lang| {
+            let res = do str::as_c_str("lang") |FC_LANG| {
+                do str::as_c_str(*lang) |lang| {
+                    FcPatternAddString(pattern, FC_LANG, lang as *FcChar8)
+                }
+            };
+            if res != 1 {
+                debug!("adding language to pattern failed");
+            }
+        }
+
+        if FcConfigSubstitute(config, pattern, FcMatchPattern) != 1 {
+            debug!("substitution failed");
+            return ~[];
+        }
+        FcDefaultSubstitute(pattern);
+
+        let mut families = ~[];
+        let family: *FcChar8 = ptr::null();
+        let mut v: c_int = 0;
+        do str::as_c_str("family") |FC_FAMILY| {
+            while FcPatternGetString(pattern, FC_FAMILY, v, &family) == FcResultMatch {
+                let family_name = str::raw::from_buf(family as *u8);
+                if family_name != generic.to_owned() {
+                    families.push(family_name);
+                }
+                v += 1;
+            }
+        }
+        debug!("fontconfig resolves `%s` to %?", generic, families);
+        families
     }
 }
 
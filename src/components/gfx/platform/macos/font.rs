@@ -28,6 +28,7 @@ use core_text::font_descriptor::{SymbolicTraitAccessors, TraitAccessors};
 use core_text::font_descriptor::{kCTFontDefaultOrientation};
 use core_text;
 
+use extra::arc::ARC;
 use std::ptr;
 use std::vec;
 
@@ -55,6 +56,9 @@ impl FontTableMethods for FontTable {
 pub struct FontHandle {
     priv cgfont: Option<CGFont>,
     ctfont: CTFont,
+    /// The data of a font created from memory, which the data provider of `cgfont` reads from and
+    /// every instance of the font shares.
+    priv buffer: Option<ARC<~[u8]>>,
 }
 
 impl FontHandle {
@@ -62,6 +66,7 @@ impl FontHandle {
         Ok(FontHandle {
             cgfont: None,
             ctfont: ctfont,
+            buffer: None,
         })
     }
 
@@ -78,9 +83,9 @@ impl FontHandle {
 }
 
 impl FontHandleMethods for FontHandle {
-    fn new_from_buffer(_: &FontContextHandle, buf: ~[u8], style: &SpecifiedFontStyle)
+    fn new_from_buffer(_: &FontContextHandle, buf: ARC<~[u8]>, style: &SpecifiedFontStyle)
                     -> Result<FontHandle, ()> {
-        let fontprov : CGDataProvider = vec::as_imm_buf(buf, |cbuf, len| {
+        let fontprov : CGDataProvider = vec::as_imm_buf(*buf.get(), |cbuf, len| {
             core_graphics::data_provider::new_from_buffer(cbuf, len)
         });
 
@@ -90,6 +95,7 @@ impl FontHandleMethods for FontHandle {
         let result = Ok(FontHandle {
             cgfont: Some(cgfont),
             ctfont: ctfont,
+            buffer: Some(buf),
         });
 
         return result;
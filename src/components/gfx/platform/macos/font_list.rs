@@ -57,6 +57,19 @@ impl FontListHandle {
         }
     }
 
+    /// Returns the families that the given generic family stands for.
+    pub fn get_generic_font_families(&self, generic: &str, _lang: Option<&str>) -> ~[~str] {
+        // TODO: Ask Core Text for the user's choices, and take the language into account.
+        match generic {
+            "serif" => ~[~"Times New Roman"],
+            "sans-serif" => ~[~"Arial"],
+            "cursive" => ~[~"Apple Chancery"],
+            "fantasy" => ~[~"Papyrus"],
+            "monospace" => ~[~"Menlo"],
+            _ => ~[],
+        }
+    }
+
     pub fn get_last_resort_font_families() -> ~[~str] {
         ~[~"Arial Unicode MS",~"Arial"]
     }
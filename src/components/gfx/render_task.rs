@@ -28,7 +28,11 @@ use extra::arc;
 
 pub struct RenderLayer {
     display_list: DisplayList<()>,
-    size: Size2D<uint>
+    size: Size2D<uint>,
+    /// The content that stays fixed to the viewport while the rest of the layer scrolls.
+    fixed_display_list: DisplayList<()>,
+    /// The size of the viewport, which the fixed content covers.
+    viewport_size: Size2D<uint>,
 }
 
 pub enum Msg {
@@ -99,6 +103,7 @@ impl<C: RenderListener + Owned> RenderTask<C> {
                 compositor: compositor,
                 font_ctx: @mut FontContext::new(copy opts.render_backend,
                                                 false,
+                                                copy opts.font_prefs,
                                                 profiler_chan.clone()),
                 opts: opts,
                 profiler_chan: profiler_chan,
@@ -146,6 +151,85 @@ impl<C: RenderListener + Owned> RenderTask<C> {
         }
     }
 
+    /// Divides the given display list, covering an area of the given size, into tiles and renders
+    /// them at the given scale. Opaque tiles are cleared to white first.
+    fn render_tiles(&self,
+                    display_list: &DisplayList<()>,
+                    size: Size2D<uint>,
+                    scale: f32,
+                    opaque: bool)
+                    -> ~[LayerBuffer] {
+        let tile_size = self.opts.tile_size;
+
+        // FIXME: Try not to create a new array here.
+        let mut new_buffers = ~[];
+
+        // Divide up the layer into tiles.
+        do time::profile(time::RenderingPrepBuffCategory, self.profiler_chan.clone()) {
+            let mut y = 0;
+            while y < (size.height as f32 * scale).ceil() as uint {
+                let mut x = 0;
+                while x < (size.width as f32 * scale).ceil() as uint {
+                    // Figure out the dimension of this tile.
+                    let right = uint::min(x + tile_size, (size.width as f32 * scale).ceil() as uint);
+                    let bottom = uint::min(y + tile_size, (size.height as f32 * scale).ceil() as uint);
+                    let width = right - x;
+                    let height = bottom - y;
+
+                    let tile_rect = Rect(Point2D(x as f32 / scale, y as f32 / scale), Size2D(width as f32, height as f32));
+                    let screen_rect = Rect(Point2D(x, y), Size2D(width, height));
+
+                    let buffer = LayerBuffer {
+                        draw_target: DrawTarget::new_with_fbo(self.opts.render_backend,
+                                                              self.share_gl_context,
+                                                              Size2D(width as i32,
+                                                                     height as i32),
+                                                              B8G8R8A8),
+                        rect: tile_rect,
+                        screen_pos: screen_rect,
+                        stride: (width * 4) as uint
+                    };
+
+                    {
+                        // Build the render context.
+                        let ctx = RenderContext {
+                            canvas: &buffer,
+                            font_ctx: self.font_ctx,
+                            opts: &self.opts
+                        };
+
+                        // Apply the translation to render the tile we want.
+                        let matrix: Matrix2D<AzFloat> = Matrix2D::identity();
+                        let matrix = matrix.scale(scale as AzFloat, scale as AzFloat);
+                        let matrix = matrix.translate(-(buffer.rect.origin.x) as AzFloat,
+                                                      -(buffer.rect.origin.y) as AzFloat);
+
+                        ctx.canvas.draw_target.set_transform(&matrix);
+
+                        // Clear the buffer. New draw targets start out transparent.
+                        if opaque {
+                            ctx.clear();
+                        }
+
+                        // Draw the display list.
+                        do profile(time::RenderingDrawingCategory, self.profiler_chan.clone()) {
+                            display_list.draw_into_context(&ctx);
+                            ctx.canvas.draw_target.flush();
+                        }
+                    }
+
+                    new_buffers.push(buffer);
+
+                    x += tile_size;
+                }
+
+                y += tile_size;
+            }
+        }
+
+        new_buffers
+    }
+
     fn render(&mut self, scale: f32) {
         debug!("render_task: rendering");
         
@@ -159,74 +243,25 @@ impl<C: RenderListener + Owned> RenderTask<C> {
 
         self.compositor.set_render_state(RenderingRenderState);
         do time::profile(time::RenderingCategory, self.profiler_chan.clone()) {
-            let tile_size = self.opts.tile_size;
-
-            // FIXME: Try not to create a new array here.
-            let mut new_buffers = ~[];
-
-            // Divide up the layer into tiles.
-            do time::profile(time::RenderingPrepBuffCategory, self.profiler_chan.clone()) {
-                let mut y = 0;
-                while y < (render_layer.size.height as f32 * scale).ceil() as uint {
-                    let mut x = 0;
-                    while x < (render_layer.size.width as f32 * scale).ceil() as uint {
-                        // Figure out the dimension of this tile.
-                        let right = uint::min(x + tile_size, (render_layer.size.width as f32 * scale).ceil() as uint);
-                        let bottom = uint::min(y + tile_size, (render_layer.size.height as f32 * scale).ceil() as uint);
-                        let width = right - x;
-                        let height = bottom - y;
-
-                        let tile_rect = Rect(Point2D(x as f32 / scale, y as f32 / scale), Size2D(width as f32, height as f32));
-                        let screen_rect = Rect(Point2D(x, y), Size2D(width, height));
-
-                        let buffer = LayerBuffer {
-                            draw_target: DrawTarget::new_with_fbo(self.opts.render_backend,
-                                                                  self.share_gl_context,
-                                                                  Size2D(width as i32,
-                                                                         height as i32),
-                                                                  B8G8R8A8),
-                            rect: tile_rect,
-                            screen_pos: screen_rect,
-                            stride: (width * 4) as uint
-                        };
-
-                        {
-                            // Build the render context.
-                            let ctx = RenderContext {
-                                canvas: &buffer,
-                                font_ctx: self.font_ctx,
-                                opts: &self.opts
-                            };
-
-                            // Apply the translation to render the tile we want.
-                            let matrix: Matrix2D<AzFloat> = Matrix2D::identity();
-                            let matrix = matrix.scale(scale as AzFloat, scale as AzFloat);
-                            let matrix = matrix.translate(-(buffer.rect.origin.x) as AzFloat,
-                                                          -(buffer.rect.origin.y) as AzFloat);
-
-                            ctx.canvas.draw_target.set_transform(&matrix);
-
-                            // Clear the buffer.
-                            ctx.clear();
-
-                            // Draw the display list.
-                            do profile(time::RenderingDrawingCategory, self.profiler_chan.clone()) {
-                                render_layer.display_list.draw_into_context(&ctx);
-                                ctx.canvas.draw_target.flush();
-                            }
-                        }
-
-                        new_buffers.push(buffer);
-
-                        x += tile_size;
-                    }
-
-                    y += tile_size;
-                }
-            }
+            let new_buffers = self.render_tiles(&render_layer.display_list,
+                                                render_layer.size,
+                                                scale,
+                                                true);
+
+            // The fixed content is composited over the rest of the page, so its tiles are left
+            // transparent where it draws nothing.
+            let fixed_buffers = if render_layer.fixed_display_list.list.is_empty() {
+                ~[]
+            } else {
+                self.render_tiles(&render_layer.fixed_display_list,
+                                  render_layer.viewport_size,
+                                  scale,
+                                  false)
+            };
 
             let layer_buffer_set = LayerBufferSet {
                 buffers: new_buffers,
+                fixed_buffers: fixed_buffers,
             };
             let layer_buffer_set = arc::ARC(layer_buffer_set);
 
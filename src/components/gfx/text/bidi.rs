@@ -0,0 +1,1166 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The Unicode Bidirectional Algorithm, as described in Unicode Standard Annex #9. It finds the
+//! embedding level of each character of a paragraph, and from the levels, the visual order of
+//! the characters of a line.
+//!
+//! Directional isolates (LRI, RLI, FSI and PDI) are treated as other neutrals, and paired
+//! brackets are not given the direction of their contents (rule N0).
+
+use std::u8;
+use std::uint;
+use std::vec;
+
+/// The bidirectional character type of a character, as given by the `Bidi_Class` property in the
+/// Unicode Character Database. See UAX #9, section 3.2.
+#[deriving(Eq)]
+pub enum BidiClass {
+    BidiL,      // Left-to-right
+    BidiR,      // Right-to-left
+    BidiAL,     // Right-to-left Arabic
+    BidiEN,     // European number
+    BidiES,     // European number separator
+    BidiET,     // European number terminator
+    BidiAN,     // Arabic number
+    BidiCS,     // Common number separator
+    BidiNSM,    // Nonspacing mark
+    BidiBN,     // Boundary neutral
+    BidiB,      // Paragraph separator
+    BidiS,      // Segment separator
+    BidiWS,     // Whitespace
+    BidiON,     // Other neutrals
+    BidiLRE,    // Left-to-right embedding
+    BidiLRO,    // Left-to-right override
+    BidiRLE,    // Right-to-left embedding
+    BidiRLO,    // Right-to-left override
+    BidiPDF,    // Pop directional format
+    BidiLRI,    // Left-to-right isolate
+    BidiRLI,    // Right-to-left isolate
+    BidiFSI,    // First strong isolate
+    BidiPDI,    // Pop directional isolate
+}
+
+/// The deepest embedding level that explicit embeddings and overrides may reach.
+pub static MAX_DEPTH: u8 = 61;
+
+/// The explicit directional formatting characters, with which callers can express embeddings
+/// that are not in the text itself, such as those from the CSS `unicode-bidi` property.
+pub static LRE: char = '\u202A';
+pub static RLE: char = '\u202B';
+pub static PDF: char = '\u202C';
+pub static LRO: char = '\u202D';
+pub static RLO: char = '\u202E';
+
+/// Whether text at the given embedding level runs from right to left.
+pub fn is_rtl(level: u8) -> bool {
+    level % 2 == 1
+}
+
+/// Returns the bidirectional character type of the given character.
+pub fn bidi_class(ch: char) -> BidiClass {
+    let mut low = 0;
+    let mut high = BIDI_CLASS_TABLE.len();
+    while low < high {
+        let mid = (low + high) / 2;
+        let (first, last, class) = BIDI_CLASS_TABLE[mid];
+        if ch < first {
+            high = mid;
+        } else if ch > last {
+            low = mid + 1;
+        } else {
+            return class;
+        }
+    }
+    BidiL
+}
+
+/// Finds the embedding level of each character of a paragraph of text whose base level is
+/// `paragraph_level`: 0 for a left-to-right paragraph, or 1 for a right-to-left one. Whitespace
+/// at the end of the text is given the paragraph level, as at the end of a line (rule L1).
+///
+/// Characters that the algorithm ignores, such as the explicit formatting characters, are given
+/// the level of the character before them, so that they never split a run of text.
+pub fn resolve_levels(text: &str, paragraph_level: u8) -> ~[u8] {
+    let mut classes = ~[];
+    for text.iter().advance |ch: char| {
+        classes.push(bidi_class(ch));
+    }
+
+    let (embedding_levels, types, removed) = resolve_explicit_levels(classes, paragraph_level);
+    let mut levels = copy embedding_levels;
+
+    // X10: Resolve each maximal run of characters at the same embedding level separately,
+    // skipping those removed by rule X9.
+    let mut runs: ~[~[uint]] = ~[];
+    for uint::range(0, classes.len()) |i| {
+        if removed[i] {
+            loop;
+        }
+        let continues_run = match runs.last_opt() {
+            Some(run) => embedding_levels[*run.last()] == embedding_levels[i],
+            None => false,
+        };
+        if continues_run {
+            runs[runs.len() - 1].push(i);
+        } else {
+            runs.push(~[i]);
+        }
+    }
+
+    for runs.iter().enumerate().advance |(run_i, run)| {
+        let level = embedding_levels[run[0]];
+        let prev_level = if run_i == 0 {
+            paragraph_level
+        } else {
+            embedding_levels[runs[run_i - 1][0]]
+        };
+        let next_level = if run_i + 1 == runs.len() {
+            paragraph_level
+        } else {
+            embedding_levels[runs[run_i + 1][0]]
+        };
+        let sor = strong_direction_of_level(u8::max(level, prev_level));
+        let eor = strong_direction_of_level(u8::max(level, next_level));
+
+        let mut run_types = run.map(|&i| types[i]);
+        resolve_weak_types(run_types, sor);
+        resolve_neutral_types(run_types, level, sor, eor);
+        for run.iter().zip(run_types.iter()).advance |(&i, &class)| {
+            levels[i] = implicit_level(level, class);
+        }
+    }
+
+    for uint::range(0, classes.len()) |i| {
+        if removed[i] {
+            levels[i] = if i == 0 { paragraph_level } else { levels[i - 1] };
+        }
+    }
+
+    reset_whitespace_levels(classes, removed, paragraph_level, levels);
+    levels
+}
+
+/// Finds the visual order of the characters of a line, given their embedding levels (rule L2).
+/// The result lists the logical index of each character, from left to right.
+pub fn reorder_visually(levels: &[u8]) -> ~[uint] {
+    let mut order = vec::from_fn(levels.len(), |i| i);
+    if levels.is_empty() {
+        return order;
+    }
+
+    let highest_level = levels.iter().fold(0u8, |a, &b| u8::max(a, b));
+    let lowest_level = levels.iter().fold(MAX_DEPTH + 1, |a, &b| u8::min(a, b));
+    let lowest_odd_level = lowest_level | 1;
+
+    // From the highest level down to the lowest odd level, reverse every sequence of characters
+    // at that level or higher.
+    let mut level = highest_level;
+    while level >= lowest_odd_level {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] < level {
+                i += 1;
+                loop;
+            }
+            let mut end = i;
+            while end < order.len() && levels[order[end]] >= level {
+                end += 1;
+            }
+            vec::reverse(order.mut_slice(i, end));
+            i = end;
+        }
+        level -= 1;
+    }
+    order
+}
+
+/// Applies rules X1 to X9, returning the embedding level of each character, its type after any
+/// directional override, and whether it is removed from further consideration.
+fn resolve_explicit_levels(classes: &[BidiClass], paragraph_level: u8)
+                           -> (~[u8], ~[BidiClass], ~[bool]) {
+    let mut levels = vec::from_elem(classes.len(), paragraph_level);
+    let mut types = classes.to_owned();
+    let mut removed = vec::from_elem(classes.len(), false);
+
+    // The current embeddings, each with the type it overrides characters to, if any.
+    let mut stack: ~[(u8, Option<BidiClass>)] = ~[(paragraph_level, None)];
+    // The number of embeddings too deep to be pushed that have not been popped.
+    let mut overflow_count = 0u;
+
+    for uint::range(0, classes.len()) |i| {
+        let (level, direction_override) = *stack.last();
+        levels[i] = level;
+        match classes[i] {
+            BidiRLE | BidiRLO | BidiLRE | BidiLRO => {
+                let (new_level, new_override) = match classes[i] {
+                    BidiRLE => ((level + 1) | 1, None),
+                    BidiRLO => ((level + 1) | 1, Some(BidiR)),
+                    BidiLRE => ((level + 2) & !1u8, None),
+                    _ => ((level + 2) & !1u8, Some(BidiL)),
+                };
+                if new_level <= MAX_DEPTH && overflow_count == 0 {
+                    stack.push((new_level, new_override));
+                } else {
+                    overflow_count += 1;
+                }
+                removed[i] = true;
+            }
+            BidiPDF => {
+                if overflow_count > 0 {
+                    overflow_count -= 1;
+                } else if stack.len() > 1 {
+                    stack.pop();
+                }
+                removed[i] = true;
+            }
+            BidiBN => removed[i] = true,
+            BidiB => {
+                // X8: A paragraph separator ends all embeddings.
+                levels[i] = paragraph_level;
+                stack = ~[(paragraph_level, None)];
+                overflow_count = 0;
+            }
+            BidiLRI | BidiRLI | BidiFSI | BidiPDI => types[i] = BidiON,
+            _ => {
+                match direction_override {
+                    Some(class) => types[i] = class,
+                    None => {}
+                }
+            }
+        }
+    }
+
+    (levels, types, removed)
+}
+
+fn strong_direction_of_level(level: u8) -> BidiClass {
+    if is_rtl(level) { BidiR } else { BidiL }
+}
+
+/// Applies rules W1 to W7 to the types of a level run.
+fn resolve_weak_types(types: &mut [BidiClass], sor: BidiClass) {
+    // W1: Nonspacing marks take the type of the character before them.
+    for uint::range(0, types.len()) |i| {
+        if types[i] == BidiNSM {
+            types[i] = if i == 0 { sor } else { types[i - 1] };
+        }
+    }
+
+    // W2: European numbers after Arabic letters are Arabic numbers.
+    let mut last_strong = sor;
+    for uint::range(0, types.len()) |i| {
+        match types[i] {
+            BidiL | BidiR | BidiAL => last_strong = types[i],
+            BidiEN if last_strong == BidiAL => types[i] = BidiAN,
+            _ => {}
+        }
+    }
+
+    // W3: Arabic letters are right-to-left.
+    for uint::range(0, types.len()) |i| {
+        if types[i] == BidiAL {
+            types[i] = BidiR;
+        }
+    }
+
+    // W4: A single separator between two numbers of the same type joins them.
+    for uint::range(1, uint::max(types.len(), 1) - 1) |i| {
+        match (types[i - 1], types[i], types[i + 1]) {
+            (BidiEN, BidiES, BidiEN) | (BidiEN, BidiCS, BidiEN) => types[i] = BidiEN,
+            (BidiAN, BidiCS, BidiAN) => types[i] = BidiAN,
+            _ => {}
+        }
+    }
+
+    // W5: Terminators next to European numbers are part of them.
+    let mut i = 0;
+    while i < types.len() {
+        if types[i] != BidiET {
+            i += 1;
+            loop;
+        }
+        let mut end = i;
+        while end < types.len() && types[end] == BidiET {
+            end += 1;
+        }
+        let next_to_number = (i > 0 && types[i - 1] == BidiEN) ||
+            (end < types.len() && types[end] == BidiEN);
+        if next_to_number {
+            for uint::range(i, end) |j| {
+                types[j] = BidiEN;
+            }
+        }
+        i = end;
+    }
+
+    // W6: Remaining separators and terminators are neutral.
+    for uint::range(0, types.len()) |i| {
+        match types[i] {
+            BidiES | BidiET | BidiCS => types[i] = BidiON,
+            _ => {}
+        }
+    }
+
+    // W7: European numbers in left-to-right text are left-to-right.
+    let mut last_strong = sor;
+    for uint::range(0, types.len()) |i| {
+        match types[i] {
+            BidiL | BidiR => last_strong = types[i],
+            BidiEN if last_strong == BidiL => types[i] = BidiL,
+            _ => {}
+        }
+    }
+}
+
+/// Applies rules N1 and N2 to the types of a level run at the given embedding level.
+fn resolve_neutral_types(types: &mut [BidiClass], level: u8, sor: BidiClass, eor: BidiClass) {
+    fn is_neutral(class: BidiClass) -> bool {
+        match class {
+            BidiB | BidiS | BidiWS | BidiON => true,
+            _ => false
+        }
+    }
+
+    // Numbers act as right-to-left text for the purposes of these rules.
+    fn strong_direction(class: BidiClass) -> Option<BidiClass> {
+        match class {
+            BidiL => Some(BidiL),
+            BidiR | BidiEN | BidiAN => Some(BidiR),
+            _ => None
+        }
+    }
+
+    let mut i = 0;
+    while i < types.len() {
+        if !is_neutral(types[i]) {
+            i += 1;
+            loop;
+        }
+        let mut end = i;
+        while end < types.len() && is_neutral(types[end]) {
+            end += 1;
+        }
+
+        // N1: Neutrals between text of the same direction take that direction. N2: Other
+        // neutrals take the direction of the embedding.
+        let before = if i == 0 { Some(sor) } else { strong_direction(types[i - 1]) };
+        let after = if end == types.len() { Some(eor) } else { strong_direction(types[end]) };
+        let direction = match (before, after) {
+            (Some(before), Some(after)) if before == after => before,
+            _ => strong_direction_of_level(level),
+        };
+        for uint::range(i, end) |j| {
+            types[j] = direction;
+        }
+        i = end;
+    }
+}
+
+/// Applies rules I1 and I2, returning the level of a character of the given resolved type in a
+/// level run at the given embedding level.
+fn implicit_level(level: u8, class: BidiClass) -> u8 {
+    match (is_rtl(level), class) {
+        (false, BidiR) => level + 1,
+        (false, BidiAN) | (false, BidiEN) => level + 2,
+        (true, BidiL) | (true, BidiAN) | (true, BidiEN) => level + 1,
+        _ => level
+    }
+}
+
+/// L1: Gives segment and paragraph separators the paragraph level, along with any whitespace
+/// before them or at the end of the text.
+fn reset_whitespace_levels(classes: &[BidiClass],
+                           removed: &[bool],
+                           paragraph_level: u8,
+                           levels: &mut [u8]) {
+    let mut trailing = true;
+    let mut i = classes.len();
+    while i > 0 {
+        i -= 1;
+        match classes[i] {
+            BidiS | BidiB => {
+                levels[i] = paragraph_level;
+                trailing = true;
+            }
+            BidiWS | BidiLRI | BidiRLI | BidiFSI | BidiPDI => {
+                if trailing {
+                    levels[i] = paragraph_level;
+                }
+            }
+            _ if removed[i] => {
+                if trailing {
+                    levels[i] = paragraph_level;
+                }
+            }
+            _ => trailing = false,
+        }
+    }
+}
+
+/// The bidirectional character types of all characters, as sorted, non-overlapping inclusive
+/// ranges. Characters that are not in any range are of type L. Generated from the `Bidi_Class`
+/// property of the Unicode Character Database, version 14.0.0.
+static BIDI_CLASS_TABLE: [(char, char, BidiClass), ..712] = [
+    ('\u0000', '\u0008', BidiBN),
+    ('\u0009', '\u0009', BidiS),
+    ('\u000A', '\u000A', BidiB),
+    ('\u000B', '\u000B', BidiS),
+    ('\u000C', '\u000C', BidiWS),
+    ('\u000D', '\u000D', BidiB),
+    ('\u000E', '\u001B', BidiBN),
+    ('\u001C', '\u001E', BidiB),
+    ('\u001F', '\u001F', BidiS),
+    ('\u0020', '\u0020', BidiWS),
+    ('\u0021', '\u0022', BidiON),
+    ('\u0023', '\u0025', BidiET),
+    ('\u0026', '\u002A', BidiON),
+    ('\u002B', '\u002B', BidiES),
+    ('\u002C', '\u002C', BidiCS),
+    ('\u002D', '\u002D', BidiES),
+    ('\u002E', '\u002F', BidiCS),
+    ('\u0030', '\u0039', BidiEN),
+    ('\u003A', '\u003A', BidiCS),
+    ('\u003B', '\u0040', BidiON),
+    ('\u005B', '\u0060', BidiON),
+    ('\u007B', '\u007E', BidiON),
+    ('\u007F', '\u0084', BidiBN),
+    ('\u0085', '\u0085', BidiB),
+    ('\u0086', '\u009F', BidiBN),
+    ('\u00A0', '\u00A0', BidiCS),
+    ('\u00A1', '\u00A1', BidiON),
+    ('\u00A2', '\u00A5', BidiET),
+    ('\u00A6', '\u00A9', BidiON),
+    ('\u00AB', '\u00AC', BidiON),
+    ('\u00AD', '\u00AD', BidiBN),
+    ('\u00AE', '\u00AF', BidiON),
+    ('\u00B0', '\u00B1', BidiET),
+    ('\u00B2', '\u00B3', BidiEN),
+    ('\u00B4', '\u00B4', BidiON),
+    ('\u00B6', '\u00B8', BidiON),
+    ('\u00B9', '\u00B9', BidiEN),
+    ('\u00BB', '\u00BF', BidiON),
+    ('\u00D7', '\u00D7', BidiON),
+    ('\u00F7', '\u00F7', BidiON),
+    ('\u02B9', '\u02BA', BidiON),
+    ('\u02C2', '\u02CF', BidiON),
+    ('\u02D2', '\u02DF', BidiON),
+    ('\u02E5', '\u02ED', BidiON),
+    ('\u02EF', '\u02FF', BidiON),
+    ('\u0300', '\u036F', BidiNSM),
+    ('\u0374', '\u0375', BidiON),
+    ('\u037E', '\u037E', BidiON),
+    ('\u0384', '\u0385', BidiON),
+    ('\u0387', '\u0387', BidiON),
+    ('\u03F6', '\u03F6', BidiON),
+    ('\u0483', '\u0489', BidiNSM),
+    ('\u058A', '\u058A', BidiON),
+    ('\u058D', '\u058E', BidiON),
+    ('\u058F', '\u058F', BidiET),
+    ('\u0590', '\u0590', BidiR),
+    ('\u0591', '\u05BD', BidiNSM),
+    ('\u05BE', '\u05BE', BidiR),
+    ('\u05BF', '\u05BF', BidiNSM),
+    ('\u05C0', '\u05C0', BidiR),
+    ('\u05C1', '\u05C2', BidiNSM),
+    ('\u05C3', '\u05C3', BidiR),
+    ('\u05C4', '\u05C5', BidiNSM),
+    ('\u05C6', '\u05C6', BidiR),
+    ('\u05C7', '\u05C7', BidiNSM),
+    ('\u05C8', '\u05FF', BidiR),
+    ('\u0600', '\u0605', BidiAN),
+    ('\u0606', '\u0607', BidiON),
+    ('\u0608', '\u0608', BidiAL),
+    ('\u0609', '\u060A', BidiET),
+    ('\u060B', '\u060B', BidiAL),
+    ('\u060C', '\u060C', BidiCS),
+    ('\u060D', '\u060D', BidiAL),
+    ('\u060E', '\u060F', BidiON),
+    ('\u0610', '\u061A', BidiNSM),
+    ('\u061B', '\u064A', BidiAL),
+    ('\u064B', '\u065F', BidiNSM),
+    ('\u0660', '\u0669', BidiAN),
+    ('\u066A', '\u066A', BidiET),
+    ('\u066B', '\u066C', BidiAN),
+    ('\u066D', '\u066F', BidiAL),
+    ('\u0670', '\u0670', BidiNSM),
+    ('\u0671', '\u06D5', BidiAL),
+    ('\u06D6', '\u06DC', BidiNSM),
+    ('\u06DD', '\u06DD', BidiAN),
+    ('\u06DE', '\u06DE', BidiON),
+    ('\u06DF', '\u06E4', BidiNSM),
+    ('\u06E5', '\u06E6', BidiAL),
+    ('\u06E7', '\u06E8', BidiNSM),
+    ('\u06E9', '\u06E9', BidiON),
+    ('\u06EA', '\u06ED', BidiNSM),
+    ('\u06EE', '\u06EF', BidiAL),
+    ('\u06F0', '\u06F9', BidiEN),
+    ('\u06FA', '\u0710', BidiAL),
+    ('\u0711', '\u0711', BidiNSM),
+    ('\u0712', '\u072F', BidiAL),
+    ('\u0730', '\u074A', BidiNSM),
+    ('\u074B', '\u07A5', BidiAL),
+    ('\u07A6', '\u07B0', BidiNSM),
+    ('\u07B1', '\u07BF', BidiAL),
+    ('\u07C0', '\u07EA', BidiR),
+    ('\u07EB', '\u07F3', BidiNSM),
+    ('\u07F4', '\u07F5', BidiR),
+    ('\u07F6', '\u07F9', BidiON),
+    ('\u07FA', '\u07FC', BidiR),
+    ('\u07FD', '\u07FD', BidiNSM),
+    ('\u07FE', '\u0815', BidiR),
+    ('\u0816', '\u0819', BidiNSM),
+    ('\u081A', '\u081A', BidiR),
+    ('\u081B', '\u0823', BidiNSM),
+    ('\u0824', '\u0824', BidiR),
+    ('\u0825', '\u0827', BidiNSM),
+    ('\u0828', '\u0828', BidiR),
+    ('\u0829', '\u082D', BidiNSM),
+    ('\u082E', '\u0858', BidiR),
+    ('\u0859', '\u085B', BidiNSM),
+    ('\u085C', '\u085F', BidiR),
+    ('\u0860', '\u088F', BidiAL),
+    ('\u0890', '\u0891', BidiAN),
+    ('\u0892', '\u0897', BidiAL),
+    ('\u0898', '\u089F', BidiNSM),
+    ('\u08A0', '\u08C9', BidiAL),
+    ('\u08CA', '\u08E1', BidiNSM),
+    ('\u08E2', '\u08E2', BidiAN),
+    ('\u08E3', '\u0902', BidiNSM),
+    ('\u093A', '\u093A', BidiNSM),
+    ('\u093C', '\u093C', BidiNSM),
+    ('\u0941', '\u0948', BidiNSM),
+    ('\u094D', '\u094D', BidiNSM),
+    ('\u0951', '\u0957', BidiNSM),
+    ('\u0962', '\u0963', BidiNSM),
+    ('\u0981', '\u0981', BidiNSM),
+    ('\u09BC', '\u09BC', BidiNSM),
+    ('\u09C1', '\u09C4', BidiNSM),
+    ('\u09CD', '\u09CD', BidiNSM),
+    ('\u09E2', '\u09E3', BidiNSM),
+    ('\u09F2', '\u09F3', BidiET),
+    ('\u09FB', '\u09FB', BidiET),
+    ('\u09FE', '\u09FE', BidiNSM),
+    ('\u0A01', '\u0A02', BidiNSM),
+    ('\u0A3C', '\u0A3C', BidiNSM),
+    ('\u0A41', '\u0A42', BidiNSM),
+    ('\u0A47', '\u0A48', BidiNSM),
+    ('\u0A4B', '\u0A4D', BidiNSM),
+    ('\u0A51', '\u0A51', BidiNSM),
+    ('\u0A70', '\u0A71', BidiNSM),
+    ('\u0A75', '\u0A75', BidiNSM),
+    ('\u0A81', '\u0A82', BidiNSM),
+    ('\u0ABC', '\u0ABC', BidiNSM),
+    ('\u0AC1', '\u0AC5', BidiNSM),
+    ('\u0AC7', '\u0AC8', BidiNSM),
+    ('\u0ACD', '\u0ACD', BidiNSM),
+    ('\u0AE2', '\u0AE3', BidiNSM),
+    ('\u0AF1', '\u0AF1', BidiET),
+    ('\u0AFA', '\u0AFF', BidiNSM),
+    ('\u0B01', '\u0B01', BidiNSM),
+    ('\u0B3C', '\u0B3C', BidiNSM),
+    ('\u0B3F', '\u0B3F', BidiNSM),
+    ('\u0B41', '\u0B44', BidiNSM),
+    ('\u0B4D', '\u0B4D', BidiNSM),
+    ('\u0B55', '\u0B56', BidiNSM),
+    ('\u0B62', '\u0B63', BidiNSM),
+    ('\u0B82', '\u0B82', BidiNSM),
+    ('\u0BC0', '\u0BC0', BidiNSM),
+    ('\u0BCD', '\u0BCD', BidiNSM),
+    ('\u0BF3', '\u0BF8', BidiON),
+    ('\u0BF9', '\u0BF9', BidiET),
+    ('\u0BFA', '\u0BFA', BidiON),
+    ('\u0C00', '\u0C00', BidiNSM),
+    ('\u0C04', '\u0C04', BidiNSM),
+    ('\u0C3C', '\u0C3C', BidiNSM),
+    ('\u0C3E', '\u0C40', BidiNSM),
+    ('\u0C46', '\u0C48', BidiNSM),
+    ('\u0C4A', '\u0C4D', BidiNSM),
+    ('\u0C55', '\u0C56', BidiNSM),
+    ('\u0C62', '\u0C63', BidiNSM),
+    ('\u0C78', '\u0C7E', BidiON),
+    ('\u0C81', '\u0C81', BidiNSM),
+    ('\u0CBC', '\u0CBC', BidiNSM),
+    ('\u0CCC', '\u0CCD', BidiNSM),
+    ('\u0CE2', '\u0CE3', BidiNSM),
+    ('\u0D00', '\u0D01', BidiNSM),
+    ('\u0D3B', '\u0D3C', BidiNSM),
+    ('\u0D41', '\u0D44', BidiNSM),
+    ('\u0D4D', '\u0D4D', BidiNSM),
+    ('\u0D62', '\u0D63', BidiNSM),
+    ('\u0D81', '\u0D81', BidiNSM),
+    ('\u0DCA', '\u0DCA', BidiNSM),
+    ('\u0DD2', '\u0DD4', BidiNSM),
+    ('\u0DD6', '\u0DD6', BidiNSM),
+    ('\u0E31', '\u0E31', BidiNSM),
+    ('\u0E34', '\u0E3A', BidiNSM),
+    ('\u0E3F', '\u0E3F', BidiET),
+    ('\u0E47', '\u0E4E', BidiNSM),
+    ('\u0EB1', '\u0EB1', BidiNSM),
+    ('\u0EB4', '\u0EBC', BidiNSM),
+    ('\u0EC8', '\u0ECD', BidiNSM),
+    ('\u0F18', '\u0F19', BidiNSM),
+    ('\u0F35', '\u0F35', BidiNSM),
+    ('\u0F37', '\u0F37', BidiNSM),
+    ('\u0F39', '\u0F39', BidiNSM),
+    ('\u0F3A', '\u0F3D', BidiON),
+    ('\u0F71', '\u0F7E', BidiNSM),
+    ('\u0F80', '\u0F84', BidiNSM),
+    ('\u0F86', '\u0F87', BidiNSM),
+    ('\u0F8D', '\u0F97', BidiNSM),
+    ('\u0F99', '\u0FBC', BidiNSM),
+    ('\u0FC6', '\u0FC6', BidiNSM),
+    ('\u102D', '\u1030', BidiNSM),
+    ('\u1032', '\u1037', BidiNSM),
+    ('\u1039', '\u103A', BidiNSM),
+    ('\u103D', '\u103E', BidiNSM),
+    ('\u1058', '\u1059', BidiNSM),
+    ('\u105E', '\u1060', BidiNSM),
+    ('\u1071', '\u1074', BidiNSM),
+    ('\u1082', '\u1082', BidiNSM),
+    ('\u1085', '\u1086', BidiNSM),
+    ('\u108D', '\u108D', BidiNSM),
+    ('\u109D', '\u109D', BidiNSM),
+    ('\u135D', '\u135F', BidiNSM),
+    ('\u1390', '\u1399', BidiON),
+    ('\u1400', '\u1400', BidiON),
+    ('\u1680', '\u1680', BidiWS),
+    ('\u169B', '\u169C', BidiON),
+    ('\u1712', '\u1714', BidiNSM),
+    ('\u1732', '\u1733', BidiNSM),
+    ('\u1752', '\u1753', BidiNSM),
+    ('\u1772', '\u1773', BidiNSM),
+    ('\u17B4', '\u17B5', BidiNSM),
+    ('\u17B7', '\u17BD', BidiNSM),
+    ('\u17C6', '\u17C6', BidiNSM),
+    ('\u17C9', '\u17D3', BidiNSM),
+    ('\u17DB', '\u17DB', BidiET),
+    ('\u17DD', '\u17DD', BidiNSM),
+    ('\u17F0', '\u17F9', BidiON),
+    ('\u1800', '\u180A', BidiON),
+    ('\u180B', '\u180D', BidiNSM),
+    ('\u180E', '\u180E', BidiBN),
+    ('\u180F', '\u180F', BidiNSM),
+    ('\u1885', '\u1886', BidiNSM),
+    ('\u18A9', '\u18A9', BidiNSM),
+    ('\u1920', '\u1922', BidiNSM),
+    ('\u1927', '\u1928', BidiNSM),
+    ('\u1932', '\u1932', BidiNSM),
+    ('\u1939', '\u193B', BidiNSM),
+    ('\u1940', '\u1940', BidiON),
+    ('\u1944', '\u1945', BidiON),
+    ('\u19DE', '\u19FF', BidiON),
+    ('\u1A17', '\u1A18', BidiNSM),
+    ('\u1A1B', '\u1A1B', BidiNSM),
+    ('\u1A56', '\u1A56', BidiNSM),
+    ('\u1A58', '\u1A5E', BidiNSM),
+    ('\u1A60', '\u1A60', BidiNSM),
+    ('\u1A62', '\u1A62', BidiNSM),
+    ('\u1A65', '\u1A6C', BidiNSM),
+    ('\u1A73', '\u1A7C', BidiNSM),
+    ('\u1A7F', '\u1A7F', BidiNSM),
+    ('\u1AB0', '\u1ACE', BidiNSM),
+    ('\u1B00', '\u1B03', BidiNSM),
+    ('\u1B34', '\u1B34', BidiNSM),
+    ('\u1B36', '\u1B3A', BidiNSM),
+    ('\u1B3C', '\u1B3C', BidiNSM),
+    ('\u1B42', '\u1B42', BidiNSM),
+    ('\u1B6B', '\u1B73', BidiNSM),
+    ('\u1B80', '\u1B81', BidiNSM),
+    ('\u1BA2', '\u1BA5', BidiNSM),
+    ('\u1BA8', '\u1BA9', BidiNSM),
+    ('\u1BAB', '\u1BAD', BidiNSM),
+    ('\u1BE6', '\u1BE6', BidiNSM),
+    ('\u1BE8', '\u1BE9', BidiNSM),
+    ('\u1BED', '\u1BED', BidiNSM),
+    ('\u1BEF', '\u1BF1', BidiNSM),
+    ('\u1C2C', '\u1C33', BidiNSM),
+    ('\u1C36', '\u1C37', BidiNSM),
+    ('\u1CD0', '\u1CD2', BidiNSM),
+    ('\u1CD4', '\u1CE0', BidiNSM),
+    ('\u1CE2', '\u1CE8', BidiNSM),
+    ('\u1CED', '\u1CED', BidiNSM),
+    ('\u1CF4', '\u1CF4', BidiNSM),
+    ('\u1CF8', '\u1CF9', BidiNSM),
+    ('\u1DC0', '\u1DFF', BidiNSM),
+    ('\u1FBD', '\u1FBD', BidiON),
+    ('\u1FBF', '\u1FC1', BidiON),
+    ('\u1FCD', '\u1FCF', BidiON),
+    ('\u1FDD', '\u1FDF', BidiON),
+    ('\u1FED', '\u1FEF', BidiON),
+    ('\u1FFD', '\u1FFE', BidiON),
+    ('\u2000', '\u200A', BidiWS),
+    ('\u200B', '\u200D', BidiBN),
+    ('\u200F', '\u200F', BidiR),
+    ('\u2010', '\u2027', BidiON),
+    ('\u2028', '\u2028', BidiWS),
+    ('\u2029', '\u2029', BidiB),
+    ('\u202A', '\u202A', BidiLRE),
+    ('\u202B', '\u202B', BidiRLE),
+    ('\u202C', '\u202C', BidiPDF),
+    ('\u202D', '\u202D', BidiLRO),
+    ('\u202E', '\u202E', BidiRLO),
+    ('\u202F', '\u202F', BidiCS),
+    ('\u2030', '\u2034', BidiET),
+    ('\u2035', '\u2043', BidiON),
+    ('\u2044', '\u2044', BidiCS),
+    ('\u2045', '\u205E', BidiON),
+    ('\u205F', '\u205F', BidiWS),
+    ('\u2060', '\u2065', BidiBN),
+    ('\u2066', '\u2066', BidiLRI),
+    ('\u2067', '\u2067', BidiRLI),
+    ('\u2068', '\u2068', BidiFSI),
+    ('\u2069', '\u2069', BidiPDI),
+    ('\u206A', '\u206F', BidiBN),
+    ('\u2070', '\u2070', BidiEN),
+    ('\u2074', '\u2079', BidiEN),
+    ('\u207A', '\u207B', BidiES),
+    ('\u207C', '\u207E', BidiON),
+    ('\u2080', '\u2089', BidiEN),
+    ('\u208A', '\u208B', BidiES),
+    ('\u208C', '\u208E', BidiON),
+    ('\u20A0', '\u20CF', BidiET),
+    ('\u20D0', '\u20F0', BidiNSM),
+    ('\u2100', '\u2101', BidiON),
+    ('\u2103', '\u2106', BidiON),
+    ('\u2108', '\u2109', BidiON),
+    ('\u2114', '\u2114', BidiON),
+    ('\u2116', '\u2118', BidiON),
+    ('\u211E', '\u2123', BidiON),
+    ('\u2125', '\u2125', BidiON),
+    ('\u2127', '\u2127', BidiON),
+    ('\u2129', '\u2129', BidiON),
+    ('\u212E', '\u212E', BidiET),
+    ('\u213A', '\u213B', BidiON),
+    ('\u2140', '\u2144', BidiON),
+    ('\u214A', '\u214D', BidiON),
+    ('\u2150', '\u215F', BidiON),
+    ('\u2189', '\u218B', BidiON),
+    ('\u2190', '\u2211', BidiON),
+    ('\u2212', '\u2212', BidiES),
+    ('\u2213', '\u2213', BidiET),
+    ('\u2214', '\u2335', BidiON),
+    ('\u237B', '\u2394', BidiON),
+    ('\u2396', '\u2426', BidiON),
+    ('\u2440', '\u244A', BidiON),
+    ('\u2460', '\u2487', BidiON),
+    ('\u2488', '\u249B', BidiEN),
+    ('\u24EA', '\u26AB', BidiON),
+    ('\u26AD', '\u27FF', BidiON),
+    ('\u2900', '\u2B73', BidiON),
+    ('\u2B76', '\u2B95', BidiON),
+    ('\u2B97', '\u2BFF', BidiON),
+    ('\u2CE5', '\u2CEA', BidiON),
+    ('\u2CEF', '\u2CF1', BidiNSM),
+    ('\u2CF9', '\u2CFF', BidiON),
+    ('\u2D7F', '\u2D7F', BidiNSM),
+    ('\u2DE0', '\u2DFF', BidiNSM),
+    ('\u2E00', '\u2E5D', BidiON),
+    ('\u2E80', '\u2E99', BidiON),
+    ('\u2E9B', '\u2EF3', BidiON),
+    ('\u2F00', '\u2FD5', BidiON),
+    ('\u2FF0', '\u2FFB', BidiON),
+    ('\u3000', '\u3000', BidiWS),
+    ('\u3001', '\u3004', BidiON),
+    ('\u3008', '\u3020', BidiON),
+    ('\u302A', '\u302D', BidiNSM),
+    ('\u3030', '\u3030', BidiON),
+    ('\u3036', '\u3037', BidiON),
+    ('\u303D', '\u303F', BidiON),
+    ('\u3099', '\u309A', BidiNSM),
+    ('\u309B', '\u309C', BidiON),
+    ('\u30A0', '\u30A0', BidiON),
+    ('\u30FB', '\u30FB', BidiON),
+    ('\u31C0', '\u31E3', BidiON),
+    ('\u321D', '\u321E', BidiON),
+    ('\u3250', '\u325F', BidiON),
+    ('\u327C', '\u327E', BidiON),
+    ('\u32B1', '\u32BF', BidiON),
+    ('\u32CC', '\u32CF', BidiON),
+    ('\u3377', '\u337A', BidiON),
+    ('\u33DE', '\u33DF', BidiON),
+    ('\u33FF', '\u33FF', BidiON),
+    ('\u4DC0', '\u4DFF', BidiON),
+    ('\uA490', '\uA4C6', BidiON),
+    ('\uA60D', '\uA60F', BidiON),
+    ('\uA66F', '\uA672', BidiNSM),
+    ('\uA673', '\uA673', BidiON),
+    ('\uA674', '\uA67D', BidiNSM),
+    ('\uA67E', '\uA67F', BidiON),
+    ('\uA69E', '\uA69F', BidiNSM),
+    ('\uA6F0', '\uA6F1', BidiNSM),
+    ('\uA700', '\uA721', BidiON),
+    ('\uA788', '\uA788', BidiON),
+    ('\uA802', '\uA802', BidiNSM),
+    ('\uA806', '\uA806', BidiNSM),
+    ('\uA80B', '\uA80B', BidiNSM),
+    ('\uA825', '\uA826', BidiNSM),
+    ('\uA828', '\uA82B', BidiON),
+    ('\uA82C', '\uA82C', BidiNSM),
+    ('\uA838', '\uA839', BidiET),
+    ('\uA874', '\uA877', BidiON),
+    ('\uA8C4', '\uA8C5', BidiNSM),
+    ('\uA8E0', '\uA8F1', BidiNSM),
+    ('\uA8FF', '\uA8FF', BidiNSM),
+    ('\uA926', '\uA92D', BidiNSM),
+    ('\uA947', '\uA951', BidiNSM),
+    ('\uA980', '\uA982', BidiNSM),
+    ('\uA9B3', '\uA9B3', BidiNSM),
+    ('\uA9B6', '\uA9B9', BidiNSM),
+    ('\uA9BC', '\uA9BD', BidiNSM),
+    ('\uA9E5', '\uA9E5', BidiNSM),
+    ('\uAA29', '\uAA2E', BidiNSM),
+    ('\uAA31', '\uAA32', BidiNSM),
+    ('\uAA35', '\uAA36', BidiNSM),
+    ('\uAA43', '\uAA43', BidiNSM),
+    ('\uAA4C', '\uAA4C', BidiNSM),
+    ('\uAA7C', '\uAA7C', BidiNSM),
+    ('\uAAB0', '\uAAB0', BidiNSM),
+    ('\uAAB2', '\uAAB4', BidiNSM),
+    ('\uAAB7', '\uAAB8', BidiNSM),
+    ('\uAABE', '\uAABF', BidiNSM),
+    ('\uAAC1', '\uAAC1', BidiNSM),
+    ('\uAAEC', '\uAAED', BidiNSM),
+    ('\uAAF6', '\uAAF6', BidiNSM),
+    ('\uAB6A', '\uAB6B', BidiON),
+    ('\uABE5', '\uABE5', BidiNSM),
+    ('\uABE8', '\uABE8', BidiNSM),
+    ('\uABED', '\uABED', BidiNSM),
+    ('\uFB1D', '\uFB1D', BidiR),
+    ('\uFB1E', '\uFB1E', BidiNSM),
+    ('\uFB1F', '\uFB28', BidiR),
+    ('\uFB29', '\uFB29', BidiES),
+    ('\uFB2A', '\uFB4F', BidiR),
+    ('\uFB50', '\uFD3D', BidiAL),
+    ('\uFD3E', '\uFD4F', BidiON),
+    ('\uFD50', '\uFDCE', BidiAL),
+    ('\uFDCF', '\uFDCF', BidiON),
+    ('\uFDD0', '\uFDEF', BidiBN),
+    ('\uFDF0', '\uFDFC', BidiAL),
+    ('\uFDFD', '\uFDFF', BidiON),
+    ('\uFE00', '\uFE0F', BidiNSM),
+    ('\uFE10', '\uFE19', BidiON),
+    ('\uFE20', '\uFE2F', BidiNSM),
+    ('\uFE30', '\uFE4F', BidiON),
+    ('\uFE50', '\uFE50', BidiCS),
+    ('\uFE51', '\uFE51', BidiON),
+    ('\uFE52', '\uFE52', BidiCS),
+    ('\uFE54', '\uFE54', BidiON),
+    ('\uFE55', '\uFE55', BidiCS),
+    ('\uFE56', '\uFE5E', BidiON),
+    ('\uFE5F', '\uFE5F', BidiET),
+    ('\uFE60', '\uFE61', BidiON),
+    ('\uFE62', '\uFE63', BidiES),
+    ('\uFE64', '\uFE66', BidiON),
+    ('\uFE68', '\uFE68', BidiON),
+    ('\uFE69', '\uFE6A', BidiET),
+    ('\uFE6B', '\uFE6B', BidiON),
+    ('\uFE70', '\uFEFE', BidiAL),
+    ('\uFEFF', '\uFEFF', BidiBN),
+    ('\uFF01', '\uFF02', BidiON),
+    ('\uFF03', '\uFF05', BidiET),
+    ('\uFF06', '\uFF0A', BidiON),
+    ('\uFF0B', '\uFF0B', BidiES),
+    ('\uFF0C', '\uFF0C', BidiCS),
+    ('\uFF0D', '\uFF0D', BidiES),
+    ('\uFF0E', '\uFF0F', BidiCS),
+    ('\uFF10', '\uFF19', BidiEN),
+    ('\uFF1A', '\uFF1A', BidiCS),
+    ('\uFF1B', '\uFF20', BidiON),
+    ('\uFF3B', '\uFF40', BidiON),
+    ('\uFF5B', '\uFF65', BidiON),
+    ('\uFFE0', '\uFFE1', BidiET),
+    ('\uFFE2', '\uFFE4', BidiON),
+    ('\uFFE5', '\uFFE6', BidiET),
+    ('\uFFE8', '\uFFEE', BidiON),
+    ('\uFFF0', '\uFFF8', BidiBN),
+    ('\uFFF9', '\uFFFD', BidiON),
+    ('\uFFFE', '\uFFFF', BidiBN),
+    ('\U00010101', '\U00010101', BidiON),
+    ('\U00010140', '\U0001018C', BidiON),
+    ('\U00010190', '\U0001019C', BidiON),
+    ('\U000101A0', '\U000101A0', BidiON),
+    ('\U000101FD', '\U000101FD', BidiNSM),
+    ('\U000102E0', '\U000102E0', BidiNSM),
+    ('\U000102E1', '\U000102FB', BidiEN),
+    ('\U00010376', '\U0001037A', BidiNSM),
+    ('\U00010800', '\U0001091E', BidiR),
+    ('\U0001091F', '\U0001091F', BidiON),
+    ('\U00010920', '\U00010A00', BidiR),
+    ('\U00010A01', '\U00010A03', BidiNSM),
+    ('\U00010A04', '\U00010A04', BidiR),
+    ('\U00010A05', '\U00010A06', BidiNSM),
+    ('\U00010A07', '\U00010A0B', BidiR),
+    ('\U00010A0C', '\U00010A0F', BidiNSM),
+    ('\U00010A10', '\U00010A37', BidiR),
+    ('\U00010A38', '\U00010A3A', BidiNSM),
+    ('\U00010A3B', '\U00010A3E', BidiR),
+    ('\U00010A3F', '\U00010A3F', BidiNSM),
+    ('\U00010A40', '\U00010AE4', BidiR),
+    ('\U00010AE5', '\U00010AE6', BidiNSM),
+    ('\U00010AE7', '\U00010B38', BidiR),
+    ('\U00010B39', '\U00010B3F', BidiON),
+    ('\U00010B40', '\U00010CFF', BidiR),
+    ('\U00010D00', '\U00010D23', BidiAL),
+    ('\U00010D24', '\U00010D27', BidiNSM),
+    ('\U00010D28', '\U00010D2F', BidiAL),
+    ('\U00010D30', '\U00010D39', BidiAN),
+    ('\U00010D3A', '\U00010D3F', BidiAL),
+    ('\U00010D40', '\U00010E5F', BidiR),
+    ('\U00010E60', '\U00010E7E', BidiAN),
+    ('\U00010E7F', '\U00010EAA', BidiR),
+    ('\U00010EAB', '\U00010EAC', BidiNSM),
+    ('\U00010EAD', '\U00010F2F', BidiR),
+    ('\U00010F30', '\U00010F45', BidiAL),
+    ('\U00010F46', '\U00010F50', BidiNSM),
+    ('\U00010F51', '\U00010F6F', BidiAL),
+    ('\U00010F70', '\U00010F81', BidiR),
+    ('\U00010F82', '\U00010F85', BidiNSM),
+    ('\U00010F86', '\U00010FFF', BidiR),
+    ('\U00011001', '\U00011001', BidiNSM),
+    ('\U00011038', '\U00011046', BidiNSM),
+    ('\U00011052', '\U00011065', BidiON),
+    ('\U00011070', '\U00011070', BidiNSM),
+    ('\U00011073', '\U00011074', BidiNSM),
+    ('\U0001107F', '\U00011081', BidiNSM),
+    ('\U000110B3', '\U000110B6', BidiNSM),
+    ('\U000110B9', '\U000110BA', BidiNSM),
+    ('\U000110C2', '\U000110C2', BidiNSM),
+    ('\U00011100', '\U00011102', BidiNSM),
+    ('\U00011127', '\U0001112B', BidiNSM),
+    ('\U0001112D', '\U00011134', BidiNSM),
+    ('\U00011173', '\U00011173', BidiNSM),
+    ('\U00011180', '\U00011181', BidiNSM),
+    ('\U000111B6', '\U000111BE', BidiNSM),
+    ('\U000111C9', '\U000111CC', BidiNSM),
+    ('\U000111CF', '\U000111CF', BidiNSM),
+    ('\U0001122F', '\U00011231', BidiNSM),
+    ('\U00011234', '\U00011234', BidiNSM),
+    ('\U00011236', '\U00011237', BidiNSM),
+    ('\U0001123E', '\U0001123E', BidiNSM),
+    ('\U000112DF', '\U000112DF', BidiNSM),
+    ('\U000112E3', '\U000112EA', BidiNSM),
+    ('\U00011300', '\U00011301', BidiNSM),
+    ('\U0001133B', '\U0001133C', BidiNSM),
+    ('\U00011340', '\U00011340', BidiNSM),
+    ('\U00011366', '\U0001136C', BidiNSM),
+    ('\U00011370', '\U00011374', BidiNSM),
+    ('\U00011438', '\U0001143F', BidiNSM),
+    ('\U00011442', '\U00011444', BidiNSM),
+    ('\U00011446', '\U00011446', BidiNSM),
+    ('\U0001145E', '\U0001145E', BidiNSM),
+    ('\U000114B3', '\U000114B8', BidiNSM),
+    ('\U000114BA', '\U000114BA', BidiNSM),
+    ('\U000114BF', '\U000114C0', BidiNSM),
+    ('\U000114C2', '\U000114C3', BidiNSM),
+    ('\U000115B2', '\U000115B5', BidiNSM),
+    ('\U000115BC', '\U000115BD', BidiNSM),
+    ('\U000115BF', '\U000115C0', BidiNSM),
+    ('\U000115DC', '\U000115DD', BidiNSM),
+    ('\U00011633', '\U0001163A', BidiNSM),
+    ('\U0001163D', '\U0001163D', BidiNSM),
+    ('\U0001163F', '\U00011640', BidiNSM),
+    ('\U00011660', '\U0001166C', BidiON),
+    ('\U000116AB', '\U000116AB', BidiNSM),
+    ('\U000116AD', '\U000116AD', BidiNSM),
+    ('\U000116B0', '\U000116B5', BidiNSM),
+    ('\U000116B7', '\U000116B7', BidiNSM),
+    ('\U0001171D', '\U0001171F', BidiNSM),
+    ('\U00011722', '\U00011725', BidiNSM),
+    ('\U00011727', '\U0001172B', BidiNSM),
+    ('\U0001182F', '\U00011837', BidiNSM),
+    ('\U00011839', '\U0001183A', BidiNSM),
+    ('\U0001193B', '\U0001193C', BidiNSM),
+    ('\U0001193E', '\U0001193E', BidiNSM),
+    ('\U00011943', '\U00011943', BidiNSM),
+    ('\U000119D4', '\U000119D7', BidiNSM),
+    ('\U000119DA', '\U000119DB', BidiNSM),
+    ('\U000119E0', '\U000119E0', BidiNSM),
+    ('\U00011A01', '\U00011A06', BidiNSM),
+    ('\U00011A09', '\U00011A0A', BidiNSM),
+    ('\U00011A33', '\U00011A38', BidiNSM),
+    ('\U00011A3B', '\U00011A3E', BidiNSM),
+    ('\U00011A47', '\U00011A47', BidiNSM),
+    ('\U00011A51', '\U00011A56', BidiNSM),
+    ('\U00011A59', '\U00011A5B', BidiNSM),
+    ('\U00011A8A', '\U00011A96', BidiNSM),
+    ('\U00011A98', '\U00011A99', BidiNSM),
+    ('\U00011C30', '\U00011C36', BidiNSM),
+    ('\U00011C38', '\U00011C3D', BidiNSM),
+    ('\U00011C92', '\U00011CA7', BidiNSM),
+    ('\U00011CAA', '\U00011CB0', BidiNSM),
+    ('\U00011CB2', '\U00011CB3', BidiNSM),
+    ('\U00011CB5', '\U00011CB6', BidiNSM),
+    ('\U00011D31', '\U00011D36', BidiNSM),
+    ('\U00011D3A', '\U00011D3A', BidiNSM),
+    ('\U00011D3C', '\U00011D3D', BidiNSM),
+    ('\U00011D3F', '\U00011D45', BidiNSM),
+    ('\U00011D47', '\U00011D47', BidiNSM),
+    ('\U00011D90', '\U00011D91', BidiNSM),
+    ('\U00011D95', '\U00011D95', BidiNSM),
+    ('\U00011D97', '\U00011D97', BidiNSM),
+    ('\U00011EF3', '\U00011EF4', BidiNSM),
+    ('\U00011FD5', '\U00011FDC', BidiON),
+    ('\U00011FDD', '\U00011FE0', BidiET),
+    ('\U00011FE1', '\U00011FF1', BidiON),
+    ('\U00016AF0', '\U00016AF4', BidiNSM),
+    ('\U00016B30', '\U00016B36', BidiNSM),
+    ('\U00016F4F', '\U00016F4F', BidiNSM),
+    ('\U00016F8F', '\U00016F92', BidiNSM),
+    ('\U00016FE2', '\U00016FE2', BidiON),
+    ('\U00016FE4', '\U00016FE4', BidiNSM),
+    ('\U0001BC9D', '\U0001BC9E', BidiNSM),
+    ('\U0001BCA0', '\U0001BCA3', BidiBN),
+    ('\U0001CF00', '\U0001CF2D', BidiNSM),
+    ('\U0001CF30', '\U0001CF46', BidiNSM),
+    ('\U0001D167', '\U0001D169', BidiNSM),
+    ('\U0001D173', '\U0001D17A', BidiBN),
+    ('\U0001D17B', '\U0001D182', BidiNSM),
+    ('\U0001D185', '\U0001D18B', BidiNSM),
+    ('\U0001D1AA', '\U0001D1AD', BidiNSM),
+    ('\U0001D1E9', '\U0001D1EA', BidiON),
+    ('\U0001D200', '\U0001D241', BidiON),
+    ('\U0001D242', '\U0001D244', BidiNSM),
+    ('\U0001D245', '\U0001D245', BidiON),
+    ('\U0001D300', '\U0001D356', BidiON),
+    ('\U0001D6DB', '\U0001D6DB', BidiON),
+    ('\U0001D715', '\U0001D715', BidiON),
+    ('\U0001D74F', '\U0001D74F', BidiON),
+    ('\U0001D789', '\U0001D789', BidiON),
+    ('\U0001D7C3', '\U0001D7C3', BidiON),
+    ('\U0001D7CE', '\U0001D7FF', BidiEN),
+    ('\U0001DA00', '\U0001DA36', BidiNSM),
+    ('\U0001DA3B', '\U0001DA6C', BidiNSM),
+    ('\U0001DA75', '\U0001DA75', BidiNSM),
+    ('\U0001DA84', '\U0001DA84', BidiNSM),
+    ('\U0001DA9B', '\U0001DA9F', BidiNSM),
+    ('\U0001DAA1', '\U0001DAAF', BidiNSM),
+    ('\U0001E000', '\U0001E006', BidiNSM),
+    ('\U0001E008', '\U0001E018', BidiNSM),
+    ('\U0001E01B', '\U0001E021', BidiNSM),
+    ('\U0001E023', '\U0001E024', BidiNSM),
+    ('\U0001E026', '\U0001E02A', BidiNSM),
+    ('\U0001E130', '\U0001E136', BidiNSM),
+    ('\U0001E2AE', '\U0001E2AE', BidiNSM),
+    ('\U0001E2EC', '\U0001E2EF', BidiNSM),
+    ('\U0001E2FF', '\U0001E2FF', BidiET),
+    ('\U0001E800', '\U0001E8CF', BidiR),
+    ('\U0001E8D0', '\U0001E8D6', BidiNSM),
+    ('\U0001E8D7', '\U0001E943', BidiR),
+    ('\U0001E944', '\U0001E94A', BidiNSM),
+    ('\U0001E94B', '\U0001EC6F', BidiR),
+    ('\U0001EC70', '\U0001ECBF', BidiAL),
+    ('\U0001ECC0', '\U0001ECFF', BidiR),
+    ('\U0001ED00', '\U0001ED4F', BidiAL),
+    ('\U0001ED50', '\U0001EDFF', BidiR),
+    ('\U0001EE00', '\U0001EEEF', BidiAL),
+    ('\U0001EEF0', '\U0001EEF1', BidiON),
+    ('\U0001EEF2', '\U0001EEFF', BidiAL),
+    ('\U0001EF00', '\U0001EFFF', BidiR),
+    ('\U0001F000', '\U0001F02B', BidiON),
+    ('\U0001F030', '\U0001F093', BidiON),
+    ('\U0001F0A0', '\U0001F0AE', BidiON),
+    ('\U0001F0B1', '\U0001F0BF', BidiON),
+    ('\U0001F0C1', '\U0001F0CF', BidiON),
+    ('\U0001F0D1', '\U0001F0F5', BidiON),
+    ('\U0001F100', '\U0001F10A', BidiEN),
+    ('\U0001F10B', '\U0001F10F', BidiON),
+    ('\U0001F12F', '\U0001F12F', BidiON),
+    ('\U0001F16A', '\U0001F16F', BidiON),
+    ('\U0001F1AD', '\U0001F1AD', BidiON),
+    ('\U0001F260', '\U0001F265', BidiON),
+    ('\U0001F300', '\U0001F6D7', BidiON),
+    ('\U0001F6DD', '\U0001F6EC', BidiON),
+    ('\U0001F6F0', '\U0001F6FC', BidiON),
+    ('\U0001F700', '\U0001F773', BidiON),
+    ('\U0001F780', '\U0001F7D8', BidiON),
+    ('\U0001F7E0', '\U0001F7EB', BidiON),
+    ('\U0001F7F0', '\U0001F7F0', BidiON),
+    ('\U0001F800', '\U0001F80B', BidiON),
+    ('\U0001F810', '\U0001F847', BidiON),
+    ('\U0001F850', '\U0001F859', BidiON),
+    ('\U0001F860', '\U0001F887', BidiON),
+    ('\U0001F890', '\U0001F8AD', BidiON),
+    ('\U0001F8B0', '\U0001F8B1', BidiON),
+    ('\U0001F900', '\U0001FA53', BidiON),
+    ('\U0001FA60', '\U0001FA6D', BidiON),
+    ('\U0001FA70', '\U0001FA74', BidiON),
+    ('\U0001FA78', '\U0001FA7C', BidiON),
+    ('\U0001FA80', '\U0001FA86', BidiON),
+    ('\U0001FA90', '\U0001FAAC', BidiON),
+    ('\U0001FAB0', '\U0001FABA', BidiON),
+    ('\U0001FAC0', '\U0001FAC5', BidiON),
+    ('\U0001FAD0', '\U0001FAD9', BidiON),
+    ('\U0001FAE0', '\U0001FAE7', BidiON),
+    ('\U0001FAF0', '\U0001FAF6', BidiON),
+    ('\U0001FB00', '\U0001FB92', BidiON),
+    ('\U0001FB94', '\U0001FBCA', BidiON),
+    ('\U0001FBF0', '\U0001FBF9', BidiEN),
+    ('\U0001FFFE', '\U0001FFFF', BidiBN),
+    ('\U0002FFFE', '\U0002FFFF', BidiBN),
+    ('\U0003FFFE', '\U0003FFFF', BidiBN),
+    ('\U0004FFFE', '\U0004FFFF', BidiBN),
+    ('\U0005FFFE', '\U0005FFFF', BidiBN),
+    ('\U0006FFFE', '\U0006FFFF', BidiBN),
+    ('\U0007FFFE', '\U0007FFFF', BidiBN),
+    ('\U0008FFFE', '\U0008FFFF', BidiBN),
+    ('\U0009FFFE', '\U0009FFFF', BidiBN),
+    ('\U000AFFFE', '\U000AFFFF', BidiBN),
+    ('\U000BFFFE', '\U000BFFFF', BidiBN),
+    ('\U000CFFFE', '\U000CFFFF', BidiBN),
+    ('\U000DFFFE', '\U000E00FF', BidiBN),
+    ('\U000E0100', '\U000E01EF', BidiNSM),
+    ('\U000E01F0', '\U000E0FFF', BidiBN),
+    ('\U000EFFFE', '\U000EFFFF', BidiBN),
+    ('\U000FFFFE', '\U000FFFFF', BidiBN),
+    ('\U0010FFFE', '\U0010FFFF', BidiBN),
+];
+
+#[test]
+fn test_bidi_class() {
+    assert!(bidi_class('a') == BidiL);
+    assert!(bidi_class('א') == BidiR);
+    assert!(bidi_class('ع') == BidiAL);
+    assert!(bidi_class('1') == BidiEN);
+    assert!(bidi_class('١') == BidiAN);
+    assert!(bidi_class(' ') == BidiWS);
+    assert!(bidi_class('\u0301') == BidiNSM);
+    assert!(bidi_class(RLO) == BidiRLO);
+    assert!(bidi_class('漢') == BidiL);
+}
+
+#[test]
+fn test_resolve_levels() {
+    assert!(resolve_levels("abc", 0) == ~[0, 0, 0]);
+    assert!(resolve_levels("אבג", 0) == ~[1, 1, 1]);
+    // Numbers in right-to-left text.
+    assert!(resolve_levels("car אבג 123 xyz", 0) ==
+            ~[0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 0, 0, 0, 0]);
+    assert!(resolve_levels("عرب 123", 0) == ~[1, 1, 1, 1, 2, 2, 2]);
+    assert!(resolve_levels("1.5 אב", 1) == ~[2, 2, 2, 1, 1, 1]);
+    assert!(resolve_levels("$10 אב", 0) == ~[0, 0, 0, 0, 1, 1]);
+    // Neutrals between text of different directions take the paragraph direction.
+    assert!(resolve_levels("אב, גד.", 0) == ~[1, 1, 1, 1, 1, 1, 0]);
+    assert!(resolve_levels("abc!", 1) == ~[2, 2, 2, 1]);
+    // Trailing whitespace and segment separators are at the paragraph level.
+    assert!(resolve_levels("a\tאב\tc ", 1) == ~[2, 1, 1, 1, 1, 2, 1]);
+}
+
+#[test]
+fn test_explicit_embeddings() {
+    let text = fmt!("a%cb%cc", RLE, PDF);
+    assert!(resolve_levels(text, 0) == ~[0, 0, 2, 2, 0]);
+    let text = fmt!("%cabc%c", RLO, PDF);
+    assert!(resolve_levels(text, 0) == ~[0, 1, 1, 1, 0]);
+}
+
+#[test]
+fn test_reorder_visually() {
+    assert!(reorder_visually([]) == ~[]);
+    assert!(reorder_visually([0, 0, 1, 1, 1, 0]) == ~[0, 1, 4, 3, 2, 5]);
+    assert!(reorder_visually([1, 1, 2, 2, 1]) == ~[4, 2, 3, 1, 0]);
+    assert!(reorder_visually([0, 1, 2, 2, 1, 0]) == ~[0, 4, 2, 3, 1, 5]);
+    let levels = resolve_levels("car אבג 123 xyz", 0);
+    assert!(reorder_visually(levels) == ~[0, 1, 2, 3, 8, 9, 10, 7, 6, 5, 4, 11, 12, 13, 14]);
+}
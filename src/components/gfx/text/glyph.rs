@@ -13,6 +13,7 @@ use std::num::NumCast;
 use std::u16;
 use std::vec;
 use std::uint;
+use std::sys;
 use std::util;
 use geom::point::Point2D;
 use extra::sort;
@@ -88,7 +89,7 @@ impl GlyphEntry {
 pub type GlyphIndex = u32;
 
 // TODO: unify with bit flags?
-#[deriving(Eq)]
+#[deriving(Clone, Eq, IterBytes)]
 pub enum BreakType {
     BreakTypeNone,
     BreakTypeNormal,
@@ -316,6 +317,11 @@ impl<'self> DetailedGlyphStore {
         }
     }
 
+    fn heap_size(&self) -> uint {
+        self.detail_buffer.len() * sys::size_of::<DetailedGlyph>() +
+            self.detail_lookup.len() * sys::size_of::<DetailedGlyphRecord>()
+    }
+
     fn add_detailed_glyphs_for_entry(&mut self, entry_offset: uint, glyphs: &[DetailedGlyph]) {
         let entry = DetailedGlyphRecord {
             entry_offset: entry_offset,
@@ -531,6 +537,11 @@ impl<'self> GlyphStore {
         self.is_whitespace
     }
 
+    /// An estimate of the memory used by this glyph store, in bytes.
+    pub fn heap_size(&self) -> uint {
+        self.entry_buffer.len() * sys::size_of::<GlyphEntry>() + self.detail_store.heap_size()
+    }
+
     pub fn finalize_changes(&mut self) {
         self.detail_store.ensure_sorted();
     }
@@ -0,0 +1,239 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Automatic hyphenation, using Frank Liang's algorithm with the pattern dictionaries made for
+//! TeX.
+//!
+//! A dictionary file holds whitespace-separated patterns, such as `hy3ph` and `.ach4`, and
+//! exceptions, which are words with their hyphens in place, such as `ta-ble`. A `%` starts a
+//! comment that runs to the end of the line.
+
+use std::ascii::StrAsciiExt;
+use std::char;
+use std::hashmap::HashMap;
+use std::io;
+use std::path::Path;
+use std::str;
+use std::uint;
+use std::vec;
+
+/// The fewest characters that hyphenation leaves before the first hyphen of a word.
+static LEFT_HYPHEN_MIN: uint = 2;
+/// The fewest characters that hyphenation leaves after the last hyphen of a word.
+static RIGHT_HYPHEN_MIN: uint = 3;
+
+/// The hyphenation dictionary of one language.
+pub struct Hyphenator {
+    /// The priorities of the patterns, by the letters of the pattern. A pattern of n letters has
+    /// n + 1 priorities, one for each place between and around its letters.
+    priv patterns: HashMap<~str, ~[u8]>,
+    /// The hyphens of the exceptions, by word.
+    priv exceptions: HashMap<~str, ~[uint]>,
+    /// The length, in characters, of the longest pattern.
+    priv max_pattern_len: uint,
+}
+
+impl Hyphenator {
+    /// Reads a dictionary from the given file.
+    pub fn load(path: &str) -> Result<Hyphenator, ~str> {
+        match io::read_whole_file_str(&Path(path)) {
+            Ok(source) => Hyphenator::parse(source),
+            Err(message) => Err(fmt!("couldn't read hyphenation dictionary %s: %s",
+                                     path, message)),
+        }
+    }
+
+    /// Parses a dictionary in the format of a dictionary file.
+    pub fn parse(source: &str) -> Result<Hyphenator, ~str> {
+        let mut hyphenator = Hyphenator {
+            patterns: HashMap::new(),
+            exceptions: HashMap::new(),
+            max_pattern_len: 0,
+        };
+
+        for source.any_line_iter().advance |line| {
+            let line = match line.find('%') {
+                Some(i) => line.slice_to(i),
+                None => line,
+            };
+            for line.word_iter().advance |token| {
+                if token.contains_char('-') {
+                    hyphenator.add_exception(token);
+                } else {
+                    if !hyphenator.add_pattern(token) {
+                        return Err(fmt!("bad hyphenation pattern `%s`", token));
+                    }
+                }
+            }
+        }
+        Ok(hyphenator)
+    }
+
+    priv fn add_pattern(&mut self, pattern: &str) -> bool {
+        let mut letters = ~"";
+        let mut priorities = ~[0u8];
+        for pattern.iter().advance |ch| {
+            match char::to_digit(ch, 10) {
+                Some(digit) => {
+                    let last = priorities.len() - 1;
+                    priorities[last] = digit as u8;
+                }
+                None => {
+                    letters.push_char(ch);
+                    priorities.push(0);
+                }
+            }
+        }
+        if letters.is_empty() {
+            return false;
+        }
+
+        let len = letters.char_len();
+        if len > self.max_pattern_len {
+            self.max_pattern_len = len;
+        }
+        self.patterns.insert(letters.to_ascii_lower(), priorities);
+        true
+    }
+
+    priv fn add_exception(&mut self, exception: &str) {
+        let mut word = ~"";
+        let mut hyphens = ~[];
+        let mut len = 0;
+        for exception.iter().advance |ch| {
+            if ch == '-' {
+                hyphens.push(len);
+            } else {
+                word.push_char(ch);
+                len += 1;
+            }
+        }
+        self.exceptions.insert(word.to_ascii_lower(), hyphens);
+    }
+
+    /// Returns the character indices in the given word before which it may be hyphenated, in
+    /// increasing order.
+    pub fn hyphenate(&self, word: &str) -> ~[uint] {
+        let lower_word = word.to_ascii_lower();
+        let len = lower_word.char_len();
+        if len < LEFT_HYPHEN_MIN + RIGHT_HYPHEN_MIN {
+            return ~[];
+        }
+        match self.exceptions.find(&lower_word) {
+            Some(hyphens) => return copy *hyphens,
+            None => {}
+        }
+
+        // Find every pattern in the word, marked with periods at each end. The priority of each
+        // place in the word is the highest given to it by any pattern; odd priorities allow a
+        // hyphen there.
+        let mut marked_word = ~".";
+        marked_word.push_str(lower_word);
+        marked_word.push_char('.');
+        let marked: ~[char] = marked_word.iter().collect();
+        let mut priorities = vec::from_elem(marked.len() + 1, 0u8);
+        for uint::range(0, marked.len()) |start| {
+            let max_end = uint::min(marked.len(), start + self.max_pattern_len);
+            for uint::range(start + 1, max_end + 1) |end| {
+                let letters = str::from_chars(marked.slice(start, end));
+                match self.patterns.find(&letters) {
+                    Some(pattern_priorities) => {
+                        for pattern_priorities.iter().enumerate().advance |(i, &priority)| {
+                            if priority > priorities[start + i] {
+                                priorities[start + i] = priority;
+                            }
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        // The place before character i of the word is place i + 1 of the marked word.
+        let mut hyphens = ~[];
+        for uint::range(LEFT_HYPHEN_MIN, len - RIGHT_HYPHEN_MIN + 1) |i| {
+            if priorities[i + 1] % 2 == 1 {
+                hyphens.push(i);
+            }
+        }
+        hyphens
+    }
+}
+
+/// The hyphenation dictionaries of the languages met so far, loaded on demand from a directory
+/// holding a file named `<language>.pat` for each language, such as `en-us.pat`.
+pub struct HyphenationDictionaries {
+    priv directory: Option<~str>,
+    /// The dictionary of each language asked for, or None if there is no dictionary for it.
+    priv dictionaries: HashMap<~str, Option<@Hyphenator>>,
+}
+
+impl HyphenationDictionaries {
+    /// Creates dictionaries that are loaded from the given directory, or that are all missing if
+    /// there is none.
+    pub fn new(directory: Option<~str>) -> HyphenationDictionaries {
+        HyphenationDictionaries {
+            directory: directory,
+            dictionaries: HashMap::new(),
+        }
+    }
+
+    /// Returns the dictionary for the given language, or failing that for its primary language
+    /// (`en` for `en-GB`), if there is one.
+    pub fn get(&mut self, lang: &str) -> Option<@Hyphenator> {
+        let lang = lang.to_ascii_lower();
+        match self.get_exact(lang) {
+            Some(hyphenator) => Some(hyphenator),
+            None => {
+                let primary_lang = lang.split_iter('-').next().get();
+                if primary_lang.len() == lang.len() {
+                    None
+                } else {
+                    self.get_exact(primary_lang)
+                }
+            }
+        }
+    }
+
+    priv fn get_exact(&mut self, lang: &str) -> Option<@Hyphenator> {
+        match self.dictionaries.find_equiv(&lang) {
+            Some(hyphenator) => return *hyphenator,
+            None => {}
+        }
+
+        let hyphenator = match self.directory {
+            None => None,
+            Some(ref directory) => {
+                let path = fmt!("%s/%s.pat", *directory, lang);
+                match Hyphenator::load(path) {
+                    Ok(hyphenator) => Some(@hyphenator),
+                    Err(message) => {
+                        debug!("no hyphenation for `%s`: %s", lang, message);
+                        None
+                    }
+                }
+            }
+        };
+        self.dictionaries.insert(lang.to_owned(), hyphenator);
+        hyphenator
+    }
+}
+
+#[test]
+fn test_hyphenate_with_patterns() {
+    let hyphenator = Hyphenator::parse("% Knuth's example\n\
+                                        hy3ph he2n hena4 hen5at\n\
+                                        1na n2at 1tio 2io o2n\n").unwrap();
+    assert_eq!(hyphenator.hyphenate("hyphenation"), ~[2, 6]);
+    assert_eq!(hyphenator.hyphenate("Hyphenation"), ~[2, 6]);
+    // Words too short to leave enough characters on each side of a hyphen aren't hyphenated.
+    assert!(hyphenator.hyphenate("hyph").is_empty());
+}
+
+#[test]
+fn test_hyphenate_exceptions() {
+    let hyphenator = Hyphenator::parse("1na\nta-ble\n").unwrap();
+    assert_eq!(hyphenator.hyphenate("table"), ~[2]);
+    assert!(Hyphenator::parse("12").is_err());
+}
@@ -0,0 +1,3051 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The Unicode line breaking algorithm, as described in Unicode Standard Annex #14 for Unicode
+//! 17.0.0.
+//!
+//! Complex-context (SA) characters are treated as alphabetic, since breaking them properly needs
+//! a dictionary.
+
+use std::uint;
+
+/// The line breaking class of a character, as given by the `Line_Break` property in the Unicode
+/// Character Database. See UAX #14, section 5.1 for the meaning of each class.
+#[deriving(Eq)]
+pub enum LineBreakClass {
+    ClassBK,    // Mandatory break
+    ClassCR,    // Carriage return
+    ClassLF,    // Line feed
+    ClassCM,    // Combining mark
+    ClassNL,    // Next line
+    ClassWJ,    // Word joiner
+    ClassZW,    // Zero width space
+    ClassGL,    // Non-breaking ("glue")
+    ClassSP,    // Space
+    ClassZWJ,   // Zero width joiner
+    ClassB2,    // Break opportunity before and after
+    ClassBA,    // Break after
+    ClassBB,    // Break before
+    ClassHY,    // Hyphen
+    ClassHH,    // Unambiguous hyphen
+    ClassCB,    // Contingent break opportunity
+    ClassCL,    // Close punctuation
+    ClassCP,    // Close parenthesis
+    ClassEX,    // Exclamation/interrogation
+    ClassIN,    // Inseparable
+    ClassNS,    // Nonstarter
+    ClassOP,    // Open punctuation
+    ClassQU,    // Quotation
+    ClassIS,    // Infix numeric separator
+    ClassNU,    // Numeric
+    ClassPO,    // Postfix numeric
+    ClassPR,    // Prefix numeric
+    ClassSY,    // Symbols allowing break after
+    ClassAI,    // Ambiguous (alphabetic or ideographic)
+    ClassAK,    // Aksara
+    ClassAL,    // Alphabetic
+    ClassAP,    // Aksara pre-base
+    ClassAS,    // Aksara start
+    ClassCJ,    // Conditional Japanese starter
+    ClassEB,    // Emoji base
+    ClassEM,    // Emoji modifier
+    ClassH2,    // Hangul LV syllable
+    ClassH3,    // Hangul LVT syllable
+    ClassHL,    // Hebrew letter
+    ClassID,    // Ideographic
+    ClassJL,    // Hangul L jamo
+    ClassJV,    // Hangul V jamo
+    ClassJT,    // Hangul T jamo
+    ClassRI,    // Regional indicator
+    ClassSA,    // Complex context dependent (South East Asian)
+    ClassVF,    // Virama final
+    ClassVI,    // Virama
+    ClassXX,    // Unknown
+}
+
+/// Whether the line may be broken before a character.
+#[deriving(Eq)]
+pub enum BreakOpportunity {
+    NoBreak,
+    AllowedBreak,
+    /// The line must be broken here, as after a newline.
+    MandatoryBreak,
+}
+
+/// Returns the line breaking class of the given character.
+pub fn line_break_class(ch: char) -> LineBreakClass {
+    // Hangul syllables alternate between LV and LVT syllables, so they are not in the table.
+    if ch >= HANGUL_SYLLABLE_FIRST && ch <= HANGUL_SYLLABLE_LAST {
+        return if (ch as uint - HANGUL_SYLLABLE_FIRST as uint) % HANGUL_T_COUNT == 0 {
+            ClassH2
+        } else {
+            ClassH3
+        }
+    }
+
+    let mut low = 0;
+    let mut high = LINE_BREAK_TABLE.len();
+    while low < high {
+        let mid = (low + high) / 2;
+        let (first, last, class) = LINE_BREAK_TABLE[mid];
+        if ch < first {
+            high = mid;
+        } else if ch > last {
+            low = mid + 1;
+        } else {
+            return class;
+        }
+    }
+    ClassXX
+}
+
+/// Finds the line break opportunities in the given text. The result has one entry per character,
+/// saying whether the line may be broken before that character. The first entry is always
+/// `NoBreak`; whether the line may be broken at the start of the text depends on what precedes
+/// it.
+pub fn find_break_opportunities(text: &str) -> ~[BreakOpportunity] {
+    let mut chars = ~[];
+    let mut classes = ~[];
+    for text.iter().advance |ch: char| {
+        chars.push(ch);
+        classes.push(resolve_class(line_break_class(ch)));
+    }
+
+    let mut opportunities = ~[];
+    if chars.is_empty() {
+        return opportunities;
+    }
+
+    // LB2: Never break at the start of text.
+    opportunities.push(NoBreak);
+    let mut state = LineBreakState::new(chars[0], classes[0]);
+    for uint::range(1, chars.len()) |i| {
+        opportunities.push(state.break_before(chars, classes, i));
+        state.advance(chars[i], classes[i]);
+    }
+    opportunities
+}
+
+/// LB1: Resolves the classes whose behavior the algorithm leaves to the implementation. Complex
+/// context characters that are combining marks have class CM in the table already.
+fn resolve_class(class: LineBreakClass) -> LineBreakClass {
+    match class {
+        ClassAI | ClassSA | ClassXX => ClassAL,
+        ClassCJ => ClassNS,
+        _ => class
+    }
+}
+
+/// Returns the index of the first character at or after `i` that is not a combining mark, for
+/// the rules that look ahead past the character being considered.
+fn next_base(classes: &[LineBreakClass], i: uint) -> Option<uint> {
+    let mut i = i;
+    while i < classes.len() {
+        match classes[i] {
+            ClassCM | ClassZWJ => i += 1,
+            _ => return Some(i),
+        }
+    }
+    None
+}
+
+/// Whether `ch` is in one of the sorted, non-overlapping inclusive ranges of `ranges`.
+fn in_ranges(ranges: &[(char, char)], ch: char) -> bool {
+    let mut low = 0;
+    let mut high = ranges.len();
+    while low < high {
+        let mid = (low + high) / 2;
+        let (first, last) = ranges[mid];
+        if ch < first {
+            high = mid;
+        } else if ch > last {
+            low = mid + 1;
+        } else {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether a character is East Asian fullwidth, wide or halfwidth, for rules LB19a and LB30.
+fn is_east_asian(ch: char) -> bool {
+    in_ranges(EAST_ASIAN_TABLE, ch)
+}
+
+/// Whether a quotation mark is an initial punctuation mark (general category Pi).
+fn is_initial_quote(ch: char) -> bool {
+    in_ranges(INITIAL_QUOTE_TABLE, ch)
+}
+
+/// Whether a quotation mark is a final punctuation mark (general category Pf).
+fn is_final_quote(ch: char) -> bool {
+    in_ranges(FINAL_QUOTE_TABLE, ch)
+}
+
+/// Whether a character is an unassigned code point reserved for pictographs, for rule LB30b.
+fn is_unassigned_pictographic(ch: char) -> bool {
+    in_ranges(UNASSIGNED_PICTOGRAPHIC_TABLE, ch)
+}
+
+/// Whether a character may start an orthographic syllable in rule LB28a: an aksara, an aksara
+/// start, or the dotted circle that stands in for a missing base.
+fn is_aksara(ch: char, class: LineBreakClass) -> bool {
+    class == ClassAK || class == ClassAS || ch == DOTTED_CIRCLE
+}
+
+/// Where we are in a number, for rule LB25.
+#[deriving(Eq)]
+enum NumericState {
+    NotInNumber,
+    /// After `NU (SY | IS)*`.
+    InNumber,
+    /// After `NU (SY | IS)* (CL | CP)`.
+    AfterNumberClose,
+}
+
+/// The context the pair rules need about the characters already seen.
+struct LineBreakState {
+    /// The class of the previous character, after combining marks have been attached to their
+    /// base character (LB9) and the remaining ones treated as alphabetic (LB10).
+    prev: LineBreakClass,
+    /// The previous character, or the base character the previous combining marks attach to.
+    prev_ch: char,
+    /// The class of the character before the previous one, likewise resolved.
+    prev_prev: Option<LineBreakClass>,
+    prev_prev_ch: Option<char>,
+    /// The class of the last character that was not a space.
+    last_non_space: Option<LineBreakClass>,
+    /// Whether the last character that was not a space is an initial quotation mark that opens a
+    /// quotation (LB15a).
+    in_opening_quote: bool,
+    /// The class of the previous character before LB9 and LB10 were applied.
+    prev_unresolved: LineBreakClass,
+    /// The number of regional indicators immediately before this point.
+    regional_indicator_count: uint,
+    numeric_state: NumericState,
+    /// Whether the previous two characters are an aksara followed by a virama (LB28a).
+    after_aksara_virama: bool,
+}
+
+impl LineBreakState {
+    fn new(first_ch: char, first: LineBreakClass) -> LineBreakState {
+        let mut state = LineBreakState {
+            prev: ClassXX,
+            prev_ch: first_ch,
+            prev_prev: None,
+            prev_prev_ch: None,
+            last_non_space: None,
+            in_opening_quote: false,
+            prev_unresolved: first,
+            regional_indicator_count: 0,
+            numeric_state: NotInNumber,
+            after_aksara_virama: false,
+        };
+        // LB10 applies to a combining mark at the start of the text.
+        state.push(first_ch, match first {
+            ClassCM | ClassZWJ => ClassAL,
+            _ => first
+        }, true);
+        // There is nothing before the first character.
+        state.prev_prev = None;
+        state.prev_prev_ch = None;
+        state
+    }
+
+    /// Whether a combining mark or zero width joiner of class `class` attaches to the previous
+    /// character (LB9).
+    fn attaches_to_prev(&self, class: LineBreakClass) -> bool {
+        match (class, self.prev) {
+            (ClassCM, ClassBK) | (ClassCM, ClassCR) | (ClassCM, ClassLF) | (ClassCM, ClassNL) |
+            (ClassCM, ClassSP) | (ClassCM, ClassZW) => false,
+            (ClassZWJ, ClassBK) | (ClassZWJ, ClassCR) | (ClassZWJ, ClassLF) |
+            (ClassZWJ, ClassNL) | (ClassZWJ, ClassSP) | (ClassZWJ, ClassZW) => false,
+            (ClassCM, _) | (ClassZWJ, _) => true,
+            _ => false
+        }
+    }
+
+    /// Records the character `ch` of class `class` after the break opportunity before it is
+    /// found.
+    fn advance(&mut self, ch: char, class: LineBreakClass) {
+        if !self.attaches_to_prev(class) {
+            // LB10: Treat any remaining combining mark or ZWJ as AL.
+            self.push(ch, match class {
+                ClassCM | ClassZWJ => ClassAL,
+                _ => class
+            }, false);
+        }
+        self.prev_unresolved = class;
+    }
+
+    fn push(&mut self, ch: char, class: LineBreakClass, at_start: bool) {
+        self.after_aksara_virama = class == ClassVI && !at_start &&
+            is_aksara(self.prev_ch, self.prev);
+
+        if class != ClassSP {
+            self.last_non_space = Some(class);
+            let opens_quotation = at_start || match self.prev {
+                ClassBK | ClassCR | ClassLF | ClassNL | ClassOP | ClassQU | ClassGL | ClassSP |
+                ClassZW => true,
+                _ => false
+            };
+            self.in_opening_quote = class == ClassQU && is_initial_quote(ch) && opens_quotation;
+        }
+
+        self.prev_prev = Some(self.prev);
+        self.prev_prev_ch = Some(self.prev_ch);
+        self.prev = class;
+        self.prev_ch = ch;
+
+        self.regional_indicator_count = if class == ClassRI {
+            self.regional_indicator_count + 1
+        } else {
+            0
+        };
+
+        self.numeric_state = match (class, self.numeric_state) {
+            (ClassNU, _) => InNumber,
+            (ClassSY, InNumber) | (ClassIS, InNumber) => InNumber,
+            (ClassCL, InNumber) | (ClassCP, InNumber) => AfterNumberClose,
+            _ => NotInNumber,
+        };
+    }
+
+    /// Applies the rules to find whether the line may be broken before the character at `i`.
+    /// `classes` holds the classes of `chars`, resolved by LB1.
+    fn break_before(&self, chars: &[char], classes: &[LineBreakClass], i: uint)
+                    -> BreakOpportunity {
+        let ch = chars[i];
+        let class = classes[i];
+        let prev = self.prev;
+        let next = next_base(classes, i + 1);
+        let next_class = next.map(|&next| classes[next]);
+
+        // LB4, LB5: Always break after hard line breaks, but never between CR and LF.
+        match (prev, class) {
+            (ClassBK, _) => return MandatoryBreak,
+            (ClassCR, ClassLF) => return NoBreak,
+            (ClassCR, _) | (ClassLF, _) | (ClassNL, _) => return MandatoryBreak,
+            _ => {}
+        }
+
+        // LB6, LB7: Do not break before hard line breaks, spaces or zero width spaces.
+        match class {
+            ClassBK | ClassCR | ClassLF | ClassNL | ClassSP | ClassZW => return NoBreak,
+            _ => {}
+        }
+
+        // LB8: Break after a zero width space, even if spaces follow it.
+        if self.last_non_space == Some(ClassZW) {
+            return AllowedBreak;
+        }
+
+        // LB8a: Do not break after a zero width joiner.
+        if self.prev_unresolved == ClassZWJ {
+            return NoBreak;
+        }
+
+        // LB9: Do not break before a combining mark that attaches to the previous character.
+        if self.attaches_to_prev(class) {
+            return NoBreak;
+        }
+
+        // LB10: Treat any remaining combining mark or ZWJ as AL.
+        let class = match class {
+            ClassCM | ClassZWJ => ClassAL,
+            _ => class
+        };
+
+        // LB11, LB12, LB12a: Do not break around word joiners or after non-breaking characters,
+        // nor before them except after spaces and hyphens.
+        match (prev, class) {
+            (ClassWJ, _) | (_, ClassWJ) | (ClassGL, _) => return NoBreak,
+            (ClassSP, ClassGL) | (ClassBA, ClassGL) | (ClassHY, ClassGL) |
+            (ClassHH, ClassGL) => {}
+            (_, ClassGL) => return NoBreak,
+            _ => {}
+        }
+
+        // LB13: Do not break before closing punctuation, even after spaces.
+        match class {
+            ClassCL | ClassCP | ClassEX | ClassSY => return NoBreak,
+            _ => {}
+        }
+
+        // LB14, LB15a: Do not break within `OP SP*`, or after an opening quotation mark and the
+        // spaces after it.
+        if self.last_non_space == Some(ClassOP) || self.in_opening_quote {
+            return NoBreak;
+        }
+
+        // LB15b: Do not break before a closing quotation mark that ends a quotation.
+        if class == ClassQU && is_final_quote(ch) {
+            match next_class {
+                None | Some(ClassSP) | Some(ClassGL) | Some(ClassWJ) | Some(ClassCL) |
+                Some(ClassQU) | Some(ClassCP) | Some(ClassEX) | Some(ClassIS) | Some(ClassSY) |
+                Some(ClassBK) | Some(ClassCR) | Some(ClassLF) | Some(ClassNL) |
+                Some(ClassZW) => return NoBreak,
+                _ => {}
+            }
+        }
+
+        // LB15c, LB15d: Break before a numeric separator that starts a number after a space, but
+        // not before any other numeric separator.
+        if class == ClassIS {
+            return if prev == ClassSP && next_class == Some(ClassNU) {
+                AllowedBreak
+            } else {
+                NoBreak
+            }
+        }
+
+        // LB16, LB17: Do not break within `(CL | CP) SP* NS` or `B2 SP* B2`.
+        match (self.last_non_space, class) {
+            (Some(ClassCL), ClassNS) | (Some(ClassCP), ClassNS) |
+            (Some(ClassB2), ClassB2) => return NoBreak,
+            _ => {}
+        }
+
+        // LB18: Break after spaces.
+        if prev == ClassSP {
+            return AllowedBreak;
+        }
+
+        // LB19: Do not break before quotation marks that are not initial punctuation, or after
+        // ones that are not final punctuation.
+        if (class == ClassQU && !is_initial_quote(ch)) ||
+                (prev == ClassQU && !is_final_quote(self.prev_ch)) {
+            return NoBreak;
+        }
+
+        // LB19a: Only break around quotation marks that are between East Asian characters.
+        if class == ClassQU && (!is_east_asian(self.prev_ch) ||
+                                !next.map_default(false, |&next| is_east_asian(chars[next]))) {
+            return NoBreak;
+        }
+        if prev == ClassQU && (!is_east_asian(ch) ||
+                               !self.prev_prev_ch.map_default(false, |&ch| is_east_asian(ch))) {
+            return NoBreak;
+        }
+
+        // LB20: Break around contingent break opportunities.
+        if prev == ClassCB || class == ClassCB {
+            return AllowedBreak;
+        }
+
+        // LB20a: Do not break after a hyphen that starts a word.
+        match (self.prev_prev, prev, class) {
+            (None, ClassHY, ClassAL) | (None, ClassHY, ClassHL) |
+            (None, ClassHH, ClassAL) | (None, ClassHH, ClassHL) => return NoBreak,
+            (Some(before), ClassHY, ClassAL) | (Some(before), ClassHY, ClassHL) |
+            (Some(before), ClassHH, ClassAL) | (Some(before), ClassHH, ClassHL) => {
+                match before {
+                    ClassBK | ClassCR | ClassLF | ClassNL | ClassSP | ClassZW | ClassCB |
+                    ClassGL => return NoBreak,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        match (prev, class) {
+            // LB21: Do not break before hyphens, small kana and other nonstarters, or after
+            // acute accents.
+            (_, ClassBA) | (_, ClassHH) | (_, ClassHY) | (_, ClassNS) | (ClassBB, _) => NoBreak,
+            // LB21a: Do not break after the hyphen in a Hebrew word.
+            (ClassHY, _) | (ClassHH, _) if self.prev_prev == Some(ClassHL) && class != ClassHL => {
+                NoBreak
+            }
+            // LB21b: Do not break between a solidus and a Hebrew letter.
+            (ClassSY, ClassHL) => NoBreak,
+            // LB22: Do not break before ellipses.
+            (_, ClassIN) => NoBreak,
+            // LB23: Do not break between digits and letters.
+            (ClassAL, ClassNU) | (ClassHL, ClassNU) |
+            (ClassNU, ClassAL) | (ClassNU, ClassHL) => NoBreak,
+            // LB23a: Do not break between numeric prefixes and ideographs, or between
+            // ideographs and numeric postfixes.
+            (ClassPR, ClassID) | (ClassPR, ClassEB) | (ClassPR, ClassEM) |
+            (ClassID, ClassPO) | (ClassEB, ClassPO) | (ClassEM, ClassPO) => NoBreak,
+            // LB24: Do not break between numeric prefixes or postfixes and letters.
+            (ClassPR, ClassAL) | (ClassPR, ClassHL) | (ClassPO, ClassAL) | (ClassPO, ClassHL) |
+            (ClassAL, ClassPR) | (ClassAL, ClassPO) | (ClassHL, ClassPR) | (ClassHL, ClassPO) => {
+                NoBreak
+            }
+            // LB25: Do not break within numbers such as `$(12.35)`.
+            (_, ClassPO) | (_, ClassPR) if self.numeric_state != NotInNumber => NoBreak,
+            (ClassPR, ClassOP) | (ClassPO, ClassOP) if starts_number(classes, next) => {
+                NoBreak
+            }
+            (ClassPR, ClassNU) | (ClassPO, ClassNU) |
+            (ClassHY, ClassNU) | (ClassIS, ClassNU) => NoBreak,
+            (_, ClassNU) if self.numeric_state == InNumber => NoBreak,
+            // LB26: Do not break within Korean syllable blocks.
+            (ClassJL, ClassJL) | (ClassJL, ClassJV) | (ClassJL, ClassH2) | (ClassJL, ClassH3) |
+            (ClassJV, ClassJV) | (ClassJV, ClassJT) | (ClassH2, ClassJV) | (ClassH2, ClassJT) |
+            (ClassJT, ClassJT) | (ClassH3, ClassJT) => NoBreak,
+            // LB27: Treat a Korean syllable block like an ideograph.
+            (ClassJL, ClassPO) | (ClassJV, ClassPO) | (ClassJT, ClassPO) |
+            (ClassH2, ClassPO) | (ClassH3, ClassPO) |
+            (ClassPR, ClassJL) | (ClassPR, ClassJV) | (ClassPR, ClassJT) |
+            (ClassPR, ClassH2) | (ClassPR, ClassH3) => NoBreak,
+            // LB28: Do not break between letters.
+            (ClassAL, ClassAL) | (ClassAL, ClassHL) | (ClassHL, ClassAL) | (ClassHL, ClassHL) => {
+                NoBreak
+            }
+            // LB28a: Do not break within orthographic syllables of Brahmic scripts.
+            _ if self.within_aksara_syllable(ch, class, next_class) => NoBreak,
+            // LB29: Do not break between a numeric punctuation mark and a letter.
+            (ClassIS, ClassAL) | (ClassIS, ClassHL) => NoBreak,
+            // LB30: Do not break between letters or digits and narrow parentheses.
+            (ClassAL, ClassOP) | (ClassHL, ClassOP) | (ClassNU, ClassOP)
+                    if !is_east_asian(ch) => NoBreak,
+            (ClassCP, ClassAL) | (ClassCP, ClassHL) | (ClassCP, ClassNU)
+                    if !is_east_asian(self.prev_ch) => NoBreak,
+            // LB30a: Do not break within pairs of regional indicators.
+            (ClassRI, ClassRI) if self.regional_indicator_count % 2 == 1 => NoBreak,
+            // LB30b: Do not break between an emoji base, or a code point reserved for
+            // pictographs, and an emoji modifier.
+            (ClassEB, ClassEM) => NoBreak,
+            (_, ClassEM) if is_unassigned_pictographic(self.prev_ch) => NoBreak,
+            // LB31: Break everywhere else.
+            _ => AllowedBreak,
+        }
+    }
+
+    /// Whether the character `ch` of class `class` continues the orthographic syllable before it
+    /// (LB28a). `next_class` is the class of the first non-combining character after it, if any.
+    fn within_aksara_syllable(&self, ch: char, class: LineBreakClass,
+                              next_class: Option<LineBreakClass>) -> bool {
+        let prev_is_aksara = is_aksara(self.prev_ch, self.prev);
+        (self.prev == ClassAP && is_aksara(ch, class)) ||
+            (prev_is_aksara && (class == ClassVF || class == ClassVI)) ||
+            (self.after_aksara_virama && (class == ClassAK || ch == DOTTED_CIRCLE)) ||
+            (prev_is_aksara && is_aksara(ch, class) && next_class == Some(ClassVF))
+    }
+}
+
+/// Whether the opening punctuation before the character at `next` starts a number, as in `OP NU`
+/// or `OP IS NU` (LB25).
+fn starts_number(classes: &[LineBreakClass], next: Option<uint>) -> bool {
+    match next {
+        Some(next) if classes[next] == ClassNU => true,
+        Some(next) if classes[next] == ClassIS => {
+            next_base(classes, next + 1).map_default(false, |&after| classes[after] == ClassNU)
+        }
+        _ => false
+    }
+}
+
+static HANGUL_SYLLABLE_FIRST: char = '\uAC00';
+static HANGUL_SYLLABLE_LAST: char = '\uD7A3';
+/// The number of trailing jamo a Hangul syllable may end with, plus one for none.
+static HANGUL_T_COUNT: uint = 28;
+
+static DOTTED_CIRCLE: char = '\u25CC';
+
+/// The line breaking classes of all characters other than Hangul syllables, as sorted,
+/// non-overlapping inclusive ranges. Characters that are not in any range have class XX.
+/// Generated from the `Line_Break` property of the Unicode Character Database, version 17.0.0,
+/// with the complex context characters that are combining marks given class CM (LB1).
+static LINE_BREAK_TABLE: [(char, char, LineBreakClass), ..2159] = [
+    ('\u0000', '\u0008', ClassCM),
+    ('\u0009', '\u0009', ClassBA),
+    ('\u000A', '\u000A', ClassLF),
+    ('\u000B', '\u000C', ClassBK),
+    ('\u000D', '\u000D', ClassCR),
+    ('\u000E', '\u001F', ClassCM),
+    ('\u0020', '\u0020', ClassSP),
+    ('\u0021', '\u0021', ClassEX),
+    ('\u0022', '\u0022', ClassQU),
+    ('\u0023', '\u0023', ClassAL),
+    ('\u0024', '\u0024', ClassPR),
+    ('\u0025', '\u0025', ClassPO),
+    ('\u0026', '\u0026', ClassAL),
+    ('\u0027', '\u0027', ClassQU),
+    ('\u0028', '\u0028', ClassOP),
+    ('\u0029', '\u0029', ClassCP),
+    ('\u002A', '\u002A', ClassAL),
+    ('\u002B', '\u002B', ClassPR),
+    ('\u002C', '\u002C', ClassIS),
+    ('\u002D', '\u002D', ClassHY),
+    ('\u002E', '\u002E', ClassIS),
+    ('\u002F', '\u002F', ClassSY),
+    ('\u0030', '\u0039', ClassNU),
+    ('\u003A', '\u003B', ClassIS),
+    ('\u003C', '\u003E', ClassAL),
+    ('\u003F', '\u003F', ClassEX),
+    ('\u0040', '\u005A', ClassAL),
+    ('\u005B', '\u005B', ClassOP),
+    ('\u005C', '\u005C', ClassPR),
+    ('\u005D', '\u005D', ClassCP),
+    ('\u005E', '\u007A', ClassAL),
+    ('\u007B', '\u007B', ClassOP),
+    ('\u007C', '\u007C', ClassBA),
+    ('\u007D', '\u007D', ClassCL),
+    ('\u007E', '\u007E', ClassAL),
+    ('\u007F', '\u0084', ClassCM),
+    ('\u0085', '\u0085', ClassNL),
+    ('\u0086', '\u009F', ClassCM),
+    ('\u00A0', '\u00A0', ClassGL),
+    ('\u00A1', '\u00A1', ClassOP),
+    ('\u00A2', '\u00A2', ClassPO),
+    ('\u00A3', '\u00A5', ClassPR),
+    ('\u00A6', '\u00A6', ClassAL),
+    ('\u00A7', '\u00A8', ClassAI),
+    ('\u00A9', '\u00A9', ClassAL),
+    ('\u00AA', '\u00AA', ClassAI),
+    ('\u00AB', '\u00AB', ClassQU),
+    ('\u00AC', '\u00AC', ClassAL),
+    ('\u00AD', '\u00AD', ClassBA),
+    ('\u00AE', '\u00AF', ClassAL),
+    ('\u00B0', '\u00B0', ClassPO),
+    ('\u00B1', '\u00B1', ClassPR),
+    ('\u00B2', '\u00B3', ClassAI),
+    ('\u00B4', '\u00B4', ClassBB),
+    ('\u00B5', '\u00B5', ClassAL),
+    ('\u00B6', '\u00BA', ClassAI),
+    ('\u00BB', '\u00BB', ClassQU),
+    ('\u00BC', '\u00BE', ClassAI),
+    ('\u00BF', '\u00BF', ClassOP),
+    ('\u00C0', '\u00D6', ClassAL),
+    ('\u00D7', '\u00D7', ClassAI),
+    ('\u00D8', '\u00F6', ClassAL),
+    ('\u00F7', '\u00F7', ClassAI),
+    ('\u00F8', '\u02C6', ClassAL),
+    ('\u02C7', '\u02C7', ClassAI),
+    ('\u02C8', '\u02C8', ClassBB),
+    ('\u02C9', '\u02CB', ClassAI),
+    ('\u02CC', '\u02CC', ClassBB),
+    ('\u02CD', '\u02CD', ClassAI),
+    ('\u02CE', '\u02CF', ClassAL),
+    ('\u02D0', '\u02D0', ClassAI),
+    ('\u02D1', '\u02D7', ClassAL),
+    ('\u02D8', '\u02DB', ClassAI),
+    ('\u02DC', '\u02DC', ClassAL),
+    ('\u02DD', '\u02DD', ClassAI),
+    ('\u02DE', '\u02DE', ClassAL),
+    ('\u02DF', '\u02DF', ClassBB),
+    ('\u02E0', '\u02FF', ClassAL),
+    ('\u0300', '\u035B', ClassCM),
+    ('\u035C', '\u0362', ClassGL),
+    ('\u0363', '\u036F', ClassCM),
+    ('\u0370', '\u0377', ClassAL),
+    ('\u037A', '\u037D', ClassAL),
+    ('\u037E', '\u037E', ClassIS),
+    ('\u037F', '\u037F', ClassAL),
+    ('\u0384', '\u038A', ClassAL),
+    ('\u038C', '\u038C', ClassAL),
+    ('\u038E', '\u03A1', ClassAL),
+    ('\u03A3', '\u0482', ClassAL),
+    ('\u0483', '\u0489', ClassCM),
+    ('\u048A', '\u052F', ClassAL),
+    ('\u0531', '\u0556', ClassAL),
+    ('\u0559', '\u0588', ClassAL),
+    ('\u0589', '\u0589', ClassIS),
+    ('\u058A', '\u058A', ClassHH),
+    ('\u058D', '\u058E', ClassAL),
+    ('\u058F', '\u058F', ClassPR),
+    ('\u0591', '\u05BD', ClassCM),
+    ('\u05BE', '\u05BE', ClassHH),
+    ('\u05BF', '\u05BF', ClassCM),
+    ('\u05C0', '\u05C0', ClassAL),
+    ('\u05C1', '\u05C2', ClassCM),
+    ('\u05C3', '\u05C3', ClassAL),
+    ('\u05C4', '\u05C5', ClassCM),
+    ('\u05C6', '\u05C6', ClassEX),
+    ('\u05C7', '\u05C7', ClassCM),
+    ('\u05D0', '\u05EA', ClassHL),
+    ('\u05EF', '\u05F2', ClassHL),
+    ('\u05F3', '\u05F4', ClassAL),
+    ('\u0600', '\u0605', ClassNU),
+    ('\u0606', '\u0608', ClassAL),
+    ('\u0609', '\u060B', ClassPO),
+    ('\u060C', '\u060D', ClassIS),
+    ('\u060E', '\u060F', ClassAL),
+    ('\u0610', '\u061A', ClassCM),
+    ('\u061B', '\u061B', ClassEX),
+    ('\u061C', '\u061C', ClassCM),
+    ('\u061D', '\u061F', ClassEX),
+    ('\u0620', '\u064A', ClassAL),
+    ('\u064B', '\u065F', ClassCM),
+    ('\u0660', '\u0669', ClassNU),
+    ('\u066A', '\u066A', ClassPO),
+    ('\u066B', '\u066C', ClassNU),
+    ('\u066D', '\u066F', ClassAL),
+    ('\u0670', '\u0670', ClassCM),
+    ('\u0671', '\u06D3', ClassAL),
+    ('\u06D4', '\u06D4', ClassEX),
+    ('\u06D5', '\u06D5', ClassAL),
+    ('\u06D6', '\u06DC', ClassCM),
+    ('\u06DD', '\u06DD', ClassNU),
+    ('\u06DE', '\u06DE', ClassAL),
+    ('\u06DF', '\u06E4', ClassCM),
+    ('\u06E5', '\u06E6', ClassAL),
+    ('\u06E7', '\u06E8', ClassCM),
+    ('\u06E9', '\u06E9', ClassAL),
+    ('\u06EA', '\u06ED', ClassCM),
+    ('\u06EE', '\u06EF', ClassAL),
+    ('\u06F0', '\u06F9', ClassNU),
+    ('\u06FA', '\u070D', ClassAL),
+    ('\u070F', '\u0710', ClassAL),
+    ('\u0711', '\u0711', ClassCM),
+    ('\u0712', '\u072F', ClassAL),
+    ('\u0730', '\u074A', ClassCM),
+    ('\u074D', '\u07A5', ClassAL),
+    ('\u07A6', '\u07B0', ClassCM),
+    ('\u07B1', '\u07B1', ClassAL),
+    ('\u07C0', '\u07C9', ClassNU),
+    ('\u07CA', '\u07EA', ClassAL),
+    ('\u07EB', '\u07F3', ClassCM),
+    ('\u07F4', '\u07F7', ClassAL),
+    ('\u07F8', '\u07F8', ClassIS),
+    ('\u07F9', '\u07F9', ClassEX),
+    ('\u07FA', '\u07FA', ClassAL),
+    ('\u07FD', '\u07FD', ClassCM),
+    ('\u07FE', '\u07FF', ClassPR),
+    ('\u0800', '\u0815', ClassAL),
+    ('\u0816', '\u0819', ClassCM),
+    ('\u081A', '\u081A', ClassAL),
+    ('\u081B', '\u0823', ClassCM),
+    ('\u0824', '\u0824', ClassAL),
+    ('\u0825', '\u0827', ClassCM),
+    ('\u0828', '\u0828', ClassAL),
+    ('\u0829', '\u082D', ClassCM),
+    ('\u0830', '\u083E', ClassAL),
+    ('\u0840', '\u0858', ClassAL),
+    ('\u0859', '\u085B', ClassCM),
+    ('\u085E', '\u085E', ClassAL),
+    ('\u0860', '\u086A', ClassAL),
+    ('\u0870', '\u088F', ClassAL),
+    ('\u0890', '\u0891', ClassNU),
+    ('\u0897', '\u089F', ClassCM),
+    ('\u08A0', '\u08C9', ClassAL),
+    ('\u08CA', '\u08E1', ClassCM),
+    ('\u08E2', '\u08E2', ClassNU),
+    ('\u08E3', '\u0903', ClassCM),
+    ('\u0904', '\u0939', ClassAL),
+    ('\u093A', '\u093C', ClassCM),
+    ('\u093D', '\u093D', ClassAL),
+    ('\u093E', '\u094F', ClassCM),
+    ('\u0950', '\u0950', ClassAL),
+    ('\u0951', '\u0957', ClassCM),
+    ('\u0958', '\u0961', ClassAL),
+    ('\u0962', '\u0963', ClassCM),
+    ('\u0964', '\u0965', ClassBA),
+    ('\u0966', '\u096F', ClassNU),
+    ('\u0970', '\u0980', ClassAL),
+    ('\u0981', '\u0983', ClassCM),
+    ('\u0985', '\u098C', ClassAL),
+    ('\u098F', '\u0990', ClassAL),
+    ('\u0993', '\u09A8', ClassAL),
+    ('\u09AA', '\u09B0', ClassAL),
+    ('\u09B2', '\u09B2', ClassAL),
+    ('\u09B6', '\u09B9', ClassAL),
+    ('\u09BC', '\u09BC', ClassCM),
+    ('\u09BD', '\u09BD', ClassAL),
+    ('\u09BE', '\u09C4', ClassCM),
+    ('\u09C7', '\u09C8', ClassCM),
+    ('\u09CB', '\u09CD', ClassCM),
+    ('\u09CE', '\u09CE', ClassAL),
+    ('\u09D7', '\u09D7', ClassCM),
+    ('\u09DC', '\u09DD', ClassAL),
+    ('\u09DF', '\u09E1', ClassAL),
+    ('\u09E2', '\u09E3', ClassCM),
+    ('\u09E6', '\u09EF', ClassNU),
+    ('\u09F0', '\u09F1', ClassAL),
+    ('\u09F2', '\u09F3', ClassPO),
+    ('\u09F4', '\u09F8', ClassAL),
+    ('\u09F9', '\u09F9', ClassPO),
+    ('\u09FA', '\u09FA', ClassAL),
+    ('\u09FB', '\u09FB', ClassPR),
+    ('\u09FC', '\u09FD', ClassAL),
+    ('\u09FE', '\u09FE', ClassCM),
+    ('\u0A01', '\u0A03', ClassCM),
+    ('\u0A05', '\u0A0A', ClassAL),
+    ('\u0A0F', '\u0A10', ClassAL),
+    ('\u0A13', '\u0A28', ClassAL),
+    ('\u0A2A', '\u0A30', ClassAL),
+    ('\u0A32', '\u0A33', ClassAL),
+    ('\u0A35', '\u0A36', ClassAL),
+    ('\u0A38', '\u0A39', ClassAL),
+    ('\u0A3C', '\u0A3C', ClassCM),
+    ('\u0A3E', '\u0A42', ClassCM),
+    ('\u0A47', '\u0A48', ClassCM),
+    ('\u0A4B', '\u0A4D', ClassCM),
+    ('\u0A51', '\u0A51', ClassCM),
+    ('\u0A59', '\u0A5C', ClassAL),
+    ('\u0A5E', '\u0A5E', ClassAL),
+    ('\u0A66', '\u0A6F', ClassNU),
+    ('\u0A70', '\u0A71', ClassCM),
+    ('\u0A72', '\u0A74', ClassAL),
+    ('\u0A75', '\u0A75', ClassCM),
+    ('\u0A76', '\u0A76', ClassAL),
+    ('\u0A81', '\u0A83', ClassCM),
+    ('\u0A85', '\u0A8D', ClassAL),
+    ('\u0A8F', '\u0A91', ClassAL),
+    ('\u0A93', '\u0AA8', ClassAL),
+    ('\u0AAA', '\u0AB0', ClassAL),
+    ('\u0AB2', '\u0AB3', ClassAL),
+    ('\u0AB5', '\u0AB9', ClassAL),
+    ('\u0ABC', '\u0ABC', ClassCM),
+    ('\u0ABD', '\u0ABD', ClassAL),
+    ('\u0ABE', '\u0AC5', ClassCM),
+    ('\u0AC7', '\u0AC9', ClassCM),
+    ('\u0ACB', '\u0ACD', ClassCM),
+    ('\u0AD0', '\u0AD0', ClassAL),
+    ('\u0AE0', '\u0AE1', ClassAL),
+    ('\u0AE2', '\u0AE3', ClassCM),
+    ('\u0AE6', '\u0AEF', ClassNU),
+    ('\u0AF0', '\u0AF0', ClassAL),
+    ('\u0AF1', '\u0AF1', ClassPR),
+    ('\u0AF9', '\u0AF9', ClassAL),
+    ('\u0AFA', '\u0AFF', ClassCM),
+    ('\u0B01', '\u0B03', ClassCM),
+    ('\u0B05', '\u0B0C', ClassAL),
+    ('\u0B0F', '\u0B10', ClassAL),
+    ('\u0B13', '\u0B28', ClassAL),
+    ('\u0B2A', '\u0B30', ClassAL),
+    ('\u0B32', '\u0B33', ClassAL),
+    ('\u0B35', '\u0B39', ClassAL),
+    ('\u0B3C', '\u0B3C', ClassCM),
+    ('\u0B3D', '\u0B3D', ClassAL),
+    ('\u0B3E', '\u0B44', ClassCM),
+    ('\u0B47', '\u0B48', ClassCM),
+    ('\u0B4B', '\u0B4D', ClassCM),
+    ('\u0B55', '\u0B57', ClassCM),
+    ('\u0B5C', '\u0B5D', ClassAL),
+    ('\u0B5F', '\u0B61', ClassAL),
+    ('\u0B62', '\u0B63', ClassCM),
+    ('\u0B66', '\u0B6F', ClassNU),
+    ('\u0B70', '\u0B77', ClassAL),
+    ('\u0B82', '\u0B82', ClassCM),
+    ('\u0B83', '\u0B83', ClassAL),
+    ('\u0B85', '\u0B8A', ClassAL),
+    ('\u0B8E', '\u0B90', ClassAL),
+    ('\u0B92', '\u0B95', ClassAL),
+    ('\u0B99', '\u0B9A', ClassAL),
+    ('\u0B9C', '\u0B9C', ClassAL),
+    ('\u0B9E', '\u0B9F', ClassAL),
+    ('\u0BA3', '\u0BA4', ClassAL),
+    ('\u0BA8', '\u0BAA', ClassAL),
+    ('\u0BAE', '\u0BB9', ClassAL),
+    ('\u0BBE', '\u0BC2', ClassCM),
+    ('\u0BC6', '\u0BC8', ClassCM),
+    ('\u0BCA', '\u0BCD', ClassCM),
+    ('\u0BD0', '\u0BD0', ClassAL),
+    ('\u0BD7', '\u0BD7', ClassCM),
+    ('\u0BE6', '\u0BEF', ClassNU),
+    ('\u0BF0', '\u0BF8', ClassAL),
+    ('\u0BF9', '\u0BF9', ClassPR),
+    ('\u0BFA', '\u0BFA', ClassAL),
+    ('\u0C00', '\u0C04', ClassCM),
+    ('\u0C05', '\u0C0C', ClassAL),
+    ('\u0C0E', '\u0C10', ClassAL),
+    ('\u0C12', '\u0C28', ClassAL),
+    ('\u0C2A', '\u0C39', ClassAL),
+    ('\u0C3C', '\u0C3C', ClassCM),
+    ('\u0C3D', '\u0C3D', ClassAL),
+    ('\u0C3E', '\u0C44', ClassCM),
+    ('\u0C46', '\u0C48', ClassCM),
+    ('\u0C4A', '\u0C4D', ClassCM),
+    ('\u0C55', '\u0C56', ClassCM),
+    ('\u0C58', '\u0C5A', ClassAL),
+    ('\u0C5C', '\u0C5D', ClassAL),
+    ('\u0C60', '\u0C61', ClassAL),
+    ('\u0C62', '\u0C63', ClassCM),
+    ('\u0C66', '\u0C6F', ClassNU),
+    ('\u0C77', '\u0C77', ClassBB),
+    ('\u0C78', '\u0C80', ClassAL),
+    ('\u0C81', '\u0C83', ClassCM),
+    ('\u0C84', '\u0C84', ClassBB),
+    ('\u0C85', '\u0C8C', ClassAL),
+    ('\u0C8E', '\u0C90', ClassAL),
+    ('\u0C92', '\u0CA8', ClassAL),
+    ('\u0CAA', '\u0CB3', ClassAL),
+    ('\u0CB5', '\u0CB9', ClassAL),
+    ('\u0CBC', '\u0CBC', ClassCM),
+    ('\u0CBD', '\u0CBD', ClassAL),
+    ('\u0CBE', '\u0CC4', ClassCM),
+    ('\u0CC6', '\u0CC8', ClassCM),
+    ('\u0CCA', '\u0CCD', ClassCM),
+    ('\u0CD5', '\u0CD6', ClassCM),
+    ('\u0CDC', '\u0CDE', ClassAL),
+    ('\u0CE0', '\u0CE1', ClassAL),
+    ('\u0CE2', '\u0CE3', ClassCM),
+    ('\u0CE6', '\u0CEF', ClassNU),
+    ('\u0CF1', '\u0CF2', ClassAL),
+    ('\u0CF3', '\u0CF3', ClassCM),
+    ('\u0D00', '\u0D03', ClassCM),
+    ('\u0D04', '\u0D0C', ClassAL),
+    ('\u0D0E', '\u0D10', ClassAL),
+    ('\u0D12', '\u0D3A', ClassAL),
+    ('\u0D3B', '\u0D3C', ClassCM),
+    ('\u0D3D', '\u0D3D', ClassAL),
+    ('\u0D3E', '\u0D44', ClassCM),
+    ('\u0D46', '\u0D48', ClassCM),
+    ('\u0D4A', '\u0D4D', ClassCM),
+    ('\u0D4E', '\u0D4F', ClassAL),
+    ('\u0D54', '\u0D56', ClassAL),
+    ('\u0D57', '\u0D57', ClassCM),
+    ('\u0D58', '\u0D61', ClassAL),
+    ('\u0D62', '\u0D63', ClassCM),
+    ('\u0D66', '\u0D6F', ClassNU),
+    ('\u0D70', '\u0D78', ClassAL),
+    ('\u0D79', '\u0D79', ClassPO),
+    ('\u0D7A', '\u0D7F', ClassAL),
+    ('\u0D81', '\u0D83', ClassCM),
+    ('\u0D85', '\u0D96', ClassAL),
+    ('\u0D9A', '\u0DB1', ClassAL),
+    ('\u0DB3', '\u0DBB', ClassAL),
+    ('\u0DBD', '\u0DBD', ClassAL),
+    ('\u0DC0', '\u0DC6', ClassAL),
+    ('\u0DCA', '\u0DCA', ClassCM),
+    ('\u0DCF', '\u0DD4', ClassCM),
+    ('\u0DD6', '\u0DD6', ClassCM),
+    ('\u0DD8', '\u0DDF', ClassCM),
+    ('\u0DE6', '\u0DEF', ClassNU),
+    ('\u0DF2', '\u0DF3', ClassCM),
+    ('\u0DF4', '\u0DF4', ClassAL),
+    ('\u0E01', '\u0E30', ClassSA),
+    ('\u0E31', '\u0E31', ClassCM),
+    ('\u0E32', '\u0E33', ClassSA),
+    ('\u0E34', '\u0E3A', ClassCM),
+    ('\u0E3F', '\u0E3F', ClassPR),
+    ('\u0E40', '\u0E46', ClassSA),
+    ('\u0E47', '\u0E4E', ClassCM),
+    ('\u0E4F', '\u0E4F', ClassAL),
+    ('\u0E50', '\u0E59', ClassNU),
+    ('\u0E5A', '\u0E5B', ClassBA),
+    ('\u0E81', '\u0E82', ClassSA),
+    ('\u0E84', '\u0E84', ClassSA),
+    ('\u0E86', '\u0E8A', ClassSA),
+    ('\u0E8C', '\u0EA3', ClassSA),
+    ('\u0EA5', '\u0EA5', ClassSA),
+    ('\u0EA7', '\u0EB0', ClassSA),
+    ('\u0EB1', '\u0EB1', ClassCM),
+    ('\u0EB2', '\u0EB3', ClassSA),
+    ('\u0EB4', '\u0EBC', ClassCM),
+    ('\u0EBD', '\u0EBD', ClassSA),
+    ('\u0EC0', '\u0EC4', ClassSA),
+    ('\u0EC6', '\u0EC6', ClassSA),
+    ('\u0EC8', '\u0ECE', ClassCM),
+    ('\u0ED0', '\u0ED9', ClassNU),
+    ('\u0EDC', '\u0EDF', ClassSA),
+    ('\u0F00', '\u0F00', ClassAL),
+    ('\u0F01', '\u0F04', ClassBB),
+    ('\u0F05', '\u0F05', ClassAL),
+    ('\u0F06', '\u0F07', ClassBB),
+    ('\u0F08', '\u0F08', ClassGL),
+    ('\u0F09', '\u0F0A', ClassBB),
+    ('\u0F0B', '\u0F0B', ClassBA),
+    ('\u0F0C', '\u0F0C', ClassGL),
+    ('\u0F0D', '\u0F11', ClassEX),
+    ('\u0F12', '\u0F12', ClassGL),
+    ('\u0F13', '\u0F13', ClassAL),
+    ('\u0F14', '\u0F14', ClassEX),
+    ('\u0F15', '\u0F17', ClassAL),
+    ('\u0F18', '\u0F19', ClassCM),
+    ('\u0F1A', '\u0F1F', ClassAL),
+    ('\u0F20', '\u0F29', ClassNU),
+    ('\u0F2A', '\u0F33', ClassAL),
+    ('\u0F34', '\u0F34', ClassBA),
+    ('\u0F35', '\u0F35', ClassCM),
+    ('\u0F36', '\u0F36', ClassAL),
+    ('\u0F37', '\u0F37', ClassCM),
+    ('\u0F38', '\u0F38', ClassAL),
+    ('\u0F39', '\u0F39', ClassCM),
+    ('\u0F3A', '\u0F3A', ClassOP),
+    ('\u0F3B', '\u0F3B', ClassCL),
+    ('\u0F3C', '\u0F3C', ClassOP),
+    ('\u0F3D', '\u0F3D', ClassCL),
+    ('\u0F3E', '\u0F3F', ClassCM),
+    ('\u0F40', '\u0F47', ClassAL),
+    ('\u0F49', '\u0F6C', ClassAL),
+    ('\u0F71', '\u0F7E', ClassCM),
+    ('\u0F7F', '\u0F7F', ClassBA),
+    ('\u0F80', '\u0F84', ClassCM),
+    ('\u0F85', '\u0F85', ClassBA),
+    ('\u0F86', '\u0F87', ClassCM),
+    ('\u0F88', '\u0F8C', ClassAL),
+    ('\u0F8D', '\u0F97', ClassCM),
+    ('\u0F99', '\u0FBC', ClassCM),
+    ('\u0FBE', '\u0FBF', ClassBA),
+    ('\u0FC0', '\u0FC5', ClassAL),
+    ('\u0FC6', '\u0FC6', ClassCM),
+    ('\u0FC7', '\u0FCC', ClassAL),
+    ('\u0FCE', '\u0FCF', ClassAL),
+    ('\u0FD0', '\u0FD1', ClassBB),
+    ('\u0FD2', '\u0FD2', ClassBA),
+    ('\u0FD3', '\u0FD3', ClassBB),
+    ('\u0FD4', '\u0FD8', ClassAL),
+    ('\u0FD9', '\u0FDA', ClassGL),
+    ('\u1000', '\u102A', ClassSA),
+    ('\u102B', '\u103E', ClassCM),
+    ('\u103F', '\u103F', ClassSA),
+    ('\u1040', '\u1049', ClassNU),
+    ('\u104A', '\u104B', ClassBA),
+    ('\u104C', '\u104F', ClassAL),
+    ('\u1050', '\u1055', ClassSA),
+    ('\u1056', '\u1059', ClassCM),
+    ('\u105A', '\u105D', ClassSA),
+    ('\u105E', '\u1060', ClassCM),
+    ('\u1061', '\u1061', ClassSA),
+    ('\u1062', '\u1064', ClassCM),
+    ('\u1065', '\u1066', ClassSA),
+    ('\u1067', '\u106D', ClassCM),
+    ('\u106E', '\u1070', ClassSA),
+    ('\u1071', '\u1074', ClassCM),
+    ('\u1075', '\u1081', ClassSA),
+    ('\u1082', '\u108D', ClassCM),
+    ('\u108E', '\u108E', ClassSA),
+    ('\u108F', '\u108F', ClassCM),
+    ('\u1090', '\u1099', ClassNU),
+    ('\u109A', '\u109D', ClassCM),
+    ('\u109E', '\u109F', ClassSA),
+    ('\u10A0', '\u10C5', ClassAL),
+    ('\u10C7', '\u10C7', ClassAL),
+    ('\u10CD', '\u10CD', ClassAL),
+    ('\u10D0', '\u10FF', ClassAL),
+    ('\u1100', '\u115F', ClassJL),
+    ('\u1160', '\u11A7', ClassJV),
+    ('\u11A8', '\u11FF', ClassJT),
+    ('\u1200', '\u1248', ClassAL),
+    ('\u124A', '\u124D', ClassAL),
+    ('\u1250', '\u1256', ClassAL),
+    ('\u1258', '\u1258', ClassAL),
+    ('\u125A', '\u125D', ClassAL),
+    ('\u1260', '\u1288', ClassAL),
+    ('\u128A', '\u128D', ClassAL),
+    ('\u1290', '\u12B0', ClassAL),
+    ('\u12B2', '\u12B5', ClassAL),
+    ('\u12B8', '\u12BE', ClassAL),
+    ('\u12C0', '\u12C0', ClassAL),
+    ('\u12C2', '\u12C5', ClassAL),
+    ('\u12C8', '\u12D6', ClassAL),
+    ('\u12D8', '\u1310', ClassAL),
+    ('\u1312', '\u1315', ClassAL),
+    ('\u1318', '\u135A', ClassAL),
+    ('\u135D', '\u135F', ClassCM),
+    ('\u1360', '\u1360', ClassAL),
+    ('\u1361', '\u1361', ClassBA),
+    ('\u1362', '\u137C', ClassAL),
+    ('\u1380', '\u1399', ClassAL),
+    ('\u13A0', '\u13F5', ClassAL),
+    ('\u13F8', '\u13FD', ClassAL),
+    ('\u1400', '\u1400', ClassHH),
+    ('\u1401', '\u167F', ClassAL),
+    ('\u1680', '\u1680', ClassBA),
+    ('\u1681', '\u169A', ClassAL),
+    ('\u169B', '\u169B', ClassOP),
+    ('\u169C', '\u169C', ClassCL),
+    ('\u16A0', '\u16EA', ClassAL),
+    ('\u16EB', '\u16ED', ClassBA),
+    ('\u16EE', '\u16F8', ClassAL),
+    ('\u1700', '\u1711', ClassAL),
+    ('\u1712', '\u1715', ClassCM),
+    ('\u171F', '\u1731', ClassAL),
+    ('\u1732', '\u1734', ClassCM),
+    ('\u1735', '\u1736', ClassBA),
+    ('\u1740', '\u1751', ClassAL),
+    ('\u1752', '\u1753', ClassCM),
+    ('\u1760', '\u176C', ClassAL),
+    ('\u176E', '\u1770', ClassAL),
+    ('\u1772', '\u1773', ClassCM),
+    ('\u1780', '\u17B3', ClassSA),
+    ('\u17B4', '\u17D3', ClassCM),
+    ('\u17D4', '\u17D5', ClassBA),
+    ('\u17D6', '\u17D6', ClassNS),
+    ('\u17D7', '\u17D7', ClassSA),
+    ('\u17D8', '\u17D8', ClassBA),
+    ('\u17D9', '\u17D9', ClassAL),
+    ('\u17DA', '\u17DA', ClassBA),
+    ('\u17DB', '\u17DB', ClassPR),
+    ('\u17DC', '\u17DC', ClassSA),
+    ('\u17DD', '\u17DD', ClassCM),
+    ('\u17E0', '\u17E9', ClassNU),
+    ('\u17F0', '\u17F9', ClassAL),
+    ('\u1800', '\u1801', ClassAL),
+    ('\u1802', '\u1803', ClassEX),
+    ('\u1804', '\u1805', ClassBA),
+    ('\u1806', '\u1806', ClassBB),
+    ('\u1807', '\u1807', ClassAL),
+    ('\u1808', '\u1809', ClassEX),
+    ('\u180A', '\u180A', ClassAL),
+    ('\u180B', '\u180D', ClassCM),
+    ('\u180E', '\u180E', ClassGL),
+    ('\u180F', '\u180F', ClassCM),
+    ('\u1810', '\u1819', ClassNU),
+    ('\u1820', '\u1878', ClassAL),
+    ('\u1880', '\u1884', ClassAL),
+    ('\u1885', '\u1886', ClassCM),
+    ('\u1887', '\u18A8', ClassAL),
+    ('\u18A9', '\u18A9', ClassCM),
+    ('\u18AA', '\u18AA', ClassAL),
+    ('\u18B0', '\u18F5', ClassAL),
+    ('\u1900', '\u191E', ClassAL),
+    ('\u1920', '\u192B', ClassCM),
+    ('\u1930', '\u193B', ClassCM),
+    ('\u1940', '\u1940', ClassAL),
+    ('\u1944', '\u1945', ClassEX),
+    ('\u1946', '\u194F', ClassNU),
+    ('\u1950', '\u196D', ClassSA),
+    ('\u1970', '\u1974', ClassSA),
+    ('\u1980', '\u19AB', ClassSA),
+    ('\u19B0', '\u19C9', ClassSA),
+    ('\u19D0', '\u19DA', ClassNU),
+    ('\u19DE', '\u19DF', ClassSA),
+    ('\u19E0', '\u1A16', ClassAL),
+    ('\u1A17', '\u1A1B', ClassCM),
+    ('\u1A1E', '\u1A1F', ClassAL),
+    ('\u1A20', '\u1A54', ClassSA),
+    ('\u1A55', '\u1A5E', ClassCM),
+    ('\u1A60', '\u1A7C', ClassCM),
+    ('\u1A7F', '\u1A7F', ClassCM),
+    ('\u1A80', '\u1A89', ClassNU),
+    ('\u1A90', '\u1A99', ClassNU),
+    ('\u1AA0', '\u1AAD', ClassSA),
+    ('\u1AB0', '\u1ADD', ClassCM),
+    ('\u1AE0', '\u1AEA', ClassCM),
+    ('\u1AEB', '\u1AEB', ClassGL),
+    ('\u1B00', '\u1B04', ClassCM),
+    ('\u1B05', '\u1B33', ClassAK),
+    ('\u1B34', '\u1B43', ClassCM),
+    ('\u1B44', '\u1B44', ClassVI),
+    ('\u1B45', '\u1B4C', ClassAK),
+    ('\u1B4E', '\u1B4F', ClassBA),
+    ('\u1B50', '\u1B59', ClassAS),
+    ('\u1B5A', '\u1B5B', ClassBA),
+    ('\u1B5C', '\u1B5C', ClassID),
+    ('\u1B5D', '\u1B60', ClassBA),
+    ('\u1B61', '\u1B6A', ClassID),
+    ('\u1B6B', '\u1B73', ClassCM),
+    ('\u1B74', '\u1B7C', ClassID),
+    ('\u1B7D', '\u1B7F', ClassBA),
+    ('\u1B80', '\u1B82', ClassCM),
+    ('\u1B83', '\u1BA0', ClassAL),
+    ('\u1BA1', '\u1BAD', ClassCM),
+    ('\u1BAE', '\u1BAF', ClassAL),
+    ('\u1BB0', '\u1BB9', ClassNU),
+    ('\u1BBA', '\u1BBF', ClassAL),
+    ('\u1BC0', '\u1BE5', ClassAS),
+    ('\u1BE6', '\u1BF1', ClassCM),
+    ('\u1BF2', '\u1BF3', ClassVF),
+    ('\u1BFC', '\u1C23', ClassAL),
+    ('\u1C24', '\u1C37', ClassCM),
+    ('\u1C3B', '\u1C3F', ClassBA),
+    ('\u1C40', '\u1C49', ClassNU),
+    ('\u1C4D', '\u1C4F', ClassAL),
+    ('\u1C50', '\u1C59', ClassNU),
+    ('\u1C5A', '\u1C7D', ClassAL),
+    ('\u1C7E', '\u1C7F', ClassBA),
+    ('\u1C80', '\u1C8A', ClassAL),
+    ('\u1C90', '\u1CBA', ClassAL),
+    ('\u1CBD', '\u1CC7', ClassAL),
+    ('\u1CD0', '\u1CD2', ClassCM),
+    ('\u1CD3', '\u1CD3', ClassAL),
+    ('\u1CD4', '\u1CE8', ClassCM),
+    ('\u1CE9', '\u1CEC', ClassAL),
+    ('\u1CED', '\u1CED', ClassCM),
+    ('\u1CEE', '\u1CF3', ClassAL),
+    ('\u1CF4', '\u1CF4', ClassCM),
+    ('\u1CF5', '\u1CF6', ClassAL),
+    ('\u1CF7', '\u1CF9', ClassCM),
+    ('\u1CFA', '\u1CFA', ClassAL),
+    ('\u1D00', '\u1DBF', ClassAL),
+    ('\u1DC0', '\u1DCC', ClassCM),
+    ('\u1DCD', '\u1DCD', ClassGL),
+    ('\u1DCE', '\u1DFB', ClassCM),
+    ('\u1DFC', '\u1DFC', ClassGL),
+    ('\u1DFD', '\u1DFF', ClassCM),
+    ('\u1E00', '\u1F15', ClassAL),
+    ('\u1F18', '\u1F1D', ClassAL),
+    ('\u1F20', '\u1F45', ClassAL),
+    ('\u1F48', '\u1F4D', ClassAL),
+    ('\u1F50', '\u1F57', ClassAL),
+    ('\u1F59', '\u1F59', ClassAL),
+    ('\u1F5B', '\u1F5B', ClassAL),
+    ('\u1F5D', '\u1F5D', ClassAL),
+    ('\u1F5F', '\u1F7D', ClassAL),
+    ('\u1F80', '\u1FB4', ClassAL),
+    ('\u1FB6', '\u1FC4', ClassAL),
+    ('\u1FC6', '\u1FD3', ClassAL),
+    ('\u1FD6', '\u1FDB', ClassAL),
+    ('\u1FDD', '\u1FEF', ClassAL),
+    ('\u1FF2', '\u1FF4', ClassAL),
+    ('\u1FF6', '\u1FFC', ClassAL),
+    ('\u1FFD', '\u1FFD', ClassBB),
+    ('\u1FFE', '\u1FFE', ClassAL),
+    ('\u2000', '\u2006', ClassBA),
+    ('\u2007', '\u2007', ClassGL),
+    ('\u2008', '\u200A', ClassBA),
+    ('\u200B', '\u200B', ClassZW),
+    ('\u200C', '\u200C', ClassCM),
+    ('\u200D', '\u200D', ClassZWJ),
+    ('\u200E', '\u200F', ClassCM),
+    ('\u2010', '\u2010', ClassHH),
+    ('\u2011', '\u2011', ClassGL),
+    ('\u2012', '\u2013', ClassHH),
+    ('\u2014', '\u2014', ClassB2),
+    ('\u2015', '\u2016', ClassAI),
+    ('\u2017', '\u2017', ClassAL),
+    ('\u2018', '\u2019', ClassQU),
+    ('\u201A', '\u201A', ClassOP),
+    ('\u201B', '\u201D', ClassQU),
+    ('\u201E', '\u201E', ClassOP),
+    ('\u201F', '\u201F', ClassQU),
+    ('\u2020', '\u2021', ClassAI),
+    ('\u2022', '\u2023', ClassAL),
+    ('\u2024', '\u2026', ClassIN),
+    ('\u2027', '\u2027', ClassBA),
+    ('\u2028', '\u2029', ClassBK),
+    ('\u202A', '\u202E', ClassCM),
+    ('\u202F', '\u202F', ClassGL),
+    ('\u2030', '\u2037', ClassPO),
+    ('\u2038', '\u2038', ClassAL),
+    ('\u2039', '\u203A', ClassQU),
+    ('\u203B', '\u203B', ClassAI),
+    ('\u203C', '\u203D', ClassNS),
+    ('\u203E', '\u2043', ClassAL),
+    ('\u2044', '\u2044', ClassIS),
+    ('\u2045', '\u2045', ClassOP),
+    ('\u2046', '\u2046', ClassCL),
+    ('\u2047', '\u2049', ClassNS),
+    ('\u204A', '\u2055', ClassAL),
+    ('\u2056', '\u2056', ClassBA),
+    ('\u2057', '\u2057', ClassPO),
+    ('\u2058', '\u205B', ClassBA),
+    ('\u205C', '\u205C', ClassAL),
+    ('\u205D', '\u205F', ClassBA),
+    ('\u2060', '\u2060', ClassWJ),
+    ('\u2061', '\u2064', ClassAL),
+    ('\u2066', '\u206F', ClassCM),
+    ('\u2070', '\u2071', ClassAL),
+    ('\u2074', '\u2074', ClassAI),
+    ('\u2075', '\u207C', ClassAL),
+    ('\u207D', '\u207D', ClassOP),
+    ('\u207E', '\u207E', ClassCL),
+    ('\u207F', '\u207F', ClassAI),
+    ('\u2080', '\u2080', ClassAL),
+    ('\u2081', '\u2084', ClassAI),
+    ('\u2085', '\u208C', ClassAL),
+    ('\u208D', '\u208D', ClassOP),
+    ('\u208E', '\u208E', ClassCL),
+    ('\u2090', '\u209C', ClassAL),
+    ('\u20A0', '\u20A6', ClassPR),
+    ('\u20A7', '\u20A7', ClassPO),
+    ('\u20A8', '\u20B5', ClassPR),
+    ('\u20B6', '\u20B6', ClassPO),
+    ('\u20B7', '\u20BA', ClassPR),
+    ('\u20BB', '\u20BB', ClassPO),
+    ('\u20BC', '\u20BD', ClassPR),
+    ('\u20BE', '\u20BE', ClassPO),
+    ('\u20BF', '\u20BF', ClassPR),
+    ('\u20C0', '\u20C0', ClassPO),
+    ('\u20C1', '\u20CF', ClassPR),
+    ('\u20D0', '\u20F0', ClassCM),
+    ('\u2100', '\u2102', ClassAL),
+    ('\u2103', '\u2103', ClassPO),
+    ('\u2104', '\u2104', ClassAL),
+    ('\u2105', '\u2105', ClassAI),
+    ('\u2106', '\u2108', ClassAL),
+    ('\u2109', '\u2109', ClassPO),
+    ('\u210A', '\u2112', ClassAL),
+    ('\u2113', '\u2113', ClassAI),
+    ('\u2114', '\u2115', ClassAL),
+    ('\u2116', '\u2116', ClassPR),
+    ('\u2117', '\u2120', ClassAL),
+    ('\u2121', '\u2122', ClassAI),
+    ('\u2123', '\u212A', ClassAL),
+    ('\u212B', '\u212B', ClassAI),
+    ('\u212C', '\u214F', ClassAL),
+    ('\u2150', '\u215E', ClassAI),
+    ('\u215F', '\u215F', ClassAL),
+    ('\u2160', '\u216B', ClassAI),
+    ('\u216C', '\u216F', ClassAL),
+    ('\u2170', '\u2179', ClassAI),
+    ('\u217A', '\u2188', ClassAL),
+    ('\u2189', '\u2189', ClassAI),
+    ('\u218A', '\u218B', ClassAL),
+    ('\u2190', '\u2199', ClassAI),
+    ('\u219A', '\u21D1', ClassAL),
+    ('\u21D2', '\u21D2', ClassAI),
+    ('\u21D3', '\u21D3', ClassAL),
+    ('\u21D4', '\u21D4', ClassAI),
+    ('\u21D5', '\u21FF', ClassAL),
+    ('\u2200', '\u2200', ClassAI),
+    ('\u2201', '\u2201', ClassAL),
+    ('\u2202', '\u2203', ClassAI),
+    ('\u2204', '\u2206', ClassAL),
+    ('\u2207', '\u2208', ClassAI),
+    ('\u2209', '\u220A', ClassAL),
+    ('\u220B', '\u220B', ClassAI),
+    ('\u220C', '\u220E', ClassAL),
+    ('\u220F', '\u220F', ClassAI),
+    ('\u2210', '\u2210', ClassAL),
+    ('\u2211', '\u2211', ClassAI),
+    ('\u2212', '\u2213', ClassPR),
+    ('\u2214', '\u2214', ClassAL),
+    ('\u2215', '\u2215', ClassAI),
+    ('\u2216', '\u2219', ClassAL),
+    ('\u221A', '\u221A', ClassAI),
+    ('\u221B', '\u221C', ClassAL),
+    ('\u221D', '\u2220', ClassAI),
+    ('\u2221', '\u2222', ClassAL),
+    ('\u2223', '\u2223', ClassAI),
+    ('\u2224', '\u2224', ClassAL),
+    ('\u2225', '\u2225', ClassAI),
+    ('\u2226', '\u2226', ClassAL),
+    ('\u2227', '\u222C', ClassAI),
+    ('\u222D', '\u222D', ClassAL),
+    ('\u222E', '\u222E', ClassAI),
+    ('\u222F', '\u2233', ClassAL),
+    ('\u2234', '\u2237', ClassAI),
+    ('\u2238', '\u223B', ClassAL),
+    ('\u223C', '\u223D', ClassAI),
+    ('\u223E', '\u2247', ClassAL),
+    ('\u2248', '\u2248', ClassAI),
+    ('\u2249', '\u224B', ClassAL),
+    ('\u224C', '\u224C', ClassAI),
+    ('\u224D', '\u2251', ClassAL),
+    ('\u2252', '\u2252', ClassAI),
+    ('\u2253', '\u225F', ClassAL),
+    ('\u2260', '\u2261', ClassAI),
+    ('\u2262', '\u2263', ClassAL),
+    ('\u2264', '\u2267', ClassAI),
+    ('\u2268', '\u2269', ClassAL),
+    ('\u226A', '\u226B', ClassAI),
+    ('\u226C', '\u226D', ClassAL),
+    ('\u226E', '\u226F', ClassAI),
+    ('\u2270', '\u2281', ClassAL),
+    ('\u2282', '\u2283', ClassAI),
+    ('\u2284', '\u2285', ClassAL),
+    ('\u2286', '\u2287', ClassAI),
+    ('\u2288', '\u2294', ClassAL),
+    ('\u2295', '\u2295', ClassAI),
+    ('\u2296', '\u2298', ClassAL),
+    ('\u2299', '\u2299', ClassAI),
+    ('\u229A', '\u22A4', ClassAL),
+    ('\u22A5', '\u22A5', ClassAI),
+    ('\u22A6', '\u22BE', ClassAL),
+    ('\u22BF', '\u22BF', ClassAI),
+    ('\u22C0', '\u22EE', ClassAL),
+    ('\u22EF', '\u22EF', ClassIN),
+    ('\u22F0', '\u2307', ClassAL),
+    ('\u2308', '\u2308', ClassOP),
+    ('\u2309', '\u2309', ClassCL),
+    ('\u230A', '\u230A', ClassOP),
+    ('\u230B', '\u230B', ClassCL),
+    ('\u230C', '\u2311', ClassAL),
+    ('\u2312', '\u2312', ClassAI),
+    ('\u2313', '\u2319', ClassAL),
+    ('\u231A', '\u231B', ClassID),
+    ('\u231C', '\u2328', ClassAL),
+    ('\u2329', '\u2329', ClassOP),
+    ('\u232A', '\u232A', ClassCL),
+    ('\u232B', '\u23EF', ClassAL),
+    ('\u23F0', '\u23F3', ClassID),
+    ('\u23F4', '\u2429', ClassAL),
+    ('\u2440', '\u244A', ClassAL),
+    ('\u2460', '\u24FE', ClassAI),
+    ('\u24FF', '\u24FF', ClassAL),
+    ('\u2500', '\u254B', ClassAI),
+    ('\u254C', '\u254F', ClassAL),
+    ('\u2550', '\u2574', ClassAI),
+    ('\u2575', '\u257F', ClassAL),
+    ('\u2580', '\u258F', ClassAI),
+    ('\u2590', '\u2591', ClassAL),
+    ('\u2592', '\u2595', ClassAI),
+    ('\u2596', '\u259F', ClassAL),
+    ('\u25A0', '\u25A1', ClassAI),
+    ('\u25A2', '\u25A2', ClassAL),
+    ('\u25A3', '\u25A9', ClassAI),
+    ('\u25AA', '\u25B1', ClassAL),
+    ('\u25B2', '\u25B3', ClassAI),
+    ('\u25B4', '\u25B5', ClassAL),
+    ('\u25B6', '\u25B7', ClassAI),
+    ('\u25B8', '\u25BB', ClassAL),
+    ('\u25BC', '\u25BD', ClassAI),
+    ('\u25BE', '\u25BF', ClassAL),
+    ('\u25C0', '\u25C1', ClassAI),
+    ('\u25C2', '\u25C5', ClassAL),
+    ('\u25C6', '\u25C8', ClassAI),
+    ('\u25C9', '\u25CA', ClassAL),
+    ('\u25CB', '\u25CB', ClassAI),
+    ('\u25CC', '\u25CD', ClassAL),
+    ('\u25CE', '\u25D1', ClassAI),
+    ('\u25D2', '\u25E1', ClassAL),
+    ('\u25E2', '\u25E5', ClassAI),
+    ('\u25E6', '\u25EE', ClassAL),
+    ('\u25EF', '\u25EF', ClassAI),
+    ('\u25F0', '\u25FF', ClassAL),
+    ('\u2600', '\u2603', ClassID),
+    ('\u2604', '\u2604', ClassAL),
+    ('\u2605', '\u2606', ClassAI),
+    ('\u2607', '\u2608', ClassAL),
+    ('\u2609', '\u2609', ClassAI),
+    ('\u260A', '\u260D', ClassAL),
+    ('\u260E', '\u260F', ClassAI),
+    ('\u2610', '\u2613', ClassAL),
+    ('\u2614', '\u2615', ClassID),
+    ('\u2616', '\u2617', ClassAI),
+    ('\u2618', '\u2618', ClassID),
+    ('\u2619', '\u2619', ClassAL),
+    ('\u261A', '\u261C', ClassID),
+    ('\u261D', '\u261D', ClassEB),
+    ('\u261E', '\u261F', ClassID),
+    ('\u2620', '\u2638', ClassAL),
+    ('\u2639', '\u263B', ClassID),
+    ('\u263C', '\u263F', ClassAL),
+    ('\u2640', '\u2640', ClassAI),
+    ('\u2641', '\u2641', ClassAL),
+    ('\u2642', '\u2642', ClassAI),
+    ('\u2643', '\u265F', ClassAL),
+    ('\u2660', '\u2661', ClassAI),
+    ('\u2662', '\u2662', ClassAL),
+    ('\u2663', '\u2665', ClassAI),
+    ('\u2666', '\u2666', ClassAL),
+    ('\u2667', '\u2667', ClassAI),
+    ('\u2668', '\u2668', ClassID),
+    ('\u2669', '\u266A', ClassAI),
+    ('\u266B', '\u266B', ClassAL),
+    ('\u266C', '\u266D', ClassAI),
+    ('\u266E', '\u266E', ClassAL),
+    ('\u266F', '\u266F', ClassAI),
+    ('\u2670', '\u267E', ClassAL),
+    ('\u267F', '\u267F', ClassID),
+    ('\u2680', '\u269D', ClassAL),
+    ('\u269E', '\u269F', ClassAI),
+    ('\u26A0', '\u26BC', ClassAL),
+    ('\u26BD', '\u26C8', ClassID),
+    ('\u26C9', '\u26CC', ClassAI),
+    ('\u26CD', '\u26CD', ClassID),
+    ('\u26CE', '\u26CE', ClassAL),
+    ('\u26CF', '\u26D1', ClassID),
+    ('\u26D2', '\u26D2', ClassAI),
+    ('\u26D3', '\u26D4', ClassID),
+    ('\u26D5', '\u26D7', ClassAI),
+    ('\u26D8', '\u26D9', ClassID),
+    ('\u26DA', '\u26DB', ClassAI),
+    ('\u26DC', '\u26DC', ClassID),
+    ('\u26DD', '\u26DE', ClassAI),
+    ('\u26DF', '\u26E1', ClassID),
+    ('\u26E2', '\u26E2', ClassAL),
+    ('\u26E3', '\u26E3', ClassAI),
+    ('\u26E4', '\u26E7', ClassAL),
+    ('\u26E8', '\u26E9', ClassAI),
+    ('\u26EA', '\u26EA', ClassID),
+    ('\u26EB', '\u26F0', ClassAI),
+    ('\u26F1', '\u26F5', ClassID),
+    ('\u26F6', '\u26F6', ClassAI),
+    ('\u26F7', '\u26F8', ClassID),
+    ('\u26F9', '\u26F9', ClassEB),
+    ('\u26FA', '\u26FA', ClassID),
+    ('\u26FB', '\u26FC', ClassAI),
+    ('\u26FD', '\u2704', ClassID),
+    ('\u2705', '\u2707', ClassAL),
+    ('\u2708', '\u2709', ClassID),
+    ('\u270A', '\u270D', ClassEB),
+    ('\u270E', '\u2756', ClassAL),
+    ('\u2757', '\u2757', ClassAI),
+    ('\u2758', '\u275A', ClassAL),
+    ('\u275B', '\u2760', ClassQU),
+    ('\u2761', '\u2761', ClassAL),
+    ('\u2762', '\u2763', ClassEX),
+    ('\u2764', '\u2764', ClassID),
+    ('\u2765', '\u2767', ClassAL),
+    ('\u2768', '\u2768', ClassOP),
+    ('\u2769', '\u2769', ClassCL),
+    ('\u276A', '\u276A', ClassOP),
+    ('\u276B', '\u276B', ClassCL),
+    ('\u276C', '\u276C', ClassOP),
+    ('\u276D', '\u276D', ClassCL),
+    ('\u276E', '\u276E', ClassOP),
+    ('\u276F', '\u276F', ClassCL),
+    ('\u2770', '\u2770', ClassOP),
+    ('\u2771', '\u2771', ClassCL),
+    ('\u2772', '\u2772', ClassOP),
+    ('\u2773', '\u2773', ClassCL),
+    ('\u2774', '\u2774', ClassOP),
+    ('\u2775', '\u2775', ClassCL),
+    ('\u2776', '\u2793', ClassAI),
+    ('\u2794', '\u27C4', ClassAL),
+    ('\u27C5', '\u27C5', ClassOP),
+    ('\u27C6', '\u27C6', ClassCL),
+    ('\u27C7', '\u27E5', ClassAL),
+    ('\u27E6', '\u27E6', ClassOP),
+    ('\u27E7', '\u27E7', ClassCL),
+    ('\u27E8', '\u27E8', ClassOP),
+    ('\u27E9', '\u27E9', ClassCL),
+    ('\u27EA', '\u27EA', ClassOP),
+    ('\u27EB', '\u27EB', ClassCL),
+    ('\u27EC', '\u27EC', ClassOP),
+    ('\u27ED', '\u27ED', ClassCL),
+    ('\u27EE', '\u27EE', ClassOP),
+    ('\u27EF', '\u27EF', ClassCL),
+    ('\u27F0', '\u27FF', ClassAL),
+    ('\u2800', '\u2800', ClassBA),
+    ('\u2801', '\u2982', ClassAL),
+    ('\u2983', '\u2983', ClassOP),
+    ('\u2984', '\u2984', ClassCL),
+    ('\u2985', '\u2985', ClassOP),
+    ('\u2986', '\u2986', ClassCL),
+    ('\u2987', '\u2987', ClassOP),
+    ('\u2988', '\u2988', ClassCL),
+    ('\u2989', '\u2989', ClassOP),
+    ('\u298A', '\u298A', ClassCL),
+    ('\u298B', '\u298B', ClassOP),
+    ('\u298C', '\u298C', ClassCL),
+    ('\u298D', '\u298D', ClassOP),
+    ('\u298E', '\u298E', ClassCL),
+    ('\u298F', '\u298F', ClassOP),
+    ('\u2990', '\u2990', ClassCL),
+    ('\u2991', '\u2991', ClassOP),
+    ('\u2992', '\u2992', ClassCL),
+    ('\u2993', '\u2993', ClassOP),
+    ('\u2994', '\u2994', ClassCL),
+    ('\u2995', '\u2995', ClassOP),
+    ('\u2996', '\u2996', ClassCL),
+    ('\u2997', '\u2997', ClassOP),
+    ('\u2998', '\u2998', ClassCL),
+    ('\u2999', '\u29D7', ClassAL),
+    ('\u29D8', '\u29D8', ClassOP),
+    ('\u29D9', '\u29D9', ClassCL),
+    ('\u29DA', '\u29DA', ClassOP),
+    ('\u29DB', '\u29DB', ClassCL),
+    ('\u29DC', '\u29FB', ClassAL),
+    ('\u29FC', '\u29FC', ClassOP),
+    ('\u29FD', '\u29FD', ClassCL),
+    ('\u29FE', '\u2B54', ClassAL),
+    ('\u2B55', '\u2B59', ClassAI),
+    ('\u2B5A', '\u2B73', ClassAL),
+    ('\u2B76', '\u2CEE', ClassAL),
+    ('\u2CEF', '\u2CF1', ClassCM),
+    ('\u2CF2', '\u2CF3', ClassAL),
+    ('\u2CF9', '\u2CF9', ClassEX),
+    ('\u2CFA', '\u2CFC', ClassBA),
+    ('\u2CFD', '\u2CFD', ClassAL),
+    ('\u2CFE', '\u2CFE', ClassEX),
+    ('\u2CFF', '\u2CFF', ClassBA),
+    ('\u2D00', '\u2D25', ClassAL),
+    ('\u2D27', '\u2D27', ClassAL),
+    ('\u2D2D', '\u2D2D', ClassAL),
+    ('\u2D30', '\u2D67', ClassAL),
+    ('\u2D6F', '\u2D6F', ClassAL),
+    ('\u2D70', '\u2D70', ClassBA),
+    ('\u2D7F', '\u2D7F', ClassCM),
+    ('\u2D80', '\u2D96', ClassAL),
+    ('\u2DA0', '\u2DA6', ClassAL),
+    ('\u2DA8', '\u2DAE', ClassAL),
+    ('\u2DB0', '\u2DB6', ClassAL),
+    ('\u2DB8', '\u2DBE', ClassAL),
+    ('\u2DC0', '\u2DC6', ClassAL),
+    ('\u2DC8', '\u2DCE', ClassAL),
+    ('\u2DD0', '\u2DD6', ClassAL),
+    ('\u2DD8', '\u2DDE', ClassAL),
+    ('\u2DE0', '\u2DFF', ClassCM),
+    ('\u2E00', '\u2E0D', ClassQU),
+    ('\u2E0E', '\u2E15', ClassBA),
+    ('\u2E16', '\u2E16', ClassAL),
+    ('\u2E17', '\u2E17', ClassHH),
+    ('\u2E18', '\u2E18', ClassOP),
+    ('\u2E19', '\u2E19', ClassBA),
+    ('\u2E1A', '\u2E1B', ClassAL),
+    ('\u2E1C', '\u2E1D', ClassQU),
+    ('\u2E1E', '\u2E1F', ClassAL),
+    ('\u2E20', '\u2E21', ClassQU),
+    ('\u2E22', '\u2E22', ClassOP),
+    ('\u2E23', '\u2E23', ClassCL),
+    ('\u2E24', '\u2E24', ClassOP),
+    ('\u2E25', '\u2E25', ClassCL),
+    ('\u2E26', '\u2E26', ClassOP),
+    ('\u2E27', '\u2E27', ClassCL),
+    ('\u2E28', '\u2E28', ClassOP),
+    ('\u2E29', '\u2E29', ClassCL),
+    ('\u2E2A', '\u2E2D', ClassBA),
+    ('\u2E2E', '\u2E2E', ClassEX),
+    ('\u2E2F', '\u2E2F', ClassAL),
+    ('\u2E30', '\u2E31', ClassBA),
+    ('\u2E32', '\u2E32', ClassAL),
+    ('\u2E33', '\u2E34', ClassBA),
+    ('\u2E35', '\u2E39', ClassAL),
+    ('\u2E3A', '\u2E3B', ClassB2),
+    ('\u2E3C', '\u2E3E', ClassBA),
+    ('\u2E3F', '\u2E3F', ClassAL),
+    ('\u2E40', '\u2E40', ClassHH),
+    ('\u2E41', '\u2E41', ClassBA),
+    ('\u2E42', '\u2E42', ClassOP),
+    ('\u2E43', '\u2E4A', ClassBA),
+    ('\u2E4B', '\u2E4B', ClassAL),
+    ('\u2E4C', '\u2E4C', ClassBA),
+    ('\u2E4D', '\u2E4D', ClassAL),
+    ('\u2E4E', '\u2E4F', ClassBA),
+    ('\u2E50', '\u2E52', ClassAL),
+    ('\u2E53', '\u2E54', ClassEX),
+    ('\u2E55', '\u2E55', ClassOP),
+    ('\u2E56', '\u2E56', ClassCP),
+    ('\u2E57', '\u2E57', ClassOP),
+    ('\u2E58', '\u2E58', ClassCP),
+    ('\u2E59', '\u2E59', ClassOP),
+    ('\u2E5A', '\u2E5A', ClassCP),
+    ('\u2E5B', '\u2E5B', ClassOP),
+    ('\u2E5C', '\u2E5C', ClassCP),
+    ('\u2E5D', '\u2E5D', ClassHH),
+    ('\u2E80', '\u2E99', ClassID),
+    ('\u2E9B', '\u2EF3', ClassID),
+    ('\u2F00', '\u2FD5', ClassID),
+    ('\u2FF0', '\u2FFF', ClassID),
+    ('\u3000', '\u3000', ClassBA),
+    ('\u3001', '\u3002', ClassCL),
+    ('\u3003', '\u3004', ClassID),
+    ('\u3005', '\u3005', ClassNS),
+    ('\u3006', '\u3007', ClassID),
+    ('\u3008', '\u3008', ClassOP),
+    ('\u3009', '\u3009', ClassCL),
+    ('\u300A', '\u300A', ClassOP),
+    ('\u300B', '\u300B', ClassCL),
+    ('\u300C', '\u300C', ClassOP),
+    ('\u300D', '\u300D', ClassCL),
+    ('\u300E', '\u300E', ClassOP),
+    ('\u300F', '\u300F', ClassCL),
+    ('\u3010', '\u3010', ClassOP),
+    ('\u3011', '\u3011', ClassCL),
+    ('\u3012', '\u3013', ClassID),
+    ('\u3014', '\u3014', ClassOP),
+    ('\u3015', '\u3015', ClassCL),
+    ('\u3016', '\u3016', ClassOP),
+    ('\u3017', '\u3017', ClassCL),
+    ('\u3018', '\u3018', ClassOP),
+    ('\u3019', '\u3019', ClassCL),
+    ('\u301A', '\u301A', ClassOP),
+    ('\u301B', '\u301B', ClassCL),
+    ('\u301C', '\u301C', ClassNS),
+    ('\u301D', '\u301D', ClassOP),
+    ('\u301E', '\u301F', ClassCL),
+    ('\u3020', '\u3029', ClassID),
+    ('\u302A', '\u302F', ClassCM),
+    ('\u3030', '\u3034', ClassID),
+    ('\u3035', '\u3035', ClassCM),
+    ('\u3036', '\u303A', ClassID),
+    ('\u303B', '\u303C', ClassNS),
+    ('\u303D', '\u303F', ClassID),
+    ('\u3041', '\u3041', ClassCJ),
+    ('\u3042', '\u3042', ClassID),
+    ('\u3043', '\u3043', ClassCJ),
+    ('\u3044', '\u3044', ClassID),
+    ('\u3045', '\u3045', ClassCJ),
+    ('\u3046', '\u3046', ClassID),
+    ('\u3047', '\u3047', ClassCJ),
+    ('\u3048', '\u3048', ClassID),
+    ('\u3049', '\u3049', ClassCJ),
+    ('\u304A', '\u3062', ClassID),
+    ('\u3063', '\u3063', ClassCJ),
+    ('\u3064', '\u3082', ClassID),
+    ('\u3083', '\u3083', ClassCJ),
+    ('\u3084', '\u3084', ClassID),
+    ('\u3085', '\u3085', ClassCJ),
+    ('\u3086', '\u3086', ClassID),
+    ('\u3087', '\u3087', ClassCJ),
+    ('\u3088', '\u308D', ClassID),
+    ('\u308E', '\u308E', ClassCJ),
+    ('\u308F', '\u3094', ClassID),
+    ('\u3095', '\u3096', ClassCJ),
+    ('\u3099', '\u309A', ClassCM),
+    ('\u309B', '\u309E', ClassNS),
+    ('\u309F', '\u309F', ClassID),
+    ('\u30A0', '\u30A0', ClassNS),
+    ('\u30A1', '\u30A1', ClassCJ),
+    ('\u30A2', '\u30A2', ClassID),
+    ('\u30A3', '\u30A3', ClassCJ),
+    ('\u30A4', '\u30A4', ClassID),
+    ('\u30A5', '\u30A5', ClassCJ),
+    ('\u30A6', '\u30A6', ClassID),
+    ('\u30A7', '\u30A7', ClassCJ),
+    ('\u30A8', '\u30A8', ClassID),
+    ('\u30A9', '\u30A9', ClassCJ),
+    ('\u30AA', '\u30C2', ClassID),
+    ('\u30C3', '\u30C3', ClassCJ),
+    ('\u30C4', '\u30E2', ClassID),
+    ('\u30E3', '\u30E3', ClassCJ),
+    ('\u30E4', '\u30E4', ClassID),
+    ('\u30E5', '\u30E5', ClassCJ),
+    ('\u30E6', '\u30E6', ClassID),
+    ('\u30E7', '\u30E7', ClassCJ),
+    ('\u30E8', '\u30ED', ClassID),
+    ('\u30EE', '\u30EE', ClassCJ),
+    ('\u30EF', '\u30F4', ClassID),
+    ('\u30F5', '\u30F6', ClassCJ),
+    ('\u30F7', '\u30FA', ClassID),
+    ('\u30FB', '\u30FB', ClassNS),
+    ('\u30FC', '\u30FC', ClassCJ),
+    ('\u30FD', '\u30FE', ClassNS),
+    ('\u30FF', '\u30FF', ClassID),
+    ('\u3105', '\u312F', ClassID),
+    ('\u3131', '\u318E', ClassID),
+    ('\u3190', '\u31E5', ClassID),
+    ('\u31EF', '\u31EF', ClassID),
+    ('\u31F0', '\u31FF', ClassCJ),
+    ('\u3200', '\u321E', ClassID),
+    ('\u3220', '\u3247', ClassID),
+    ('\u3248', '\u324F', ClassAI),
+    ('\u3250', '\u4DBF', ClassID),
+    ('\u4DC0', '\u4DFF', ClassAL),
+    ('\u4E00', '\uA014', ClassID),
+    ('\uA015', '\uA015', ClassNS),
+    ('\uA016', '\uA48C', ClassID),
+    ('\uA490', '\uA4C6', ClassID),
+    ('\uA4D0', '\uA4FD', ClassAL),
+    ('\uA4FE', '\uA4FF', ClassBA),
+    ('\uA500', '\uA60C', ClassAL),
+    ('\uA60D', '\uA60D', ClassBA),
+    ('\uA60E', '\uA60E', ClassEX),
+    ('\uA60F', '\uA60F', ClassBA),
+    ('\uA610', '\uA61F', ClassAL),
+    ('\uA620', '\uA629', ClassNU),
+    ('\uA62A', '\uA62B', ClassAL),
+    ('\uA640', '\uA66E', ClassAL),
+    ('\uA66F', '\uA672', ClassCM),
+    ('\uA673', '\uA673', ClassAL),
+    ('\uA674', '\uA67D', ClassCM),
+    ('\uA67E', '\uA69D', ClassAL),
+    ('\uA69E', '\uA69F', ClassCM),
+    ('\uA6A0', '\uA6EF', ClassAL),
+    ('\uA6F0', '\uA6F1', ClassCM),
+    ('\uA6F2', '\uA6F2', ClassAL),
+    ('\uA6F3', '\uA6F7', ClassBA),
+    ('\uA700', '\uA7DC', ClassAL),
+    ('\uA7F1', '\uA801', ClassAL),
+    ('\uA802', '\uA802', ClassCM),
+    ('\uA803', '\uA805', ClassAL),
+    ('\uA806', '\uA806', ClassCM),
+    ('\uA807', '\uA80A', ClassAL),
+    ('\uA80B', '\uA80B', ClassCM),
+    ('\uA80C', '\uA822', ClassAL),
+    ('\uA823', '\uA827', ClassCM),
+    ('\uA828', '\uA82B', ClassAL),
+    ('\uA82C', '\uA82C', ClassCM),
+    ('\uA830', '\uA837', ClassAL),
+    ('\uA838', '\uA838', ClassPO),
+    ('\uA839', '\uA839', ClassAL),
+    ('\uA840', '\uA873', ClassAL),
+    ('\uA874', '\uA875', ClassBB),
+    ('\uA876', '\uA877', ClassEX),
+    ('\uA880', '\uA881', ClassCM),
+    ('\uA882', '\uA8B3', ClassAL),
+    ('\uA8B4', '\uA8C5', ClassCM),
+    ('\uA8CE', '\uA8CF', ClassBA),
+    ('\uA8D0', '\uA8D9', ClassNU),
+    ('\uA8E0', '\uA8F1', ClassCM),
+    ('\uA8F2', '\uA8FB', ClassAL),
+    ('\uA8FC', '\uA8FC', ClassBB),
+    ('\uA8FD', '\uA8FE', ClassAL),
+    ('\uA8FF', '\uA8FF', ClassCM),
+    ('\uA900', '\uA909', ClassNU),
+    ('\uA90A', '\uA925', ClassAL),
+    ('\uA926', '\uA92D', ClassCM),
+    ('\uA92E', '\uA92F', ClassBA),
+    ('\uA930', '\uA946', ClassAL),
+    ('\uA947', '\uA953', ClassCM),
+    ('\uA95F', '\uA95F', ClassAL),
+    ('\uA960', '\uA97C', ClassJL),
+    ('\uA980', '\uA983', ClassCM),
+    ('\uA984', '\uA9B2', ClassAK),
+    ('\uA9B3', '\uA9BF', ClassCM),
+    ('\uA9C0', '\uA9C0', ClassVI),
+    ('\uA9C1', '\uA9C6', ClassID),
+    ('\uA9C7', '\uA9C9', ClassBA),
+    ('\uA9CA', '\uA9CD', ClassID),
+    ('\uA9CF', '\uA9CF', ClassBA),
+    ('\uA9D0', '\uA9D9', ClassAS),
+    ('\uA9DE', '\uA9DF', ClassID),
+    ('\uA9E0', '\uA9E4', ClassSA),
+    ('\uA9E5', '\uA9E5', ClassCM),
+    ('\uA9E6', '\uA9EF', ClassSA),
+    ('\uA9F0', '\uA9F9', ClassNU),
+    ('\uA9FA', '\uA9FE', ClassSA),
+    ('\uAA00', '\uAA28', ClassAS),
+    ('\uAA29', '\uAA36', ClassCM),
+    ('\uAA40', '\uAA42', ClassBA),
+    ('\uAA43', '\uAA43', ClassCM),
+    ('\uAA44', '\uAA4B', ClassBA),
+    ('\uAA4C', '\uAA4D', ClassCM),
+    ('\uAA50', '\uAA59', ClassAS),
+    ('\uAA5C', '\uAA5C', ClassID),
+    ('\uAA5D', '\uAA5F', ClassBA),
+    ('\uAA60', '\uAA7A', ClassSA),
+    ('\uAA7B', '\uAA7D', ClassCM),
+    ('\uAA7E', '\uAAAF', ClassSA),
+    ('\uAAB0', '\uAAB0', ClassCM),
+    ('\uAAB1', '\uAAB1', ClassSA),
+    ('\uAAB2', '\uAAB4', ClassCM),
+    ('\uAAB5', '\uAAB6', ClassSA),
+    ('\uAAB7', '\uAAB8', ClassCM),
+    ('\uAAB9', '\uAABD', ClassSA),
+    ('\uAABE', '\uAABF', ClassCM),
+    ('\uAAC0', '\uAAC0', ClassSA),
+    ('\uAAC1', '\uAAC1', ClassCM),
+    ('\uAAC2', '\uAAC2', ClassSA),
+    ('\uAADB', '\uAADF', ClassSA),
+    ('\uAAE0', '\uAAEA', ClassAL),
+    ('\uAAEB', '\uAAEF', ClassCM),
+    ('\uAAF0', '\uAAF1', ClassBA),
+    ('\uAAF2', '\uAAF4', ClassAL),
+    ('\uAAF5', '\uAAF6', ClassCM),
+    ('\uAB01', '\uAB06', ClassAL),
+    ('\uAB09', '\uAB0E', ClassAL),
+    ('\uAB11', '\uAB16', ClassAL),
+    ('\uAB20', '\uAB26', ClassAL),
+    ('\uAB28', '\uAB2E', ClassAL),
+    ('\uAB30', '\uAB6B', ClassAL),
+    ('\uAB70', '\uABE2', ClassAL),
+    ('\uABE3', '\uABEA', ClassCM),
+    ('\uABEB', '\uABEB', ClassBA),
+    ('\uABEC', '\uABED', ClassCM),
+    ('\uABF0', '\uABF9', ClassNU),
+    ('\uD7B0', '\uD7C6', ClassJV),
+    ('\uD7CB', '\uD7FB', ClassJT),
+    ('\uF900', '\uFAFF', ClassID),
+    ('\uFB00', '\uFB06', ClassAL),
+    ('\uFB13', '\uFB17', ClassAL),
+    ('\uFB1D', '\uFB1D', ClassHL),
+    ('\uFB1E', '\uFB1E', ClassCM),
+    ('\uFB1F', '\uFB28', ClassHL),
+    ('\uFB29', '\uFB29', ClassAL),
+    ('\uFB2A', '\uFB36', ClassHL),
+    ('\uFB38', '\uFB3C', ClassHL),
+    ('\uFB3E', '\uFB3E', ClassHL),
+    ('\uFB40', '\uFB41', ClassHL),
+    ('\uFB43', '\uFB44', ClassHL),
+    ('\uFB46', '\uFB4F', ClassHL),
+    ('\uFB50', '\uFD3D', ClassAL),
+    ('\uFD3E', '\uFD3E', ClassCL),
+    ('\uFD3F', '\uFD3F', ClassOP),
+    ('\uFD40', '\uFDCF', ClassAL),
+    ('\uFDF0', '\uFDFB', ClassAL),
+    ('\uFDFC', '\uFDFC', ClassPO),
+    ('\uFDFD', '\uFDFF', ClassAL),
+    ('\uFE00', '\uFE0F', ClassCM),
+    ('\uFE10', '\uFE12', ClassCL),
+    ('\uFE13', '\uFE14', ClassNS),
+    ('\uFE15', '\uFE16', ClassEX),
+    ('\uFE17', '\uFE17', ClassOP),
+    ('\uFE18', '\uFE18', ClassCL),
+    ('\uFE19', '\uFE19', ClassIN),
+    ('\uFE20', '\uFE20', ClassGL),
+    ('\uFE21', '\uFE21', ClassCM),
+    ('\uFE22', '\uFE22', ClassGL),
+    ('\uFE23', '\uFE23', ClassCM),
+    ('\uFE24', '\uFE24', ClassGL),
+    ('\uFE25', '\uFE25', ClassCM),
+    ('\uFE26', '\uFE27', ClassGL),
+    ('\uFE28', '\uFE28', ClassCM),
+    ('\uFE29', '\uFE29', ClassGL),
+    ('\uFE2A', '\uFE2A', ClassCM),
+    ('\uFE2B', '\uFE2B', ClassGL),
+    ('\uFE2C', '\uFE2C', ClassCM),
+    ('\uFE2D', '\uFE2E', ClassGL),
+    ('\uFE2F', '\uFE2F', ClassCM),
+    ('\uFE30', '\uFE34', ClassID),
+    ('\uFE35', '\uFE35', ClassOP),
+    ('\uFE36', '\uFE36', ClassCL),
+    ('\uFE37', '\uFE37', ClassOP),
+    ('\uFE38', '\uFE38', ClassCL),
+    ('\uFE39', '\uFE39', ClassOP),
+    ('\uFE3A', '\uFE3A', ClassCL),
+    ('\uFE3B', '\uFE3B', ClassOP),
+    ('\uFE3C', '\uFE3C', ClassCL),
+    ('\uFE3D', '\uFE3D', ClassOP),
+    ('\uFE3E', '\uFE3E', ClassCL),
+    ('\uFE3F', '\uFE3F', ClassOP),
+    ('\uFE40', '\uFE40', ClassCL),
+    ('\uFE41', '\uFE41', ClassOP),
+    ('\uFE42', '\uFE42', ClassCL),
+    ('\uFE43', '\uFE43', ClassOP),
+    ('\uFE44', '\uFE44', ClassCL),
+    ('\uFE45', '\uFE46', ClassID),
+    ('\uFE47', '\uFE47', ClassOP),
+    ('\uFE48', '\uFE48', ClassCL),
+    ('\uFE49', '\uFE4F', ClassID),
+    ('\uFE50', '\uFE50', ClassCL),
+    ('\uFE51', '\uFE51', ClassID),
+    ('\uFE52', '\uFE52', ClassCL),
+    ('\uFE54', '\uFE55', ClassNS),
+    ('\uFE56', '\uFE57', ClassEX),
+    ('\uFE58', '\uFE58', ClassID),
+    ('\uFE59', '\uFE59', ClassOP),
+    ('\uFE5A', '\uFE5A', ClassCL),
+    ('\uFE5B', '\uFE5B', ClassOP),
+    ('\uFE5C', '\uFE5C', ClassCL),
+    ('\uFE5D', '\uFE5D', ClassOP),
+    ('\uFE5E', '\uFE5E', ClassCL),
+    ('\uFE5F', '\uFE66', ClassID),
+    ('\uFE68', '\uFE68', ClassID),
+    ('\uFE69', '\uFE69', ClassPR),
+    ('\uFE6A', '\uFE6A', ClassPO),
+    ('\uFE6B', '\uFE6B', ClassID),
+    ('\uFE70', '\uFE74', ClassAL),
+    ('\uFE76', '\uFEFC', ClassAL),
+    ('\uFEFF', '\uFEFF', ClassWJ),
+    ('\uFF01', '\uFF01', ClassEX),
+    ('\uFF02', '\uFF03', ClassID),
+    ('\uFF04', '\uFF04', ClassPR),
+    ('\uFF05', '\uFF05', ClassPO),
+    ('\uFF06', '\uFF07', ClassID),
+    ('\uFF08', '\uFF08', ClassOP),
+    ('\uFF09', '\uFF09', ClassCL),
+    ('\uFF0A', '\uFF0B', ClassID),
+    ('\uFF0C', '\uFF0C', ClassCL),
+    ('\uFF0D', '\uFF0D', ClassID),
+    ('\uFF0E', '\uFF0E', ClassCL),
+    ('\uFF0F', '\uFF19', ClassID),
+    ('\uFF1A', '\uFF1B', ClassNS),
+    ('\uFF1C', '\uFF1E', ClassID),
+    ('\uFF1F', '\uFF1F', ClassEX),
+    ('\uFF20', '\uFF3A', ClassID),
+    ('\uFF3B', '\uFF3B', ClassOP),
+    ('\uFF3C', '\uFF3C', ClassID),
+    ('\uFF3D', '\uFF3D', ClassCL),
+    ('\uFF3E', '\uFF5A', ClassID),
+    ('\uFF5B', '\uFF5B', ClassOP),
+    ('\uFF5C', '\uFF5C', ClassID),
+    ('\uFF5D', '\uFF5D', ClassCL),
+    ('\uFF5E', '\uFF5E', ClassID),
+    ('\uFF5F', '\uFF5F', ClassOP),
+    ('\uFF60', '\uFF61', ClassCL),
+    ('\uFF62', '\uFF62', ClassOP),
+    ('\uFF63', '\uFF64', ClassCL),
+    ('\uFF65', '\uFF65', ClassNS),
+    ('\uFF66', '\uFF66', ClassID),
+    ('\uFF67', '\uFF70', ClassCJ),
+    ('\uFF71', '\uFF9D', ClassID),
+    ('\uFF9E', '\uFF9F', ClassNS),
+    ('\uFFA0', '\uFFBE', ClassID),
+    ('\uFFC2', '\uFFC7', ClassID),
+    ('\uFFCA', '\uFFCF', ClassID),
+    ('\uFFD2', '\uFFD7', ClassID),
+    ('\uFFDA', '\uFFDC', ClassID),
+    ('\uFFE0', '\uFFE0', ClassPO),
+    ('\uFFE1', '\uFFE1', ClassPR),
+    ('\uFFE2', '\uFFE4', ClassID),
+    ('\uFFE5', '\uFFE6', ClassPR),
+    ('\uFFE8', '\uFFEE', ClassAL),
+    ('\uFFF9', '\uFFFB', ClassCM),
+    ('\uFFFC', '\uFFFC', ClassCB),
+    ('\uFFFD', '\uFFFD', ClassAI),
+    ('\U00010000', '\U0001000B', ClassAL),
+    ('\U0001000D', '\U00010026', ClassAL),
+    ('\U00010028', '\U0001003A', ClassAL),
+    ('\U0001003C', '\U0001003D', ClassAL),
+    ('\U0001003F', '\U0001004D', ClassAL),
+    ('\U00010050', '\U0001005D', ClassAL),
+    ('\U00010080', '\U000100FA', ClassAL),
+    ('\U00010100', '\U00010102', ClassBA),
+    ('\U00010107', '\U00010133', ClassAL),
+    ('\U00010137', '\U0001018E', ClassAL),
+    ('\U00010190', '\U0001019C', ClassAL),
+    ('\U000101A0', '\U000101A0', ClassAL),
+    ('\U000101D0', '\U000101FC', ClassAL),
+    ('\U000101FD', '\U000101FD', ClassCM),
+    ('\U00010280', '\U0001029C', ClassAL),
+    ('\U000102A0', '\U000102D0', ClassAL),
+    ('\U000102E0', '\U000102E0', ClassCM),
+    ('\U000102E1', '\U000102FB', ClassAL),
+    ('\U00010300', '\U00010323', ClassAL),
+    ('\U0001032D', '\U0001034A', ClassAL),
+    ('\U00010350', '\U00010375', ClassAL),
+    ('\U00010376', '\U0001037A', ClassCM),
+    ('\U00010380', '\U0001039D', ClassAL),
+    ('\U0001039F', '\U0001039F', ClassBA),
+    ('\U000103A0', '\U000103C3', ClassAL),
+    ('\U000103C8', '\U000103CF', ClassAL),
+    ('\U000103D0', '\U000103D0', ClassBA),
+    ('\U000103D1', '\U000103D5', ClassAL),
+    ('\U00010400', '\U0001049D', ClassAL),
+    ('\U000104A0', '\U000104A9', ClassNU),
+    ('\U000104B0', '\U000104D3', ClassAL),
+    ('\U000104D8', '\U000104FB', ClassAL),
+    ('\U00010500', '\U00010527', ClassAL),
+    ('\U00010530', '\U00010563', ClassAL),
+    ('\U0001056F', '\U0001057A', ClassAL),
+    ('\U0001057C', '\U0001058A', ClassAL),
+    ('\U0001058C', '\U00010592', ClassAL),
+    ('\U00010594', '\U00010595', ClassAL),
+    ('\U00010597', '\U000105A1', ClassAL),
+    ('\U000105A3', '\U000105B1', ClassAL),
+    ('\U000105B3', '\U000105B9', ClassAL),
+    ('\U000105BB', '\U000105BC', ClassAL),
+    ('\U000105C0', '\U000105F3', ClassAL),
+    ('\U00010600', '\U00010736', ClassAL),
+    ('\U00010740', '\U00010755', ClassAL),
+    ('\U00010760', '\U00010767', ClassAL),
+    ('\U00010780', '\U00010785', ClassAL),
+    ('\U00010787', '\U000107B0', ClassAL),
+    ('\U000107B2', '\U000107BA', ClassAL),
+    ('\U00010800', '\U00010805', ClassAL),
+    ('\U00010808', '\U00010808', ClassAL),
+    ('\U0001080A', '\U00010835', ClassAL),
+    ('\U00010837', '\U00010838', ClassAL),
+    ('\U0001083C', '\U0001083C', ClassAL),
+    ('\U0001083F', '\U00010855', ClassAL),
+    ('\U00010857', '\U00010857', ClassBA),
+    ('\U00010858', '\U0001089E', ClassAL),
+    ('\U000108A7', '\U000108AF', ClassAL),
+    ('\U000108E0', '\U000108F2', ClassAL),
+    ('\U000108F4', '\U000108F5', ClassAL),
+    ('\U000108FB', '\U0001091B', ClassAL),
+    ('\U0001091F', '\U0001091F', ClassBA),
+    ('\U00010920', '\U00010939', ClassAL),
+    ('\U0001093F', '\U00010959', ClassAL),
+    ('\U00010980', '\U000109B7', ClassAL),
+    ('\U000109BC', '\U000109CF', ClassAL),
+    ('\U000109D2', '\U00010A00', ClassAL),
+    ('\U00010A01', '\U00010A03', ClassCM),
+    ('\U00010A05', '\U00010A06', ClassCM),
+    ('\U00010A0C', '\U00010A0F', ClassCM),
+    ('\U00010A10', '\U00010A13', ClassAL),
+    ('\U00010A15', '\U00010A17', ClassAL),
+    ('\U00010A19', '\U00010A35', ClassAL),
+    ('\U00010A38', '\U00010A3A', ClassCM),
+    ('\U00010A3F', '\U00010A3F', ClassCM),
+    ('\U00010A40', '\U00010A48', ClassAL),
+    ('\U00010A50', '\U00010A57', ClassBA),
+    ('\U00010A58', '\U00010A58', ClassAL),
+    ('\U00010A60', '\U00010A9F', ClassAL),
+    ('\U00010AC0', '\U00010AE4', ClassAL),
+    ('\U00010AE5', '\U00010AE6', ClassCM),
+    ('\U00010AEB', '\U00010AEF', ClassAL),
+    ('\U00010AF0', '\U00010AF5', ClassBA),
+    ('\U00010AF6', '\U00010AF6', ClassIN),
+    ('\U00010B00', '\U00010B35', ClassAL),
+    ('\U00010B39', '\U00010B3F', ClassBA),
+    ('\U00010B40', '\U00010B55', ClassAL),
+    ('\U00010B58', '\U00010B72', ClassAL),
+    ('\U00010B78', '\U00010B91', ClassAL),
+    ('\U00010B99', '\U00010B9C', ClassAL),
+    ('\U00010BA9', '\U00010BAF', ClassAL),
+    ('\U00010C00', '\U00010C48', ClassAL),
+    ('\U00010C80', '\U00010CB2', ClassAL),
+    ('\U00010CC0', '\U00010CF2', ClassAL),
+    ('\U00010CFA', '\U00010D23', ClassAL),
+    ('\U00010D24', '\U00010D27', ClassCM),
+    ('\U00010D30', '\U00010D39', ClassNU),
+    ('\U00010D40', '\U00010D49', ClassNU),
+    ('\U00010D4A', '\U00010D65', ClassAL),
+    ('\U00010D69', '\U00010D6D', ClassCM),
+    ('\U00010D6E', '\U00010D6E', ClassHH),
+    ('\U00010D6F', '\U00010D85', ClassAL),
+    ('\U00010D8E', '\U00010D8F', ClassAL),
+    ('\U00010E60', '\U00010E7E', ClassAL),
+    ('\U00010E80', '\U00010EA9', ClassAL),
+    ('\U00010EAB', '\U00010EAC', ClassCM),
+    ('\U00010EAD', '\U00010EAD', ClassHH),
+    ('\U00010EB0', '\U00010EB1', ClassAL),
+    ('\U00010EC2', '\U00010EC7', ClassAL),
+    ('\U00010ED0', '\U00010ED0', ClassBA),
+    ('\U00010ED1', '\U00010ED8', ClassAL),
+    ('\U00010EFA', '\U00010EFF', ClassCM),
+    ('\U00010F00', '\U00010F27', ClassAL),
+    ('\U00010F30', '\U00010F45', ClassAL),
+    ('\U00010F46', '\U00010F50', ClassCM),
+    ('\U00010F51', '\U00010F59', ClassAL),
+    ('\U00010F70', '\U00010F81', ClassAL),
+    ('\U00010F82', '\U00010F85', ClassCM),
+    ('\U00010F86', '\U00010F89', ClassAL),
+    ('\U00010FB0', '\U00010FCB', ClassAL),
+    ('\U00010FE0', '\U00010FF6', ClassAL),
+    ('\U00011000', '\U00011002', ClassCM),
+    ('\U00011003', '\U00011004', ClassAP),
+    ('\U00011005', '\U00011037', ClassAK),
+    ('\U00011038', '\U00011045', ClassCM),
+    ('\U00011046', '\U00011046', ClassVI),
+    ('\U00011047', '\U00011048', ClassBA),
+    ('\U00011049', '\U0001104D', ClassID),
+    ('\U00011052', '\U00011065', ClassID),
+    ('\U00011066', '\U0001106F', ClassAS),
+    ('\U00011070', '\U00011070', ClassCM),
+    ('\U00011071', '\U00011072', ClassAK),
+    ('\U00011073', '\U00011074', ClassCM),
+    ('\U00011075', '\U00011075', ClassAK),
+    ('\U0001107F', '\U0001107F', ClassGL),
+    ('\U00011080', '\U00011082', ClassCM),
+    ('\U00011083', '\U000110AF', ClassAL),
+    ('\U000110B0', '\U000110BA', ClassCM),
+    ('\U000110BB', '\U000110BC', ClassAL),
+    ('\U000110BD', '\U000110BD', ClassNU),
+    ('\U000110BE', '\U000110C1', ClassBA),
+    ('\U000110C2', '\U000110C2', ClassCM),
+    ('\U000110CD', '\U000110CD', ClassNU),
+    ('\U000110D0', '\U000110E8', ClassAL),
+    ('\U000110F0', '\U000110F9', ClassNU),
+    ('\U00011100', '\U00011102', ClassCM),
+    ('\U00011103', '\U00011126', ClassAL),
+    ('\U00011127', '\U00011134', ClassCM),
+    ('\U00011136', '\U0001113F', ClassNU),
+    ('\U00011140', '\U00011143', ClassBA),
+    ('\U00011144', '\U00011144', ClassAL),
+    ('\U00011145', '\U00011146', ClassCM),
+    ('\U00011147', '\U00011147', ClassAL),
+    ('\U00011150', '\U00011172', ClassAL),
+    ('\U00011173', '\U00011173', ClassCM),
+    ('\U00011174', '\U00011174', ClassAL),
+    ('\U00011175', '\U00011175', ClassBB),
+    ('\U00011176', '\U00011176', ClassAL),
+    ('\U00011180', '\U00011182', ClassCM),
+    ('\U00011183', '\U000111B2', ClassAL),
+    ('\U000111B3', '\U000111C0', ClassCM),
+    ('\U000111C1', '\U000111C4', ClassAL),
+    ('\U000111C5', '\U000111C6', ClassBA),
+    ('\U000111C7', '\U000111C7', ClassAL),
+    ('\U000111C8', '\U000111C8', ClassBA),
+    ('\U000111C9', '\U000111CC', ClassCM),
+    ('\U000111CD', '\U000111CD', ClassAL),
+    ('\U000111CE', '\U000111CF', ClassCM),
+    ('\U000111D0', '\U000111D9', ClassNU),
+    ('\U000111DA', '\U000111DA', ClassAL),
+    ('\U000111DB', '\U000111DB', ClassBB),
+    ('\U000111DC', '\U000111DC', ClassAL),
+    ('\U000111DD', '\U000111DF', ClassBA),
+    ('\U000111E1', '\U000111F4', ClassAL),
+    ('\U00011200', '\U00011211', ClassAL),
+    ('\U00011213', '\U0001122B', ClassAL),
+    ('\U0001122C', '\U00011237', ClassCM),
+    ('\U00011238', '\U00011239', ClassBA),
+    ('\U0001123A', '\U0001123A', ClassAL),
+    ('\U0001123B', '\U0001123C', ClassBA),
+    ('\U0001123D', '\U0001123D', ClassAL),
+    ('\U0001123E', '\U0001123E', ClassCM),
+    ('\U0001123F', '\U00011240', ClassAL),
+    ('\U00011241', '\U00011241', ClassCM),
+    ('\U00011280', '\U00011286', ClassAL),
+    ('\U00011288', '\U00011288', ClassAL),
+    ('\U0001128A', '\U0001128D', ClassAL),
+    ('\U0001128F', '\U0001129D', ClassAL),
+    ('\U0001129F', '\U000112A8', ClassAL),
+    ('\U000112A9', '\U000112A9', ClassBA),
+    ('\U000112B0', '\U000112DE', ClassAL),
+    ('\U000112DF', '\U000112EA', ClassCM),
+    ('\U000112F0', '\U000112F9', ClassNU),
+    ('\U00011300', '\U00011303', ClassCM),
+    ('\U00011305', '\U0001130C', ClassAK),
+    ('\U0001130F', '\U00011310', ClassAK),
+    ('\U00011313', '\U00011328', ClassAK),
+    ('\U0001132A', '\U00011330', ClassAK),
+    ('\U00011332', '\U00011333', ClassAK),
+    ('\U00011335', '\U00011339', ClassAK),
+    ('\U0001133B', '\U0001133C', ClassCM),
+    ('\U0001133D', '\U0001133D', ClassBA),
+    ('\U0001133E', '\U00011344', ClassCM),
+    ('\U00011347', '\U00011348', ClassCM),
+    ('\U0001134B', '\U0001134C', ClassCM),
+    ('\U0001134D', '\U0001134D', ClassVI),
+    ('\U00011350', '\U00011350', ClassAS),
+    ('\U00011357', '\U00011357', ClassCM),
+    ('\U0001135D', '\U0001135D', ClassBA),
+    ('\U0001135E', '\U0001135F', ClassAS),
+    ('\U00011360', '\U00011361', ClassAK),
+    ('\U00011362', '\U00011363', ClassCM),
+    ('\U00011366', '\U0001136C', ClassCM),
+    ('\U00011370', '\U00011374', ClassCM),
+    ('\U00011380', '\U00011389', ClassAS),
+    ('\U0001138B', '\U0001138B', ClassAS),
+    ('\U0001138E', '\U0001138E', ClassAS),
+    ('\U00011390', '\U00011391', ClassAS),
+    ('\U00011392', '\U000113B5', ClassAK),
+    ('\U000113B7', '\U000113B7', ClassID),
+    ('\U000113B8', '\U000113C0', ClassCM),
+    ('\U000113C2', '\U000113C2', ClassCM),
+    ('\U000113C5', '\U000113C5', ClassCM),
+    ('\U000113C7', '\U000113CA', ClassCM),
+    ('\U000113CC', '\U000113CF', ClassCM),
+    ('\U000113D0', '\U000113D0', ClassVI),
+    ('\U000113D1', '\U000113D1', ClassAP),
+    ('\U000113D2', '\U000113D2', ClassCM),
+    ('\U000113D3', '\U000113D5', ClassID),
+    ('\U000113D7', '\U000113D8', ClassID),
+    ('\U000113E1', '\U000113E2', ClassCM),
+    ('\U00011400', '\U00011434', ClassAL),
+    ('\U00011435', '\U00011446', ClassCM),
+    ('\U00011447', '\U0001144A', ClassAL),
+    ('\U0001144B', '\U0001144E', ClassBA),
+    ('\U0001144F', '\U0001144F', ClassAL),
+    ('\U00011450', '\U00011459', ClassNU),
+    ('\U0001145A', '\U0001145B', ClassBA),
+    ('\U0001145D', '\U0001145D', ClassAL),
+    ('\U0001145E', '\U0001145E', ClassCM),
+    ('\U0001145F', '\U00011461', ClassAL),
+    ('\U00011480', '\U000114AF', ClassAL),
+    ('\U000114B0', '\U000114C3', ClassCM),
+    ('\U000114C4', '\U000114C7', ClassAL),
+    ('\U000114D0', '\U000114D9', ClassNU),
+    ('\U00011580', '\U000115AE', ClassAL),
+    ('\U000115AF', '\U000115B5', ClassCM),
+    ('\U000115B8', '\U000115C0', ClassCM),
+    ('\U000115C1', '\U000115C1', ClassBB),
+    ('\U000115C2', '\U000115C3', ClassBA),
+    ('\U000115C4', '\U000115C5', ClassEX),
+    ('\U000115C6', '\U000115C8', ClassAL),
+    ('\U000115C9', '\U000115D7', ClassBA),
+    ('\U000115D8', '\U000115DB', ClassAL),
+    ('\U000115DC', '\U000115DD', ClassCM),
+    ('\U00011600', '\U0001162F', ClassAL),
+    ('\U00011630', '\U00011640', ClassCM),
+    ('\U00011641', '\U00011642', ClassBA),
+    ('\U00011643', '\U00011644', ClassAL),
+    ('\U00011650', '\U00011659', ClassNU),
+    ('\U00011660', '\U0001166C', ClassBB),
+    ('\U00011680', '\U000116AA', ClassAL),
+    ('\U000116AB', '\U000116B7', ClassCM),
+    ('\U000116B8', '\U000116B9', ClassAL),
+    ('\U000116C0', '\U000116C9', ClassNU),
+    ('\U000116D0', '\U000116E3', ClassNU),
+    ('\U00011700', '\U0001171A', ClassSA),
+    ('\U0001171D', '\U0001172B', ClassCM),
+    ('\U00011730', '\U00011739', ClassNU),
+    ('\U0001173A', '\U0001173B', ClassSA),
+    ('\U0001173C', '\U0001173E', ClassBA),
+    ('\U0001173F', '\U00011746', ClassSA),
+    ('\U00011800', '\U0001182B', ClassAL),
+    ('\U0001182C', '\U0001183A', ClassCM),
+    ('\U0001183B', '\U0001183B', ClassAL),
+    ('\U000118A0', '\U000118DF', ClassAL),
+    ('\U000118E0', '\U000118E9', ClassNU),
+    ('\U000118EA', '\U000118F2', ClassAL),
+    ('\U000118FF', '\U000118FF', ClassAL),
+    ('\U00011900', '\U00011906', ClassAK),
+    ('\U00011909', '\U00011909', ClassAK),
+    ('\U0001190C', '\U00011913', ClassAK),
+    ('\U00011915', '\U00011916', ClassAK),
+    ('\U00011918', '\U0001192F', ClassAK),
+    ('\U00011930', '\U00011935', ClassCM),
+    ('\U00011937', '\U00011938', ClassCM),
+    ('\U0001193B', '\U0001193D', ClassCM),
+    ('\U0001193E', '\U0001193E', ClassVI),
+    ('\U0001193F', '\U0001193F', ClassAP),
+    ('\U00011940', '\U00011940', ClassCM),
+    ('\U00011941', '\U00011941', ClassAP),
+    ('\U00011942', '\U00011943', ClassCM),
+    ('\U00011944', '\U00011946', ClassBA),
+    ('\U00011950', '\U00011959', ClassAS),
+    ('\U000119A0', '\U000119A7', ClassAL),
+    ('\U000119AA', '\U000119D0', ClassAL),
+    ('\U000119D1', '\U000119D7', ClassCM),
+    ('\U000119DA', '\U000119E0', ClassCM),
+    ('\U000119E1', '\U000119E1', ClassAL),
+    ('\U000119E2', '\U000119E2', ClassBB),
+    ('\U000119E3', '\U000119E3', ClassAL),
+    ('\U000119E4', '\U000119E4', ClassCM),
+    ('\U00011A00', '\U00011A00', ClassAL),
+    ('\U00011A01', '\U00011A0A', ClassCM),
+    ('\U00011A0B', '\U00011A32', ClassAL),
+    ('\U00011A33', '\U00011A39', ClassCM),
+    ('\U00011A3A', '\U00011A3A', ClassAL),
+    ('\U00011A3B', '\U00011A3E', ClassCM),
+    ('\U00011A3F', '\U00011A3F', ClassBB),
+    ('\U00011A40', '\U00011A40', ClassAL),
+    ('\U00011A41', '\U00011A44', ClassBA),
+    ('\U00011A45', '\U00011A45', ClassBB),
+    ('\U00011A46', '\U00011A46', ClassAL),
+    ('\U00011A47', '\U00011A47', ClassCM),
+    ('\U00011A50', '\U00011A50', ClassAL),
+    ('\U00011A51', '\U00011A5B', ClassCM),
+    ('\U00011A5C', '\U00011A89', ClassAL),
+    ('\U00011A8A', '\U00011A99', ClassCM),
+    ('\U00011A9A', '\U00011A9C', ClassBA),
+    ('\U00011A9D', '\U00011A9D', ClassAL),
+    ('\U00011A9E', '\U00011AA0', ClassBB),
+    ('\U00011AA1', '\U00011AA2', ClassBA),
+    ('\U00011AB0', '\U00011AF8', ClassAL),
+    ('\U00011B00', '\U00011B09', ClassBB),
+    ('\U00011B60', '\U00011B67', ClassCM),
+    ('\U00011BC0', '\U00011BE1', ClassAL),
+    ('\U00011BF0', '\U00011BF9', ClassNU),
+    ('\U00011C00', '\U00011C08', ClassAL),
+    ('\U00011C0A', '\U00011C2E', ClassAL),
+    ('\U00011C2F', '\U00011C36', ClassCM),
+    ('\U00011C38', '\U00011C3F', ClassCM),
+    ('\U00011C40', '\U00011C40', ClassAL),
+    ('\U00011C41', '\U00011C45', ClassBA),
+    ('\U00011C50', '\U00011C59', ClassNU),
+    ('\U00011C5A', '\U00011C6C', ClassAL),
+    ('\U00011C70', '\U00011C70', ClassBB),
+    ('\U00011C71', '\U00011C71', ClassEX),
+    ('\U00011C72', '\U00011C8F', ClassAL),
+    ('\U00011C92', '\U00011CA7', ClassCM),
+    ('\U00011CA9', '\U00011CB6', ClassCM),
+    ('\U00011D00', '\U00011D06', ClassAL),
+    ('\U00011D08', '\U00011D09', ClassAL),
+    ('\U00011D0B', '\U00011D30', ClassAL),
+    ('\U00011D31', '\U00011D36', ClassCM),
+    ('\U00011D3A', '\U00011D3A', ClassCM),
+    ('\U00011D3C', '\U00011D3D', ClassCM),
+    ('\U00011D3F', '\U00011D45', ClassCM),
+    ('\U00011D46', '\U00011D46', ClassAL),
+    ('\U00011D47', '\U00011D47', ClassCM),
+    ('\U00011D50', '\U00011D59', ClassNU),
+    ('\U00011D60', '\U00011D65', ClassAL),
+    ('\U00011D67', '\U00011D68', ClassAL),
+    ('\U00011D6A', '\U00011D89', ClassAL),
+    ('\U00011D8A', '\U00011D8E', ClassCM),
+    ('\U00011D90', '\U00011D91', ClassCM),
+    ('\U00011D93', '\U00011D97', ClassCM),
+    ('\U00011D98', '\U00011D98', ClassAL),
+    ('\U00011DA0', '\U00011DA9', ClassNU),
+    ('\U00011DB0', '\U00011DDB', ClassAL),
+    ('\U00011DE0', '\U00011DE9', ClassNU),
+    ('\U00011EE0', '\U00011EF1', ClassAS),
+    ('\U00011EF2', '\U00011EF2', ClassBA),
+    ('\U00011EF3', '\U00011EF6', ClassCM),
+    ('\U00011EF7', '\U00011EF8', ClassBA),
+    ('\U00011F00', '\U00011F01', ClassCM),
+    ('\U00011F02', '\U00011F02', ClassAP),
+    ('\U00011F03', '\U00011F03', ClassCM),
+    ('\U00011F04', '\U00011F10', ClassAK),
+    ('\U00011F12', '\U00011F33', ClassAK),
+    ('\U00011F34', '\U00011F3A', ClassCM),
+    ('\U00011F3E', '\U00011F41', ClassCM),
+    ('\U00011F42', '\U00011F42', ClassVI),
+    ('\U00011F43', '\U00011F44', ClassBA),
+    ('\U00011F45', '\U00011F4F', ClassID),
+    ('\U00011F50', '\U00011F59', ClassAS),
+    ('\U00011F5A', '\U00011F5A', ClassCM),
+    ('\U00011FB0', '\U00011FB0', ClassAL),
+    ('\U00011FC0', '\U00011FDC', ClassAL),
+    ('\U00011FDD', '\U00011FE0', ClassPO),
+    ('\U00011FE1', '\U00011FF1', ClassAL),
+    ('\U00011FFF', '\U00011FFF', ClassBA),
+    ('\U00012000', '\U00012399', ClassAL),
+    ('\U00012400', '\U0001246E', ClassAL),
+    ('\U00012470', '\U00012474', ClassBA),
+    ('\U00012480', '\U00012543', ClassAL),
+    ('\U00012F90', '\U00012FF2', ClassAL),
+    ('\U00013000', '\U00013257', ClassAL),
+    ('\U00013258', '\U0001325A', ClassOP),
+    ('\U0001325B', '\U0001325D', ClassCL),
+    ('\U0001325E', '\U00013281', ClassAL),
+    ('\U00013282', '\U00013282', ClassCL),
+    ('\U00013283', '\U00013285', ClassAL),
+    ('\U00013286', '\U00013286', ClassOP),
+    ('\U00013287', '\U00013287', ClassCL),
+    ('\U00013288', '\U00013288', ClassOP),
+    ('\U00013289', '\U00013289', ClassCL),
+    ('\U0001328A', '\U00013378', ClassAL),
+    ('\U00013379', '\U00013379', ClassOP),
+    ('\U0001337A', '\U0001337B', ClassCL),
+    ('\U0001337C', '\U0001342E', ClassAL),
+    ('\U0001342F', '\U0001342F', ClassOP),
+    ('\U00013430', '\U00013436', ClassGL),
+    ('\U00013437', '\U00013437', ClassOP),
+    ('\U00013438', '\U00013438', ClassCL),
+    ('\U00013439', '\U0001343B', ClassGL),
+    ('\U0001343C', '\U0001343C', ClassOP),
+    ('\U0001343D', '\U0001343D', ClassCL),
+    ('\U0001343E', '\U0001343E', ClassOP),
+    ('\U0001343F', '\U0001343F', ClassCL),
+    ('\U00013440', '\U00013440', ClassCM),
+    ('\U00013441', '\U00013446', ClassAL),
+    ('\U00013447', '\U00013455', ClassCM),
+    ('\U00013460', '\U000143FA', ClassAL),
+    ('\U00014400', '\U000145CD', ClassAL),
+    ('\U000145CE', '\U000145CE', ClassOP),
+    ('\U000145CF', '\U000145CF', ClassCL),
+    ('\U000145D0', '\U00014646', ClassAL),
+    ('\U00016100', '\U0001611D', ClassAS),
+    ('\U0001611E', '\U0001612F', ClassCM),
+    ('\U00016130', '\U00016139', ClassAS),
+    ('\U00016800', '\U00016A38', ClassAL),
+    ('\U00016A40', '\U00016A5E', ClassAL),
+    ('\U00016A60', '\U00016A69', ClassNU),
+    ('\U00016A6E', '\U00016A6F', ClassBA),
+    ('\U00016A70', '\U00016ABE', ClassAL),
+    ('\U00016AC0', '\U00016AC9', ClassNU),
+    ('\U00016AD0', '\U00016AED', ClassAL),
+    ('\U00016AF0', '\U00016AF4', ClassCM),
+    ('\U00016AF5', '\U00016AF5', ClassBA),
+    ('\U00016B00', '\U00016B2F', ClassAL),
+    ('\U00016B30', '\U00016B36', ClassCM),
+    ('\U00016B37', '\U00016B39', ClassBA),
+    ('\U00016B3A', '\U00016B43', ClassAL),
+    ('\U00016B44', '\U00016B44', ClassBA),
+    ('\U00016B45', '\U00016B45', ClassAL),
+    ('\U00016B50', '\U00016B59', ClassNU),
+    ('\U00016B5B', '\U00016B61', ClassAL),
+    ('\U00016B63', '\U00016B77', ClassAL),
+    ('\U00016B7D', '\U00016B8F', ClassAL),
+    ('\U00016D40', '\U00016D6D', ClassAL),
+    ('\U00016D6E', '\U00016D6F', ClassBA),
+    ('\U00016D70', '\U00016D79', ClassNU),
+    ('\U00016E40', '\U00016E96', ClassAL),
+    ('\U00016E97', '\U00016E98', ClassBA),
+    ('\U00016E99', '\U00016E9A', ClassAL),
+    ('\U00016EA0', '\U00016EB8', ClassAL),
+    ('\U00016EBB', '\U00016ED3', ClassAL),
+    ('\U00016F00', '\U00016F4A', ClassAL),
+    ('\U00016F4F', '\U00016F4F', ClassCM),
+    ('\U00016F50', '\U00016F50', ClassAL),
+    ('\U00016F51', '\U00016F87', ClassCM),
+    ('\U00016F8F', '\U00016F92', ClassCM),
+    ('\U00016F93', '\U00016F9F', ClassAL),
+    ('\U00016FE0', '\U00016FE3', ClassNS),
+    ('\U00016FE4', '\U00016FE4', ClassGL),
+    ('\U00016FF0', '\U00016FF1', ClassCM),
+    ('\U00016FF2', '\U00016FF3', ClassNS),
+    ('\U00016FF4', '\U00016FF6', ClassID),
+    ('\U00017000', '\U00018AFF', ClassID),
+    ('\U00018B00', '\U00018CD5', ClassAL),
+    ('\U00018CFF', '\U00018CFF', ClassAL),
+    ('\U00018D00', '\U00018D1E', ClassID),
+    ('\U00018D80', '\U00018DF2', ClassID),
+    ('\U0001AFF0', '\U0001AFF3', ClassAL),
+    ('\U0001AFF5', '\U0001AFFB', ClassAL),
+    ('\U0001AFFD', '\U0001AFFE', ClassAL),
+    ('\U0001B000', '\U0001B122', ClassID),
+    ('\U0001B132', '\U0001B132', ClassCJ),
+    ('\U0001B150', '\U0001B152', ClassCJ),
+    ('\U0001B155', '\U0001B155', ClassCJ),
+    ('\U0001B164', '\U0001B167', ClassCJ),
+    ('\U0001B170', '\U0001B2FB', ClassID),
+    ('\U0001BC00', '\U0001BC6A', ClassAL),
+    ('\U0001BC70', '\U0001BC7C', ClassAL),
+    ('\U0001BC80', '\U0001BC88', ClassAL),
+    ('\U0001BC90', '\U0001BC99', ClassAL),
+    ('\U0001BC9C', '\U0001BC9C', ClassAL),
+    ('\U0001BC9D', '\U0001BC9E', ClassCM),
+    ('\U0001BC9F', '\U0001BC9F', ClassBA),
+    ('\U0001BCA0', '\U0001BCA3', ClassCM),
+    ('\U0001CC00', '\U0001CCEF', ClassAL),
+    ('\U0001CCF0', '\U0001CCF9', ClassNU),
+    ('\U0001CCFA', '\U0001CCFC', ClassAL),
+    ('\U0001CD00', '\U0001CEB3', ClassAL),
+    ('\U0001CEBA', '\U0001CED0', ClassAL),
+    ('\U0001CEE0', '\U0001CEF0', ClassAL),
+    ('\U0001CF00', '\U0001CF2D', ClassCM),
+    ('\U0001CF30', '\U0001CF46', ClassCM),
+    ('\U0001CF50', '\U0001CFC3', ClassAL),
+    ('\U0001D000', '\U0001D0F5', ClassAL),
+    ('\U0001D100', '\U0001D126', ClassAL),
+    ('\U0001D129', '\U0001D164', ClassAL),
+    ('\U0001D165', '\U0001D169', ClassCM),
+    ('\U0001D16A', '\U0001D16C', ClassAL),
+    ('\U0001D16D', '\U0001D182', ClassCM),
+    ('\U0001D183', '\U0001D184', ClassAL),
+    ('\U0001D185', '\U0001D18B', ClassCM),
+    ('\U0001D18C', '\U0001D1A9', ClassAL),
+    ('\U0001D1AA', '\U0001D1AD', ClassCM),
+    ('\U0001D1AE', '\U0001D1EA', ClassAL),
+    ('\U0001D200', '\U0001D241', ClassAL),
+    ('\U0001D242', '\U0001D244', ClassCM),
+    ('\U0001D245', '\U0001D245', ClassAL),
+    ('\U0001D2C0', '\U0001D2D3', ClassAL),
+    ('\U0001D2E0', '\U0001D2F3', ClassAL),
+    ('\U0001D300', '\U0001D356', ClassAL),
+    ('\U0001D360', '\U0001D378', ClassAL),
+    ('\U0001D400', '\U0001D454', ClassAL),
+    ('\U0001D456', '\U0001D49C', ClassAL),
+    ('\U0001D49E', '\U0001D49F', ClassAL),
+    ('\U0001D4A2', '\U0001D4A2', ClassAL),
+    ('\U0001D4A5', '\U0001D4A6', ClassAL),
+    ('\U0001D4A9', '\U0001D4AC', ClassAL),
+    ('\U0001D4AE', '\U0001D4B9', ClassAL),
+    ('\U0001D4BB', '\U0001D4BB', ClassAL),
+    ('\U0001D4BD', '\U0001D4C3', ClassAL),
+    ('\U0001D4C5', '\U0001D505', ClassAL),
+    ('\U0001D507', '\U0001D50A', ClassAL),
+    ('\U0001D50D', '\U0001D514', ClassAL),
+    ('\U0001D516', '\U0001D51C', ClassAL),
+    ('\U0001D51E', '\U0001D539', ClassAL),
+    ('\U0001D53B', '\U0001D53E', ClassAL),
+    ('\U0001D540', '\U0001D544', ClassAL),
+    ('\U0001D546', '\U0001D546', ClassAL),
+    ('\U0001D54A', '\U0001D550', ClassAL),
+    ('\U0001D552', '\U0001D6A5', ClassAL),
+    ('\U0001D6A8', '\U0001D7CB', ClassAL),
+    ('\U0001D7CE', '\U0001D7FF', ClassNU),
+    ('\U0001D800', '\U0001D9FF', ClassAL),
+    ('\U0001DA00', '\U0001DA36', ClassCM),
+    ('\U0001DA37', '\U0001DA3A', ClassAL),
+    ('\U0001DA3B', '\U0001DA6C', ClassCM),
+    ('\U0001DA6D', '\U0001DA74', ClassAL),
+    ('\U0001DA75', '\U0001DA75', ClassCM),
+    ('\U0001DA76', '\U0001DA83', ClassAL),
+    ('\U0001DA84', '\U0001DA84', ClassCM),
+    ('\U0001DA85', '\U0001DA86', ClassAL),
+    ('\U0001DA87', '\U0001DA8A', ClassBA),
+    ('\U0001DA8B', '\U0001DA8B', ClassAL),
+    ('\U0001DA9B', '\U0001DA9F', ClassCM),
+    ('\U0001DAA1', '\U0001DAAF', ClassCM),
+    ('\U0001DF00', '\U0001DF1E', ClassAL),
+    ('\U0001DF25', '\U0001DF2A', ClassAL),
+    ('\U0001E000', '\U0001E006', ClassCM),
+    ('\U0001E008', '\U0001E018', ClassCM),
+    ('\U0001E01B', '\U0001E021', ClassCM),
+    ('\U0001E023', '\U0001E024', ClassCM),
+    ('\U0001E026', '\U0001E02A', ClassCM),
+    ('\U0001E030', '\U0001E06D', ClassAL),
+    ('\U0001E08F', '\U0001E08F', ClassCM),
+    ('\U0001E100', '\U0001E12C', ClassAL),
+    ('\U0001E130', '\U0001E136', ClassCM),
+    ('\U0001E137', '\U0001E13D', ClassAL),
+    ('\U0001E140', '\U0001E149', ClassNU),
+    ('\U0001E14E', '\U0001E14F', ClassAL),
+    ('\U0001E290', '\U0001E2AD', ClassAL),
+    ('\U0001E2AE', '\U0001E2AE', ClassCM),
+    ('\U0001E2C0', '\U0001E2EB', ClassAL),
+    ('\U0001E2EC', '\U0001E2EF', ClassCM),
+    ('\U0001E2F0', '\U0001E2F9', ClassNU),
+    ('\U0001E2FF', '\U0001E2FF', ClassPR),
+    ('\U0001E4D0', '\U0001E4EB', ClassAL),
+    ('\U0001E4EC', '\U0001E4EF', ClassCM),
+    ('\U0001E4F0', '\U0001E4F9', ClassNU),
+    ('\U0001E5D0', '\U0001E5ED', ClassAL),
+    ('\U0001E5EE', '\U0001E5EF', ClassCM),
+    ('\U0001E5F0', '\U0001E5F0', ClassAL),
+    ('\U0001E5F1', '\U0001E5FA', ClassNU),
+    ('\U0001E5FF', '\U0001E5FF', ClassAL),
+    ('\U0001E6C0', '\U0001E6DE', ClassAL),
+    ('\U0001E6E0', '\U0001E6E2', ClassAL),
+    ('\U0001E6E3', '\U0001E6E3', ClassCM),
+    ('\U0001E6E4', '\U0001E6E5', ClassAL),
+    ('\U0001E6E6', '\U0001E6E6', ClassCM),
+    ('\U0001E6E7', '\U0001E6ED', ClassAL),
+    ('\U0001E6EE', '\U0001E6EF', ClassCM),
+    ('\U0001E6F0', '\U0001E6F4', ClassAL),
+    ('\U0001E6F5', '\U0001E6F5', ClassCM),
+    ('\U0001E6FE', '\U0001E6FF', ClassAL),
+    ('\U0001E7E0', '\U0001E7E6', ClassAL),
+    ('\U0001E7E8', '\U0001E7EB', ClassAL),
+    ('\U0001E7ED', '\U0001E7EE', ClassAL),
+    ('\U0001E7F0', '\U0001E7FE', ClassAL),
+    ('\U0001E800', '\U0001E8C4', ClassAL),
+    ('\U0001E8C7', '\U0001E8CF', ClassAL),
+    ('\U0001E8D0', '\U0001E8D6', ClassCM),
+    ('\U0001E900', '\U0001E943', ClassAL),
+    ('\U0001E944', '\U0001E94A', ClassCM),
+    ('\U0001E94B', '\U0001E94B', ClassAL),
+    ('\U0001E950', '\U0001E959', ClassNU),
+    ('\U0001E95E', '\U0001E95F', ClassOP),
+    ('\U0001EC71', '\U0001ECAB', ClassAL),
+    ('\U0001ECAC', '\U0001ECAC', ClassPO),
+    ('\U0001ECAD', '\U0001ECAF', ClassAL),
+    ('\U0001ECB0', '\U0001ECB0', ClassPO),
+    ('\U0001ECB1', '\U0001ECB4', ClassAL),
+    ('\U0001ED01', '\U0001ED3D', ClassAL),
+    ('\U0001EE00', '\U0001EE03', ClassAL),
+    ('\U0001EE05', '\U0001EE1F', ClassAL),
+    ('\U0001EE21', '\U0001EE22', ClassAL),
+    ('\U0001EE24', '\U0001EE24', ClassAL),
+    ('\U0001EE27', '\U0001EE27', ClassAL),
+    ('\U0001EE29', '\U0001EE32', ClassAL),
+    ('\U0001EE34', '\U0001EE37', ClassAL),
+    ('\U0001EE39', '\U0001EE39', ClassAL),
+    ('\U0001EE3B', '\U0001EE3B', ClassAL),
+    ('\U0001EE42', '\U0001EE42', ClassAL),
+    ('\U0001EE47', '\U0001EE47', ClassAL),
+    ('\U0001EE49', '\U0001EE49', ClassAL),
+    ('\U0001EE4B', '\U0001EE4B', ClassAL),
+    ('\U0001EE4D', '\U0001EE4F', ClassAL),
+    ('\U0001EE51', '\U0001EE52', ClassAL),
+    ('\U0001EE54', '\U0001EE54', ClassAL),
+    ('\U0001EE57', '\U0001EE57', ClassAL),
+    ('\U0001EE59', '\U0001EE59', ClassAL),
+    ('\U0001EE5B', '\U0001EE5B', ClassAL),
+    ('\U0001EE5D', '\U0001EE5D', ClassAL),
+    ('\U0001EE5F', '\U0001EE5F', ClassAL),
+    ('\U0001EE61', '\U0001EE62', ClassAL),
+    ('\U0001EE64', '\U0001EE64', ClassAL),
+    ('\U0001EE67', '\U0001EE6A', ClassAL),
+    ('\U0001EE6C', '\U0001EE72', ClassAL),
+    ('\U0001EE74', '\U0001EE77', ClassAL),
+    ('\U0001EE79', '\U0001EE7C', ClassAL),
+    ('\U0001EE7E', '\U0001EE7E', ClassAL),
+    ('\U0001EE80', '\U0001EE89', ClassAL),
+    ('\U0001EE8B', '\U0001EE9B', ClassAL),
+    ('\U0001EEA1', '\U0001EEA3', ClassAL),
+    ('\U0001EEA5', '\U0001EEA9', ClassAL),
+    ('\U0001EEAB', '\U0001EEBB', ClassAL),
+    ('\U0001EEF0', '\U0001EEF1', ClassAL),
+    ('\U0001F000', '\U0001F0FF', ClassID),
+    ('\U0001F100', '\U0001F10C', ClassAI),
+    ('\U0001F10D', '\U0001F10F', ClassAL),
+    ('\U0001F110', '\U0001F12D', ClassAI),
+    ('\U0001F12E', '\U0001F12F', ClassAL),
+    ('\U0001F130', '\U0001F169', ClassAI),
+    ('\U0001F16A', '\U0001F16F', ClassAL),
+    ('\U0001F170', '\U0001F1AC', ClassAI),
+    ('\U0001F1AD', '\U0001F1AD', ClassAL),
+    ('\U0001F1AE', '\U0001F1E5', ClassID),
+    ('\U0001F1E6', '\U0001F1FF', ClassRI),
+    ('\U0001F200', '\U0001F384', ClassID),
+    ('\U0001F385', '\U0001F385', ClassEB),
+    ('\U0001F386', '\U0001F39B', ClassID),
+    ('\U0001F39C', '\U0001F39D', ClassAL),
+    ('\U0001F39E', '\U0001F3B4', ClassID),
+    ('\U0001F3B5', '\U0001F3B6', ClassAL),
+    ('\U0001F3B7', '\U0001F3BB', ClassID),
+    ('\U0001F3BC', '\U0001F3BC', ClassAL),
+    ('\U0001F3BD', '\U0001F3C1', ClassID),
+    ('\U0001F3C2', '\U0001F3C4', ClassEB),
+    ('\U0001F3C5', '\U0001F3C6', ClassID),
+    ('\U0001F3C7', '\U0001F3C7', ClassEB),
+    ('\U0001F3C8', '\U0001F3C9', ClassID),
+    ('\U0001F3CA', '\U0001F3CC', ClassEB),
+    ('\U0001F3CD', '\U0001F3FA', ClassID),
+    ('\U0001F3FB', '\U0001F3FF', ClassEM),
+    ('\U0001F400', '\U0001F441', ClassID),
+    ('\U0001F442', '\U0001F443', ClassEB),
+    ('\U0001F444', '\U0001F445', ClassID),
+    ('\U0001F446', '\U0001F450', ClassEB),
+    ('\U0001F451', '\U0001F465', ClassID),
+    ('\U0001F466', '\U0001F478', ClassEB),
+    ('\U0001F479', '\U0001F47B', ClassID),
+    ('\U0001F47C', '\U0001F47C', ClassEB),
+    ('\U0001F47D', '\U0001F480', ClassID),
+    ('\U0001F481', '\U0001F483', ClassEB),
+    ('\U0001F484', '\U0001F484', ClassID),
+    ('\U0001F485', '\U0001F487', ClassEB),
+    ('\U0001F488', '\U0001F48E', ClassID),
+    ('\U0001F48F', '\U0001F48F', ClassEB),
+    ('\U0001F490', '\U0001F490', ClassID),
+    ('\U0001F491', '\U0001F491', ClassEB),
+    ('\U0001F492', '\U0001F49F', ClassID),
+    ('\U0001F4A0', '\U0001F4A0', ClassAL),
+    ('\U0001F4A1', '\U0001F4A1', ClassID),
+    ('\U0001F4A2', '\U0001F4A2', ClassAL),
+    ('\U0001F4A3', '\U0001F4A3', ClassID),
+    ('\U0001F4A4', '\U0001F4A4', ClassAL),
+    ('\U0001F4A5', '\U0001F4A9', ClassID),
+    ('\U0001F4AA', '\U0001F4AA', ClassEB),
+    ('\U0001F4AB', '\U0001F4AE', ClassID),
+    ('\U0001F4AF', '\U0001F4AF', ClassAL),
+    ('\U0001F4B0', '\U0001F4B0', ClassID),
+    ('\U0001F4B1', '\U0001F4B2', ClassAL),
+    ('\U0001F4B3', '\U0001F4FF', ClassID),
+    ('\U0001F500', '\U0001F506', ClassAL),
+    ('\U0001F507', '\U0001F516', ClassID),
+    ('\U0001F517', '\U0001F524', ClassAL),
+    ('\U0001F525', '\U0001F531', ClassID),
+    ('\U0001F532', '\U0001F549', ClassAL),
+    ('\U0001F54A', '\U0001F573', ClassID),
+    ('\U0001F574', '\U0001F575', ClassEB),
+    ('\U0001F576', '\U0001F579', ClassID),
+    ('\U0001F57A', '\U0001F57A', ClassEB),
+    ('\U0001F57B', '\U0001F58F', ClassID),
+    ('\U0001F590', '\U0001F590', ClassEB),
+    ('\U0001F591', '\U0001F594', ClassID),
+    ('\U0001F595', '\U0001F596', ClassEB),
+    ('\U0001F597', '\U0001F5D3', ClassID),
+    ('\U0001F5D4', '\U0001F5DB', ClassAL),
+    ('\U0001F5DC', '\U0001F5F3', ClassID),
+    ('\U0001F5F4', '\U0001F5F9', ClassAL),
+    ('\U0001F5FA', '\U0001F644', ClassID),
+    ('\U0001F645', '\U0001F647', ClassEB),
+    ('\U0001F648', '\U0001F64A', ClassID),
+    ('\U0001F64B', '\U0001F64F', ClassEB),
+    ('\U0001F650', '\U0001F675', ClassAL),
+    ('\U0001F676', '\U0001F678', ClassQU),
+    ('\U0001F679', '\U0001F67B', ClassNS),
+    ('\U0001F67C', '\U0001F67F', ClassAL),
+    ('\U0001F680', '\U0001F6A2', ClassID),
+    ('\U0001F6A3', '\U0001F6A3', ClassEB),
+    ('\U0001F6A4', '\U0001F6B3', ClassID),
+    ('\U0001F6B4', '\U0001F6B6', ClassEB),
+    ('\U0001F6B7', '\U0001F6BF', ClassID),
+    ('\U0001F6C0', '\U0001F6C0', ClassEB),
+    ('\U0001F6C1', '\U0001F6CB', ClassID),
+    ('\U0001F6CC', '\U0001F6CC', ClassEB),
+    ('\U0001F6CD', '\U0001F6FF', ClassID),
+    ('\U0001F700', '\U0001F773', ClassAL),
+    ('\U0001F774', '\U0001F776', ClassID),
+    ('\U0001F777', '\U0001F77A', ClassAL),
+    ('\U0001F77B', '\U0001F77F', ClassID),
+    ('\U0001F780', '\U0001F7D4', ClassAL),
+    ('\U0001F7D5', '\U0001F7FF', ClassID),
+    ('\U0001F800', '\U0001F80B', ClassAL),
+    ('\U0001F810', '\U0001F847', ClassAL),
+    ('\U0001F850', '\U0001F859', ClassAL),
+    ('\U0001F860', '\U0001F887', ClassAL),
+    ('\U0001F890', '\U0001F8AD', ClassAL),
+    ('\U0001F8B0', '\U0001F8BB', ClassAL),
+    ('\U0001F8C0', '\U0001F8C1', ClassAL),
+    ('\U0001F8D0', '\U0001F8D8', ClassAL),
+    ('\U0001F900', '\U0001F90B', ClassAL),
+    ('\U0001F90C', '\U0001F90C', ClassEB),
+    ('\U0001F90D', '\U0001F90E', ClassID),
+    ('\U0001F90F', '\U0001F90F', ClassEB),
+    ('\U0001F910', '\U0001F917', ClassID),
+    ('\U0001F918', '\U0001F91F', ClassEB),
+    ('\U0001F920', '\U0001F925', ClassID),
+    ('\U0001F926', '\U0001F926', ClassEB),
+    ('\U0001F927', '\U0001F92F', ClassID),
+    ('\U0001F930', '\U0001F939', ClassEB),
+    ('\U0001F93A', '\U0001F93B', ClassID),
+    ('\U0001F93C', '\U0001F93E', ClassEB),
+    ('\U0001F93F', '\U0001F976', ClassID),
+    ('\U0001F977', '\U0001F977', ClassEB),
+    ('\U0001F978', '\U0001F9B4', ClassID),
+    ('\U0001F9B5', '\U0001F9B6', ClassEB),
+    ('\U0001F9B7', '\U0001F9B7', ClassID),
+    ('\U0001F9B8', '\U0001F9B9', ClassEB),
+    ('\U0001F9BA', '\U0001F9BA', ClassID),
+    ('\U0001F9BB', '\U0001F9BB', ClassEB),
+    ('\U0001F9BC', '\U0001F9CC', ClassID),
+    ('\U0001F9CD', '\U0001F9CF', ClassEB),
+    ('\U0001F9D0', '\U0001F9D0', ClassID),
+    ('\U0001F9D1', '\U0001F9DD', ClassEB),
+    ('\U0001F9DE', '\U0001F9FF', ClassID),
+    ('\U0001FA00', '\U0001FA57', ClassAL),
+    ('\U0001FA58', '\U0001FAC2', ClassID),
+    ('\U0001FAC3', '\U0001FAC5', ClassEB),
+    ('\U0001FAC6', '\U0001FAEF', ClassID),
+    ('\U0001FAF0', '\U0001FAF8', ClassEB),
+    ('\U0001FAF9', '\U0001FAFF', ClassID),
+    ('\U0001FB00', '\U0001FB92', ClassAL),
+    ('\U0001FB94', '\U0001FBEF', ClassAL),
+    ('\U0001FBF0', '\U0001FBF9', ClassNU),
+    ('\U0001FBFA', '\U0001FBFA', ClassAL),
+    ('\U0001FC00', '\U0001FFFD', ClassID),
+    ('\U00020000', '\U0002FFFD', ClassID),
+    ('\U00030000', '\U0003FFFD', ClassID),
+    ('\U000E0001', '\U000E0001', ClassCM),
+    ('\U000E0020', '\U000E007F', ClassCM),
+    ('\U000E0100', '\U000E01EF', ClassCM),
+];
+
+/// The characters whose `East_Asian_Width` property is F, W or H, from version 17.0.0 of the
+/// Unicode Character Database.
+static EAST_ASIAN_TABLE: [(char, char), ..129] = [
+    ('\u1100', '\u115F'),
+    ('\u20A9', '\u20A9'),
+    ('\u231A', '\u231B'),
+    ('\u2329', '\u232A'),
+    ('\u23E9', '\u23EC'),
+    ('\u23F0', '\u23F0'),
+    ('\u23F3', '\u23F3'),
+    ('\u25FD', '\u25FE'),
+    ('\u2614', '\u2615'),
+    ('\u2630', '\u2637'),
+    ('\u2648', '\u2653'),
+    ('\u267F', '\u267F'),
+    ('\u268A', '\u268F'),
+    ('\u2693', '\u2693'),
+    ('\u26A1', '\u26A1'),
+    ('\u26AA', '\u26AB'),
+    ('\u26BD', '\u26BE'),
+    ('\u26C4', '\u26C5'),
+    ('\u26CE', '\u26CE'),
+    ('\u26D4', '\u26D4'),
+    ('\u26EA', '\u26EA'),
+    ('\u26F2', '\u26F3'),
+    ('\u26F5', '\u26F5'),
+    ('\u26FA', '\u26FA'),
+    ('\u26FD', '\u26FD'),
+    ('\u2705', '\u2705'),
+    ('\u270A', '\u270B'),
+    ('\u2728', '\u2728'),
+    ('\u274C', '\u274C'),
+    ('\u274E', '\u274E'),
+    ('\u2753', '\u2755'),
+    ('\u2757', '\u2757'),
+    ('\u2795', '\u2797'),
+    ('\u27B0', '\u27B0'),
+    ('\u27BF', '\u27BF'),
+    ('\u2B1B', '\u2B1C'),
+    ('\u2B50', '\u2B50'),
+    ('\u2B55', '\u2B55'),
+    ('\u2E80', '\u2E99'),
+    ('\u2E9B', '\u2EF3'),
+    ('\u2F00', '\u2FD5'),
+    ('\u2FF0', '\u303E'),
+    ('\u3041', '\u3096'),
+    ('\u3099', '\u30FF'),
+    ('\u3105', '\u312F'),
+    ('\u3131', '\u318E'),
+    ('\u3190', '\u31E5'),
+    ('\u31EF', '\u321E'),
+    ('\u3220', '\u3247'),
+    ('\u3250', '\uA48C'),
+    ('\uA490', '\uA4C6'),
+    ('\uA960', '\uA97C'),
+    ('\uAC00', '\uD7A3'),
+    ('\uF900', '\uFAFF'),
+    ('\uFE10', '\uFE19'),
+    ('\uFE30', '\uFE52'),
+    ('\uFE54', '\uFE66'),
+    ('\uFE68', '\uFE6B'),
+    ('\uFF01', '\uFFBE'),
+    ('\uFFC2', '\uFFC7'),
+    ('\uFFCA', '\uFFCF'),
+    ('\uFFD2', '\uFFD7'),
+    ('\uFFDA', '\uFFDC'),
+    ('\uFFE0', '\uFFE6'),
+    ('\uFFE8', '\uFFEE'),
+    ('\U00016FE0', '\U00016FE4'),
+    ('\U00016FF0', '\U00016FF6'),
+    ('\U00017000', '\U00018CD5'),
+    ('\U00018CFF', '\U00018D1E'),
+    ('\U00018D80', '\U00018DF2'),
+    ('\U0001AFF0', '\U0001AFF3'),
+    ('\U0001AFF5', '\U0001AFFB'),
+    ('\U0001AFFD', '\U0001AFFE'),
+    ('\U0001B000', '\U0001B122'),
+    ('\U0001B132', '\U0001B132'),
+    ('\U0001B150', '\U0001B152'),
+    ('\U0001B155', '\U0001B155'),
+    ('\U0001B164', '\U0001B167'),
+    ('\U0001B170', '\U0001B2FB'),
+    ('\U0001D300', '\U0001D356'),
+    ('\U0001D360', '\U0001D376'),
+    ('\U0001F004', '\U0001F004'),
+    ('\U0001F0CF', '\U0001F0CF'),
+    ('\U0001F18E', '\U0001F18E'),
+    ('\U0001F191', '\U0001F19A'),
+    ('\U0001F200', '\U0001F202'),
+    ('\U0001F210', '\U0001F23B'),
+    ('\U0001F240', '\U0001F248'),
+    ('\U0001F250', '\U0001F251'),
+    ('\U0001F260', '\U0001F265'),
+    ('\U0001F300', '\U0001F320'),
+    ('\U0001F32D', '\U0001F335'),
+    ('\U0001F337', '\U0001F37C'),
+    ('\U0001F37E', '\U0001F393'),
+    ('\U0001F3A0', '\U0001F3CA'),
+    ('\U0001F3CF', '\U0001F3D3'),
+    ('\U0001F3E0', '\U0001F3F0'),
+    ('\U0001F3F4', '\U0001F3F4'),
+    ('\U0001F3F8', '\U0001F43E'),
+    ('\U0001F440', '\U0001F440'),
+    ('\U0001F442', '\U0001F4FC'),
+    ('\U0001F4FF', '\U0001F53D'),
+    ('\U0001F54B', '\U0001F54E'),
+    ('\U0001F550', '\U0001F567'),
+    ('\U0001F57A', '\U0001F57A'),
+    ('\U0001F595', '\U0001F596'),
+    ('\U0001F5A4', '\U0001F5A4'),
+    ('\U0001F5FB', '\U0001F64F'),
+    ('\U0001F680', '\U0001F6C5'),
+    ('\U0001F6CC', '\U0001F6CC'),
+    ('\U0001F6D0', '\U0001F6D2'),
+    ('\U0001F6D5', '\U0001F6D8'),
+    ('\U0001F6DC', '\U0001F6DF'),
+    ('\U0001F6EB', '\U0001F6EC'),
+    ('\U0001F6F4', '\U0001F6FC'),
+    ('\U0001F7E0', '\U0001F7EB'),
+    ('\U0001F7F0', '\U0001F7F0'),
+    ('\U0001F90C', '\U0001F93A'),
+    ('\U0001F93C', '\U0001F945'),
+    ('\U0001F947', '\U0001F9FF'),
+    ('\U0001FA70', '\U0001FA7C'),
+    ('\U0001FA80', '\U0001FA8A'),
+    ('\U0001FA8E', '\U0001FAC6'),
+    ('\U0001FAC8', '\U0001FAC8'),
+    ('\U0001FACD', '\U0001FADC'),
+    ('\U0001FADF', '\U0001FAEA'),
+    ('\U0001FAEF', '\U0001FAF8'),
+    ('\U00020000', '\U0002FFFD'),
+    ('\U00030000', '\U0003FFFD'),
+];
+
+/// The quotation marks (class QU) whose general category is Pi, initial punctuation.
+static INITIAL_QUOTE_TABLE: [(char, char), ..11] = [
+    ('\u00AB', '\u00AB'),
+    ('\u2018', '\u2018'),
+    ('\u201B', '\u201C'),
+    ('\u201F', '\u201F'),
+    ('\u2039', '\u2039'),
+    ('\u2E02', '\u2E02'),
+    ('\u2E04', '\u2E04'),
+    ('\u2E09', '\u2E09'),
+    ('\u2E0C', '\u2E0C'),
+    ('\u2E1C', '\u2E1C'),
+    ('\u2E20', '\u2E20'),
+];
+
+/// The quotation marks (class QU) whose general category is Pf, final punctuation.
+static FINAL_QUOTE_TABLE: [(char, char), ..10] = [
+    ('\u00BB', '\u00BB'),
+    ('\u2019', '\u2019'),
+    ('\u201D', '\u201D'),
+    ('\u203A', '\u203A'),
+    ('\u2E03', '\u2E03'),
+    ('\u2E05', '\u2E05'),
+    ('\u2E0A', '\u2E0A'),
+    ('\u2E0D', '\u2E0D'),
+    ('\u2E1D', '\u2E1D'),
+    ('\u2E21', '\u2E21'),
+];
+
+/// The unassigned code points whose `Extended_Pictographic` property is true.
+static UNASSIGNED_PICTOGRAPHIC_TABLE: [(char, char), ..36] = [
+    ('\U0001F02C', '\U0001F02F'),
+    ('\U0001F094', '\U0001F09F'),
+    ('\U0001F0AF', '\U0001F0B0'),
+    ('\U0001F0C0', '\U0001F0C0'),
+    ('\U0001F0D0', '\U0001F0D0'),
+    ('\U0001F0F6', '\U0001F0FF'),
+    ('\U0001F1AE', '\U0001F1E5'),
+    ('\U0001F203', '\U0001F20F'),
+    ('\U0001F23C', '\U0001F23F'),
+    ('\U0001F249', '\U0001F24F'),
+    ('\U0001F252', '\U0001F25F'),
+    ('\U0001F266', '\U0001F2FF'),
+    ('\U0001F6D9', '\U0001F6DB'),
+    ('\U0001F6ED', '\U0001F6EF'),
+    ('\U0001F6FD', '\U0001F6FF'),
+    ('\U0001F7DA', '\U0001F7DF'),
+    ('\U0001F7EC', '\U0001F7EF'),
+    ('\U0001F7F1', '\U0001F7FF'),
+    ('\U0001F80C', '\U0001F80F'),
+    ('\U0001F848', '\U0001F84F'),
+    ('\U0001F85A', '\U0001F85F'),
+    ('\U0001F888', '\U0001F88F'),
+    ('\U0001F8AE', '\U0001F8AF'),
+    ('\U0001F8BC', '\U0001F8BF'),
+    ('\U0001F8C2', '\U0001F8CF'),
+    ('\U0001F8D9', '\U0001F8FF'),
+    ('\U0001FA58', '\U0001FA5F'),
+    ('\U0001FA6E', '\U0001FA6F'),
+    ('\U0001FA7D', '\U0001FA7F'),
+    ('\U0001FA8B', '\U0001FA8D'),
+    ('\U0001FAC7', '\U0001FAC7'),
+    ('\U0001FAC9', '\U0001FACC'),
+    ('\U0001FADD', '\U0001FADE'),
+    ('\U0001FAEB', '\U0001FAEE'),
+    ('\U0001FAF9', '\U0001FAFF'),
+    ('\U0001FC00', '\U0001FFFD'),
+];
+
+#[cfg(test)]
+fn check_line_break_test(test: &str) {
+    // A test is written as in the Unicode LineBreakTest data: code points in hexadecimal,
+    // separated by `÷` where the line may be broken and `×` where it may not.
+    let mut text = ~"";
+    let mut expected = ~[];
+    for test.split_iter(' ').advance |token| {
+        if token == "÷" {
+            expected.push(true);
+        } else if token == "×" {
+            expected.push(false);
+        } else {
+            text.push_char(uint::from_str_radix(token, 16).get() as char);
+        }
+    }
+
+    let opportunities = find_break_opportunities(text);
+    // Leave out the start and end of the text, which the data always marks as no break and
+    // break respectively.
+    assert!(opportunities.len() + 1 == expected.len());
+    for uint::range(1, opportunities.len()) |i| {
+        if (opportunities[i] != NoBreak) != expected[i] {
+            fail!(fmt!("wrong break opportunity before character %u of %s", i, test));
+        }
+    }
+}
+
+#[test]
+fn test_line_break_class() {
+    assert!(line_break_class('a') == ClassAL);
+    assert!(line_break_class(' ') == ClassSP);
+    assert!(line_break_class('\u00A0') == ClassGL);
+    assert!(line_break_class('\u6F22') == ClassID);
+    assert!(line_break_class('\uAC00') == ClassH2);
+    assert!(line_break_class('\uAC01') == ClassH3);
+    assert!(line_break_class('\U0001F1E6') == ClassRI);
+    assert!(line_break_class('\u1B13') == ClassAK);
+    assert!(line_break_class('\u1B44') == ClassVI);
+    assert!(line_break_class('\U000E01F0') == ClassXX);
+}
+
+#[test]
+fn test_line_break_conformance() {
+    let mut count = 0;
+    for include_str!("LineBreakTest.txt").line_iter().advance |line| {
+        let test = match line.find('#') {
+            Some(comment_start) => line.slice_to(comment_start),
+            None => line,
+        }.trim();
+        if test.is_empty() {
+            loop
+        }
+        check_line_break_test(test);
+        count += 1;
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn test_line_break_rules() {
+    let tests = [
+        // LB4, LB5: Mandatory breaks after hard line breaks.
+        "× 0061 × 000A ÷ 0062 ÷",
+        "× 0061 × 000D × 000A ÷ 0062 ÷",
+        // LB7, LB18: Break after spaces, not before them.
+        "× 0061 × 0062 × 0020 × 0020 ÷ 0063 × 0064 ÷",
+        // LB8: Break after a zero width space.
+        "× 0061 × 200B ÷ 0062 ÷",
+        // LB9, LB10: Combining marks attach to their base, or act as letters after a space.
+        "× 0061 × 0301 × 0062 × 0020 ÷ 0301 ÷",
+        "× 0061 × 200D × 6F22 ÷",
+        // LB11, LB12, LB12a: Word joiners and non-breaking spaces.
+        "× 0061 × 2060 × 0020 ÷ 0062 ÷",
+        "× 0061 × 00A0 × 0062 ÷",
+        "× 0061 × 0020 ÷ 00A0 × 0062 ÷",
+        // LB13: Closing punctuation.
+        "× 0028 × 0061 × 0029 × 0020 ÷ 0062 × 0021 ÷",
+        "× 0061 × 0020 × 0021 × 0020 ÷ 0062 ÷",
+        // LB14 - LB17: Spaces after opening punctuation, quotes and dashes.
+        "× 0028 × 0020 × 0061 ÷",
+        "× 201C × 0020 × 0028 × 0061 ÷",
+        "× 0029 × 0020 × 30FC ÷ 0078 ÷",
+        "× 2014 × 0020 × 2014 ÷",
+        // LB15c: Numbers that start with a separator.
+        "× 0061 × 0020 ÷ 002E × 0031 ÷",
+        // LB19, LB19a: Quotation marks, which only break between East Asian characters.
+        "× 0061 × 0022 × 0062 × 0022 × 0020 ÷ 0063 ÷",
+        "× 0061 × 201C × 0062 × 201D × 0020 ÷ 0063 ÷",
+        "× 6F22 ÷ 201C × 5B57 × 201D ÷ 6F22 ÷",
+        // LB21, LB21a: Hyphens, soft hyphens and slashes.
+        "× 0077 × 0065 × 006C × 006C × 002D ÷ 006B × 006E × 006F × 0077 × 006E ÷",
+        "× 0061 × 2010 ÷ 0062 ÷",
+        "× 0061 × 00AD ÷ 0062 ÷",
+        "× 05D0 × 002D × 0061 ÷",
+        // LB20a: Hyphens that start a word.
+        "× 002D × 0061 ÷",
+        "× 0061 × 006E × 0064 × 002F ÷ 006F × 0072 ÷",
+        // LB22: Ellipses.
+        "× 0061 × 2026 ÷",
+        // LB23 - LB25: Numbers.
+        "× 0024 × 0031 × 002C × 0030 × 0030 × 0030 × 002E × 0030 × 0030 ÷",
+        "× 0031 × 0032 × 0025 ÷",
+        "× 0028 × 0031 × 0032 × 0029 × 0025 ÷",
+        "× 0061 × 0031 × 0062 ÷",
+        "× 0024 × 0028 × 0031 × 0029 ÷",
+        "× 0024 ÷ 0028 × 0061 × 0029 ÷",
+        "× 002D × 0031 ÷",
+        // LB26, LB27: Korean.
+        "× D55C ÷ AD6D ÷ C5B4 ÷",
+        "× 1100 × 1161 × 11A8 ÷ 1100 ÷",
+        // LB28 - LB30: Letters, numeric punctuation and parentheses.
+        "× 0061 × 0062 × 0063 ÷",
+        "× 0061 × 002E × 0062 ÷",
+        "× 0061 × 0028 × 0062 × 0029 × 0063 ÷",
+        "× 0061 ÷ 3008 × 0062 ÷",
+        // LB28a: Orthographic syllables of Brahmic scripts.
+        "× 1B13 × 1B44 × 1B13 ÷ 1B13 ÷",
+        // LB30a, LB30b: Flags and emoji modifiers.
+        "× 1F1E6 × 1F1E7 ÷ 1F1E8 × 1F1E9 ÷ 1F1EA ÷",
+        "× 261D × 1F3FB ÷ 261D ÷",
+        // LB31: Ideographs and kana, with CJK punctuation and nonstarters.
+        "× 6F22 ÷ 5B57 ÷ 304B ÷ 306A ÷",
+        "× 6F22 × 3002 ÷ 5B57 × 3001 ÷ 300C × 304B × 300D ÷",
+        "× 30C6 × 30FC × 30A3 × 3041 ÷",
+    ];
+    for tests.iter().advance |test| {
+        check_line_break_test(*test);
+    }
+}
+
+#[test]
+fn test_mandatory_breaks() {
+    let opportunities = find_break_opportunities("a\nb c");
+    assert!(opportunities == ~[NoBreak, NoBreak, MandatoryBreak, NoBreak, AllowedBreak]);
+}
@@ -8,7 +8,7 @@ use font::{Font, FontHandleMethods, FontTableMethods, FontTableTag};
 use geometry::Au;
 use platform::font::FontTable;
 use text::glyph::{GlyphStore, GlyphIndex, GlyphData};
-use text::shaping::ShaperMethods;
+use text::shaping::{ShaperMethods, ShapingOptions};
 use servo_util::range::Range;
 use text::util::{float_to_fixed, fixed_to_float, fixed_to_rounded_int};
 
@@ -25,7 +25,7 @@ use harfbuzz::{hb_blob_create, hb_face_create_for_tables};
 use harfbuzz::{hb_buffer_add_utf8};
 use harfbuzz::{hb_buffer_get_glyph_positions};
 use harfbuzz::{hb_buffer_set_direction};
-use harfbuzz::{hb_buffer_destroy};
+use harfbuzz::{hb_buffer_destroy, hb_buffer_reverse};
 use harfbuzz::{hb_face_destroy};
 use harfbuzz::{hb_font_create};
 use harfbuzz::{hb_font_destroy, hb_buffer_create};
@@ -37,12 +37,12 @@ use harfbuzz::{hb_font_set_funcs};
 use harfbuzz::{hb_font_set_ppem};
 use harfbuzz::{hb_font_set_scale};
 use harfbuzz::{hb_shape, hb_buffer_get_glyph_infos};
-use harfbuzz::{HB_MEMORY_MODE_READONLY, HB_DIRECTION_LTR};
+use harfbuzz::{HB_MEMORY_MODE_READONLY, HB_DIRECTION_LTR, HB_DIRECTION_RTL};
 use harfbuzz::{hb_blob_t};
 use harfbuzz::{hb_bool_t};
 use harfbuzz::{hb_face_t, hb_font_t};
 use harfbuzz::{hb_font_funcs_t, hb_buffer_t, hb_codepoint_t};
-use harfbuzz::{hb_glyph_info_t};
+use harfbuzz::{hb_feature_t, hb_glyph_info_t};
 use harfbuzz::{hb_glyph_position_t};
 use harfbuzz::{hb_position_t, hb_tag_t};
 
@@ -211,10 +211,11 @@ impl Shaper {
 impl ShaperMethods for Shaper {
     /// Calculate the layout metrics associated with the given text when rendered in a specific
     /// font.
-    fn shape_text(&self, text: &str, glyphs: &mut GlyphStore) {
+    fn shape_text(&self, text: &str, options: &ShapingOptions, glyphs: &mut GlyphStore) {
         unsafe {
             let hb_buffer: *hb_buffer_t = hb_buffer_create();
-            hb_buffer_set_direction(hb_buffer, HB_DIRECTION_LTR);
+            let direction = if options.is_rtl { HB_DIRECTION_RTL } else { HB_DIRECTION_LTR };
+            hb_buffer_set_direction(hb_buffer, direction);
 
             // Using as_buf because it never does a copy - we don't need the trailing null
             do str::as_buf(text) |ctext: *u8, _: uint| {
@@ -225,7 +226,24 @@ impl ShaperMethods for Shaper {
                                    text.len() as c_int);
             }
 
-            hb_shape(self.hb_font, hb_buffer, null(), 0);
+            let hb_features = do options.features.map |feature| {
+                hb_feature_t {
+                    tag: feature.tag as hb_tag_t,
+                    value: feature.value,
+                    start: 0,
+                    end: uint::max_value as c_uint,
+                }
+            };
+            hb_shape(self.hb_font,
+                     hb_buffer,
+                     vec::raw::to_ptr(hb_features),
+                     hb_features.len() as c_uint);
+
+            // Harfbuzz returns right-to-left glyphs in visual order, but glyph stores are in
+            // logical order; reversing the buffer puts the clusters back in logical order.
+            if options.is_rtl {
+                hb_buffer_reverse(hb_buffer);
+            }
             self.save_glyph_results(text, glyphs, hb_buffer);
             hb_buffer_destroy(hb_buffer);
         }
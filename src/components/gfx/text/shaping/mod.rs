@@ -7,13 +7,70 @@
 ///
 /// Currently, only harfbuzz bindings are implemented.
 
-use text::glyph::GlyphStore;
+use text::glyph::{BreakType, GlyphStore};
 
 pub use Shaper = text::shaping::harfbuzz::Shaper;
 
 pub mod harfbuzz;
 
+/// An OpenType feature for the shaper to turn on or off, such as `smcp` for small capitals or
+/// `kern` for kerning.
+#[deriving(Clone, Eq, IterBytes)]
+pub struct FontFeature {
+    /// The feature's four-character tag, packed big-endian.
+    tag: u32,
+    /// 0 turns the feature off and 1 turns it on. Larger values choose among alternates.
+    value: u32,
+}
+
+impl FontFeature {
+    /// Creates a feature from its four-character tag. Fails if the tag is not four characters.
+    pub fn new(tag: &str, value: u32) -> FontFeature {
+        assert!(tag.len() == 4);
+        let mut packed_tag = 0u32;
+        for tag.bytes_iter().advance |byte| {
+            packed_tag = (packed_tag << 8) | (byte as u32);
+        }
+        FontFeature {
+            tag: packed_tag,
+            value: value,
+        }
+    }
+}
+
+/// Per-run settings that affect the result of shaping. Two runs of the same text in the same
+/// font shape identically exactly when their options are equal, so these form part of the key of
+/// `Font`'s shaping cache.
+#[deriving(Clone, Eq, IterBytes)]
+pub struct ShapingOptions {
+    /// Whether the text consists entirely of whitespace.
+    is_whitespace: bool,
+    /// Whether the line may be broken before the first character of the text, and if so whether
+    /// breaking it there hyphenates a word.
+    break_before: BreakType,
+    /// Whether the text runs from right to left.
+    is_rtl: bool,
+    /// The OpenType features to turn on or off, in addition to the shaper's defaults. Where
+    /// several set the same feature, the last wins.
+    features: ~[FontFeature],
+}
+
+impl ShapingOptions {
+    pub fn new(is_whitespace: bool,
+               break_before: BreakType,
+               is_rtl: bool,
+               features: &[FontFeature])
+               -> ShapingOptions {
+        ShapingOptions {
+            is_whitespace: is_whitespace,
+            break_before: break_before,
+            is_rtl: is_rtl,
+            features: features.to_owned(),
+        }
+    }
+}
+
 pub trait ShaperMethods {
-    fn shape_text(&self, text: &str, glyphs: &mut GlyphStore);
+    fn shape_text(&self, text: &str, options: &ShapingOptions, glyphs: &mut GlyphStore);
 }
 
@@ -4,102 +4,216 @@
 
 use font_context::FontContext;
 use geometry::Au;
-use text::glyph::GlyphStore;
+use text::glyph::{BreakType, BreakTypeHyphen, BreakTypeNone, BreakTypeNormal, GlyphStore};
+use text::line_break::{NoBreak, find_break_opportunities};
+use text::shaping::{FontFeature, ShapingOptions};
 use font::{Font, FontDescriptor, RunMetrics};
+use servo_util::cache::Cache;
 use servo_util::range::Range;
 use extra::arc::ARC;
+use std::ptr;
 
 /// A text run.
 pub struct TextRun {
     text: ~str,
+    /// The primary font of the run: the first font of the font group it was created from.
     font: @mut Font,
+    /// Every font used to shape some part of the run. The primary font is always first.
+    fonts: ~[@mut Font],
     underline: bool,
+    /// Whether the text runs from right to left. The glyphs are stored in logical order either
+    /// way.
+    rtl: bool,
     glyphs: ~[ARC<GlyphStore>],
+    /// The index into `fonts` of the font each entry of `glyphs` was shaped with.
+    glyph_fonts: ~[uint],
 }
 
 /// This is a hack until TextRuns are normally sendable, or we instead use ARC<TextRun> everywhere.
 pub struct SendableTextRun {
     text: ~str,
-    font: FontDescriptor,
+    fonts: ~[FontDescriptor],
     underline: bool,
+    rtl: bool,
     priv glyphs: ~[ARC<GlyphStore>],
+    priv glyph_fonts: ~[uint],
+}
+
+/// The key under which a font context caches the text run it deserialized from a
+/// `SendableTextRun`. The glyph stores are identified by address: they are shared, not copied,
+/// by every sendable run serialized from the same text run, and the cached text run keeps them
+/// alive, so no other glyph store can take their place in memory.
+#[deriving(Clone, Eq, IterBytes)]
+pub struct TextRunCacheKey {
+    glyphs: ~[uint],
+    glyph_fonts: ~[uint],
+    underline: bool,
+    rtl: bool,
 }
 
 impl SendableTextRun {
+    /// Returns the text run for this sendable run, deserializing it only if the font context
+    /// hasn't already done so, as it will have when the same display list is drawn again.
+    pub fn deserialize_cached(&self, fctx: @mut FontContext) -> @TextRun {
+        let key = TextRunCacheKey {
+            glyphs: self.glyphs.map(|glyphs| ptr::to_unsafe_ptr(glyphs.get()) as uint),
+            glyph_fonts: copy self.glyph_fonts,
+            underline: self.underline,
+            rtl: self.rtl,
+        };
+        match fctx.run_cache.find(&key) {
+            // A run whose glyphs are all shaped in other fonts could still differ in its primary
+            // font.
+            Some(run) if run.fonts.map(|font| font.get_descriptor()) == self.fonts => run,
+            _ => {
+                let run = @self.deserialize(fctx);
+                fctx.run_cache.insert(key, run);
+                run
+            }
+        }
+    }
+
     pub fn deserialize(&self, fctx: @mut FontContext) -> TextRun {
-        let font = match fctx.get_font_by_descriptor(&self.font) {
-            Ok(f) => f,
-            Err(_) => fail!(fmt!("Font descriptor deserialization failed! desc=%?", self.font))
+        let fonts = do self.fonts.map |descriptor| {
+            match fctx.get_font_by_descriptor(descriptor) {
+                Ok(f) => f,
+                Err(_) => fail!(fmt!("Font descriptor deserialization failed! desc=%?",
+                                     descriptor))
+            }
         };
 
         TextRun {
             text: copy self.text,
-            font: font,
+            font: fonts[0],
+            fonts: fonts,
             underline: self.underline,
+            rtl: self.rtl,
             glyphs: self.glyphs.clone(),
+            glyph_fonts: copy self.glyph_fonts,
         }
     }
 }
 
 impl<'self> TextRun {
     pub fn new(font: @mut Font, text: ~str, underline: bool) -> TextRun {
-        let glyphs = TextRun::break_and_shape(font, text);
+        TextRun::new_with_fonts(~[font], ~[(0, 0)], text, underline, false, [], [])
+    }
+
+    /// Creates a text run whose text is drawn in several fonts. `fonts[0]` is the primary font,
+    /// and `font_segments` lists, in order, the byte offset at which each segment of the text
+    /// begins and the index into `fonts` of the font it should be shaped with. The first segment
+    /// must begin at offset 0. If `rtl` is set, the text is shaped and drawn from right to left.
+    /// `features` are the OpenType features to shape the text with. `hyphen_breaks` are the
+    /// character indices, in increasing order, before which the line may be broken by
+    /// hyphenating a word.
+    pub fn new_with_fonts(fonts: ~[@mut Font],
+                          font_segments: &[(uint, uint)],
+                          text: ~str,
+                          underline: bool,
+                          rtl: bool,
+                          features: &[FontFeature],
+                          hyphen_breaks: &[uint])
+                          -> TextRun {
+        assert!(fonts.len() > 0);
+        let (glyphs, glyph_fonts) = TextRun::break_and_shape(fonts,
+                                                             font_segments,
+                                                             text,
+                                                             rtl,
+                                                             features,
+                                                             hyphen_breaks);
 
         let run = TextRun {
             text: text,
-            font: font,
+            font: fonts[0],
+            fonts: fonts,
             underline: underline,
+            rtl: rtl,
             glyphs: glyphs,
+            glyph_fonts: glyph_fonts,
         };
         return run;
     }
 
     pub fn teardown(&self) {
-        self.font.teardown();
+        for self.fonts.iter().advance |font| {
+            font.teardown();
+        }
     }
 
-    pub fn break_and_shape(font: @mut Font, text: &str) -> ~[ARC<GlyphStore>] {
-        // TODO(Issue #230): do a better job. See Gecko's LineBreaker.
+    /// Shapes the text into slices, returning the glyphs of each slice and the index of the font
+    /// that it was shaped with. A slice ends at every line break opportunity, including those at
+    /// `hyphen_breaks`, wherever the font changes, and between whitespace and other text.
+    pub fn break_and_shape(fonts: &[@mut Font],
+                           font_segments: &[(uint, uint)],
+                           text: &str,
+                           rtl: bool,
+                           features: &[FontFeature],
+                           hyphen_breaks: &[uint])
+                           -> (~[ARC<GlyphStore>], ~[uint]) {
+        assert!(font_segments.len() > 0 && font_segments[0].first() == 0);
 
+        let opportunities = find_break_opportunities(text);
         let mut glyphs = ~[];
+        let mut glyph_fonts = ~[];
         let mut byte_i = 0u;
+        let mut char_i = 0u;
         let mut cur_slice_is_whitespace = false;
+        let mut cur_slice_break_before = BreakTypeNone;
+        let mut next_hyphen_break = 0;
         let mut byte_last_boundary = 0;
+        let mut segment_i = 0;
+        let mut cur_font = font_segments[0].second();
         while byte_i < text.len() {
             let range = text.char_range_at(byte_i);
             let ch = range.ch;
             let next = range.next;
 
-            // Slices alternate between whitespace and non-whitespace,
-            // representing line break opportunities.
-            let can_break_before = if cur_slice_is_whitespace {
-                match ch {
-                    ' ' | '\t' | '\n' => false,
-                    _ => {
-                        cur_slice_is_whitespace = false;
-                        true
-                    }
-                }
+            // Slices never span a change of font.
+            let slice_font = cur_font;
+            let font_changed = segment_i + 1 < font_segments.len() &&
+                font_segments[segment_i + 1].first() == byte_i;
+            if font_changed {
+                segment_i += 1;
+                cur_font = font_segments[segment_i].second();
+            }
+
+            let is_whitespace = match ch {
+                ' ' | '\t' | '\n' => true,
+                _ => false
+            };
+            while next_hyphen_break < hyphen_breaks.len() &&
+                    hyphen_breaks[next_hyphen_break] < char_i {
+                next_hyphen_break += 1;
+            }
+            let break_before: BreakType = if opportunities[char_i] != NoBreak {
+                BreakTypeNormal
+            } else if next_hyphen_break < hyphen_breaks.len() &&
+                    hyphen_breaks[next_hyphen_break] == char_i {
+                BreakTypeHyphen
             } else {
-                match ch {
-                    ' ' | '\t' | '\n' => {
-                        cur_slice_is_whitespace = true;
-                        true
-                    },
-                    _ => false
-                }
+                BreakTypeNone
             };
 
-            // Create a glyph store for this slice if it's nonempty.
-            if can_break_before && byte_i > byte_last_boundary {
+            // Create a glyph store for the slice before this character if it ends here.
+            let slice_ends = break_before != BreakTypeNone || font_changed ||
+                is_whitespace != cur_slice_is_whitespace;
+            if slice_ends && byte_i > byte_last_boundary {
                 let slice = text.slice(byte_last_boundary, byte_i).to_owned();
                 debug!("creating glyph store for slice %? (ws? %?), %? - %? in run %?",
-                        slice, !cur_slice_is_whitespace, byte_last_boundary, byte_i, text);
-                glyphs.push(font.shape_text(slice, !cur_slice_is_whitespace));
+                        slice, cur_slice_is_whitespace, byte_last_boundary, byte_i, text);
+                let options = ShapingOptions::new(cur_slice_is_whitespace,
+                                                  cur_slice_break_before,
+                                                  rtl,
+                                                  features);
+                glyphs.push(fonts[slice_font].shape_text(slice, &options));
+                glyph_fonts.push(slice_font);
                 byte_last_boundary = byte_i;
+                cur_slice_break_before = break_before;
             }
+            cur_slice_is_whitespace = is_whitespace;
 
             byte_i = next;
+            char_i += 1;
         }
 
         // Create a glyph store for the final slice if it's nonempty.
@@ -107,18 +221,25 @@ impl<'self> TextRun {
             let slice = text.slice(byte_last_boundary, text.len()).to_owned();
             debug!("creating glyph store for final slice %? (ws? %?), %? - %? in run %?",
                 slice, cur_slice_is_whitespace, byte_last_boundary, text.len(), text);
-            glyphs.push(font.shape_text(slice, cur_slice_is_whitespace));
+            let options = ShapingOptions::new(cur_slice_is_whitespace,
+                                              cur_slice_break_before,
+                                              rtl,
+                                              features);
+            glyphs.push(fonts[cur_font].shape_text(slice, &options));
+            glyph_fonts.push(cur_font);
         }
 
-        glyphs
+        (glyphs, glyph_fonts)
     }
 
     pub fn serialize(&self) -> SendableTextRun {
         SendableTextRun {
             text: copy self.text,
-            font: self.font.get_descriptor(),
+            fonts: self.fonts.map(|font| font.get_descriptor()),
             underline: self.underline,
+            rtl: self.rtl,
             glyphs: self.glyphs.clone(),
+            glyph_fonts: copy self.glyph_fonts,
         }
     }
 
@@ -137,8 +258,18 @@ impl<'self> TextRun {
         true
     }
 
+    /// Measures the given range. The ascent and descent are the largest of those of all the fonts
+    /// used in the range.
     pub fn metrics_for_range(&self, range: &Range) -> RunMetrics {
-        self.font.measure_text(self, range)
+        let mut advance = Au(0);
+        let mut ascent = self.font.metrics.ascent;
+        let mut descent = self.font.metrics.descent;
+        for self.iter_font_ranges_for_range(range) |font, font_range| {
+            advance += font.measure_text(self, font_range).advance_width;
+            ascent = Au::max(ascent, font.metrics.ascent);
+            descent = Au::max(descent, font.metrics.descent);
+        }
+        RunMetrics::new(advance, ascent, descent)
     }
 
     pub fn metrics_for_slice(&self, glyphs: &GlyphStore, slice_range: &Range) -> RunMetrics {
@@ -148,14 +279,80 @@ impl<'self> TextRun {
     pub fn min_width_for_range(&self, range: &Range) -> Au {
         let mut max_piece_width = Au(0);
         debug!("iterating outer range %?", range);
-        for self.iter_slices_for_range(range) |glyphs, offset, slice_range| {
-            debug!("iterated on %?[%?]", offset, slice_range);
-            let metrics = self.font.measure_text_for_slice(glyphs, slice_range);
-            max_piece_width = Au::max(max_piece_width, metrics.advance_width);
+        for self.iter_break_pieces_for_range(range) |content, _, hyphenated| {
+            debug!("iterated on piece %?", content);
+            let mut piece_width = self.metrics_for_range(content).advance_width;
+            if hyphenated {
+                piece_width += self.hyphen_width();
+            }
+            max_piece_width = Au::max(max_piece_width, piece_width);
         }
         max_piece_width
     }
 
+    /// Returns a run holding just the hyphen drawn at the end of a line that breaks a word of
+    /// this run, in the run's primary font. The font shapes the hyphen only once.
+    pub fn hyphen_run(&self) -> TextRun {
+        let (text, glyphs, _) = self.font.hyphen();
+        TextRun {
+            text: text,
+            font: self.font,
+            fonts: ~[self.font],
+            underline: self.underline,
+            rtl: false,
+            glyphs: ~[glyphs],
+            glyph_fonts: ~[0],
+        }
+    }
+
+    /// Returns the advance of the hyphen drawn at the end of a line that breaks a word of this
+    /// run.
+    pub fn hyphen_width(&self) -> Au {
+        let (_, _, advance) = self.font.hyphen();
+        advance
+    }
+
+    /// Splits the given range at its line break opportunities, and calls `f` with each of the
+    /// pieces between them. A piece is passed as the range of its content followed by the range
+    /// of the whitespace at its end, which is trimmed if the line is broken after the piece.
+    /// Either may be empty. The last argument is true if breaking the line after the piece
+    /// hyphenates a word, so that a hyphen must be drawn at the end of the line.
+    pub fn iter_break_pieces_for_range(&self,
+                                       range: &Range,
+                                       f: &fn(&Range, &Range, bool) -> bool)
+                                       -> bool {
+        let mut content = Range::new(range.begin(), 0);
+        let mut whitespace = Range::new(range.begin(), 0);
+        for self.iter_slices_for_range(range) |glyphs, offset, slice_range| {
+            let slice_begin = offset + slice_range.begin();
+            let break_type = if slice_range.begin() == 0 {
+                glyphs.can_break_before(0)
+            } else {
+                BreakTypeNone
+            };
+            if break_type != BreakTypeNone && slice_begin > range.begin() {
+                if !f(&content, &whitespace, break_type == BreakTypeHyphen) {
+                    return false;
+                }
+                content = Range::new(slice_begin, 0);
+                whitespace = Range::new(slice_begin, 0);
+            }
+
+            if glyphs.is_whitespace() {
+                whitespace.extend_by(slice_range.length() as int);
+            } else {
+                // Whitespace followed by more of the piece is part of its content.
+                content.extend_by((whitespace.length() + slice_range.length()) as int);
+                whitespace = Range::new(content.end(), 0);
+            }
+        }
+
+        if content.is_empty() && whitespace.is_empty() {
+            return true;
+        }
+        f(&content, &whitespace, false)
+    }
+
     pub fn iter_slices_for_range(&self,
                                  range: &Range,
                                  f: &fn(&GlyphStore, uint, &Range) -> bool)
@@ -176,6 +373,42 @@ impl<'self> TextRun {
         true
     }
 
+    /// Splits the given range into maximal subranges that are each drawn in a single font, and
+    /// calls `f` with each of them and its font.
+    pub fn iter_font_ranges_for_range(&self,
+                                      range: &Range,
+                                      f: &fn(@mut Font, &Range) -> bool)
+                                      -> bool {
+        let mut offset = 0;
+        let mut current: Option<(uint, Range)> = None;
+        for self.glyphs.iter().zip(self.glyph_fonts.iter()).advance |(slice_glyphs, &font_i)| {
+            let slice_len = slice_glyphs.get().char_len();
+            let char_range = range.intersect(&Range::new(offset, slice_len));
+            offset += slice_len;
+            if char_range.is_empty() {
+                loop;
+            }
+
+            current = match current {
+                Some((current_font_i, current_range)) if current_font_i == font_i => {
+                    Some((font_i, Range::new(current_range.begin(),
+                                             char_range.end() - current_range.begin())))
+                }
+                Some((current_font_i, current_range)) => {
+                    if !f(self.fonts[current_font_i], &current_range) {
+                        return false;
+                    }
+                    Some((font_i, char_range))
+                }
+                None => Some((font_i, char_range)),
+            };
+        }
+        match current {
+            Some((font_i, current_range)) => f(self.fonts[font_i], &current_range),
+            None => true,
+        }
+    }
+
     pub fn iter_natural_lines_for_range(&self, range: &Range, f: &fn(&Range) -> bool) -> bool {
         let mut clump = Range::new(range.begin(), 0);
         let mut in_clump = false;
@@ -24,6 +24,101 @@ impl Eq for CompressionMode {
     }
 }
 
+/// A record of which characters of a string a transformation kept and which it skipped, for
+/// mapping character offsets between the original string and the transformed one. Like Gecko's
+/// gfxSkipChars, it is stored compactly as the lengths of alternating runs of kept and skipped
+/// characters, starting with kept ones.
+#[deriving(Clone, Eq)]
+pub struct SkipChars {
+    /// The lengths of the runs. Runs at even indices are kept and runs at odd indices skipped;
+    /// only the first may be empty.
+    priv runs: ~[uint],
+}
+
+impl SkipChars {
+    pub fn new() -> SkipChars {
+        SkipChars {
+            runs: ~[],
+        }
+    }
+
+    /// Records that the next character of the original string was kept.
+    pub fn keep_char(&mut self) {
+        self.push_char(true);
+    }
+
+    /// Records that the next character of the original string was skipped.
+    pub fn skip_char(&mut self) {
+        self.push_char(false);
+    }
+
+    priv fn push_char(&mut self, kept: bool) {
+        if self.runs.is_empty() && !kept {
+            self.runs.push(0);
+        }
+        let last_run_kept = self.runs.len() % 2 == 1;
+        if !self.runs.is_empty() && last_run_kept == kept {
+            let last = self.runs.len() - 1;
+            self.runs[last] += 1;
+        } else {
+            self.runs.push(1);
+        }
+    }
+
+    /// Returns the length, in characters, of the original string.
+    pub fn original_len(&self) -> uint {
+        self.runs.iter().fold(0, |total, &len| total + len)
+    }
+
+    /// Returns the length, in characters, of the transformed string.
+    pub fn transformed_len(&self) -> uint {
+        let mut total = 0;
+        for self.runs.iter().enumerate().advance |(i, &len)| {
+            if i % 2 == 0 {
+                total += len;
+            }
+        }
+        total
+    }
+
+    /// Returns the offset in the transformed string of the character at the given offset in the
+    /// original string. A skipped character maps to the offset of the next kept character.
+    pub fn original_to_transformed(&self, offset: uint) -> uint {
+        let mut original = 0;
+        let mut transformed = 0;
+        for self.runs.iter().enumerate().advance |(i, &len)| {
+            let kept = i % 2 == 0;
+            if offset < original + len {
+                return if kept { transformed + offset - original } else { transformed };
+            }
+            original += len;
+            if kept {
+                transformed += len;
+            }
+        }
+        transformed
+    }
+
+    /// Returns the offset in the original string of the character at the given offset in the
+    /// transformed string. The end of the transformed string maps to the end of the original
+    /// string.
+    pub fn transformed_to_original(&self, offset: uint) -> uint {
+        let mut original = 0;
+        let mut transformed = 0;
+        for self.runs.iter().enumerate().advance |(i, &len)| {
+            let kept = i % 2 == 0;
+            if kept && offset < transformed + len {
+                return original + offset - transformed;
+            }
+            original += len;
+            if kept {
+                transformed += len;
+            }
+        }
+        original
+    }
+}
+
 // ported from Gecko's nsTextFrameUtils::TransformText. 
 // 
 // High level TODOs:
@@ -31,18 +126,22 @@ impl Eq for CompressionMode {
 // * Issue #113: consider incoming text state (arabic, etc)
 //               and propogate outgoing text state (dual of above) 
 //
-// * Issue #114: record skipped and kept chars for mapping original to new text
-//
 // * Untracked: various edge cases for bidi, CJK, etc.
-pub fn transform_text(text: &str, mode: CompressionMode, incoming_whitespace: bool) -> (~str, bool) {
+//
+// Which characters of `text` are kept and which skipped is recorded in `skip_chars`.
+pub fn transform_text(text: &str,
+                      mode: CompressionMode,
+                      incoming_whitespace: bool,
+                      skip_chars: &mut SkipChars)
+                      -> (~str, bool) {
     let mut out_str: ~str = ~"";
     let out_whitespace = match mode {
         CompressNone | DiscardNewline => {
             for text.iter().advance |ch: char| {
                 if is_discardable_char(ch, mode) {
-                    // TODO: record skipped char
+                    skip_chars.skip_char();
                 } else {
-                    // TODO: record kept char
+                    skip_chars.keep_char();
                     if ch == '\t' {
                         // TODO: set "has tab" flag
                     }
@@ -62,16 +161,16 @@ pub fn transform_text(text: &str, mode: CompressionMode, incoming_whitespace: bo
                     if is_always_discardable_char(ch) {
                         // revert whitespace setting, since this char was discarded
                         next_in_whitespace = in_whitespace;
-                        // TODO: record skipped char
+                        skip_chars.skip_char();
                     } else {
-                        // TODO: record kept char
+                        skip_chars.keep_char();
                         out_str.push_char(ch);
                     }
                 } else { /* next_in_whitespace; possibly add a space char */
                     if in_whitespace {
-                        // TODO: record skipped char
+                        skip_chars.skip_char();
                     } else {
-                        // TODO: record kept char
+                        skip_chars.keep_char();
                         out_str.push_char(' ');
                     }
                 }
@@ -103,9 +202,10 @@ pub fn transform_text(text: &str, mode: CompressionMode, incoming_whitespace: bo
         }
     }
 
-    fn is_always_discardable_char(_ch: char) -> bool {
-        // TODO: check for bidi control chars, soft hyphens.
-        false
+    fn is_always_discardable_char(ch: char) -> bool {
+        // TODO: check for bidi control chars.
+        // Soft hyphens are invisible; they only mark where a word may be hyphenated.
+        ch == '\u00AD'
     }
 }
 
@@ -126,6 +226,19 @@ pub fn fixed_to_rounded_int(before: int, f: i32) -> int {
     }
 }
 
+/// Returns the offset in UTF-16 code units, as the DOM counts them, of the character at the given
+/// offset in `text`. Characters outside the Basic Multilingual Plane take two code units.
+pub fn utf16_offset(text: &str, offset: uint) -> uint {
+    let mut units = 0;
+    for text.iter().enumerate().advance |(i, ch)| {
+        if i >= offset {
+            break
+        }
+        units += if ch as uint > 0xFFFF { 2 } else { 1 };
+    }
+    units
+}
+
 /* Generate a 32-bit TrueType tag from its 4 characters */
 pub fn true_type_tag(a: char, b: char, c: char, d: char) -> u32 {
     (a << 24 | b << 16 | c << 8 | d) as u32
@@ -149,7 +262,7 @@ fn test_transform_compress_none() {
     let mode = CompressNone;
 
     for uint::range(0, test_strs.len()) |i| {
-        (trimmed_str, _out) = transform_text(test_strs[i], mode, true);
+        (trimmed_str, _out) = transform_text(test_strs[i], mode, true, &mut SkipChars::new());
         assert!(trimmed_str == test_strs[i])
     }
 }
@@ -177,7 +290,7 @@ fn test_transform_discard_newline() {
     let mode = DiscardNewline;
 
     for uint::range(0, test_strs.len()) |i| {
-        (trimmed_str, _out) = transform_text(test_strs[i], mode, true);
+        (trimmed_str, _out) = transform_text(test_strs[i], mode, true, &mut SkipChars::new());
         assert!(trimmed_str == oracle_strs[i])
     }
 }
@@ -204,7 +317,7 @@ fn test_transform_compress_whitespace() {
     let mode = CompressWhitespace;
 
     for uint::range(0, test_strs.len()) |i| {
-        (trimmed_str, _out) = transform_text(test_strs[i], mode, true);
+        (trimmed_str, _out) = transform_text(test_strs[i], mode, true, &mut SkipChars::new());
         assert!(trimmed_str == oracle_strs[i])
     }
 }
@@ -231,7 +344,7 @@ fn test_transform_compress_whitespace_newline() {
     let mode = CompressWhitespaceNewline;
 
     for uint::range(0, test_strs.len()) |i| {
-        (trimmed_str, _out) = transform_text(test_strs[i], mode, true);
+        (trimmed_str, _out) = transform_text(test_strs[i], mode, true, &mut SkipChars::new());
         assert!(trimmed_str == oracle_strs[i])
     }
 }
@@ -260,7 +373,39 @@ fn test_transform_compress_whitespace_newline() {
     let mode = CompressWhitespaceNewline;
 
     for uint::range(0, test_strs.len()) |i| {
-        (trimmed_str, _out) = transform_text(test_strs[i], mode, false);
+        (trimmed_str, _out) = transform_text(test_strs[i], mode, false, &mut SkipChars::new());
         assert!(trimmed_str == oracle_strs[i])
     }
 }
+
+#[test]
+fn test_transform_skip_chars() {
+    let mut skip_chars = SkipChars::new();
+    let (trimmed_str, _) = transform_text("  foo  b\u00ADar", CompressWhitespaceNewline, true,
+                                          &mut skip_chars);
+    assert!(trimmed_str == ~"foo bar");
+    assert!(skip_chars.original_len() == 11);
+    assert!(skip_chars.transformed_len() == 7);
+
+    // The skipped leading whitespace maps to the first kept character.
+    assert!(skip_chars.original_to_transformed(0) == 0);
+    assert!(skip_chars.original_to_transformed(2) == 0);
+    assert!(skip_chars.original_to_transformed(5) == 3);
+    assert!(skip_chars.original_to_transformed(6) == 4);
+    assert!(skip_chars.original_to_transformed(9) == 5);
+
+    assert!(skip_chars.transformed_to_original(0) == 2);
+    assert!(skip_chars.transformed_to_original(3) == 5);
+    assert!(skip_chars.transformed_to_original(4) == 7);
+    assert!(skip_chars.transformed_to_original(5) == 9);
+    assert!(skip_chars.transformed_to_original(7) == 11);
+}
+
+#[test]
+fn test_utf16_offset() {
+    let text = "a\U0001F600b";
+    assert!(utf16_offset(text, 0) == 0);
+    assert!(utf16_offset(text, 1) == 1);
+    assert!(utf16_offset(text, 2) == 3);
+    assert!(utf16_offset(text, 3) == 4);
+}
@@ -10,7 +10,7 @@ use windowing::{ApplicationMethods, WindowMethods, WindowMouseEvent, WindowClick
 use windowing::{WindowMouseDownEvent, WindowMouseUpEvent};
 
 
-use servo_msg::compositor_msg::{RenderListener, LayerBufferSet, RenderState};
+use servo_msg::compositor_msg::{RenderListener, LayerBuffer, LayerBufferSet, RenderState};
 use servo_msg::compositor_msg::{ReadyState, ScriptListener};
 use servo_msg::constellation_msg::{CompositorAck, ConstellationChan};
 use servo_msg::constellation_msg;
@@ -168,8 +168,15 @@ impl CompositorTask {
         // TODO: There should be no initial layer tree until the renderer creates one from the display
         // list. This is only here because we don't have that logic in the renderer yet.
         let context = rendergl::init_render_context();
+
+        // The root layer holds the page, and scrolls and zooms. The fixed layer holds the content
+        // that is fixed to the viewport, and stays put over it.
         let root_layer = @mut ContainerLayer();
-        let scene = @mut Scene(ContainerLayerKind(root_layer), Size2D(800.0f32, 600.0), identity());
+        let fixed_layer = @mut ContainerLayer();
+        let top_layer = @mut ContainerLayer();
+        top_layer.add_child(ContainerLayerKind(root_layer));
+        top_layer.add_child(ContainerLayerKind(fixed_layer));
+        let scene = @mut Scene(ContainerLayerKind(top_layer), Size2D(800.0f32, 600.0), identity());
         let done = @mut false;
 
         // FIXME: This should not be a separate offset applied after the fact but rather should be
@@ -285,54 +292,8 @@ impl CompositorTask {
 
                         let new_layer_buffer_set = new_layer_buffer_set.get();
 
-                        // Iterate over the children of the container layer.
-                        let mut current_layer_child = root_layer.first_child;
-
-                        for new_layer_buffer_set.buffers.iter().advance |buffer| {
-                            let width = buffer.rect.size.width as uint;
-                            let height = buffer.rect.size.height as uint;
-
-                            debug!("osmain: compositing buffer rect %?", &buffer.rect);
-
-                            // Find or create a texture layer.
-                            let texture_layer;
-                            current_layer_child = match current_layer_child {
-                                None => {
-                                    debug!("osmain: adding new texture layer");
-                                    texture_layer = @mut TextureLayer::new(@buffer.draw_target.clone() as @TextureManager,
-                                                                           buffer.screen_pos.size);
-                                    root_layer.add_child(TextureLayerKind(texture_layer));
-                                    None
-                                }
-                                Some(TextureLayerKind(existing_texture_layer)) => {
-                                    texture_layer = existing_texture_layer;
-                                    texture_layer.manager = @buffer.draw_target.clone() as @TextureManager;
-
-                                    // Move on to the next sibling.
-                                    do current_layer_child.get().with_common |common| {
-                                        common.next_sibling
-                                    }
-                                }
-                                Some(_) => fail!(~"found unexpected layer kind"),
-                            };
-
-                            let origin = buffer.screen_pos.origin;
-                            let origin = Point2D(origin.x as f32, origin.y as f32);
-
-                            // Set the layer's transform.
-                            let transform = identity().translate(origin.x, origin.y, 0.0);
-                            let transform = transform.scale(width as f32, height as f32, 1.0);
-                            texture_layer.common.set_transform(transform);
-                        }
-
-                        // Delete leftover layers
-                        while current_layer_child.is_some() {
-                            let trash = current_layer_child.get();
-                            do current_layer_child.get().with_common |common| {
-                                current_layer_child = common.next_sibling;
-                            }
-                            root_layer.remove_child(trash);
-                        }
+                        update_texture_layers(root_layer, new_layer_buffer_set.buffers);
+                        update_texture_layers(fixed_layer, new_layer_buffer_set.fixed_buffers);
 
                         // Reset zoom
                         *local_zoom = 1f32;
@@ -446,6 +407,59 @@ impl CompositorTask {
     }
 }
 
+/// Points the texture layers under the given container layer at the given buffers, adding and
+/// removing texture layers as needed.
+fn update_texture_layers(container_layer: @mut ContainerLayer, buffers: &[LayerBuffer]) {
+    // Iterate over the children of the container layer.
+    let mut current_layer_child = container_layer.first_child;
+
+    for buffers.iter().advance |buffer| {
+        let width = buffer.rect.size.width as uint;
+        let height = buffer.rect.size.height as uint;
+
+        debug!("osmain: compositing buffer rect %?", &buffer.rect);
+
+        // Find or create a texture layer.
+        let texture_layer;
+        current_layer_child = match current_layer_child {
+            None => {
+                debug!("osmain: adding new texture layer");
+                texture_layer = @mut TextureLayer::new(@buffer.draw_target.clone() as @TextureManager,
+                                                       buffer.screen_pos.size);
+                container_layer.add_child(TextureLayerKind(texture_layer));
+                None
+            }
+            Some(TextureLayerKind(existing_texture_layer)) => {
+                texture_layer = existing_texture_layer;
+                texture_layer.manager = @buffer.draw_target.clone() as @TextureManager;
+
+                // Move on to the next sibling.
+                do current_layer_child.get().with_common |common| {
+                    common.next_sibling
+                }
+            }
+            Some(_) => fail!(~"found unexpected layer kind"),
+        };
+
+        let origin = buffer.screen_pos.origin;
+        let origin = Point2D(origin.x as f32, origin.y as f32);
+
+        // Set the layer's transform.
+        let transform = identity().translate(origin.x, origin.y, 0.0);
+        let transform = transform.scale(width as f32, height as f32, 1.0);
+        texture_layer.common.set_transform(transform);
+    }
+
+    // Delete leftover layers
+    while current_layer_child.is_some() {
+        let trash = current_layer_child.get();
+        do current_layer_child.get().with_common |common| {
+            current_layer_child = common.next_sibling;
+        }
+        container_layer.remove_child(trash);
+    }
+}
+
 /// A function for spawning into the platform's main thread.
 fn on_osmain(f: ~fn()) {
     // FIXME: rust#6399
@@ -5,22 +5,37 @@
 use extra::net::url::Url;
 use url_from_str = extra::net::url::from_str;
 use std::cell::Cell;
+use std::io;
+use std::path::Path;
 use std::result;
 use newcss::stylesheet::Stylesheet;
 use newcss::select::SelectCtx;
-use newcss::types::OriginUA;
+use newcss::types::{OriginUA, OriginUser};
 use newcss::util::DataStream;
+use servo_util::url::make_url;
 
-pub fn new_css_select_ctx() -> SelectCtx {
+/// Creates a select context holding the user agent stylesheets and, if a file is given, the
+/// user's stylesheet. Author stylesheets are appended as the document loads them.
+///
+/// The select context cascades declarations by origin and importance as in CSS 2.1 § 6.4.1, so a
+/// normal user declaration overrides a user agent one and is overridden by an author one, but an
+/// `!important` user declaration overrides even an `!important` author one.
+pub fn new_css_select_ctx(user_stylesheet: Option<~str>) -> SelectCtx {
     let mut ctx = SelectCtx::new();
-    ctx.append_sheet(html4_default_style(), OriginUA);
+    ctx.append_sheet(html_default_style(), OriginUA);
     ctx.append_sheet(servo_default_style(), OriginUA);
+    for user_stylesheet.iter().advance |path| {
+        match user_style(*path) {
+            Some(stylesheet) => ctx.append_sheet(stylesheet, OriginUser),
+            None => {}
+        }
+    }
     return ctx;
 }
 
-fn html4_default_style() -> Stylesheet {
-    Stylesheet::new(default_url("html4_style"),
-                    style_stream(html4_default_style_str()))
+fn html_default_style() -> Stylesheet {
+    Stylesheet::new(default_url("html_style"),
+                    style_stream(html_default_style_str()))
 }
 
 fn servo_default_style() -> Stylesheet {
@@ -28,6 +43,19 @@ fn servo_default_style() -> Stylesheet {
                     style_stream(servo_default_style_str()))
 }
 
+/// Reads the user's stylesheet from the given file, or warns and returns None if it can't be
+/// read.
+fn user_style(path: &str) -> Option<Stylesheet> {
+    let style = match io::read_whole_file_str(&Path(path)) {
+        Ok(style) => style,
+        Err(message) => {
+            warn!("couldn't read user stylesheet %s: %s", path, message);
+            return None
+        }
+    };
+    Some(Stylesheet::new(make_url(path.to_owned(), None), style_stream(style)))
+}
+
 fn default_url(name: &str) -> Url {
     result::unwrap(url_from_str(fmt!("http://%s", name)))
 }
@@ -42,91 +70,226 @@ fn style_stream(style: &str) -> DataStream {
     return d;
 }
 
-fn html4_default_style_str() -> ~str {
+/// The user agent stylesheet of the rendering section of the HTML Living Standard (§ 15), less
+/// what Servo can't yet parse or lay out. Where Servo lacks a feature the standard's rules rely
+/// on, the rules are approximated and say so.
+fn html_default_style_str() -> ~str {
 ~"
-html, address,
-blockquote,
-body, dd, div,
-dl, dt, fieldset, form,
-frame, frameset,
-h1, h2, h3, h4,
-h5, h6, noframes,
-ol, p, ul, center,
-    dir, hr, menu, pre   { display: block; unicode-bidi: embed }
-    li              { display: list-item }
-    head            { display: none }
-    table           { display: table }
-    tr              { display: table-row }
-    thead           { display: table-header-group }
-    tbody           { display: table-row-group }
-    tfoot           { display: table-footer-group }
-    col             { display: table-column }
-    colgroup        { display: table-column-group }
-    td, th          { display: table-cell }
-    caption         { display: table-caption }
-    th              { font-weight: bolder; text-align: center }
-    caption         { text-align: center }
-    body            { margin: 8px }
-    h1              { font-size: 2em; margin: .67em 0 }
-    h2              { font-size: 1.5em; margin: .75em 0 }
-    h3              { font-size: 1.17em; margin: .83em 0 }
-h4, p,
-blockquote, ul,
-fieldset, form,
-ol, dl, dir,
-    menu            { margin: 1.12em 0 }
-    h5              { font-size: .83em; margin: 1.5em 0 }
-    h6              { font-size: .75em; margin: 1.67em 0 }
-h1, h2, h3, h4,
-h5, h6, b,
-    strong          { font-weight: bolder }
-    blockquote      { margin-left: 40px; margin-right: 40px }
-i, cite, em,
-    var, address    { font-style: italic }
-pre, tt, code,
-    kbd, samp       { font-family: monospace }
-    pre             { white-space: pre }
-button, textarea,
-    input, select   { display: inline-block }
-    big             { font-size: 1.17em }
-    small, sub, sup { font-size: .83em }
-    sub             { vertical-align: sub }
-    sup             { vertical-align: super }
-    table           { border-spacing: 2px; }
-thead, tbody,
-    tfoot           { vertical-align: middle }
-    td, th, tr      { vertical-align: inherit }
-    s, strike, del  { text-decoration: line-through }
-    hr              { border: 1px inset }
-ol, ul, dir,
-    menu, dd        { margin-left: 40px }
-    ol              { list-style-type: decimal }
-ol ul, ul ol,
-    ul ul, ol ol    { margin-top: 0; margin-bottom: 0 }
-    u, ins          { text-decoration: underline }
-    br:before       { content: \"\\A\"; white-space: pre-line }
-center          { text-align: center }
-:link, :visited { text-decoration: underline }
-:focus          { outline: thin dotted invert }
-
-/* Begin bidirectionality settings (do not change) */
-BDO[DIR=\"ltr\"]  { direction: ltr; unicode-bidi: bidi-override }
-BDO[DIR=\"rtl\"]  { direction: rtl; unicode-bidi: bidi-override }
-
-*[DIR=\"ltr\"]    { direction: ltr; unicode-bidi: embed }
-*[DIR=\"rtl\"]    { direction: rtl; unicode-bidi: embed }
-
-@media print {
-h1            { page-break-before: always }
-  h1, h2, h3,
-h4, h5, h6    { page-break-after: avoid }
-ul, ol, dl    { page-break-before: avoid }
-}
-
-/* Servo additions */
-:link           { color: blue }
-script          { display: none }
-style           { display: none }
+/* 15.3.1 Hidden elements */
+area, base, basefont, datalist, head, link, meta, noembed,
+noframes, param, rp, script, style, template, title {
+  display: none;
+}
+
+/* The standard exempts `hidden=until-found` with :not(), which isn't supported. */
+[hidden] { display: none; }
+embed[hidden] { display: inline; height: 0; width: 0; }
+
+input[type=hidden] { display: none !important; }
+
+/* 15.3.2 The page */
+html, body { display: block; }
+
+body { margin: 8px; }
+
+/* 15.3.3 Flow content */
+address, blockquote, center, div, figure, figcaption, footer, form, header, hr,
+legend, listing, main, p, plaintext, pre, search, xmp {
+  display: block;
+}
+
+blockquote, figure, listing, p, plaintext, pre, xmp {
+  margin-top: 1em; margin-bottom: 1em;
+}
+
+blockquote, figure { margin-left: 40px; margin-right: 40px; }
+
+address { font-style: italic; }
+listing, plaintext, pre, xmp {
+  font-family: monospace; white-space: pre;
+}
+
+dialog { display: none; }
+dialog[open] { display: block; }
+dialog {
+  position: absolute;
+  left: 0; right: 0;
+  margin: auto;
+  border: solid;
+  padding: 1em;
+  background-color: white;
+  color: black;
+}
+
+/* 15.3.4 Phrasing content */
+cite, dfn, em, i, var { font-style: italic; }
+b, strong { font-weight: bolder; }
+code, kbd, samp, tt { font-family: monospace; }
+big { font-size: larger; }
+small { font-size: smaller; }
+
+sub { vertical-align: sub; }
+sup { vertical-align: super; }
+sub, sup { line-height: normal; font-size: smaller; }
+
+ruby { display: ruby; }
+rt { display: ruby-text; }
+
+:link { color: #0000EE; }
+:visited { color: #551A8B; }
+:link, :visited { text-decoration: underline; cursor: pointer; }
+
+mark { background: yellow; color: black; }
+
+ins, u { text-decoration: underline; }
+del, s, strike { text-decoration: line-through; }
+
+q:before { content: open-quote; }
+q:after { content: close-quote; }
+
+br:before { content: \"\\A\"; white-space: pre-line; }
+nobr { white-space: nowrap; }
+
+/* 15.3.5 Bidirectional text. The standard isolates rather than embeds, which Servo doesn't
+   support yet. */
+[dir] { unicode-bidi: embed; }
+bdi, output { unicode-bidi: embed; }
+bdo, bdo[dir] { unicode-bidi: bidi-override; }
+
+[dir=ltr] { direction: ltr; }
+[dir=rtl] { direction: rtl; }
+
+/* 15.3.6 Sections and headings */
+article, aside, h1, h2, h3, h4, h5, h6, hgroup, nav, section {
+  display: block;
+}
+
+h1 { margin-top: 0.67em; margin-bottom: 0.67em; font-size: 2.00em; font-weight: bold; }
+h2 { margin-top: 0.83em; margin-bottom: 0.83em; font-size: 1.50em; font-weight: bold; }
+h3 { margin-top: 1.00em; margin-bottom: 1.00em; font-size: 1.17em; font-weight: bold; }
+h4 { margin-top: 1.33em; margin-bottom: 1.33em; font-size: 1.00em; font-weight: bold; }
+h5 { margin-top: 1.67em; margin-bottom: 1.67em; font-size: 0.83em; font-weight: bold; }
+h6 { margin-top: 2.33em; margin-bottom: 2.33em; font-size: 0.67em; font-weight: bold; }
+
+/* 15.3.7 Lists */
+dir, dd, dl, dt, menu, ol, ul { display: block; }
+li { display: list-item; }
+
+dir, dl, menu, ol, ul { margin-top: 1em; margin-bottom: 1em; }
+
+dir dir, dir dl, dir menu, dir ol, dir ul,
+dl dir, dl dl, dl menu, dl ol, dl ul,
+menu dir, menu dl, menu menu, menu ol, menu ul,
+ol dir, ol dl, ol menu, ol ol, ol ul,
+ul dir, ul dl, ul menu, ul ol, ul ul {
+  margin-top: 0; margin-bottom: 0;
+}
+
+dd { margin-left: 40px; }
+dir, menu, ol, ul { padding-left: 40px; }
+
+ol { list-style-type: decimal; }
+
+dir, menu, ul { list-style-type: disc; }
+dir dir, dir menu, dir ul, menu dir, menu menu, menu ul,
+ol dir, ol menu, ol ul, ul dir, ul menu, ul ul {
+  list-style-type: circle;
+}
+
+ol[type=\"1\"], li[type=\"1\"] { list-style-type: decimal; }
+ol[type=a], li[type=a] { list-style-type: lower-alpha; }
+ol[type=A], li[type=A] { list-style-type: upper-alpha; }
+ol[type=i], li[type=i] { list-style-type: lower-roman; }
+ol[type=I], li[type=I] { list-style-type: upper-roman; }
+ul[type=none], li[type=none] { list-style-type: none; }
+ul[type=disc], li[type=disc] { list-style-type: disc; }
+ul[type=circle], li[type=circle] { list-style-type: circle; }
+ul[type=square], li[type=square] { list-style-type: square; }
+
+/* 15.3.8 Tables */
+table { display: table; }
+caption { display: table-caption; }
+colgroup { display: table-column-group; }
+col { display: table-column; }
+thead { display: table-header-group; }
+tbody { display: table-row-group; }
+tfoot { display: table-footer-group; }
+tr { display: table-row; }
+td, th { display: table-cell; }
+
+table {
+  border-spacing: 2px;
+  border-collapse: separate;
+  text-indent: 0;
+}
+td, th { padding: 1px; }
+th { font-weight: bold; text-align: center; }
+
+table[rules=none], table[rules=groups], table[rules=rows], table[rules=cols],
+table[rules=all] {
+  border-collapse: collapse;
+}
+
+caption { text-align: center; }
+caption[align=bottom] { caption-side: bottom; }
+
+thead, tbody, tfoot, table > tr { vertical-align: middle; }
+tr, td, th { vertical-align: inherit; }
+
+/* 15.3.10 Form controls */
+input, select, button, textarea {
+  letter-spacing: normal;
+  word-spacing: normal;
+  line-height: normal;
+  text-transform: none;
+  text-indent: 0;
+  text-shadow: none;
+  display: inline-block;
+}
+
+input, select, textarea { text-align: left; }
+
+input[type=reset], input[type=button], input[type=submit], button {
+  text-align: center;
+}
+
+meter, progress { display: inline-block; }
+
+/* 15.3.11 The hr element */
+hr {
+  color: gray;
+  border-style: inset;
+  border-width: 1px;
+  margin: 0.5em auto;
+  overflow: hidden;
+}
+
+/* 15.3.12 The fieldset and legend elements */
+fieldset {
+  display: block;
+  margin-left: 2px; margin-right: 2px;
+  border: groove 2px ThreeDFace;
+  padding: 0.35em 0.75em 0.625em;
+}
+
+legend { padding-left: 2px; padding-right: 2px; }
+
+/* 15.4.4 Embedded content */
+iframe { border: 2px inset; }
+
+/* 15.5.4 The details and summary elements. The standard hides the contents of a closed
+   details element with a shadow tree, which Servo doesn't have; until it does, the element
+   children of an open details element are made blocks. */
+details, summary { display: block; }
+details > * { display: none; }
+details[open] > * { display: block; }
+details > summary:first-of-type {
+  display: list-item;
+  list-style: disclosure-closed inside;
+}
+details[open] > summary:first-of-type {
+  list-style-type: disclosure-open;
+}
 "
 }
 
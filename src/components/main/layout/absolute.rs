@@ -0,0 +1,459 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! CSS absolute and fixed positioning (CSS 2.1 § 9.6, § 10.3.7 and § 10.6.4).
+//!
+//! An absolutely positioned element's flow takes up no room among its siblings: its parent only
+//! gives it the static position, where it would have been had it been in the flow. The flow of
+//! its containing block lists it in `abs_descendants`, and lays it out once its own size is known.
+
+use css::node_style::StyledNode;
+use layout::block::{BlockFlowData, BlockLayout, stack_block_children};
+use layout::context::LayoutContext;
+use layout::flow::{AbsoluteFlow, BlockFlow, FloatFlow, FlowContext, InlineFlow, TableFlow};
+use layout::float_context::FloatContext;
+use layout::model::{Auto, MaybeAuto, SizeConstraint, Specified, definite_height};
+
+use geom::point::Point2D;
+use geom::rect::Rect;
+use geom::size::Size2D;
+use gfx::geometry::Au;
+use gfx::geometry;
+use newcss::values::{CSSDisplayInline, CSSPositionFixed, CSSPositionStatic};
+use script::dom::node::{AbstractNode, LayoutView};
+use servo_util::tree::{TreeNodeRef, TreeUtils};
+
+impl BlockFlowData {
+    /// Whether this absolutely positioned flow is positioned relative to the viewport.
+    pub fn is_fixed(&self) -> bool {
+        do self.box.map_default(false) |box| {
+            box.style().position() == CSSPositionFixed
+        }
+    }
+
+    /// Computes the borders of this flow. An absolutely positioned flow adds nothing to the widths
+    /// of its parent; it measures its children itself once its containing block is known.
+    pub fn bubble_widths_absolute(@mut self, _: &LayoutContext) {
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            do box.with_model |model| {
+                model.compute_borders(style)
+            }
+        }
+
+        self.common.min_width = Au(0);
+        self.common.pref_width = Au(0);
+        self.common.num_floats = 0;
+    }
+
+    /// Solves for the width, horizontal margins and horizontal position of this flow within its
+    /// containing block. The parent has set the horizontal position to the static position.
+    pub fn assign_widths_absolute(@mut self, ctx: &LayoutContext) {
+        // The containing block of an inline element is only known once the lines are formed, so
+        // `layout_abs_descendants` solves for the width then.
+        if self.containing_inline.is_none() {
+            self.solve_widths_absolute(ctx)
+        }
+    }
+
+    priv fn solve_widths_absolute(@mut self, ctx: &LayoutContext) {
+        let flow = AbsoluteFlow(self);
+        let containing_block = containing_block_of_absolute(flow);
+        let cb_rect = containing_block_rect(containing_block, self.containing_inline, ctx);
+        let static_x = offset_to_ancestor(flow, containing_block, self.common.position.origin).x -
+            cb_rect.origin.x;
+
+        // The widths of the content, for shrink-to-fit, are those of the children. Unlike the
+        // widths that blocks bubble, they leave out the margins, borders and padding of the box,
+        // which are solved for separately.
+        let mut min_width = Au(0);
+        let mut pref_width = Au(0);
+        for flow.each_child |kid| {
+            do kid.with_base |child_node| {
+                min_width = geometry::max(min_width, child_node.min_width);
+                pref_width = geometry::max(pref_width, child_node.pref_width);
+            }
+        }
+
+        let mut x = static_x;
+        let mut x_offset = Au(0);
+        let mut width = Au(0);
+        let mut noncontent_width = Au(0);
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            do box.with_model |model| {
+                model.compute_padding(style, cb_rect.size.width);
+                let pb = model.border.left + model.border.right + model.padding.left +
+                    model.padding.right;
+
+                // The other values are solved for again if `min-width` or `max-width` changes
+                // the width (CSS 2.1 § 10.4).
+                let constraint = SizeConstraint::for_width(style, cb_rect.size.width);
+                let width = MaybeAuto::from_width(style.width(), cb_rect.size.width);
+                let (content_width, (left, margin_left, margin_right)) =
+                        do constraint.apply(width) |width| {
+                    let (left, margin_left, content_width, margin_right) =
+                        solve_axis(MaybeAuto::from_offset(style.left(), cb_rect.size.width),
+                                   MaybeAuto::from_margin(style.margin_left(), cb_rect.size.width),
+                                   width,
+                                   MaybeAuto::from_margin(style.margin_right(),
+                                                          cb_rect.size.width),
+                                   MaybeAuto::from_offset(style.right(), cb_rect.size.width),
+                                   cb_rect.size.width - pb,
+                                   static_x,
+                                   |room| geometry::min(geometry::max(min_width, room),
+                                                        pref_width));
+                    (content_width, (left, margin_left, margin_right))
+                };
+
+                model.margin.left = margin_left;
+                model.margin.right = margin_right;
+
+                x = left;
+                x_offset = model.offset();
+                width = content_width;
+                noncontent_width = model.noncontent_width();
+            }
+
+            do box.with_mut_base |base| {
+                //The associated box is the border box of this flow
+                base.position.origin.x = base.model.margin.left;
+                base.position.size.width = width + noncontent_width - base.model.margin.left -
+                    base.model.margin.right;
+            }
+        }
+
+        self.common.position.origin.x = cb_rect.origin.x + x;
+        self.common.position.size.width = width + noncontent_width;
+
+        // The height of the containing block is not known yet, so only lengths are definite.
+        let content_height = self.box.chain(|box| definite_height(box.style(), None));
+        for flow.each_child |kid| {
+            do kid.with_mut_base |child_node| {
+                child_node.position.origin.x = x_offset;
+                child_node.position.size.width = width;
+                child_node.containing_block_height = content_height;
+            }
+        }
+    }
+
+    /// Stands in for the height pass of the parent: an absolutely positioned flow takes up no
+    /// room, so the parent only records its static position, and floats pass it by.
+    pub fn assign_static_height_absolute(@mut self) {
+        self.common.position.size.height = Au(0);
+        self.common.floats_out = self.common.floats_in.clone();
+    }
+
+    /// Lays out the children of this flow, which establishes a new block formatting context, and
+    /// returns the height of its content. Children are placed relative to the top of the content.
+    priv fn assign_content_height_absolute(@mut self, ctx: &mut LayoutContext) -> Au {
+        let flow = AbsoluteFlow(self);
+
+        let mut num_floats = 0;
+        for flow.each_child |kid| {
+            num_floats += kid.with_base(|child_node| child_node.num_floats);
+        }
+
+        let stacked = stack_block_children(flow, ctx, FloatContext::new(num_floats), Au(0), false);
+        stacked.height + stacked.bottom_margins.collapse()
+    }
+
+    /// Solves for the height, vertical margins and vertical position of this flow within the
+    /// given containing block, given the static position and the height of the content.
+    priv fn position_absolute(@mut self, cb_rect: &Rect<Au>, static_y: Au, content_height: Au) {
+        let mut y = static_y;
+        let mut top_offset = Au(0);
+        let mut height = content_height;
+        let mut noncontent_height = Au(0);
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            do box.with_model |model| {
+                let pb = model.border.top + model.border.bottom + model.padding.top +
+                    model.padding.bottom;
+
+                // Vertical margins are percentages of the containing block's width, too. The
+                // other values are solved for again if `min-height` or `max-height` changes the
+                // height (CSS 2.1 § 10.7).
+                let cb_height = Some(cb_rect.size.height);
+                let constraint = SizeConstraint::for_height(style, cb_height);
+                let height = MaybeAuto::from_height(style.height(), cb_height);
+                let (box_height, (top, margin_top, margin_bottom)) =
+                        do constraint.apply(height) |height| {
+                    let (top, margin_top, box_height, margin_bottom) =
+                        solve_axis(MaybeAuto::from_offset(style.top(), cb_rect.size.height),
+                                   MaybeAuto::from_margin(style.margin_top(), cb_rect.size.width),
+                                   height,
+                                   MaybeAuto::from_margin(style.margin_bottom(),
+                                                          cb_rect.size.width),
+                                   MaybeAuto::from_offset(style.bottom(), cb_rect.size.height),
+                                   cb_rect.size.height - pb,
+                                   static_y,
+                                   |_| content_height);
+                    (box_height, (top, margin_top, margin_bottom))
+                };
+
+                model.margin.top = margin_top;
+                model.margin.bottom = margin_bottom;
+
+                y = top;
+                top_offset = margin_top + model.border.top + model.padding.top;
+                height = box_height;
+                noncontent_height = pb + margin_top + margin_bottom;
+            }
+
+            do box.with_mut_base |base| {
+                //The associated box is the border box of this flow
+                base.position.origin.y = base.model.margin.top;
+                base.position.size.height = height + noncontent_height - base.model.margin.top -
+                    base.model.margin.bottom;
+            }
+        }
+
+        self.common.position.origin.y = cb_rect.origin.y + y;
+        self.common.position.size.height = height + noncontent_height;
+
+        for AbsoluteFlow(self).each_child |kid| {
+            do kid.with_mut_base |child_node| {
+                child_node.position.origin.y = child_node.position.origin.y + top_offset;
+            }
+        }
+    }
+}
+
+/// Lays out the absolutely positioned flows whose containing block the given flow establishes.
+/// Called at the end of the flow's height pass, once its size is known.
+pub fn layout_abs_descendants(flow: FlowContext, ctx: &mut LayoutContext) {
+    let descendants = flow.with_base(|base| copy base.abs_descendants);
+    if descendants.is_empty() {
+        return
+    }
+
+    for descendants.iter().advance |&descendant| {
+        let info = match descendant {
+            AbsoluteFlow(info) => info,
+            _ => loop,
+        };
+
+        // The widths inside a flow contained by an inline element are assigned now that the
+        // lines, and so the containing block, are known.
+        if info.containing_inline.is_some() {
+            info.solve_widths_absolute(ctx);
+            for descendant.each_child |kid| {
+                for kid.traverse_preorder |flow| {
+                    flow.assign_widths(ctx);
+                }
+            }
+        }
+
+        let cb_rect = containing_block_rect(flow, info.containing_inline, ctx);
+        let static_y = offset_to_ancestor(descendant, flow, descendant.position().origin).y -
+            cb_rect.origin.y;
+        let content_height = info.assign_content_height_absolute(ctx);
+        info.position_absolute(&cb_rect, static_y, content_height);
+
+        layout_abs_descendants(descendant, ctx);
+    }
+}
+
+/// Returns the flow that establishes the containing block (CSS 2.1 § 10.1) of an absolutely
+/// positioned element whose parent's flow is given: that of the nearest positioned ancestor, or
+/// the root flow if there is none or the element is fixed.
+pub fn containing_block_of(parent: FlowContext, fixed: bool) -> FlowContext {
+    let mut flow = parent;
+    loop {
+        if !fixed && is_positioned(flow) {
+            return flow
+        }
+        match flow.with_base(|base| base.parent) {
+            None => return flow,
+            Some(flow_parent) => flow = flow_parent,
+        }
+    }
+}
+
+/// Returns the nearest relatively positioned inline element that the given element is in, without
+/// a block in between. Its boxes make the containing block of the element if it is absolutely
+/// positioned.
+pub fn positioned_inline_ancestor(node: AbstractNode<LayoutView>)
+                                  -> Option<AbstractNode<LayoutView>> {
+    let mut current = node.parent_node();
+    loop {
+        match current {
+            Some(ancestor) if ancestor.is_element() => {
+                let style = ancestor.style();
+                match style.display(false) {
+                    CSSDisplayInline => {}
+                    _ => return None,
+                }
+                if style.position() != CSSPositionStatic {
+                    return Some(ancestor)
+                }
+                current = ancestor.parent_node();
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Returns the flow that establishes the containing block of the given absolutely positioned flow.
+/// That of a flow contained by an inline element is the inline flow of the element.
+fn containing_block_of_absolute(flow: FlowContext) -> FlowContext {
+    let parent = match flow.with_base(|base| base.parent) {
+        None => return flow,
+        Some(parent) => parent,
+    };
+    match flow {
+        AbsoluteFlow(info) if info.containing_inline.is_some() => parent,
+        _ => containing_block_of(parent, flow.is_fixed()),
+    }
+}
+
+/// Whether the given flow is that of a positioned element, which is the containing block of the
+/// absolutely positioned elements inside it. Inline elements are not flows of their own, so they
+/// are found by `positioned_inline_ancestor` instead.
+fn is_positioned(flow: FlowContext) -> bool {
+    let box = match flow {
+        AbsoluteFlow(*) => return true,
+        BlockFlow(info) => info.box,
+        FloatFlow(info) => info.box,
+        TableFlow(info) => info.box,
+        _ => return false,
+    };
+    do box.map_default(false) |box| {
+        box.style().position() != CSSPositionStatic
+    }
+}
+
+/// Returns the containing block that the given flow establishes, relative to the flow: the padding
+/// box of its render box, the boxes of the given inline element in it, or the viewport for the
+/// root flow.
+fn containing_block_rect(flow: FlowContext,
+                         containing_inline: Option<AbstractNode<LayoutView>>,
+                         ctx: &LayoutContext)
+                         -> Rect<Au> {
+    match (flow, containing_inline) {
+        (InlineFlow(info), Some(node)) => return info.element_rect(node),
+        _ => {}
+    }
+
+    let viewport = Rect(Au::zero_point(), ctx.screen_size.size);
+    if flow.starts_root_flow() {
+        return viewport
+    }
+
+    let box = match flow {
+        AbsoluteFlow(info) | BlockFlow(info) => info.box,
+        FloatFlow(info) => info.box,
+        TableFlow(info) => info.box,
+        _ => None,
+    };
+    match box {
+        None => Rect(Au::zero_point(), flow.position().size),
+        Some(box) => do box.with_base |base| {
+            let border = base.model.border;
+            Rect(Point2D(base.position.origin.x + border.left,
+                         base.position.origin.y + border.top),
+                 Size2D(base.position.size.width - border.left - border.right,
+                        base.position.size.height - border.top - border.bottom))
+        }
+    }
+}
+
+/// Converts a point relative to the parent of the given flow to one relative to the given
+/// ancestor. Absolutely positioned flows in between are relative to their containing blocks, so
+/// the walk continues from those.
+fn offset_to_ancestor(flow: FlowContext, ancestor: FlowContext, point: Point2D<Au>)
+                      -> Point2D<Au> {
+    let mut point = point;
+    let mut current = flow.with_base(|base| base.parent);
+    loop {
+        match current {
+            None => return point,
+            Some(current_flow) => {
+                if current_flow.id() == ancestor.id() {
+                    return point
+                }
+                let origin = current_flow.position().origin;
+                point = Point2D(point.x + origin.x, point.y + origin.y);
+                current = if current_flow.is_absolutely_positioned() {
+                    Some(containing_block_of_absolute(current_flow))
+                } else {
+                    current_flow.with_base(|base| base.parent)
+                };
+            }
+        }
+    }
+}
+
+/// Solves `start + margin_start + size + margin_end + end == available` along one axis of an
+/// absolutely positioned box, where `available` is the size of the containing block less the
+/// box's borders and padding, and returns the start offset, the start margin, the size and the
+/// end margin. If both offsets are auto, the box keeps its static start position; an auto size is
+/// found by passing `auto_size` the room left for it. When over-constrained, `end` is ignored.
+pub fn solve_axis(start: MaybeAuto,
+                  margin_start: MaybeAuto,
+                  size: MaybeAuto,
+                  margin_end: MaybeAuto,
+                  end: MaybeAuto,
+                  available: Au,
+                  static_start: Au,
+                  auto_size: &fn(Au) -> Au)
+                  -> (Au, Au, Au, Au) {
+    let ms = margin_start.spec_or_default(Au(0));
+    let me = margin_end.spec_or_default(Au(0));
+    match (start, size, end) {
+        (Specified(start), Specified(size), Specified(end)) => {
+            // Only here do auto margins take up the slack.
+            let room = available - start - size - end;
+            let (ms, me) = match (margin_start, margin_end) {
+                // FIXME: On the vertical axis, negative room is split equally too.
+                (Auto, Auto) if room < Au(0) => (Au(0), room),
+                (Auto, Auto) => {
+                    let margin = room.scale_by(0.5);
+                    (margin, room - margin)
+                }
+                (Auto, Specified(me)) => (room - me, me),
+                (Specified(ms), Auto) => (ms, room - ms),
+                (Specified(ms), Specified(me)) => (ms, me),
+            };
+            (start, ms, size, me)
+        }
+        (Auto, Auto, Auto) => {
+            (static_start, ms, auto_size(available - static_start - ms - me), me)
+        }
+        (Auto, Auto, Specified(end)) => {
+            let size = auto_size(available - end - ms - me);
+            (available - end - ms - me - size, ms, size, me)
+        }
+        (Auto, Specified(size), Auto) => (static_start, ms, size, me),
+        (Auto, Specified(size), Specified(end)) => (available - end - ms - me - size, ms, size, me),
+        (Specified(start), Auto, Auto) => (start, ms, auto_size(available - start - ms - me), me),
+        (Specified(start), Auto, Specified(end)) => (start, ms, available - start - end - ms - me, me),
+        (Specified(start), Specified(size), Auto) => (start, ms, size, me),
+    }
+}
+
+#[test]
+fn test_solve_axis() {
+    let shrink: &fn(Au) -> Au = |room| geometry::min(room, Au(30));
+
+    // Both offsets auto: the static position and shrink-to-fit.
+    assert_eq!(solve_axis(Auto, Auto, Auto, Auto, Auto, Au(100), Au(10), shrink),
+               (Au(10), Au(0), Au(30), Au(0)));
+
+    // Only the end offset given: the box is placed against the end.
+    assert_eq!(solve_axis(Auto, Specified(Au(5)), Auto, Auto, Specified(Au(20)), Au(100), Au(10),
+                          shrink),
+               (Au(45), Au(5), Au(30), Au(0)));
+
+    // Both offsets given: an auto size stretches between them.
+    assert_eq!(solve_axis(Specified(Au(10)), Auto, Auto, Auto, Specified(Au(20)), Au(100), Au(0),
+                          shrink),
+               (Au(10), Au(0), Au(70), Au(0)));
+
+    // Everything given but the margins, which centre the box.
+    assert_eq!(solve_axis(Specified(Au(0)), Auto, Specified(Au(50)), Auto, Specified(Au(0)),
+                          Au(100), Au(0), shrink),
+               (Au(0), Au(25), Au(50), Au(25)));
+}
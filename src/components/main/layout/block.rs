@@ -4,14 +4,17 @@
 
 //! CSS block layout.
 
+use layout::absolute::layout_abs_descendants;
 use layout::box::{RenderBox};
 use layout::context::LayoutContext;
 use layout::display_list_builder::{DisplayListBuilder, ExtraDisplayListData};
 use layout::display_list_builder::{FlowDisplayListBuilderMethods};
-use layout::flow::{BlockFlow, FlowContext, FlowData, InlineBlockFlow, FloatFlow};
+use layout::flow::{AbsoluteFlow, BlockFlow, FlowContext, FlowData, InlineBlockFlow, FloatFlow};
+use layout::flow::TableFlow;
 use layout::inline::InlineLayout;
-use layout::model::{MaybeAuto, Specified, Auto};
-use layout::float_context::FloatContext;
+use layout::model::{AdjoiningMargins, MaybeAuto, SizeConstraint, Specified, Auto};
+use layout::model::definite_height;
+use layout::float_context::{FloatContext, clear_type};
 
 use std::cell::Cell;
 use geom::point::Point2D;
@@ -19,6 +22,8 @@ use geom::rect::Rect;
 use gfx::display_list::DisplayList;
 use gfx::geometry::Au;
 use gfx::geometry;
+use newcss::values::CSSDisplayInlineBlock;
+use script::dom::node::{AbstractNode, LayoutView};
 use servo_util::tree::{TreeNodeRef, TreeUtils};
 
 pub struct BlockFlowData {
@@ -29,7 +34,21 @@ pub struct BlockFlowData {
     box: Option<RenderBox>,
 
     /// Whether this block flow is the root flow.
-    is_root: bool
+    is_root: bool,
+
+    /// The margins that collapse through the top edge of this block: its own top margin and
+    /// those of the content that adjoins it.
+    collapsible_top_margin: AdjoiningMargins,
+
+    /// The margins that collapse through the bottom edge of this block.
+    collapsible_bottom_margin: AdjoiningMargins,
+
+    /// Whether this block is empty, so that its top and bottom margins adjoin each other.
+    collapses_through: bool,
+
+    /// The relatively positioned inline element whose boxes contain this absolutely positioned
+    /// flow, if its containing block is that element rather than a flow.
+    containing_inline: Option<AbstractNode<LayoutView>>,
 }
 
 impl BlockFlowData {
@@ -37,7 +56,11 @@ impl BlockFlowData {
         BlockFlowData {
             common: common,
             box: None,
-            is_root: false
+            is_root: false,
+            collapsible_top_margin: AdjoiningMargins::new(),
+            collapsible_bottom_margin: AdjoiningMargins::new(),
+            collapses_through: false,
+            containing_inline: None,
         }
     }
 
@@ -45,7 +68,11 @@ impl BlockFlowData {
         BlockFlowData {
             common: common,
             box: None,
-            is_root: true
+            is_root: true,
+            collapsible_top_margin: AdjoiningMargins::new(),
+            collapsible_bottom_margin: AdjoiningMargins::new(),
+            collapses_through: false,
+            containing_inline: None,
         }
     }
 
@@ -73,7 +100,7 @@ impl BlockLayout for FlowContext {
 
     fn starts_block_flow(&self) -> bool {
         match *self {
-            BlockFlow(*) | InlineBlockFlow(*) | FloatFlow(*) => true,
+            BlockFlow(*) | InlineBlockFlow(*) | FloatFlow(*) | TableFlow(*) | AbsoluteFlow(*) => true,
             _ => false 
         }
     }
@@ -87,7 +114,6 @@ impl BlockFlowData {
     any boxes it is responsible for flowing.  */
 
     /* TODO: floats */
-    /* TODO: absolute contexts */
     /* TODO: inline-blocks */
     pub fn bubble_widths_block(@mut self, ctx: &LayoutContext) {
         let mut min_width = Au(0);
@@ -114,8 +140,10 @@ impl BlockFlowData {
             do box.with_model |model| {
                 model.compute_borders(style)
             }
-            min_width = min_width.add(&box.get_min_width(ctx));
-            pref_width = pref_width.add(&box.get_pref_width(ctx));
+            // A length `width` replaces the width of the children, within `min-width` and
+            // `max-width`.
+            min_width = box.intrinsic_content_width(min_width) + box.get_min_width(ctx);
+            pref_width = box.intrinsic_content_width(pref_width) + box.get_pref_width(ctx);
         });
 
         self.common.min_width = min_width;
@@ -123,6 +151,60 @@ impl BlockFlowData {
         self.common.num_floats = num_floats;
     }
  
+    /// Whether this block lays out its content in a block formatting context of its own, so that
+    /// the margins of the content do not collapse with its own.
+    priv fn establishes_block_formatting_context(&self) -> bool {
+        self.is_root || match self.box {
+            Some(box) => match box.style().display(false) {
+                CSSDisplayInlineBlock => true,
+                _ => false,
+            },
+            None => false,
+        }
+    }
+
+    /// Returns the offset of the border box of this block from the top of its flow, which is its
+    /// collapsed top margin, and the height of the border box.
+    priv fn border_box_extent(&self) -> (Au, Au) {
+        match self.box {
+            Some(box) => {
+                let position = box.position();
+                (position.origin.y, position.size.height)
+            }
+            None => (Au(0), self.common.position.size.height),
+        }
+    }
+
+    /// Returns the margins that are expected to collapse through the top edge of this block, found
+    /// before its height pass from its own top margin and those of its first in-flow children.
+    /// The floats are handed to the block relative to where these margins put its top border
+    /// edge, which is where it ends up once they have collapsed.
+    ///
+    /// TODO: The margins of empty children, which collapse through them with those of the next
+    /// child, are left out.
+    pub fn expected_top_margins(@mut self) -> AdjoiningMargins {
+        let box = match self.box {
+            Some(box) => box,
+            None => return AdjoiningMargins::new(),
+        };
+        let (margin_top, top_offset) = do box.with_base |base| {
+            (base.model.margin.top, base.model.border.top + base.model.padding.top)
+        };
+        let margins = AdjoiningMargins::from_margin(margin_top);
+        if self.establishes_block_formatting_context() || top_offset != Au(0) {
+            return margins
+        }
+
+        for BlockFlow(self).each_child |kid| {
+            match kid {
+                FloatFlow(*) | AbsoluteFlow(*) => {}
+                BlockFlow(info) => return margins.union(&info.expected_top_margins()),
+                _ => break,
+            }
+        }
+        margins
+    }
+
     /// Computes left and right margins and width based on CSS 2.1 secion 10.3.3.
     /// Requires borders and padding to already be computed
     priv fn compute_horiz( &self, 
@@ -186,6 +268,9 @@ impl BlockFlowData {
             self.common.position.origin = Au::zero_point();
             self.common.position.size.width = ctx.screen_size.size.width;
             self.common.floats_in = FloatContext::new(self.common.num_floats);
+
+            // The initial containing block has the size of the viewport.
+            self.common.containing_block_height = Some(ctx.screen_size.size.height);
         }
 
         //position was set to the containing block by the flow's parent
@@ -212,10 +297,16 @@ impl BlockFlowData {
                      MaybeAuto::from_margin(style.margin_left(), remaining_width),
                      MaybeAuto::from_margin(style.margin_right(), remaining_width));
 
-                let (width, margin_left, margin_right) = self.compute_horiz(width,
-                                                                            margin_left,
-                                                                            margin_right,
-                                                                            available_width);
+                // The margins are solved for again if `min-width` or `max-width` changes the
+                // width (CSS 2.1 § 10.4).
+                let constraint = SizeConstraint::for_width(style, remaining_width);
+                let (width, (margin_left, margin_right)) = do constraint.apply(width) |width| {
+                    let (width, margin_left, margin_right) = self.compute_horiz(width,
+                                                                                margin_left,
+                                                                                margin_right,
+                                                                                available_width);
+                    (width, (margin_left, margin_right))
+                };
 
                 model.margin.top = margin_top;
                 model.margin.right = margin_right;
@@ -236,67 +327,94 @@ impl BlockFlowData {
             }
         }
 
+        let content_height = self.box.chain(|box| {
+            definite_height(box.style(), self.common.containing_block_height)
+        });
         for BlockFlow(self).each_child |kid| {
             assert!(kid.starts_block_flow() || kid.starts_inline_flow());
 
             do kid.with_mut_base |child_node| {
                 child_node.position.origin.x = x_offset;
                 child_node.position.size.width = remaining_width;
+                child_node.containing_block_height = content_height;
             }
         }
     }
 
     pub fn assign_height_block(@mut self, ctx: &mut LayoutContext) {
-        let mut cur_y = Au(0);
         let mut top_offset = Au(0);
         let mut left_offset = Au(0);
 
+        // The margins of a block collapse with those of its first and last children unless
+        // borders, padding or a formatting context of its own come between them.
+        let forms_context = self.establishes_block_formatting_context();
+        let mut top_edge_open = !forms_context && self.box.is_some();
+        let mut bottom_edge_open = top_edge_open;
+        let mut own_top_margin = AdjoiningMargins::new();
+        let mut own_bottom_margin = AdjoiningMargins::new();
+        let mut specified_height = Auto;
+        let mut height_constraint = SizeConstraint::none();
+
         for self.box.iter().advance |&box| {
+            let style = box.style();
+            let cb_height = self.common.containing_block_height;
+            specified_height = MaybeAuto::from_height(style.height(), cb_height);
+            height_constraint = SizeConstraint::for_height(style, cb_height);
+            let auto_height = match specified_height {
+                Auto => true,
+                Specified(_) => false,
+            };
             do box.with_model |model| {
-                top_offset = model.margin.top + model.border.top + model.padding.top;
-                cur_y += top_offset;
+                top_offset = model.border.top + model.padding.top;
                 left_offset = model.offset();
-            }
-        }
 
-        // TODO(eatkinson): the translation here is probably
-        // totally wrong. We need to do it right or pages
-        // with floats will look very strange.
-
-        // Floats for blocks work like this:
-        // self.floats_in -> child[0].floats_in
-        // visit child[0]
-        // child[i-1].floats_out -> child[i].floats_in
-        // visit child[i]
-        // repeat until all children are visited.
-        // last_child.floats_out -> self.floats_out (done at the end of this method)
-        let mut float_ctx = self.common.floats_in.translate(Point2D(-left_offset, -top_offset));
-        for BlockFlow(self).each_child |kid| {
-            do kid.with_mut_base |child_node| {
-                child_node.floats_in = float_ctx.clone();
+                own_top_margin = AdjoiningMargins::from_margin(model.margin.top);
+                own_bottom_margin = AdjoiningMargins::from_margin(model.margin.bottom);
+                top_edge_open = top_edge_open && top_offset == Au(0);
+                bottom_edge_open = bottom_edge_open && auto_height &&
+                    model.border.bottom + model.padding.bottom == Au(0);
             }
-            kid.assign_height(ctx);
-            do kid.with_mut_base |child_node| {
-                float_ctx = child_node.floats_out.translate(Point2D(Au(0), -child_node.position.size.height));
-            }
-
         }
-        for BlockFlow(self).each_child |kid| {
-            do kid.with_mut_base |child_node| {
-                child_node.position.origin.y = cur_y;
-                cur_y += child_node.position.size.height;
-            }
+
+        // The floats pass through the children from the top of the content box, which sits below
+        // the collapsed top margin of this block.
+        let floats_top_offset = self.expected_top_margins().collapse() + top_offset;
+        let floats = self.common.floats_in.translate(Point2D(-left_offset, -floats_top_offset));
+        let mut stacked = stack_block_children(BlockFlow(self), ctx, floats, Au(0), top_edge_open);
+        let mut height = stacked.height;
+
+        let collapsible_top_margin = own_top_margin.union(&stacked.top_margins);
+        let mut collapsible_bottom_margin = own_bottom_margin;
+        if bottom_edge_open && !stacked.empty {
+            collapsible_bottom_margin = own_bottom_margin.union(&stacked.bottom_margins);
+        } else {
+            height = height + stacked.bottom_margins.collapse();
         }
 
-        let height = if self.is_root {
-            Au::max(ctx.screen_size.size.height, cur_y)
+        // A specified height replaces the height of the content; either is kept within
+        // `min-height` and `max-height` (CSS 2.1 § 10.7).
+        height = height_constraint.clamp(specified_height.spec_or_default(height));
+
+        // An empty block lets all of its margins collapse together, and with those of its
+        // siblings.
+        let collapses_through = stacked.empty && bottom_edge_open &&
+            height_constraint.min == Au(0);
+        let (margin_top, margin_bottom) = if collapses_through {
+            (collapsible_top_margin.union(&collapsible_bottom_margin).collapse(), Au(0))
         } else {
-            cur_y - top_offset
+            (collapsible_top_margin.collapse(), collapsible_bottom_margin.collapse())
         };
-        
+
+        if self.is_root {
+            height = Au::max(ctx.screen_size.size.height, floats_top_offset + height);
+        }
+
         let mut noncontent_height = Au(0);
         self.box.map(|&box| {
             do box.with_mut_base |base| {
+                base.model.margin.top = margin_top;
+                base.model.margin.bottom = margin_bottom;
+
                 //The associated box is the border box of this flow
                 base.position.origin.y = base.model.margin.top;
 
@@ -308,11 +426,25 @@ impl BlockFlowData {
             }
         });
 
-        //TODO(eatkinson): compute heights using the 'height' property.
+        // The children were stacked from the top of the content box, which sits below the
+        // collapsed top margin.
+        let content_top = margin_top + top_offset;
+        for BlockFlow(self).each_child |kid| {
+            do kid.with_mut_base |child_node| {
+                child_node.position.origin.y += content_top;
+            }
+        }
+
         self.common.position.size.height = height + noncontent_height;
 
-    
-        self.common.floats_out = float_ctx.translate(Point2D(left_offset, self.common.position.size.height));
+        self.collapsible_top_margin = collapsible_top_margin;
+        self.collapsible_bottom_margin = collapsible_bottom_margin;
+        self.collapses_through = collapses_through;
+
+        self.common.floats_out = stacked.floats_out.translate(Point2D(left_offset,
+                                                                      floats_top_offset));
+
+        layout_abs_descendants(BlockFlow(self), ctx);
     }
 
     pub fn build_display_list_block<E:ExtraDisplayListData>(@mut self,
@@ -320,19 +452,189 @@ impl BlockFlowData {
                                                             dirty: &Rect<Au>, 
                                                             offset: &Point2D<Au>,
                                                             list: &Cell<DisplayList<E>>) {
+        // A relatively positioned block is painted, with everything in it, away from its place in
+        // the flow.
+        let containing_block_size = match self.common.parent {
+            Some(parent) => parent.position().size,
+            None => self.common.position.size,
+        };
+        let offset = match self.box {
+            Some(box) => *offset + box.relative_position(&containing_block_size),
+            None => *offset,
+        };
+        let offset = &offset;
+
         // add box that starts block context
         self.box.map(|&box| {
             box.build_display_list(builder, dirty, offset, list)
         });
 
 
-        // TODO: handle any out-of-flow elements
-
         // go deeper into the flow tree
         let flow = BlockFlow(self);
         for flow.each_child |child| {
             flow.build_display_list_for_child(builder, child, dirty, offset, list)
         }
+
+        // Absolutely positioned descendants are painted over the content of their containing
+        // block.
+        flow.build_display_list_for_abs_descendants(builder, dirty, offset, list)
     }
 }
 
+/// The outcome of stacking the children of a block container one below the other.
+pub struct StackedChildren {
+    /// The distance from the top of the container's content box to the bottom border edge of
+    /// its last in-flow child, not counting the margins below that child.
+    height: Au,
+
+    /// The margins that adjoin the top edge of the container and collapse through it.
+    top_margins: AdjoiningMargins,
+
+    /// The margins below the last in-flow child, which the caller either collapses through the
+    /// bottom edge of the container or adds to its height.
+    bottom_margins: AdjoiningMargins,
+
+    /// Whether every child let the margins collapse through it, so that the top edge of the
+    /// container adjoins its bottom edge.
+    empty: bool,
+
+    /// The floats after the last child, relative to the top of the container's content box.
+    floats_out: FloatContext,
+}
+
+/// Lays out the children of `flow` and positions them one below the other from `content_top`,
+/// collapsing their adjoining vertical margins (CSS 2.1 § 8.3.1). When `top_edge_open` is set,
+/// the margins above the first in-flow content collapse with the top margin of `flow` instead of
+/// taking up room inside it.
+///
+/// `floats` are the floats before the first child, relative to the top of the content box. Each
+/// child is given them relative to its own top and hands them on to the next child; a child that
+/// clears floats is moved below them, and margins do not collapse across its clearance (CSS 2.1
+/// § 9.5.2).
+pub fn stack_block_children(flow: FlowContext,
+                            ctx: &mut LayoutContext,
+                            floats: FloatContext,
+                            content_top: Au,
+                            top_edge_open: bool)
+                            -> StackedChildren {
+    let mut floats = floats;
+    let mut cur_y = Au(0);
+    let mut margins = AdjoiningMargins::new();
+    let mut top_margins = AdjoiningMargins::new();
+    let mut at_top = top_edge_open;
+
+    for flow.each_child |kid| {
+        // Find where the top border edge of the child goes if its top margin collapses with the
+        // margins above it, before its children have been laid out.
+        let (kid_margins, kid_clear) = match kid {
+            BlockFlow(info) => {
+                (info.expected_top_margins(), info.box.chain(|box| clear_type(box.style())))
+            }
+            TableFlow(info) => {
+                (AdjoiningMargins::new(), info.box.chain(|box| clear_type(box.style())))
+            }
+            _ => (AdjoiningMargins::new(), None),
+        };
+        let kid_margin_top = kid_margins.collapse();
+        let mut border_top = if at_top {
+            cur_y
+        } else {
+            cur_y + margins.union(&kid_margins).collapse()
+        };
+
+        // A child that clears floats starts below them, and the margins above it end there.
+        let float_bottom = kid_clear.chain(|clear| floats.lowest_float_bottom(clear));
+        let cleared = match float_bottom {
+            Some(float_bottom) if float_bottom > border_top => {
+                if at_top {
+                    top_margins = margins;
+                    at_top = false;
+                }
+                margins = AdjoiningMargins::new();
+                cur_y = float_bottom;
+                border_top = float_bottom;
+                true
+            }
+            _ => false,
+        };
+
+        let kid_top = border_top - kid_margin_top;
+        do kid.with_mut_base |child_node| {
+            child_node.floats_in = floats.translate(Point2D(Au(0), -kid_top));
+        }
+        kid.assign_height(ctx);
+        floats = do kid.with_mut_base |child_node| {
+            child_node.floats_out.translate(Point2D(Au(0), kid_top))
+        };
+
+        let y = match kid {
+            BlockFlow(info) if cleared => {
+                let (margin_top, border_box_height) = info.border_box_extent();
+                let y = cur_y - margin_top;
+                cur_y += border_box_height;
+                margins = info.collapsible_bottom_margin;
+                y
+            }
+            BlockFlow(info) => {
+                let (margin_top, border_box_height) = info.border_box_extent();
+                margins = margins.union(&info.collapsible_top_margin);
+                if info.collapses_through {
+                    // An empty block sits where the margins above it end, and lets them collapse
+                    // with those below it.
+                    let y = if at_top { cur_y } else { cur_y + margins.collapse() };
+                    margins = margins.union(&info.collapsible_bottom_margin);
+                    y - margin_top
+                } else {
+                    if at_top {
+                        top_margins = margins;
+                        at_top = false;
+                    } else {
+                        cur_y += margins.collapse();
+                    }
+                    let y = cur_y - margin_top;
+                    cur_y += border_box_height;
+                    margins = info.collapsible_bottom_margin;
+                    y
+                }
+            }
+            // Floats, absolutely positioned flows and inline flows without any lines take up no
+            // room, and margins collapse past them.
+            FloatFlow(*) | AbsoluteFlow(*) => {
+                if at_top { cur_y } else { cur_y + margins.collapse() }
+            }
+            _ if kid.position().size.height == Au(0) => {
+                if at_top { cur_y } else { cur_y + margins.collapse() }
+            }
+            _ => {
+                if at_top {
+                    top_margins = margins;
+                    at_top = false;
+                } else {
+                    cur_y += margins.collapse();
+                }
+                let y = cur_y;
+                cur_y += kid.position().size.height;
+                margins = AdjoiningMargins::new();
+                y
+            }
+        };
+
+        do kid.with_mut_base |child_node| {
+            child_node.position.origin.y = content_top + y;
+        }
+    }
+
+    if at_top {
+        top_margins = margins;
+        margins = AdjoiningMargins::new();
+    }
+
+    StackedChildren {
+        height: cur_y,
+        top_margins: top_margins,
+        bottom_margins: margins,
+        empty: at_top,
+        floats_out: floats,
+    }
+}
@@ -7,8 +7,8 @@
 use css::node_style::StyledNode;
 use layout::context::LayoutContext;
 use layout::display_list_builder::{DisplayListBuilder, ExtraDisplayListData, ToGfxColor};
-use layout::flow::FlowContext;
-use layout::model::{BoxModel, MaybeAuto};
+use layout::flow::{BlockFlow, FlowContext, InlineBlockFlow, InlineFlow};
+use layout::model::{Auto, BoxModel, MaybeAuto, SizeConstraint, Specified};
 use layout::text;
 
 use std::cell::Cell;
@@ -16,6 +16,7 @@ use std::cmp::ApproxEq;
 use std::managed;
 use std::num::Zero;
 use std::uint;
+use std::vec;
 use geom::{Point2D, Rect, Size2D};
 use gfx::display_list::{BaseDisplayItem, BorderDisplayItem, BorderDisplayItemClass};
 use gfx::display_list::{DisplayList, ImageDisplayItem, ImageDisplayItemClass};
@@ -23,7 +24,10 @@ use gfx::display_list::{SolidColorDisplayItem, SolidColorDisplayItemClass, TextD
 use gfx::display_list::{TextDisplayItemClass};
 use gfx::font::{FontStyle, FontWeight300};
 use gfx::geometry::{Au, pt_to_px};
+use gfx::geometry;
+use gfx::text::shaping::FontFeature;
 use gfx::text::text_run::TextRun;
+use gfx::text::util::{SkipChars, utf16_offset};
 use newcss::color::rgb;
 use newcss::complete::CompleteStyle;
 use newcss::units::{Cursive, Em, Fantasy, Monospace, Pt, Px, SansSerif, Serif};
@@ -31,11 +35,16 @@ use newcss::values::{CSSFontFamilyFamilyName, CSSFontFamilyGenericFamily};
 use newcss::values::{CSSFontSizeLength, CSSFontStyleItalic, CSSFontStyleNormal};
 use newcss::values::{CSSFontStyleOblique, CSSTextAlign, CSSTextDecoration, CSSLineHeight};
 use newcss::values::{CSSTextDecorationNone, CSSFloatNone, CSSPositionStatic};
-use newcss::values::{CSSDisplayInlineBlock, CSSDisplayInlineTable};
+use newcss::values::{CSSDisplayInline, CSSPositionRelative};
+use newcss::values::{CSSDisplayInlineBlock, CSSDisplayInlineTable, CSSWidthPercentage};
+use newcss::values::{CSSHyphens};
+use newcss::values::{CSSFontKerningAuto, CSSFontKerningNone, CSSFontKerningNormal};
+use newcss::values::{CSSFontVariantNormal, CSSFontVariantSmallCaps, CSSLetterSpacingLength};
 use script::dom::node::{AbstractNode, LayoutView};
 use servo_net::image::holder::ImageHolder;
 use servo_net::local_image_cache::LocalImageCache;
 use servo_util::range::*;
+use servo_util::tree::{TreeNodeRef, TreeUtils};
 use extra::net::url::Url;
 
 /// Render boxes (`struct RenderBox`) are the leaves of the layout tree. They cannot position
@@ -60,6 +69,9 @@ use extra::net::url::Url;
 pub enum RenderBox {
     GenericRenderBoxClass(@mut RenderBoxBase),
     ImageRenderBoxClass(@mut ImageRenderBox),
+    InlineBlockRenderBoxClass(@mut InlineBlockRenderBox),
+    FloatPlaceholderRenderBoxClass(@mut FloatPlaceholderRenderBox),
+    ListMarkerRenderBoxClass(@mut ListMarkerRenderBox),
     TextRenderBoxClass(@mut TextRenderBox),
     UnscannedTextRenderBoxClass(@mut UnscannedTextRenderBox),
 }
@@ -68,6 +80,7 @@ impl RenderBox {
     pub fn teardown(&self) {
         match *self {
             TextRenderBoxClass(box) => box.teardown(),
+            ListMarkerRenderBoxClass(marker_box) => marker_box.content.teardown(),
             _ => ()
         }
     }
@@ -80,15 +93,284 @@ pub struct ImageRenderBox {
 }
 
 impl ImageRenderBox {
+    /// Creates a box for the given image, which is the content of an image element or the
+    /// marker of a list item.
     pub fn new(base: RenderBoxBase, image_url: Url, local_image_cache: @mut LocalImageCache)
                -> ImageRenderBox {
-        assert!(base.node.is_image_element());
-
         ImageRenderBox {
             base: base,
             image: ImageHolder::new(image_url, local_image_cache),
         }
     }
+
+    /// Returns the intrinsic width and height of the image, or zero if it is not available.
+    pub fn intrinsic_size(&self) -> Size2D<Au> {
+        let size = self.image.get_size().get_or_default(Size2D(0, 0));
+        Size2D(Au::from_px(size.width), Au::from_px(size.height))
+    }
+
+    /// Returns the width of the image for the minimum and preferred widths of its container:
+    /// its used width where that does not depend on the containing block, which is not known
+    /// yet. A percentage width counts as `auto`, and percentage bounds are ignored.
+    pub fn intrinsic_width(&self, style: CompleteStyle) -> Au {
+        let width = match style.width() {
+            CSSWidthPercentage(*) => Auto,
+            width => MaybeAuto::from_width(width, Au(0)),
+        };
+        self.constrained_size(width,
+                              MaybeAuto::from_height(style.height(), None),
+                              &SizeConstraint::for_intrinsic_width(style),
+                              &SizeConstraint::for_height(style, None)).width
+    }
+
+    /// Returns the used width and height of the image, which has the given style, from its
+    /// intrinsic size (CSS 2.1 §§ 10.3.2 and 10.6.2).
+    pub fn used_size(&self, style: CompleteStyle, cb_width: Au, cb_height: Option<Au>)
+                     -> Size2D<Au> {
+        self.constrained_size(MaybeAuto::from_width(style.width(), cb_width),
+                              MaybeAuto::from_height(style.height(), cb_height),
+                              &SizeConstraint::for_width(style, cb_width),
+                              &SizeConstraint::for_height(style, cb_height))
+    }
+
+    /// Returns the size of the image given its specified or `auto` width and height. A specified
+    /// width or height scales the other dimension by the intrinsic ratio, and each is kept within
+    /// its `min-` and `max-` bounds.
+    priv fn constrained_size(&self,
+                             width: MaybeAuto,
+                             height: MaybeAuto,
+                             width_constraint: &SizeConstraint,
+                             height_constraint: &SizeConstraint)
+                             -> Size2D<Au> {
+        let intrinsic_size = self.intrinsic_size();
+        let intrinsic_width = intrinsic_size.width;
+        let intrinsic_height = intrinsic_size.height;
+
+        match (width, height) {
+            (Specified(width), Specified(height)) => {
+                Size2D(width_constraint.clamp(width), height_constraint.clamp(height))
+            }
+            (Specified(width), Auto) => {
+                let width = width_constraint.clamp(width);
+                let height = scale_to_ratio(width, intrinsic_width, intrinsic_height);
+                Size2D(width, height_constraint.clamp(height))
+            }
+            (Auto, Specified(height)) => {
+                let height = height_constraint.clamp(height);
+                let width = scale_to_ratio(height, intrinsic_height, intrinsic_width);
+                Size2D(width_constraint.clamp(width), height)
+            }
+            (Auto, Auto) => constrain_replaced_size(intrinsic_width,
+                                                    intrinsic_height,
+                                                    width_constraint,
+                                                    height_constraint),
+        }
+    }
+}
+
+/// Scales `length` by the ratio of `to` to `from`, or returns `to` if the ratio is undefined.
+fn scale_to_ratio(length: Au, from: Au, to: Au) -> Au {
+    if from == Au(0) {
+        to
+    } else {
+        length.scale_by(geometry::to_frac_px(to) / geometry::to_frac_px(from))
+    }
+}
+
+/// Keeps the size of replaced content whose `width` and `height` are both `auto` within its
+/// `min-` and `max-` bounds while preserving its ratio where possible, following the table in
+/// CSS 2.1 § 10.4.
+fn constrain_replaced_size(width: Au,
+                           height: Au,
+                           width_constraint: &SizeConstraint,
+                           height_constraint: &SizeConstraint)
+                           -> Size2D<Au> {
+    if width == Au(0) || height == Au(0) {
+        return Size2D(width_constraint.clamp(width), height_constraint.clamp(height))
+    }
+
+    // A maximum below the minimum is raised to it.
+    let min_width = width_constraint.min;
+    let min_height = height_constraint.min;
+    let max_width = width_constraint.max.map(|&max| geometry::max(max, min_width));
+    let max_height = height_constraint.max.map(|&max| geometry::max(max, min_height));
+
+    let too_wide = max_width.map_default(false, |&max| width > max);
+    let too_narrow = width < min_width;
+    let too_tall = max_height.map_default(false, |&max| height > max);
+    let too_short = height < min_height;
+
+    // The bounds are only looked at when they are violated, so these defaults are never used.
+    let max_w = max_width.get_or_default(width);
+    let max_h = max_height.get_or_default(height);
+
+    let height_for: &fn(Au) -> Au = |w| scale_to_ratio(w, width, height);
+    let width_for: &fn(Au) -> Au = |h| scale_to_ratio(h, height, width);
+    let cap_width: &fn(Au) -> Au = |w| max_width.map_default(w, |&max| geometry::min(w, max));
+    let cap_height: &fn(Au) -> Au = |h| max_height.map_default(h, |&max| geometry::min(h, max));
+
+    // Compares the ratios `a / b` and `c / d` without dividing.
+    let ratio_le: &fn(Au, Au, Au, Au) -> bool = |a, b, c, d| {
+        geometry::to_frac_px(a) * geometry::to_frac_px(d) <=
+            geometry::to_frac_px(c) * geometry::to_frac_px(b)
+    };
+
+    match (too_wide, too_narrow, too_tall, too_short) {
+        (true, _, _, true) => Size2D(max_w, min_height),
+        (_, true, true, _) => Size2D(min_width, max_h),
+        (true, _, true, _) if ratio_le(max_w, width, max_h, height) => {
+            Size2D(max_w, geometry::max(min_height, height_for(max_w)))
+        }
+        (true, _, true, _) => Size2D(geometry::max(min_width, width_for(max_h)), max_h),
+        (_, true, _, true) if ratio_le(min_width, width, min_height, height) => {
+            Size2D(cap_width(width_for(min_height)), min_height)
+        }
+        (_, true, _, true) => Size2D(min_width, cap_height(height_for(min_width))),
+        (true, _, _, _) => Size2D(max_w, geometry::max(height_for(max_w), min_height)),
+        (_, true, _, _) => Size2D(min_width, cap_height(height_for(min_width))),
+        (_, _, true, _) => Size2D(geometry::max(width_for(max_h), min_width), max_h),
+        (_, _, _, true) => Size2D(cap_width(width_for(min_height)), min_height),
+        _ => Size2D(width, height),
+    }
+}
+
+/// A box that stands in for an inline-block on the line of its inline flow. The inline-block lays
+/// out its content in a block flow of its own, which is a child of the inline flow; this box is
+/// placed on the line as a whole, with the size of that flow.
+pub struct InlineBlockRenderBox {
+    base: RenderBoxBase,
+    /// The flow of the inline-block.
+    flow: FlowContext,
+}
+
+impl InlineBlockRenderBox {
+    pub fn new(base: RenderBoxBase, flow: FlowContext) -> InlineBlockRenderBox {
+        InlineBlockRenderBox {
+            base: base,
+            flow: flow,
+        }
+    }
+
+    /// Returns the width of the margin box of the inline-block, given the width of its line: its
+    /// specified width if it has one, or else its shrink-to-fit width (CSS 2.1 § 10.3.9), within
+    /// its `min-width` and `max-width`.
+    pub fn margin_box_width(&self, available_width: Au) -> Au {
+        let (min_width, pref_width) = do self.flow.with_base |base| {
+            (base.min_width, base.pref_width)
+        };
+        let block_box = match self.flow {
+            InlineBlockFlow(info) => info.box,
+            _ => None,
+        };
+        let shrink_to_fit = geometry::min(geometry::max(min_width, available_width), pref_width);
+        match block_box {
+            None => shrink_to_fit,
+            Some(block_box) => {
+                let style = block_box.style();
+                do block_box.with_base |base| {
+                    // Auto margins of inline-blocks are zero.
+                    let margin_left = MaybeAuto::from_margin(style.margin_left(), available_width);
+                    let margin_right = MaybeAuto::from_margin(style.margin_right(),
+                                                              available_width);
+                    let padding_left = base.model.compute_padding_length(style.padding_left(),
+                                                                         available_width);
+                    let padding_right = base.model.compute_padding_length(style.padding_right(),
+                                                                          available_width);
+                    let noncontent_width = margin_left.spec_or_default(Au(0)) +
+                        margin_right.spec_or_default(Au(0)) + padding_left + padding_right +
+                        base.model.border.left + base.model.border.right;
+
+                    // The shrink-to-fit width takes in the margins, borders and padding.
+                    let width = match MaybeAuto::from_width(style.width(), available_width) {
+                        Auto => shrink_to_fit - noncontent_width,
+                        Specified(width) => width,
+                    };
+                    let constraint = SizeConstraint::for_width(style, available_width);
+                    constraint.clamp(width) + noncontent_width
+                }
+            }
+        }
+    }
+
+    /// Returns the distance from the top of the inline-block to its baseline: that of its last
+    /// line box, or its bottom margin edge if it has none.
+    pub fn baseline(&self) -> Au {
+        match last_baseline_of_flow(self.flow) {
+            Some(baseline) => baseline,
+            None => self.flow.position().size.height,
+        }
+    }
+}
+
+/// A box that holds the place of a float among the boxes of its inline flow. The float lays out its
+/// content in a float flow of its own, which is a child of the inline flow and is placed when the
+/// lines are formed; this box takes up no room on the line.
+pub struct FloatPlaceholderRenderBox {
+    base: RenderBoxBase,
+    /// The flow of the float.
+    flow: FlowContext,
+}
+
+impl FloatPlaceholderRenderBox {
+    pub fn new(base: RenderBoxBase, flow: FlowContext) -> FloatPlaceholderRenderBox {
+        FloatPlaceholderRenderBox {
+            base: base,
+            flow: flow,
+        }
+    }
+}
+
+/// The marker of a list item (CSS 2.1 § 12.5): a text box for its bullet or number, or an image
+/// box for its `list-style-image`, at the start of the first line of the item. This box shares
+/// the base of the box that draws the marker.
+pub struct ListMarkerRenderBox {
+    /// The box that draws the marker.
+    content: RenderBox,
+    /// Whether the marker is outside the principal box of the item, to the left of the first line,
+    /// rather than the first box on the line.
+    outside: bool,
+}
+
+impl ListMarkerRenderBox {
+    pub fn new(content: RenderBox, outside: bool) -> ListMarkerRenderBox {
+        ListMarkerRenderBox {
+            content: content,
+            outside: outside,
+        }
+    }
+
+    /// Returns the room that the marker takes up on its line: none if it is outside the item.
+    pub fn width_on_line(&self) -> Au {
+        if self.outside {
+            return Au(0)
+        }
+        match self.content {
+            ImageRenderBoxClass(image_box) => image_box.intrinsic_size().width,
+            content => content.position().size.width,
+        }
+    }
+}
+
+/// Returns the distance from the top of the given flow to the baseline of its last line box in
+/// the normal flow, if it has one.
+fn last_baseline_of_flow(flow: FlowContext) -> Option<Au> {
+    match flow {
+        InlineFlow(info) => info.last_line_baseline,
+        BlockFlow(*) | InlineBlockFlow(*) => {
+            let mut baseline = None;
+            for flow.each_child |kid| {
+                if kid.is_absolutely_positioned() {
+                    loop
+                }
+                match last_baseline_of_flow(kid) {
+                    Some(kid_baseline) => baseline = Some(kid.position().origin.y + kid_baseline),
+                    None => {}
+                }
+            }
+            baseline
+        }
+        _ => None,
+    }
 }
 
 /// A box representing a single run of text with a distinct style. A `TextRenderBox` may be split
@@ -99,6 +381,15 @@ pub struct TextRenderBox {
     base: RenderBoxBase,
     run: @TextRun,
     range: Range,
+    /// Whether this box ends a line by breaking a word, so that a hyphen is drawn after it. The
+    /// hyphen is included in the box's width.
+    hyphenated: bool,
+    /// Which characters of the text of the box's node were kept in its transformed text, which
+    /// is what the run holds.
+    skip_chars: @SkipChars,
+    /// The index in the transformed text of the box's node of the first character of the run.
+    /// It is negative if the run begins in an earlier node.
+    run_offset: int,
 }
 
 impl TextRenderBox {
@@ -159,6 +450,10 @@ pub struct RenderBoxBase {
     /// The core parameters (border, padding, margin) used by the box model.
     model: BoxModel,
 
+    /// The bidirectional embedding level of this box's content, as resolved by the Unicode
+    /// bidirectional algorithm over its paragraph. Odd levels run from right to left.
+    bidi_level: u8,
+
     /// A debug ID.
     ///
     /// TODO(#87) Make this only present in debug builds.
@@ -174,6 +469,7 @@ impl RenderBoxBase {
             ctx: flow_context,
             position: Au::zero_rect(),
             model: Zero::zero(),
+            bidi_level: 0,
             id: id,
         }
     }
@@ -188,6 +484,13 @@ impl RenderBox {
             ImageRenderBoxClass(image_box) => {
                 callback(&image_box.base)
             }
+            InlineBlockRenderBoxClass(inline_block_box) => {
+                callback(&inline_block_box.base)
+            }
+            FloatPlaceholderRenderBoxClass(placeholder_box) => {
+                callback(&placeholder_box.base)
+            }
+            ListMarkerRenderBoxClass(marker_box) => marker_box.content.with_base(callback),
             TextRenderBoxClass(text_box) => {
                 callback(&text_box.base)
             }
@@ -205,6 +508,13 @@ impl RenderBox {
             ImageRenderBoxClass(image_box) => {
                 callback(&mut image_box.base)
             }
+            InlineBlockRenderBoxClass(inline_block_box) => {
+                callback(&mut inline_block_box.base)
+            }
+            FloatPlaceholderRenderBoxClass(placeholder_box) => {
+                callback(&mut placeholder_box.base)
+            }
+            ListMarkerRenderBoxClass(marker_box) => marker_box.content.with_mut_base(callback),
             TextRenderBoxClass(text_box) => {
                 callback(&mut text_box.base)
             }
@@ -259,7 +569,10 @@ impl RenderBox {
     pub fn can_merge_with_box(&self, other: RenderBox) -> bool {
         match (self, &other) {
             (&UnscannedTextRenderBoxClass(*), &UnscannedTextRenderBoxClass(*)) => {
-                self.font_style() == other.font_style() && self.text_decoration() == other.text_decoration()
+                self.font_style() == other.font_style() &&
+                    self.font_features() == other.font_features() &&
+                    self.text_decoration() == other.text_decoration() &&
+                    self.hyphens() == other.hyphens()
             },
             (&TextRenderBoxClass(text_box_a), &TextRenderBoxClass(text_box_b)) => {
                 managed::ptr_eq(text_box_a.run, text_box_b.run)
@@ -273,7 +586,9 @@ impl RenderBox {
     pub fn split_to_width(&self, _: &LayoutContext, max_width: Au, starts_line: bool)
                       -> SplitBoxResult {
         match *self {
-            GenericRenderBoxClass(*) | ImageRenderBoxClass(*) => CannotSplit(*self),
+            GenericRenderBoxClass(*) | ImageRenderBoxClass(*) |
+            InlineBlockRenderBoxClass(*) | FloatPlaceholderRenderBoxClass(*) |
+            ListMarkerRenderBoxClass(*) => CannotSplit(*self),
             UnscannedTextRenderBoxClass(*) => {
                 fail!(~"WAT: shouldn't be an unscanned text box here.")
             }
@@ -283,61 +598,71 @@ impl RenderBox {
                 let mut remaining_width: Au = max_width;
                 let mut left_range = Range::new(text_box.range.begin(), 0);
                 let mut right_range: Option<Range> = None;
+                // Whether the left box ends in a piece that breaks a word.
+                let mut left_hyphenated = false;
 
                 debug!("split_to_width: splitting text box (strlen=%u, range=%?, avail_width=%?)",
                        text_box.run.text.len(),
                        text_box.range,
                        max_width);
 
-                for text_box.run.iter_slices_for_range(&text_box.range)
-                                                        |glyphs, offset, slice_range| {
-                    debug!("split_to_width: considering slice (offset=%?, range=%?, remain_width=%?)",
-                           offset,
-                           slice_range,
+                for text_box.run.iter_break_pieces_for_range(&text_box.range)
+                                                            |content, whitespace, hyphenated| {
+                    debug!("split_to_width: considering piece (content=%?, whitespace=%?, \
+                            hyphenated=%?, remain_width=%?)",
+                           content,
+                           whitespace,
+                           hyphenated,
                            remaining_width);
 
-                    let metrics = text_box.run.metrics_for_slice(glyphs, slice_range);
-                    let advance = metrics.advance_width;
+                    let content_advance = text_box.run.metrics_for_range(content).advance_width;
+                    // A piece that ends partway through a word can only end the line if there is
+                    // room for the hyphen after it too.
+                    let hyphen_advance = if hyphenated {
+                        text_box.run.hyphen_width()
+                    } else {
+                        Au(0)
+                    };
                     let should_continue: bool;
 
-                    if advance <= remaining_width {
+                    if starts_line && pieces_processed_count == 0 && content.is_empty() {
+                        debug!("split_to_width: case=skipping leading trimmable whitespace");
                         should_continue = true;
-
-                        if starts_line && pieces_processed_count == 0 && glyphs.is_whitespace() {
-                            debug!("split_to_width: case=skipping leading trimmable whitespace");
-                            left_range.shift_by(slice_range.length() as int);
+                        left_range.shift_by(whitespace.length() as int);
+                    } else if content_advance + hyphen_advance <= remaining_width {
+                        debug!("split_to_width: case=enlarging span");
+                        remaining_width -= content_advance;
+                        left_range.extend_by(content.length() as int);
+                        left_hyphenated = hyphenated;
+
+                        let whitespace_advance =
+                            text_box.run.metrics_for_range(whitespace).advance_width;
+                        if whitespace_advance <= remaining_width {
+                            should_continue = true;
+                            remaining_width -= whitespace_advance;
+                            left_range.extend_by(whitespace.length() as int);
                         } else {
-                            debug!("split_to_width: case=enlarging span");
-                            remaining_width -= advance;
-                            left_range.extend_by(slice_range.length() as int);
-                        }
-                    } else {    // The advance is more than the remaining width.
-                        should_continue = false;
-                        let slice_begin = offset + slice_range.begin();
-                        let slice_end = offset + slice_range.end();
-
-                        if glyphs.is_whitespace() {
+                            should_continue = false;
                             // If there are still things after the trimmable whitespace, create the
                             // right chunk.
-                            if slice_end < text_box.range.end() {
+                            if whitespace.end() < text_box.range.end() {
                                 debug!("split_to_width: case=skipping trimmable trailing \
                                         whitespace, then split remainder");
-                                let right_range_end =
-                                    text_box.range.end() - slice_end;
-                                right_range = Some(Range::new(slice_end, right_range_end));
+                                let right_range_end = text_box.range.end() - whitespace.end();
+                                right_range = Some(Range::new(whitespace.end(), right_range_end));
                             } else {
                                 debug!("split_to_width: case=skipping trimmable trailing \
                                         whitespace");
                             }
-                        } else if slice_begin < text_box.range.end() {
-                            // There are still some things left over at the end of the line. Create
-                            // the right chunk.
-                            let right_range_end =
-                                text_box.range.end() - slice_begin;
-                            right_range = Some(Range::new(slice_begin, right_range_end));
-                            debug!("split_to_width: case=splitting remainder with right range=%?",
-                                   right_range);
                         }
+                    } else {    // The advance is more than the remaining width.
+                        should_continue = false;
+                        // There are still some things left over at the end of the line. Create
+                        // the right chunk.
+                        let right_range_end = text_box.range.end() - content.begin();
+                        right_range = Some(Range::new(content.begin(), right_range_end));
+                        debug!("split_to_width: case=splitting remainder with right range=%?",
+                               right_range);
                     }
 
                     pieces_processed_count += 1;
@@ -350,7 +675,13 @@ impl RenderBox {
                 let left_box = if left_range.length() > 0 {
                     let new_text_box = @mut text::adapt_textbox_with_range(text_box.base,
                                                                            text_box.run,
-                                                                           left_range);
+                                                                           left_range,
+                                                                           text_box.skip_chars,
+                                                                           text_box.run_offset);
+                    if left_hyphenated && right_range.is_some() {
+                        new_text_box.hyphenated = true;
+                        new_text_box.base.position.size.width += text_box.run.hyphen_width();
+                    }
                     Some(TextRenderBoxClass(new_text_box))
                 } else {
                     None
@@ -359,7 +690,9 @@ impl RenderBox {
                 let right_box = do right_range.map_default(None) |range: &Range| {
                     let new_text_box = @mut text::adapt_textbox_with_range(text_box.base,
                                                                            text_box.run,
-                                                                           *range);
+                                                                           *range,
+                                                                           text_box.skip_chars,
+                                                                           text_box.run_offset);
                     Some(TextRenderBoxClass(new_text_box))
                 };
 
@@ -372,7 +705,23 @@ impl RenderBox {
         }
     }
 
-    /// Guess the intrinsic width of this box for
+    /// Returns the width of the content of this box for the minimum or preferred width of its
+    /// flow, given that of what is inside it: its `width` instead if that is a length, kept within
+    /// `min-width` and `max-width`. Percentages depend on the containing block, which is not known
+    /// yet, so they count as `auto` (CSS 2.1 § 10.3).
+    pub fn intrinsic_content_width(&self, inner_width: Au) -> Au {
+        if !self.node().is_element() {
+            return inner_width
+        }
+        let style = self.style();
+        let width = match style.width() {
+            CSSWidthPercentage(*) => inner_width,
+            width => MaybeAuto::from_width(width, Au(0)).spec_or_default(inner_width),
+        };
+        SizeConstraint::for_intrinsic_width(style).clamp(width)
+    }
+
+    /// Guess the width of the margins, borders and padding of this box for
     /// computation of min and preferred widths.
     //
     // TODO(eatkinson): this is unspecified in
@@ -388,7 +737,6 @@ impl RenderBox {
                 Au(0)
             } else {
 
-                let w = MaybeAuto::from_width(self.style().width(), Au(0)).spec_or_default(Au(0));
                 let ml = MaybeAuto::from_margin(self.style().margin_left(), Au(0)).spec_or_default(Au(0));
                 let mr = MaybeAuto::from_margin(self.style().margin_right(), Au(0)).spec_or_default(Au(0));
                 let pl = base.model.compute_padding_length(self.style().padding_left(), Au(0));
@@ -396,7 +744,7 @@ impl RenderBox {
                 let bl = base.model.compute_border_width(self.style().border_left_width());
                 let br = base.model.compute_border_width(self.style().border_right_width());
 
-                w + ml + mr + pl + pr + bl + br
+                ml + mr + pl + pr + bl + br
             }
         }
     }
@@ -406,39 +754,55 @@ impl RenderBox {
         // FIXME(pcwalton): I think we only need to calculate this if the damage says that CSS
         // needs to be restyled.
 
+        // The flow of an inline-block or a float has measured it, margins and all.
+        match *self {
+            InlineBlockRenderBoxClass(inline_block_box) => {
+                return inline_block_box.flow.with_base(|base| base.min_width)
+            }
+            FloatPlaceholderRenderBoxClass(placeholder_box) => {
+                return placeholder_box.flow.with_base(|base| base.min_width)
+            }
+            // A marker is as wide as its text or image, whatever the style of its list item.
+            ListMarkerRenderBoxClass(marker_box) => return marker_box.width_on_line(),
+            _ => {}
+        }
+
         self.guess_width() + match *self {
-            // TODO: This should account for the minimum width of the box element in isolation.
-            // That includes borders, margins, and padding, but not child widths. The block
-            // `FlowContext` will combine the width of this element and that of its children to
-            // arrive at the context width.
+            // The block `FlowContext` combines the borders, margins and padding of this element
+            // with the width of its content, found from that of its children.
             GenericRenderBoxClass(*) => Au(0),
 
-            ImageRenderBoxClass(image_box) => {
-                // TODO: Consult the CSS `width` property as well as margins and borders.
-                // TODO: If the image isn't available, consult `width`.
-                Au::from_px(image_box.image.get_size().get_or_default(Size2D(0, 0)).width)
-            }
+            ImageRenderBoxClass(image_box) => image_box.intrinsic_width(self.style()),
 
             TextRenderBoxClass(text_box) => {
                 text_box.run.min_width_for_range(&text_box.range)
             }
 
-            UnscannedTextRenderBoxClass(*) => fail!(~"Shouldn't see unscanned boxes here.")
+            UnscannedTextRenderBoxClass(*) => fail!(~"Shouldn't see unscanned boxes here."),
+            InlineBlockRenderBoxClass(*) | FloatPlaceholderRenderBoxClass(*) |
+            ListMarkerRenderBoxClass(*) => {
+                fail!(~"Inline-blocks, floats and list markers were measured above.")
+            }
         }
     }
 
     /// Returns the *preferred width* of this render box as defined by the CSS specification.
     pub fn get_pref_width(&self, _: &LayoutContext) -> Au {
+        match *self {
+            InlineBlockRenderBoxClass(inline_block_box) => {
+                return inline_block_box.flow.with_base(|base| base.pref_width)
+            }
+            FloatPlaceholderRenderBoxClass(placeholder_box) => {
+                return placeholder_box.flow.with_base(|base| base.pref_width)
+            }
+            ListMarkerRenderBoxClass(marker_box) => return marker_box.width_on_line(),
+            _ => {}
+        }
+
         self.guess_width() + match *self {
-            // TODO: This should account for the preferred width of the box element in isolation.
-            // That includes borders, margins, and padding, but not child widths. The block
-            // `FlowContext` will combine the width of this element and that of its children to
-            // arrive at the context width.
             GenericRenderBoxClass(*) => Au(0),
 
-            ImageRenderBoxClass(image_box) => {
-                Au::from_px(image_box.image.get_size().get_or_default(Size2D(0, 0)).width)
-            }
+            ImageRenderBoxClass(image_box) => image_box.intrinsic_width(self.style()),
 
             TextRenderBoxClass(text_box) => {
                 // A text box cannot span lines, so assume that this is an unsplit text box.
@@ -458,6 +822,10 @@ impl RenderBox {
             }
 
             UnscannedTextRenderBoxClass(*) => fail!(~"Shouldn't see unscanned boxes here."),
+            InlineBlockRenderBoxClass(*) | FloatPlaceholderRenderBoxClass(*) |
+            ListMarkerRenderBoxClass(*) => {
+                fail!(~"Inline-blocks, floats and list markers were measured above.")
+            }
         }
     }
 
@@ -550,6 +918,94 @@ impl RenderBox {
         }
     }
 
+    /// Returns the offset of this box from its place in the flow if its element is relatively
+    /// positioned (CSS 2.1 § 9.4.3), given the size of its containing block.
+    pub fn relative_position(&self, containing_block_size: &Size2D<Au>) -> Point2D<Au> {
+        relative_position_for_style(self.style(), containing_block_size)
+    }
+
+    /// Returns the offset of this box, in an inline flow, from its place on the line: the sum of
+    /// the offsets of the relatively positioned inline elements that it is part of.
+    pub fn inline_relative_position(&self, containing_block_size: &Size2D<Au>) -> Point2D<Au> {
+        let mut node = self.nearest_ancestor_element();
+
+        // The flow of an inline-block or a float offsets the element itself.
+        match *self {
+            InlineBlockRenderBoxClass(*) | FloatPlaceholderRenderBoxClass(*) => {
+                match node.parent_node() {
+                    Some(parent) => node = parent,
+                    None => return Au::zero_point(),
+                }
+            }
+            _ => {}
+        }
+
+        inline_relative_position_of(node, containing_block_size)
+    }
+
+    /// Returns the language of this box's content: the value of the `lang` attribute of the
+    /// nearest ancestor-or-self element that has one, if any does.
+    pub fn language(&self) -> Option<~str> {
+        let mut node = self.nearest_ancestor_element();
+        loop {
+            if node.is_element() {
+                let lang = do node.with_imm_element |element| {
+                    element.get_attr("lang").map(|lang| lang.to_owned())
+                };
+                match lang {
+                    Some(ref lang) if !lang.is_empty() => return Some(copy *lang),
+                    // An empty `lang` attribute means that the language is unknown.
+                    Some(_) => return None,
+                    None => {}
+                }
+            }
+            match node.parent_node() {
+                Some(parent) => node = parent,
+                None => return None,
+            }
+        }
+    }
+
+    /// Returns the offset in the text of this box's node of the character boundary nearest to
+    /// the given horizontal position, relative to the box's left edge, or None if this is not a
+    /// text box or the position is outside it. The offset is in UTF-16 code units, as the DOM
+    /// counts them.
+    pub fn text_offset_at(&self, x: Au) -> Option<uint> {
+        match *self {
+            TextRenderBoxClass(text_box) => {
+                let width = text_box.base.position.size.width;
+                if x < Au(0) || x > width {
+                    return None
+                }
+
+                // Measure from the start of the text, which is the right edge of the box if it
+                // is right-to-left.
+                let x = if text_box.run.rtl { width - x } else { x };
+
+                let range = &text_box.range;
+                let mut advances = vec::from_elem(range.length(), Au(0));
+                for text_box.run.iter_slices_for_range(range) |glyphs, offset, slice_range| {
+                    for glyphs.iter_glyphs_for_char_range(slice_range) |i, glyph| {
+                        // The characters after the first of a ligature have no advance of their
+                        // own, and a character with several glyphs advances by all of them.
+                        advances[offset + i - range.begin()] += glyph.advance_();
+                    }
+                }
+
+                let offset = text_offset_for_advances(advances,
+                                                      x,
+                                                      range.begin() as int + text_box.run_offset,
+                                                      text_box.skip_chars);
+                do offset.map |&offset| {
+                    do text_box.base.node.with_imm_text |text_node| {
+                        utf16_offset(text_node.parent.data.to_str(), offset)
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
     //
     // Painting
     //
@@ -569,12 +1025,28 @@ impl RenderBox {
     /// items, each box puts its display items into the correct stack layer according to CSS 2.1
     /// Appendix E. Finally, the builder flattens the list.
     pub fn build_display_list<E:ExtraDisplayListData>(&self,
-                                                  _: &DisplayListBuilder,
+                                                  builder: &DisplayListBuilder,
                                                   dirty: &Rect<Au>,
                                                   offset: &Point2D<Au>,
                                                   list: &Cell<DisplayList<E>>) {
         let box_bounds = self.position();
         let absolute_box_bounds = box_bounds.translate(offset);
+
+        // A float is painted by its flow wherever it was placed, which need not be near the place
+        // that this box holds for it.
+        match *self {
+            FloatPlaceholderRenderBoxClass(placeholder_box) => {
+                let flow_offset = *offset + placeholder_box.flow.position().origin;
+                placeholder_box.flow.build_display_list_recurse(builder, dirty, &flow_offset, list);
+                return
+            }
+            ListMarkerRenderBoxClass(marker_box) => {
+                marker_box.content.build_display_list(builder, dirty, offset, list);
+                return
+            }
+            _ => {}
+        }
+
         debug!("RenderBox::build_display_list at rel=%?, abs=%?: %s",
                box_bounds, absolute_box_bounds, self.debug_str());
         debug!("RenderBox::build_display_list: dirty=%?, offset=%?", dirty, offset);
@@ -588,6 +1060,16 @@ impl RenderBox {
 
         match *self {
             UnscannedTextRenderBoxClass(*) => fail!(~"Shouldn't see unscanned boxes here."),
+            FloatPlaceholderRenderBoxClass(*) | ListMarkerRenderBoxClass(*) => {
+                fail!(~"Floats and list markers were painted above.")
+            }
+            InlineBlockRenderBoxClass(inline_block_box) => {
+                // The flow of the inline-block paints the inline-block, borders and all, where
+                // this box was placed on the line.
+                let flow_offset = *offset + box_bounds.origin;
+                inline_block_box.flow.build_display_list_recurse(builder, dirty, &flow_offset, list);
+                return
+            }
             TextRenderBoxClass(text_box) => {
                 let nearest_ancestor_element = self.nearest_ancestor_element();
                 let color = nearest_ancestor_element.style().color().to_gfx_color();
@@ -603,6 +1085,7 @@ impl RenderBox {
                         text_run: ~text_box.run.serialize(),
                         range: text_box.range,
                         color: color,
+                        hyphenated: text_box.hyphenated,
                     };
 
                     list.append_item(TextDisplayItemClass(text_display_item))
@@ -778,15 +1261,67 @@ impl RenderBox {
             italic: italic,
             oblique: oblique,
             families: font_families,
+            lang: self.language(),
         }
     }
 
+    /// Returns the OpenType features to shape this box's text with, from the `font-variant`,
+    /// `font-kerning`, `letter-spacing` and `font-feature-settings` properties of the computed
+    /// style of the nearest ancestor-or-self `Element` node.
+    pub fn font_features(&self) -> ~[FontFeature] {
+        let my_style = self.nearest_ancestor_element().style();
+        let mut features = ~[];
+
+        match my_style.font_variant() {
+            CSSFontVariantNormal => {}
+            CSSFontVariantSmallCaps => features.push(FontFeature::new("smcp", 1)),
+        }
+
+        match my_style.font_kerning() {
+            CSSFontKerningAuto => {}
+            CSSFontKerningNormal => features.push(FontFeature::new("kern", 1)),
+            CSSFontKerningNone => features.push(FontFeature::new("kern", 0)),
+        }
+
+        // Letters spaced apart can't be joined into ligatures.
+        match my_style.letter_spacing() {
+            CSSLetterSpacingLength(Px(length)) |
+            CSSLetterSpacingLength(Pt(length)) |
+            CSSLetterSpacingLength(Em(length)) if length != 0f => {
+                features.push(FontFeature::new("liga", 0));
+                features.push(FontFeature::new("clig", 0));
+                features.push(FontFeature::new("dlig", 0));
+            }
+            _ => {}
+        }
+
+        // Explicit feature settings come last, so that they override the properties above.
+        for my_style.font_feature_settings().iter().advance |&(ref tag, value)| {
+            if tag.len() == 4 {
+                features.push(FontFeature::new(tag.as_slice(), value as u32));
+            }
+        }
+
+        features
+    }
+
+    /// Returns how words in this box's text may be hyphenated, from the computed style of the
+    /// nearest ancestor-or-self `Element` node.
+    pub fn hyphens(&self) -> CSSHyphens {
+        self.nearest_ancestor_element().style().hyphens()
+    }
+
     /// Returns the text alignment of the computed style of the nearest ancestor-or-self `Element`
     /// node.
     pub fn text_align(&self) -> CSSTextAlign {
         self.nearest_ancestor_element().style().text_align()
     }
 
+    /// Returns the bidirectional embedding level of this box's content.
+    pub fn bidi_level(&self) -> u8 {
+        self.with_base(|base| base.bidi_level)
+    }
+
     fn line_height(&self) -> CSSLineHeight {
         self.nearest_ancestor_element().style().line_height()
     }
@@ -852,6 +1387,15 @@ impl RenderBox {
         let representation = match *self {
             GenericRenderBoxClass(*) => ~"GenericRenderBox",
             ImageRenderBoxClass(*) => ~"ImageRenderBox",
+            InlineBlockRenderBoxClass(inline_block_box) => {
+                fmt!("InlineBlockRenderBox(flow=f%d)", inline_block_box.flow.id())
+            }
+            FloatPlaceholderRenderBoxClass(placeholder_box) => {
+                fmt!("FloatPlaceholderRenderBox(flow=f%d)", placeholder_box.flow.id())
+            }
+            ListMarkerRenderBoxClass(marker_box) => {
+                fmt!("ListMarkerRenderBox(%s)", marker_box.content.debug_str())
+            }
             TextRenderBoxClass(text_box) => {
                 fmt!("TextRenderBox(text=%s)", text_box.run.text.slice_chars(text_box.range.begin(),
                                                                              text_box.range.end()))
@@ -864,3 +1408,121 @@ impl RenderBox {
         fmt!("box b%?: %s", self.id(), representation)
     }
 }
+
+/// Returns the offset of the content of the given element, in an inline flow, from its place on
+/// the line: the sum of the offsets of the element and the inline elements around it that are
+/// relatively positioned.
+pub fn inline_relative_position_of(node: AbstractNode<LayoutView>,
+                                   containing_block_size: &Size2D<Au>)
+                                   -> Point2D<Au> {
+    let mut position = Au::zero_point();
+    let mut node = node;
+    while node.is_element() {
+        let style = node.style();
+        match style.display(false) {
+            CSSDisplayInline => {}
+            _ => break,
+        }
+        position = position + relative_position_for_style(style, containing_block_size);
+        match node.parent_node() {
+            Some(parent) => node = parent,
+            None => break,
+        }
+    }
+    position
+}
+
+/// Resolves the `top`, `right`, `bottom` and `left` offsets of a relatively positioned element
+/// to the offset of its boxes. If both offsets along an axis are given, `top` and `left` win.
+fn relative_position_for_style(style: CompleteStyle, containing_block_size: &Size2D<Au>)
+                               -> Point2D<Au> {
+    if style.position() != CSSPositionRelative {
+        return Au::zero_point()
+    }
+
+    let resolve = |start: MaybeAuto, end: MaybeAuto| {
+        match (start, end) {
+            (Specified(start), _) => start,
+            (Auto, Specified(end)) => -end,
+            (Auto, Auto) => Au(0),
+        }
+    };
+    let x = resolve(MaybeAuto::from_offset(style.left(), containing_block_size.width),
+                    MaybeAuto::from_offset(style.right(), containing_block_size.width));
+    let y = resolve(MaybeAuto::from_offset(style.top(), containing_block_size.height),
+                    MaybeAuto::from_offset(style.bottom(), containing_block_size.height));
+    Point2D(x, y)
+}
+
+/// Returns the offset in the original text of a node of the character boundary nearest to the
+/// given distance from the start of a run of characters with the given advances, the first of
+/// which is at the given index in the transformed text of the node. Returns None if the distance
+/// is negative or the boundary is not in the node.
+fn text_offset_for_advances(advances: &[Au], x: Au, start: int, skip_chars: &SkipChars)
+                            -> Option<uint> {
+    if x < Au(0) {
+        return None
+    }
+    let mut index = 0;
+    let mut advance = Au(0);
+    for advances.iter().advance |&char_advance| {
+        if x < advance + char_advance.scale_by(0.5) {
+            break
+        }
+        advance += char_advance;
+        index += 1;
+    }
+    let transformed_index = start + index as int;
+    if transformed_index < 0 {
+        return None
+    }
+    Some(skip_chars.transformed_to_original(transformed_index as uint))
+}
+
+#[test]
+fn test_constrain_replaced_size() {
+    let none = SizeConstraint::none();
+    let max_width = SizeConstraint { min: Au(0), max: Some(Au(50)) };
+    let min_height = SizeConstraint { min: Au(400), max: None };
+
+    // Within bounds, the intrinsic size is kept.
+    let size = constrain_replaced_size(Au(100), Au(200), &none, &none);
+    assert!(size.width == Au(100) && size.height == Au(200));
+
+    // Too wide: the width is limited and the height follows the ratio.
+    let size = constrain_replaced_size(Au(100), Au(200), &max_width, &none);
+    assert!(size.width == Au(50) && size.height == Au(100));
+
+    // Too wide and too short: the ratio gives way.
+    let size = constrain_replaced_size(Au(100), Au(200), &max_width, &min_height);
+    assert!(size.width == Au(50) && size.height == Au(400));
+}
+
+#[test]
+fn test_text_offset_for_advances() {
+    // The text "  ab  c" transformed to "ab c".
+    let mut skip_chars = SkipChars::new();
+    skip_chars.skip_char();
+    skip_chars.skip_char();
+    skip_chars.keep_char();
+    skip_chars.keep_char();
+    skip_chars.keep_char();
+    skip_chars.skip_char();
+    skip_chars.keep_char();
+    let advances = [Au(10), Au(10), Au(4), Au(10)];
+
+    // The nearest boundary, skipping the whitespace that was removed.
+    assert!(text_offset_for_advances(advances, Au(0), 0, &skip_chars) == Some(2));
+    assert!(text_offset_for_advances(advances, Au(14), 0, &skip_chars) == Some(3));
+    assert!(text_offset_for_advances(advances, Au(16), 0, &skip_chars) == Some(4));
+    assert!(text_offset_for_advances(advances, Au(23), 0, &skip_chars) == Some(6));
+    assert!(text_offset_for_advances(advances, Au(40), 0, &skip_chars) == Some(7));
+
+    // A box later in the run of a node.
+    assert!(text_offset_for_advances(advances.slice(2, 4), Au(5), 2, &skip_chars) == Some(6));
+
+    // Positions before the box, and boundaries before the node.
+    assert!(text_offset_for_advances(advances, Au(-1), 0, &skip_chars) == None);
+    assert!(text_offset_for_advances(advances, Au(0), -2, &skip_chars) == None);
+    assert!(text_offset_for_advances(advances, Au(21), -2, &skip_chars) == Some(2));
+}
@@ -4,28 +4,41 @@
 
 //! Creates CSS boxes from a DOM tree.
 
+use layout::absolute::{containing_block_of, positioned_inline_ancestor};
 use layout::aux::LayoutAuxMethods;
 use layout::block::BlockFlowData;
 use layout::float::FloatFlowData;
 use layout::box::{GenericRenderBoxClass, ImageRenderBox, ImageRenderBoxClass, RenderBox};
+use layout::box::{FloatPlaceholderRenderBox, FloatPlaceholderRenderBoxClass};
+use layout::box::{InlineBlockRenderBox, InlineBlockRenderBoxClass};
+use layout::box::{ListMarkerRenderBox, ListMarkerRenderBoxClass, TextRenderBoxClass};
 use layout::box::{RenderBoxBase, RenderBoxType, RenderBox_Generic, RenderBox_Image};
 use layout::box::{RenderBox_Text, UnscannedTextRenderBox, UnscannedTextRenderBoxClass};
 use layout::context::LayoutContext;
 use layout::flow::{AbsoluteFlow, BlockFlow, FloatFlow, Flow_Absolute, Flow_Block, Flow_Float};
-use layout::flow::{Flow_Inline, Flow_InlineBlock, Flow_Root, Flow_Table, FlowContext};
-use layout::flow::{FlowContextType, FlowData, InlineBlockFlow, InlineFlow, TableFlow};
+use layout::flow::{Flow_Inline, Flow_InlineBlock, Flow_Root, Flow_Table, Flow_TableCell};
+use layout::flow::{Flow_TableRow, Flow_TableRowGroup, FlowContext, FlowContextType, FlowData};
+use layout::flow::{InlineBlockFlow, InlineFlow, TableCellFlow, TableFlow, TableRowFlow};
+use layout::flow::{TableRowGroupFlow};
 use layout::inline::{InlineFlowData, InlineLayout};
+use layout::list::ListNumbering;
+use layout::list;
+use layout::table::TableFlowData;
 use layout::text::TextRunScanner;
+use layout::text;
 use css::node_style::StyledNode;
 
-use newcss::values::{CSSDisplay, CSSDisplayBlock, CSSDisplayInline, CSSDisplayInlineBlock};
+use gfx::text::util::SkipChars;
+
+use newcss::values::{CSSDisplayBlock, CSSDisplayInline, CSSDisplayInlineBlock};
 use newcss::values::{CSSDisplayTable, CSSDisplayInlineTable, CSSDisplayListItem};
 use newcss::values::{CSSDisplayTableRowGroup, CSSDisplayTableHeaderGroup, CSSDisplayTableFooterGroup};
 use newcss::values::{CSSDisplayTableRow, CSSDisplayTableColumnGroup, CSSDisplayTableColumn};
 use newcss::values::{CSSDisplayTableCell, CSSDisplayTableCaption};
-use newcss::values::{CSSDisplayNone};
+use newcss::values::{CSSDisplay, CSSDisplayNone};
 use newcss::values::{CSSFloatNone};
-use script::dom::element::*;
+use newcss::values::{CSSListStylePositionInside, CSSListStylePositionOutside};
+use newcss::values::{CSSPositionAbsolute, CSSPositionFixed};
 use script::dom::node::{AbstractNode, CommentNodeTypeId, DoctypeNodeTypeId};
 use script::dom::node::{ElementNodeTypeId, LayoutView, TextNodeTypeId};
 use servo_util::range::Range;
@@ -52,6 +65,8 @@ impl LayoutTreeBuilder {
 struct BoxGenerator {
     flow: FlowContext,
     range_stack: ~[uint],
+    /// The open inline elements that blocks have split, whose earlier parts are in earlier flows.
+    split_elements: ~[AbstractNode<LayoutView>],
 }
 
 enum InlineSpacerSide {
@@ -59,40 +74,6 @@ enum InlineSpacerSide {
     LogicalAfter,
 }
 
-priv fn simulate_UA_display_rules(node: AbstractNode<LayoutView>) -> CSSDisplay {
-    // FIXME
-    /*let resolved = do node.aux |nd| {
-        match nd.style.display_type {
-            Inherit | Initial => DisplayInline, // TODO: remove once resolve works
-            Specified(v) => v
-        }
-    };*/
-
-    let resolved = CSSDisplayInline;
-    if resolved == CSSDisplayNone {
-        return resolved;
-    }
-
-    match node.type_id() {
-        DoctypeNodeTypeId | CommentNodeTypeId => CSSDisplayNone,
-        TextNodeTypeId => CSSDisplayInline,
-        ElementNodeTypeId(element_type_id) => {
-            match element_type_id {
-                HTMLHeadElementTypeId |
-                HTMLScriptElementTypeId => CSSDisplayNone,
-                HTMLParagraphElementTypeId |
-                HTMLDivElementTypeId |
-                HTMLBodyElementTypeId |
-                HTMLHeadingElementTypeId |
-                HTMLHtmlElementTypeId |
-                HTMLUListElementTypeId |
-                HTMLOListElementTypeId => CSSDisplayBlock,
-                _ => resolved
-            }
-        }
-    }
-}
-
 impl BoxGenerator {
     /* Debug ids only */
 
@@ -100,7 +81,8 @@ impl BoxGenerator {
         debug!("Creating box generator for flow: %s", flow.debug_str());
         BoxGenerator {
             flow: flow,
-            range_stack: ~[]
+            range_stack: ~[],
+            split_elements: ~[],
         }
     }
 
@@ -125,9 +107,7 @@ impl BoxGenerator {
         debug!("BoxGenerator[f%d]: pushing node: %s", self.flow.id(), node.debug_str());
 
         // first, determine the box type, based on node characteristics
-        let simulated_display = simulate_UA_display_rules(node);
-        // TODO: remove this once UA styles work
-        let box_type = self.decide_box_type(node, simulated_display);
+        let box_type = self.decide_box_type(node);
 
         debug!("BoxGenerator[f%d]: point a", self.flow.id());
 
@@ -151,7 +131,7 @@ impl BoxGenerator {
                 }
                 // TODO: cases for inline-block, etc.
             },
-            BlockFlow(block) => {
+            BlockFlow(block) | AbsoluteFlow(block) | InlineBlockFlow(block) => {
                 debug!("BoxGenerator[f%d]: point b", block.common.id);
                 let new_box = self.make_box(ctx, box_type, node, self.flow, builder);
 
@@ -175,6 +155,28 @@ impl BoxGenerator {
                 assert!(float.box.is_none());
                 float.box = Some(new_box);
             },
+            TableFlow(table) => {
+                let new_box = self.make_box(ctx, box_type, node, self.flow, builder);
+
+                debug!("BoxGenerator[f%d]: attaching box[b%d] to table flow (node: %s)",
+                       table.common.id,
+                       new_box.id(),
+                       node.debug_str());
+
+                assert!(table.box.is_none());
+                table.box = Some(new_box);
+            },
+            TableRowGroupFlow(part) | TableRowFlow(part) | TableCellFlow(part) => {
+                let new_box = self.make_box(ctx, box_type, node, self.flow, builder);
+
+                debug!("BoxGenerator[f%d]: attaching box[b%d] to table part flow (node: %s)",
+                       part.common.id,
+                       new_box.id(),
+                       node.debug_str());
+
+                assert!(part.box.is_none());
+                part.box = Some(new_box);
+            },
             _ => warn!("push_node() not implemented for flow f%d", self.flow.id()),
         }
     }
@@ -205,13 +207,50 @@ impl BoxGenerator {
                 }
 
                 debug!("BoxGenerator: adding element range=%?", node_range);
-                inline.elems.add_mapping(node, &node_range);
+                let first_part = !self.was_split(node);
+                inline.elems.add_part_mapping(node, &node_range, first_part, true);
             },
-            BlockFlow(*) => assert!(self.range_stack.len() == 0),
+            BlockFlow(*) | TableFlow(*) | TableRowGroupFlow(*) | TableRowFlow(*) |
+            TableCellFlow(*) | AbsoluteFlow(*) | InlineBlockFlow(*) => assert!(self.range_stack.len() == 0),
             _ => warn!("pop_node() not implemented for flow %?", self.flow.id()),
         }
     }
 
+    /// Moves this generator on to the given inline flow, which follows the given block that split
+    /// the current one. The inlines that are open around the block end their ranges in the
+    /// current flow, and begin them again in the new one.
+    fn split_into(&mut self, block_node: AbstractNode<LayoutView>, flow: FlowContext) {
+        let inline = self.flow.inline();
+
+        // The open ranges are those of the block's ancestors, innermost last.
+        let mut element = block_node;
+        let mut i = self.range_stack.len();
+        while i > 0 {
+            i -= 1;
+            element = element.parent_node().get();
+
+            // An element with no boxes before the split begins in the next flow instead.
+            let mut node_range: Range = Range::new(self.range_stack[i], 0);
+            node_range.extend_to(inline.boxes.len());
+            if node_range.length() > 0 {
+                debug!("BoxGenerator: adding element range=%? before split", node_range);
+                let first_part = !self.was_split(element);
+                inline.elems.add_part_mapping(element, &node_range, first_part, false);
+                if first_part {
+                    self.split_elements.push(element);
+                }
+            }
+            self.range_stack[i] = 0;
+        }
+
+        self.flow = flow;
+    }
+
+    /// Whether an earlier part of the given open inline element is in an earlier flow.
+    fn was_split(&self, node: AbstractNode<LayoutView>) -> bool {
+        self.split_elements.iter().any(|&element| element == node)
+    }
+
     /// Disambiguate between different methods here instead of inlining, since each case has very
     /// different complexity.
     fn make_box(&mut self,
@@ -251,7 +290,7 @@ impl BoxGenerator {
         }
     }
 
-    fn decide_box_type(&self, node: AbstractNode<LayoutView>, _: CSSDisplay) -> RenderBoxType {
+    fn decide_box_type(&self, node: AbstractNode<LayoutView>) -> RenderBoxType {
         if node.is_text() {
             RenderBox_Text
         } else if node.is_image_element() {
@@ -282,7 +321,8 @@ impl LayoutTreeBuilder {
                              layout_ctx: &LayoutContext,
                              cur_node: AbstractNode<LayoutView>,
                              parent_generator: @mut BoxGenerator,
-                             prev_sibling_generator: Option<@mut BoxGenerator>)
+                             prev_sibling_generator: Option<@mut BoxGenerator>,
+                             item_ordinal: Option<int>)
                              -> Option<@mut BoxGenerator> {
         debug!("Considering node: %s", cur_node.debug_str());
 
@@ -297,10 +337,28 @@ impl LayoutTreeBuilder {
         this_generator.push_node(layout_ctx, cur_node, self);
         debug!("point b: %s", cur_node.debug_str());
 
-        // recurse on child nodes.
-        let mut prev_generator: Option<@mut BoxGenerator> = None;
+        // recurse on child nodes. The first line of a list item begins with its marker, and the
+        // list items among the children are numbered as they are met.
+        let mut prev_generator: Option<@mut BoxGenerator> =
+            self.create_list_marker_generator(layout_ctx, cur_node, this_generator, item_ordinal);
+        let mut numbering: Option<ListNumbering> = None;
         for cur_node.each_child |child_node| {
-            prev_generator = self.construct_recursively(layout_ctx, child_node, this_generator, prev_generator);
+            let child_ordinal = if list::is_list_item(child_node) {
+                let mut item_numbering = match numbering {
+                    Some(item_numbering) => item_numbering,
+                    None => ListNumbering::new(cur_node),
+                };
+                let ordinal = item_numbering.number(child_node);
+                numbering = Some(item_numbering);
+                Some(ordinal)
+            } else {
+                None
+            };
+            prev_generator = self.construct_recursively(layout_ctx,
+                                                        child_node,
+                                                        this_generator,
+                                                        prev_generator,
+                                                        child_ordinal);
         }
 
         this_generator.pop_node(layout_ctx, cur_node);
@@ -310,17 +368,54 @@ impl LayoutTreeBuilder {
         // boxes that correspond to child_flow.node. These boxes may
         // eventually be elided or split, but the mapping between
         // nodes and FlowContexts should not change during layout.
-        let flow: &FlowContext = &this_generator.flow;
-        for flow.each_child |child_flow| {
-            do child_flow.with_base |child_node| {
-                let dom_node = child_node.node;
-                assert!(dom_node.has_layout_data());
-                dom_node.layout_data().flow = Some(child_flow);
+        self.map_child_flows(layout_ctx, this_generator.flow);
+
+        // Absolutely positioned elements are out of the flow, so the content after them carries
+        // on in the flow of the content before them.
+        if this_generator.flow.is_absolutely_positioned() &&
+                this_generator.flow.id() != parent_generator.flow.id() {
+            return prev_sibling_generator
+        }
+
+        // The content after an inline-block, or a float on a line, carries on along the line that
+        // it is on. The content after any other float carries on in the flow of the content before
+        // the float.
+        match this_generator.flow {
+            InlineBlockFlow(*) | FloatFlow(*)
+                    if this_generator.flow.id() != parent_generator.flow.id() => {
+                let parent_flow = this_generator.flow.with_base(|base| base.parent).get();
+                if !parent_flow.starts_inline_flow() {
+                    return prev_sibling_generator
+                }
+                if parent_flow.id() == parent_generator.flow.id() {
+                    return Some(parent_generator)
+                }
+                return Some(@mut BoxGenerator::new(parent_flow))
             }
+            _ => {}
         }
         Some(this_generator)
     }
 
+    /// Maps the nodes of the given flow's children to those children. Anonymous table flows have
+    /// no nodes of their own, and are only finished once the node whose children they wrap is, so
+    /// they are simplified here and their children are mapped instead.
+    fn map_child_flows(&self, layout_ctx: &LayoutContext, flow: FlowContext) {
+        for flow.each_child |child_flow| {
+            if is_anonymous_table_flow(child_flow) {
+                let mut anonymous_flow = child_flow;
+                self.simplify_children_of_flow(layout_ctx, &mut anonymous_flow);
+                self.map_child_flows(layout_ctx, child_flow);
+            } else {
+                do child_flow.with_base |child_node| {
+                    let dom_node = child_node.node;
+                    assert!(dom_node.has_layout_data());
+                    dom_node.layout_data().flow = Some(child_flow);
+                }
+            }
+        }
+    }
+
     pub fn box_generator_for_node(&mut self, 
                               node: AbstractNode<LayoutView>, 
                               parent_generator: @mut BoxGenerator,
@@ -336,20 +431,18 @@ impl LayoutTreeBuilder {
         let display = if (node.is_element()) {
             match node.style().display(is_root(node)) {
                 CSSDisplayNone => return None, // tree ends here if 'display: none'
+                // List items are blocks whose first line begins with a marker.
+                CSSDisplayListItem => CSSDisplayBlock,
                 // TODO(eatkinson) these are hacks so that the code doesn't crash
                 // when unsupported display values are used. They should be deleted
                 // as they are implemented.
-                CSSDisplayListItem => CSSDisplayBlock,
-                CSSDisplayTable => CSSDisplayBlock,
-                CSSDisplayInlineTable => CSSDisplayInlineBlock,
-                CSSDisplayTableRowGroup => CSSDisplayBlock,
-                CSSDisplayTableHeaderGroup => CSSDisplayBlock,
-                CSSDisplayTableFooterGroup => CSSDisplayBlock,
-                CSSDisplayTableRow => CSSDisplayBlock,
+                // TODO: Inline tables should sit on the lines of their parent's inline flow.
+                CSSDisplayInlineTable => CSSDisplayTable,
+                // Columns are not flows: tables read the widths of their column elements from
+                // the document.
+                // TODO: Columns should give their backgrounds to the cells in them.
                 CSSDisplayTableColumnGroup => return None,
                 CSSDisplayTableColumn => return None,
-                CSSDisplayTableCell => CSSDisplayBlock,
-                CSSDisplayTableCaption => CSSDisplayBlock,
                 v => v
             }
         } else {
@@ -361,28 +454,79 @@ impl LayoutTreeBuilder {
             }
         };
 
+        // Absolutely positioned and fixed elements are taken out of the flow (CSS 2.1 § 9.6).
+        if node.is_element() && !is_root(node) {
+            match node.style().position() {
+                CSSPositionAbsolute | CSSPositionFixed => {
+                    return Some(self.create_absolute_generator(node, parent_generator))
+                }
+                _ => {}
+            }
+        }
+
+        // Table parts, and content misplaced among them, are wrapped in anonymous table flows
+        // (CSS 2.1 § 17.2.1).
+        match parent_generator.flow {
+            TableFlow(*) | TableRowGroupFlow(*) | TableRowFlow(*) => {
+                return self.generator_for_table_child(node,
+                                                      display,
+                                                      parent_generator,
+                                                      sibling_generator)
+            }
+            BlockFlow(*) | FloatFlow(*) | TableCellFlow(*) | AbsoluteFlow(*) |
+            InlineBlockFlow(*) if is_table_part(display) => {
+                let table_generator = self.anonymous_child_generator(node,
+                                                                     parent_generator,
+                                                                     sibling_generator,
+                                                                     Flow_Table);
+                return self.generator_for_table_child(node,
+                                                      display,
+                                                      table_generator,
+                                                      sibling_generator)
+            }
+            _ => {}
+        }
+
+        // Table cells, absolutely positioned flows and inline-blocks lay out their children just
+        // as blocks do.
+        let parent_flow = match parent_generator.flow {
+            TableCellFlow(info) | AbsoluteFlow(info) | InlineBlockFlow(info) => BlockFlow(info),
+            flow => flow,
+        };
+
+        // If the previous sibling was wrapped in anonymous table flows, the outermost of them is
+        // the sibling to follow.
+        let sibling_generator = do sibling_generator.map |&gen| {
+            match child_flow_containing(parent_generator.flow, gen.flow) {
+                Some(flow) if flow.id() != gen.flow.id() => @mut BoxGenerator::new(flow),
+                _ => gen,
+            }
+        };
         let sibling_flow: Option<FlowContext> = match sibling_generator {
             None => None,
             Some(gen) => Some(gen.flow)
         };
-        
-        // TODO(eatkinson): use the value of the float property to
-        // determine whether to float left or right.
-        let is_float = if (node.is_element()) {
-            match node.style().float() {
-                CSSFloatNone => false,
-                _ => true
-            }
-        } else {
-            false
-        };
-        
 
-        let new_generator = match (display, parent_generator.flow, sibling_flow) { 
-            (CSSDisplayBlock, BlockFlow(_), _) if is_float => {
-                self.create_child_generator(node, parent_generator, Flow_Float)
-            }
+        // A float lays out its content in a flow of its own, whatever its display (CSS 2.1
+        // § 9.7). A float among inline content is placed among the lines of its inline flow.
+        let is_float = node.is_element() && !is_root(node) && match node.style().float() {
+            CSSFloatNone => false,
+            _ => true
+        };
+        if is_float {
+            let new_generator = match (parent_flow, sibling_generator) {
+                (InlineFlow(*), _) => {
+                    self.create_float_placeholder_generator(node, parent_generator)
+                }
+                (_, Some(gen)) if gen.flow.starts_inline_flow() => {
+                    self.create_float_placeholder_generator(node, gen)
+                }
+                _ => self.create_child_generator(node, parent_generator, Flow_Float),
+            };
+            return Some(new_generator)
+        }
 
+        let new_generator = match (display, parent_flow, sibling_flow) {
             (CSSDisplayBlock, BlockFlow(info), _) => match (info.is_root, node.parent_node()) {
                 // If this is the root node, then use the root flow's
                 // context. Otherwise, make a child block context.
@@ -397,6 +541,10 @@ impl LayoutTreeBuilder {
                 self.create_child_generator(node, parent_generator, Flow_Block)
             }
 
+            (CSSDisplayTable, BlockFlow(*), _) | (CSSDisplayTable, FloatFlow(*), _) => {
+                self.create_child_generator(node, parent_generator, Flow_Table)
+            }
+
             // Inlines that are children of inlines are part of the same flow
             (CSSDisplayInline, InlineFlow(*), _) => parent_generator,
             (CSSDisplayInlineBlock, InlineFlow(*), _) => parent_generator,
@@ -404,14 +552,16 @@ impl LayoutTreeBuilder {
             // Inlines that are children of blocks create new flows if their
             // previous sibling was a block.
             (CSSDisplayInline, BlockFlow(*), Some(BlockFlow(*))) |
-            (CSSDisplayInlineBlock, BlockFlow(*), Some(BlockFlow(*))) => {
+            (CSSDisplayInlineBlock, BlockFlow(*), Some(BlockFlow(*))) |
+            (CSSDisplayInline, BlockFlow(*), Some(TableFlow(*))) |
+            (CSSDisplayInlineBlock, BlockFlow(*), Some(TableFlow(*))) => {
                 self.create_child_generator(node, parent_generator, Flow_Inline)
             }
 
-            // FIXME(eatkinson): this is bogus. Floats should not be able to split
-            // inlines. They should be appended as children of the inline flow.
-            (CSSDisplayInline, _, Some(FloatFlow(*))) |
-            (CSSDisplayInlineBlock, _, Some(FloatFlow(*))) |
+            // Inlines in floats carry on in the flow of their previous sibling if it is an inline
+            // flow, and create new flows otherwise.
+            (CSSDisplayInline, FloatFlow(*), Some(InlineFlow(*))) |
+            (CSSDisplayInlineBlock, FloatFlow(*), Some(InlineFlow(*))) => sibling_generator.get(),
             (CSSDisplayInline, FloatFlow(*), _) |
             (CSSDisplayInlineBlock, FloatFlow(*), _) => {
                 self.create_child_generator(node, parent_generator, Flow_Inline)
@@ -427,14 +577,133 @@ impl LayoutTreeBuilder {
                                                       Flow_Inline)
             }
 
-            // TODO(eatkinson): blocks that are children of inlines need
-            // to split their parent flows.
+            // Blocks that are children of inlines split their parent flows.
+            (CSSDisplayBlock, InlineFlow(*), _) => {
+                self.fixup_split_inline(node, parent_generator, Flow_Block)
+            }
+            (CSSDisplayTable, InlineFlow(*), _) => {
+                self.fixup_split_inline(node, parent_generator, Flow_Table)
+            }
+
             _ => parent_generator
         };
 
+        // An inline-block sits on the line of the inline flow it was given as a whole, and lays
+        // out its content in a flow of its own.
+        let new_generator = match (display, new_generator.flow) {
+            (CSSDisplayInlineBlock, InlineFlow(*)) => {
+                self.create_inline_block_generator(node, new_generator)
+            }
+            _ => new_generator,
+        };
+
         Some(new_generator)
     }
 
+    /// Chooses the generator for a child of a table, row group or row, wrapping the child in
+    /// anonymous row groups, rows and cells as it needs. White space between table parts is
+    /// dropped.
+    fn generator_for_table_child(&mut self,
+                                 node: AbstractNode<LayoutView>,
+                                 display: CSSDisplay,
+                                 parent_generator: @mut BoxGenerator,
+                                 sibling_generator: Option<@mut BoxGenerator>)
+                                 -> Option<@mut BoxGenerator> {
+        if node.is_text() && node.with_imm_text(|text| text.parent.data.to_str().is_whitespace()) {
+            return None
+        }
+
+        let new_generator = match (display, parent_generator.flow) {
+            (CSSDisplayTableCaption, TableFlow(*)) => {
+                self.create_child_generator(node, parent_generator, Flow_Block)
+            }
+            (CSSDisplayTableRowGroup, TableFlow(*)) |
+            (CSSDisplayTableHeaderGroup, TableFlow(*)) |
+            (CSSDisplayTableFooterGroup, TableFlow(*)) => {
+                self.create_child_generator(node, parent_generator, Flow_TableRowGroup)
+            }
+            (CSSDisplayTableRow, TableRowGroupFlow(*)) => {
+                self.create_child_generator(node, parent_generator, Flow_TableRow)
+            }
+            (CSSDisplayTableRow, TableFlow(*)) => {
+                let group_generator = self.anonymous_child_generator(node,
+                                                                     parent_generator,
+                                                                     sibling_generator,
+                                                                     Flow_TableRowGroup);
+                self.create_child_generator(node, group_generator, Flow_TableRow)
+            }
+            (CSSDisplayTableCell, _) => {
+                let row_generator = self.row_generator_for_table_child(node,
+                                                                       parent_generator,
+                                                                       sibling_generator);
+                self.create_child_generator(node, row_generator, Flow_TableCell)
+            }
+            _ => {
+                // Anything else goes in an anonymous cell, along with the content next to it.
+                let row_generator = self.row_generator_for_table_child(node,
+                                                                       parent_generator,
+                                                                       sibling_generator);
+                let cell_generator = self.anonymous_child_generator(node,
+                                                                    row_generator,
+                                                                    sibling_generator,
+                                                                    Flow_TableCell);
+                let sibling_in_cell = do sibling_generator.chain |gen| {
+                    child_flow_containing(cell_generator.flow, gen.flow).map(|_| gen)
+                };
+                return self.box_generator_for_node(node, cell_generator, sibling_in_cell)
+            }
+        };
+
+        Some(new_generator)
+    }
+
+    /// Returns the generator for the row that a cell, or other content, that is a child of the
+    /// given table, row group or row goes in.
+    fn row_generator_for_table_child(&mut self,
+                                     node: AbstractNode<LayoutView>,
+                                     parent_generator: @mut BoxGenerator,
+                                     sibling_generator: Option<@mut BoxGenerator>)
+                                     -> @mut BoxGenerator {
+        match parent_generator.flow {
+            TableRowFlow(*) => parent_generator,
+            TableRowGroupFlow(*) => {
+                self.anonymous_child_generator(node,
+                                               parent_generator,
+                                               sibling_generator,
+                                               Flow_TableRow)
+            }
+            _ => {
+                let group_generator = self.anonymous_child_generator(node,
+                                                                     parent_generator,
+                                                                     sibling_generator,
+                                                                     Flow_TableRowGroup);
+                self.anonymous_child_generator(node,
+                                               group_generator,
+                                               sibling_generator,
+                                               Flow_TableRow)
+            }
+        }
+    }
+
+    /// Returns a generator for an anonymous flow of the given type under the given parent: the
+    /// one that the previous sibling was wrapped in, if there is one, or else a new one.
+    fn anonymous_child_generator(&mut self,
+                                 node: AbstractNode<LayoutView>,
+                                 parent_generator: @mut BoxGenerator,
+                                 sibling_generator: Option<@mut BoxGenerator>,
+                                 ty: FlowContextType)
+                                 -> @mut BoxGenerator {
+        let sibling_wrapper = do sibling_generator.chain |gen| {
+            child_flow_containing(parent_generator.flow, gen.flow)
+        };
+        match sibling_wrapper {
+            Some(flow) if is_anonymous_table_flow(flow) && flow_has_type(flow, ty) => {
+                @mut BoxGenerator::new(flow)
+            }
+            _ => self.create_child_generator(node, parent_generator, ty),
+        }
+    }
+
     pub fn create_child_generator(&mut self,
                               node: AbstractNode<LayoutView>,
                               parent_generator: @mut BoxGenerator,
@@ -447,6 +716,127 @@ impl LayoutTreeBuilder {
         @mut BoxGenerator::new(new_flow)
     }
 
+    /// Creates a generator for an absolutely positioned or fixed element. Its flow stays among
+    /// the children of its parent's flow, which gives it its static position, and is registered
+    /// with the flow of its containing block, which lays it out.
+    fn create_absolute_generator(&mut self,
+                                 node: AbstractNode<LayoutView>,
+                                 parent_generator: @mut BoxGenerator)
+                                 -> @mut BoxGenerator {
+        // TODO: Absolutely positioned children of inlines should take their static position from
+        // the line they would have been on, rather than from the top left of the inline flow.
+        let generator = self.create_child_generator(node, parent_generator, Flow_Absolute);
+        let fixed = node.style().position() == CSSPositionFixed;
+
+        // A relatively positioned inline element around this one contains it. Such an element
+        // has no flow of its own, so its inline flow lays this one out.
+        let containing_inline = match parent_generator.flow {
+            InlineFlow(*) if !fixed => positioned_inline_ancestor(node),
+            _ => None,
+        };
+        let containing_block = match containing_inline {
+            Some(_) => parent_generator.flow,
+            None => containing_block_of(parent_generator.flow, fixed),
+        };
+        match generator.flow {
+            AbsoluteFlow(info) => info.containing_inline = containing_inline,
+            _ => {}
+        }
+        do containing_block.with_mut_base |base| {
+            base.abs_descendants.push(generator.flow);
+        }
+        generator
+    }
+
+    /// Creates a generator for an inline-block on a line of the given inline flow. Its flow is a
+    /// child of the inline flow, and a box stands in for it among the boxes of the inline flow.
+    fn create_inline_block_generator(&mut self,
+                                     node: AbstractNode<LayoutView>,
+                                     inline_generator: @mut BoxGenerator)
+                                     -> @mut BoxGenerator {
+        let generator = self.create_child_generator(node, inline_generator, Flow_InlineBlock);
+        let base = RenderBoxBase::new(node, inline_generator.flow, self.next_box_id());
+        let box = InlineBlockRenderBoxClass(@mut InlineBlockRenderBox::new(base, generator.flow));
+        debug!("LayoutTreeBuilder: created box: %s", box.debug_str());
+        inline_generator.flow.inline().boxes.push(box);
+        generator
+    }
+
+    /// Creates a generator for a float among the content of the given inline flow. Its flow is a
+    /// child of the inline flow, and a box holds its place among the boxes of the inline flow.
+    fn create_float_placeholder_generator(&mut self,
+                                          node: AbstractNode<LayoutView>,
+                                          inline_generator: @mut BoxGenerator)
+                                          -> @mut BoxGenerator {
+        let generator = self.create_child_generator(node, inline_generator, Flow_Float);
+        let base = RenderBoxBase::new(node, inline_generator.flow, self.next_box_id());
+        let box = FloatPlaceholderRenderBoxClass(@mut FloatPlaceholderRenderBox::new(base,
+                                                                                   generator.flow));
+        debug!("LayoutTreeBuilder: created box: %s", box.debug_str());
+        inline_generator.flow.inline().boxes.push(box);
+        generator
+    }
+
+    /// Creates the marker of the given node if it is a list item whose flow is that of the given
+    /// generator, and returns the generator of the inline flow that the marker starts, which the
+    /// content of the first line of the item carries on in. The item has the given ordinal among
+    /// the items of its list.
+    ///
+    /// TODO: If the first child of the item is a block, the marker should go on its first line
+    /// rather than on a line of its own.
+    fn create_list_marker_generator(&mut self,
+                                    layout_ctx: &LayoutContext,
+                                    node: AbstractNode<LayoutView>,
+                                    item_generator: @mut BoxGenerator,
+                                    ordinal: Option<int>)
+                                    -> Option<@mut BoxGenerator> {
+        if !list::is_list_item(node) || item_generator.flow.starts_inline_flow() {
+            return None
+        }
+        let style = node.style();
+        let marker_image = style.list_style_image();
+        let marker_text = list::list_item_marker_text(node, ordinal.get_or_default(1));
+        if marker_image.is_none() && marker_text.is_none() {
+            return None
+        }
+
+        let inline_generator = self.create_child_generator(node, item_generator, Flow_Inline);
+        let base = RenderBoxBase::new(node, inline_generator.flow, self.next_box_id());
+        let content = match (marker_image, marker_text) {
+            (Some(url), _) => {
+                ImageRenderBoxClass(@mut ImageRenderBox::new(base, url, layout_ctx.image_cache))
+            }
+            (None, Some(marker_text)) => {
+                // The marker is in the font of its list item.
+                let item_box = GenericRenderBoxClass(@mut base);
+                let font_style = item_box.font_style();
+                let fontgroup = layout_ctx.font_ctx.get_resolved_font_for_style(&font_style);
+                let run = @fontgroup.create_textrun(layout_ctx.font_ctx,
+                                                    marker_text,
+                                                    false,
+                                                    false,
+                                                    item_box.font_features(),
+                                                    ~[]);
+                let range = Range::new(0, run.char_len());
+                TextRenderBoxClass(@mut text::adapt_textbox_with_range(base,
+                                                                       run,
+                                                                       range,
+                                                                       @SkipChars::new(),
+                                                                       0))
+            }
+            (None, None) => fail!(~"A list item without a marker has no marker box."),
+        };
+        let outside = match style.list_style_position() {
+            CSSListStylePositionOutside => true,
+            CSSListStylePositionInside => false,
+        };
+
+        let box = ListMarkerRenderBoxClass(@mut ListMarkerRenderBox::new(content, outside));
+        debug!("LayoutTreeBuilder: created box: %s", box.debug_str());
+        inline_generator.flow.inline().boxes.push(box);
+        Some(inline_generator)
+    }
+
     pub fn create_child_generator_if_needed(&mut self,
                                         node: AbstractNode<LayoutView>,
                                         parent_generator: @mut BoxGenerator,
@@ -462,87 +852,41 @@ impl LayoutTreeBuilder {
 
     /// Fix up any irregularities such as:
     ///
-    /// * split inlines (CSS 2.1 Section 9.2.1.1)
     /// * elide non-preformatted whitespace-only text boxes and their flows (CSS 2.1 Section
     ///   9.2.2.1).
     ///
-    /// The latter can only be done immediately adjacent to, or at the beginning or end of a block
-    /// flow. Otherwise, the whitespace might affect whitespace collapsing with adjacent text.
+    /// Only inline flows made of nothing but whitespace are elided, since those are adjacent to
+    /// blocks, or at the beginning or end of a block flow. Otherwise, the whitespace might affect
+    /// whitespace collapsing with adjacent text.
     pub fn simplify_children_of_flow(&self, ctx: &LayoutContext, parent_flow: &mut FlowContext) {
         match *parent_flow {
-            InlineFlow(*) => {
-                let mut found_child_inline = false;
-                let mut found_child_block = false;
-
-                let flow = *parent_flow;
-                for flow.each_child |child_ctx: FlowContext| {
-                    match child_ctx {
-                        InlineFlow(*) | InlineBlockFlow(*) => found_child_inline = true,
-                        BlockFlow(*) => found_child_block = true,
-                        _ => {}
-                    }
-                }
-
-                if found_child_block && found_child_inline {
-                    self.fixup_split_inline(*parent_flow)
-                }
-            },
-            BlockFlow(*) | FloatFlow(*) => {
+            BlockFlow(*) | FloatFlow(*) | TableCellFlow(*) | AbsoluteFlow(*) |
+            InlineBlockFlow(*) => {
                 // FIXME: this will create refcounted cycles between the removed flow and any
                 // of its RenderBox or FlowContext children, and possibly keep alive other junk
 
-                // check first/last child for whitespace-ness
-                let first_child = do parent_flow.with_base |parent_node| {
-                    parent_node.first_child
-                };
-                for first_child.iter().advance |&first_flow| {
-                    if first_flow.starts_inline_flow() {
-                        // FIXME: workaround for rust#6393
-                        let mut do_remove = false;
-                        {
-                            let boxes = &first_flow.inline().boxes;
-                            if boxes.len() == 1 && boxes[0].is_whitespace_only() {
-                                debug!("LayoutTreeBuilder: pruning whitespace-only first child \
-                                        flow f%d from parent f%d", 
-                                       first_flow.id(),
-                                       parent_flow.id());
-                                do_remove = true;
-                            }
-                        }
-                        if (do_remove) { 
-                            (*parent_flow).remove_child(first_flow);
-                        }
+                // The inline flows before and after a block that split an inline are often
+                // nothing but whitespace, so check every child rather than just the first and
+                // last ones.
+                let mut whitespace_flows = ~[];
+                for parent_flow.each_child |child_flow: FlowContext| {
+                    if is_whitespace_only_inline_flow(child_flow) {
+                        whitespace_flows.push(child_flow);
                     }
                 }
-
-                let last_child = do parent_flow.with_base |parent_node| {
-                    parent_node.last_child
-                };
-                for last_child.iter().advance |&last_flow| {
-                    if last_flow.starts_inline_flow() {
-                        // FIXME: workaround for rust#6393
-                        let mut do_remove = false;
-                        {
-                            let boxes = &last_flow.inline().boxes;
-                            if boxes.len() == 1 && boxes.last().is_whitespace_only() {
-                                debug!("LayoutTreeBuilder: pruning whitespace-only last child \
-                                        flow f%d from parent f%d", 
-                                       last_flow.id(),
-                                       parent_flow.id());
-                                do_remove = true;
-                            }
-                        }
-                        if (do_remove) {
-                            (*parent_flow).remove_child(last_flow);
-                        }
-                    }
+                for whitespace_flows.iter().advance |&child_flow| {
+                    debug!("LayoutTreeBuilder: pruning whitespace-only child flow f%d from \
+                            parent f%d",
+                           child_flow.id(),
+                           parent_flow.id());
+                    (*parent_flow).remove_child(child_flow);
                 }
 
                 // Issue 543: We only need to do this if there are inline child
                 // flows, but there's not a quick way to check at the moment.
                 for (*parent_flow).each_child |child_flow: FlowContext| {
                     match child_flow {
-                        InlineFlow(*) | InlineBlockFlow(*) => {
+                        InlineFlow(*) => {
                             let mut scanner = TextRunScanner::new();
                             scanner.scan_for_runs(ctx, child_flow);
                         }
@@ -554,9 +898,31 @@ impl LayoutTreeBuilder {
         }
     }
 
-    pub fn fixup_split_inline(&self, _: FlowContext) {
-        // TODO: finish me. 
-        fail!(~"TODO: handle case where an inline is split by a block")
+    /// Splits the inline flow of the given generator around a block-level child of an inline
+    /// (CSS 2.1 Section 9.2.1.1), and returns a generator for the block. The block's flow follows
+    /// the inline flow in their block container, and is followed in turn by a new inline flow, in
+    /// which the given generator, and so the inlines that are open around the block, carry on.
+    ///
+    /// Each part of a split inline maps just the boxes that are in its own flow, so the boxes of
+    /// the first part begin the inline and those of the last part end it.
+    pub fn fixup_split_inline(&mut self,
+                              node: AbstractNode<LayoutView>,
+                              inline_generator: @mut BoxGenerator,
+                              ty: FlowContextType)
+                              -> @mut BoxGenerator {
+        let container = inline_generator.flow.with_base(|base| base.parent).get();
+        let container_generator = @mut BoxGenerator::new(container);
+        let block_generator = self.create_child_generator(node, container_generator, ty);
+
+        let after_flow = self.make_flow(Flow_Inline, node.parent_node().get());
+        container.add_child(after_flow);
+        debug!("LayoutTreeBuilder: split inline flow f%d around f%d, carrying on in f%d",
+               inline_generator.flow.id(),
+               block_generator.flow.id(),
+               after_flow.id());
+        inline_generator.split_into(node, after_flow);
+
+        block_generator
     }
 
     /// Entry point for box creation. Should only be called on the root DOM element.
@@ -566,7 +932,7 @@ impl LayoutTreeBuilder {
         let new_generator = @mut BoxGenerator::new(new_flow);
 
         self.root_flow = Some(new_flow);
-        self.construct_recursively(layout_ctx, root, new_generator, None);
+        self.construct_recursively(layout_ctx, root, new_generator, None, None);
         return Ok(new_flow)
     }
 
@@ -574,15 +940,100 @@ impl LayoutTreeBuilder {
     pub fn make_flow(&mut self, ty: FlowContextType, node: AbstractNode<LayoutView>) -> FlowContext {
         let info = FlowData::new(self.next_flow_id(), node);
         let result = match ty {
-            Flow_Absolute    => AbsoluteFlow(@mut info),
+            Flow_Absolute    => AbsoluteFlow(@mut BlockFlowData::new(info)),
             Flow_Block       => BlockFlow(@mut BlockFlowData::new(info)),
             Flow_Float       => FloatFlow(@mut FloatFlowData::new(info)),
-            Flow_InlineBlock => InlineBlockFlow(@mut info),
+            Flow_InlineBlock => InlineBlockFlow(@mut BlockFlowData::new(info)),
             Flow_Inline      => InlineFlow(@mut InlineFlowData::new(info)),
             Flow_Root        => BlockFlow(@mut BlockFlowData::new_root(info)),
-            Flow_Table       => TableFlow(@mut info),
+            Flow_Table       => TableFlow(@mut TableFlowData::new(info)),
+            Flow_TableRowGroup => TableRowGroupFlow(@mut BlockFlowData::new(info)),
+            Flow_TableRow    => TableRowFlow(@mut BlockFlowData::new(info)),
+            Flow_TableCell   => TableCellFlow(@mut BlockFlowData::new(info)),
         };
         debug!("LayoutTreeBuilder: created flow: %s", result.debug_str());
         result
     }
 }
+
+/// Whether the given flow is an inline flow whose boxes are all whitespace that can be elided.
+/// Preformatted whitespace is kept.
+fn is_whitespace_only_inline_flow(flow: FlowContext) -> bool {
+    match flow {
+        InlineFlow(inline) => {
+            flow.is_leaf() && inline.boxes.iter().all(|box| {
+                box.is_whitespace_only() && !is_preformatted(box.node())
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Whether the given node is inside an element whose whitespace is preformatted.
+///
+/// TODO(#115): Use the CSS `white-space` property. Until text runs do, the elements that the user
+/// agent style sheet gives `white-space: pre` are recognized by name.
+fn is_preformatted(node: AbstractNode<LayoutView>) -> bool {
+    let mut current = Some(node);
+    loop {
+        match current {
+            None => return false,
+            Some(node) => {
+                if node.is_element() {
+                    let tag_name = node.with_imm_element(|element| copy element.tag_name);
+                    match tag_name.as_slice() {
+                        "listing" | "plaintext" | "pre" | "xmp" => return true,
+                        _ => {}
+                    }
+                }
+                current = node.parent_node();
+            }
+        }
+    }
+}
+
+/// Whether the given display value is that of a part of a table that must be in a table.
+fn is_table_part(display: CSSDisplay) -> bool {
+    match display {
+        CSSDisplayTableRowGroup | CSSDisplayTableHeaderGroup | CSSDisplayTableFooterGroup |
+        CSSDisplayTableRow | CSSDisplayTableCell | CSSDisplayTableCaption => true,
+        _ => false,
+    }
+}
+
+/// Whether the given flow is an anonymous table, row group, row or cell: one made to wrap table
+/// parts or their content rather than for a node of its own.
+fn is_anonymous_table_flow(flow: FlowContext) -> bool {
+    match flow {
+        TableFlow(info) => info.box.is_none(),
+        TableRowGroupFlow(info) | TableRowFlow(info) | TableCellFlow(info) => info.box.is_none(),
+        _ => false,
+    }
+}
+
+/// Whether the given flow is one of the given type of table flow.
+fn flow_has_type(flow: FlowContext, ty: FlowContextType) -> bool {
+    match (flow, ty) {
+        (TableFlow(*), Flow_Table) |
+        (TableRowGroupFlow(*), Flow_TableRowGroup) |
+        (TableRowFlow(*), Flow_TableRow) |
+        (TableCellFlow(*), Flow_TableCell) => true,
+        _ => false,
+    }
+}
+
+/// Returns the child of the given parent flow that is, or is an ancestor of, the given flow.
+fn child_flow_containing(parent: FlowContext, flow: FlowContext) -> Option<FlowContext> {
+    let mut flow = flow;
+    loop {
+        match flow.with_base(|base| base.parent) {
+            None => return None,
+            Some(flow_parent) => {
+                if flow_parent.id() == parent.id() {
+                    return Some(flow)
+                }
+                flow = flow_parent;
+            }
+        }
+    }
+}
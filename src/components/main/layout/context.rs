@@ -7,11 +7,13 @@
 use geom::rect::Rect;
 use gfx::font_context::FontContext;
 use gfx::geometry::Au;
+use gfx::text::hyphenation::HyphenationDictionaries;
 use servo_net::local_image_cache::LocalImageCache;
 
 /// Data needed by the layout task.
 pub struct LayoutContext {
     font_ctx: @mut FontContext,
     image_cache: @mut LocalImageCache,
+    hyphenation: @mut HyphenationDictionaries,
     screen_size: Rect<Au>
 }
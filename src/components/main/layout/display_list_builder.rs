@@ -60,6 +60,15 @@ pub trait FlowDisplayListBuilderMethods {
                                                             c: &Rect<Au>,
                                                             d: &Point2D<Au>,
                                                             e: &Cell<DisplayList<E>>);
+    fn build_display_list_for_abs_descendants<E:ExtraDisplayListData>(&self,
+                                                                      a: &DisplayListBuilder,
+                                                                      b: &Rect<Au>,
+                                                                      c: &Point2D<Au>,
+                                                                      d: &Cell<DisplayList<E>>);
+    fn build_fixed_display_list<E:ExtraDisplayListData>(&self,
+                                                        a: &DisplayListBuilder,
+                                                        b: &Rect<Au>,
+                                                        c: &Cell<DisplayList<E>>);
 }
 
 impl FlowDisplayListBuilderMethods for FlowContext {
@@ -77,26 +86,70 @@ impl FlowDisplayListBuilderMethods for FlowContext {
                                                             dirty: &Rect<Au>,
                                                             offset: &Point2D<Au>,
                                                             list: &Cell<DisplayList<E>>) {
-        // Adjust the dirty rect to child flow context coordinates.
-        do child_flow.with_base |child_node| {
-            let abs_flow_bounds = child_node.position.translate(offset);
-            let adj_offset = offset.add(&child_node.position.origin);
-
-            debug!("build_display_list_for_child: rel=%?, abs=%?",
-                   child_node.position,
-                   abs_flow_bounds);
-            debug!("build_display_list_for_child: dirty=%?, offset=%?", dirty, offset);
-
-            if dirty.intersects(&abs_flow_bounds) {
-                debug!("build_display_list_for_child: intersected. recursing into child flow...");
-                child_flow.build_display_list_recurse(builder, dirty, &adj_offset, list);
-            } else {
-                debug!("build_display_list_for_child: Did not intersect...");
+        // Absolutely positioned flows are painted by their containing blocks instead.
+        if child_flow.is_absolutely_positioned() {
+            return
+        }
+        build_display_list_for_flow_at(builder, child_flow, dirty, offset, list)
+    }
+
+    /// Paints the absolutely positioned flows whose containing block this flow is, given the
+    /// offset of this flow. Fixed flows go in the fixed display list instead.
+    fn build_display_list_for_abs_descendants<E:ExtraDisplayListData>(&self,
+                                                                      builder: &DisplayListBuilder,
+                                                                      dirty: &Rect<Au>,
+                                                                      offset: &Point2D<Au>,
+                                                                      list: &Cell<DisplayList<E>>) {
+        let descendants = self.with_base(|base| copy base.abs_descendants);
+        for descendants.iter().advance |&descendant| {
+            if !descendant.is_fixed() {
+                build_display_list_for_flow_at(builder, descendant, dirty, offset, list)
+            }
+        }
+    }
+
+    /// Builds the display list of the fixed flows of this root flow, in viewport coordinates. The
+    /// compositor keeps it in place while the rest of the page scrolls.
+    fn build_fixed_display_list<E:ExtraDisplayListData>(&self,
+                                                        builder: &DisplayListBuilder,
+                                                        dirty: &Rect<Au>,
+                                                        list: &Cell<DisplayList<E>>) {
+        let zero = gfx::geometry::zero_point();
+        let descendants = self.with_base(|base| copy base.abs_descendants);
+        for descendants.iter().advance |&descendant| {
+            if descendant.is_fixed() {
+                build_display_list_for_flow_at(builder, descendant, dirty, &zero, list)
             }
         }
     }
 }
 
+/// Builds the display list of the given flow, whose position is relative to the given offset, if
+/// it intersects the dirty rect.
+fn build_display_list_for_flow_at<E:ExtraDisplayListData>(builder: &DisplayListBuilder,
+                                                          flow: FlowContext,
+                                                          dirty: &Rect<Au>,
+                                                          offset: &Point2D<Au>,
+                                                          list: &Cell<DisplayList<E>>) {
+    // Adjust the dirty rect to child flow context coordinates.
+    do flow.with_base |child_node| {
+        let abs_flow_bounds = child_node.position.translate(offset);
+        let adj_offset = offset.add(&child_node.position.origin);
+
+        debug!("build_display_list_for_child: rel=%?, abs=%?",
+               child_node.position,
+               abs_flow_bounds);
+        debug!("build_display_list_for_child: dirty=%?, offset=%?", dirty, offset);
+
+        if dirty.intersects(&abs_flow_bounds) {
+            debug!("build_display_list_for_child: intersected. recursing into child flow...");
+            flow.build_display_list_recurse(builder, dirty, &adj_offset, list);
+        } else {
+            debug!("build_display_list_for_child: Did not intersect...");
+        }
+    }
+}
+
 //
 // Miscellaneous useful routines
 //
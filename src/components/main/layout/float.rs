@@ -2,13 +2,15 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use layout::absolute::layout_abs_descendants;
+use layout::block::stack_block_children;
 use layout::box::{RenderBox};
 use layout::context::LayoutContext;
 use layout::display_list_builder::{DisplayListBuilder, ExtraDisplayListData};
 use layout::display_list_builder::{FlowDisplayListBuilderMethods};
 use layout::flow::{FloatFlow, FlowData};
-use layout::model::{MaybeAuto};
-use layout::float_context::{FloatContext, PlacementInfo, FloatLeft};
+use layout::model::{MaybeAuto, SizeConstraint, definite_height};
+use layout::float_context::{FloatContext, PlacementInfo, FloatLeft, FloatRight, clear_type};
 
 use std::cell::Cell;
 use geom::point::Point2D;
@@ -16,6 +18,7 @@ use geom::rect::Rect;
 use gfx::display_list::DisplayList;
 use gfx::geometry::Au;
 use gfx::geometry;
+use newcss::values::CSSFloatRight;
 use servo_util::tree::{TreeNodeRef, TreeUtils};
 
 pub struct FloatFlowData {
@@ -66,10 +69,9 @@ impl FloatFlowData {
         for FloatFlow(self).each_child |child_ctx| {
             //assert!(child_ctx.starts_block_flow() || child_ctx.starts_inline_flow());
 
-            do child_ctx.with_mut_base |child_node| {
+            do child_ctx.with_base |child_node| {
                 min_width = geometry::max(min_width, child_node.min_width);
                 pref_width = geometry::max(pref_width, child_node.pref_width);
-                child_node.floats_in = FloatContext::new(child_node.num_floats);
             }
         }
 
@@ -79,8 +81,10 @@ impl FloatFlowData {
                 model.compute_borders(style)
             }
 
-            min_width = min_width.add(&box.get_min_width(ctx));
-            pref_width = pref_width.add(&box.get_pref_width(ctx));
+            // A length `width` replaces the width of the children, within `min-width` and
+            // `max-width`.
+            min_width = box.intrinsic_content_width(min_width) + box.get_min_width(ctx);
+            pref_width = box.intrinsic_content_width(pref_width) + box.get_pref_width(ctx);
         });
 
         self.common.min_width = min_width;
@@ -111,15 +115,22 @@ impl FloatFlowData {
                 let margin_right = MaybeAuto::from_margin(style.margin_right(),
                                                            remaining_width).spec_or_default(Au(0));
 
-
-
-                let shrink_to_fit = geometry::min(self.common.pref_width, 
-                                                  geometry::max(self.common.min_width, 
-                                                                remaining_width));
+                // Shrink-to-fit finds the width of the content, so the margins, borders and
+                // padding that the minimum and preferred widths include are taken out of them and
+                // of the available width (CSS 2.1 § 10.3.5).
+                let noncontent_width = margin_left + margin_right + model.border.left +
+                    model.border.right + model.padding.left + model.padding.right;
+                let available_width = remaining_width - noncontent_width;
+                let shrink_to_fit = geometry::min(self.common.pref_width - noncontent_width,
+                                                  geometry::max(self.common.min_width -
+                                                                noncontent_width,
+                                                                available_width));
+                let shrink_to_fit = geometry::max(Au(0), shrink_to_fit);
 
 
                 let width = MaybeAuto::from_width(style.width(), 
                                                   remaining_width).spec_or_default(shrink_to_fit);
+                let width = SizeConstraint::for_width(style, remaining_width).clamp(width);
                 debug!("assign_widths_float -- width: %?", width);
 
                 model.margin.top = margin_top;
@@ -143,40 +154,53 @@ impl FloatFlowData {
 
         self.common.position.size.width = remaining_width;
 
+        let content_height = self.box.chain(|box| {
+            definite_height(box.style(), self.common.containing_block_height)
+        });
         for FloatFlow(self).each_child |kid| {
             //assert!(kid.starts_block_flow() || kid.starts_inline_flow());
 
             do kid.with_mut_base |child_node| {
                 child_node.position.origin.x = x_offset;
                 child_node.position.size.width = remaining_width;
+                child_node.containing_block_height = content_height;
             }
         }
     }
 
     pub fn assign_height_float(@mut self, ctx: &mut LayoutContext) {
-        for FloatFlow(self).each_child |kid| {
-            kid.assign_height(ctx);
-        }
-
-        let mut cur_y = Au(0);
         let mut top_offset = Au(0);
 
         for self.box.iter().advance |&box| {
             do box.with_model |model| {
                 top_offset = model.margin.top + model.border.top + model.padding.top;
-                cur_y += top_offset;
             }
         }
 
+        // A float lays out its content in a block formatting context of its own, so no margins
+        // collapse through its edges, and the floats in it stay in it.
+        let mut num_floats = 0;
         for FloatFlow(self).each_child |kid| {
-            do kid.with_mut_base |child_node| {
-                child_node.position.origin.y = cur_y;
-                cur_y += child_node.position.size.height;
-            }
+            num_floats += kid.with_base(|child_node| child_node.num_floats);
+        }
+        let stacked = stack_block_children(FloatFlow(self),
+                                           ctx,
+                                           FloatContext::new(num_floats),
+                                           top_offset,
+                                           false);
+        let mut height = stacked.height + stacked.bottom_margins.collapse();
+
+        // A specified height replaces the height of the content; either is kept within
+        // `min-height` and `max-height`.
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            let cb_height = self.common.containing_block_height;
+            let specified_height = MaybeAuto::from_height(style.height(), cb_height);
+            height = SizeConstraint::for_height(style, cb_height).clamp(
+                specified_height.spec_or_default(height));
+            debug!("assign_height_float -- height: %?", height);
         }
 
-        let mut height = cur_y - top_offset;
-        
         let mut noncontent_height = Au(0);
         self.box.map(|&box| {
             do box.with_mut_base |base| {
@@ -190,33 +214,50 @@ impl FloatFlowData {
                 noncontent_height += base.model.margin.top + base.model.margin.bottom;
             }
         });
+        height = height + noncontent_height;
 
-        
-        //TODO(eatkinson): compute heights properly using the 'height' property.
+        // The float goes on the side that it names, below the floats that it clears.
+        let mut f_type = FloatLeft;
+        let mut ceiling = Au(0);
         for self.box.iter().advance |&box| {
-
-            let height_prop = 
-                MaybeAuto::from_height(box.style().height(), Au(0)).spec_or_default(Au(0));
-
-            height = geometry::max(height, height_prop) + noncontent_height;
-            debug!("assign_height_float -- height: %?", height);
-            do box.with_mut_base |base| {
-                base.position.size.height = height;
-            }
+            let style = box.style();
+            f_type = match style.float() {
+                CSSFloatRight => FloatRight,
+                _ => FloatLeft,
+            };
+            let float_bottom = do clear_type(style).chain |clear| {
+                self.common.floats_in.lowest_float_bottom(clear)
+            };
+            ceiling = geometry::max(ceiling, float_bottom.get_or_default(Au(0)));
         }
 
+        // Floats are placed by their margin boxes.
         let info = PlacementInfo {
-            width: self.common.position.size.width,
+            width: self.margin_box_width(),
             height: height,
-            ceiling: Au(0),
+            ceiling: ceiling,
             max_width: self.containing_width,
-            f_type: FloatLeft,
+            f_type: f_type,
         };
 
         // Place the float and return the FloatContext back to the parent flow.
         // After, grab the position and use that to set our position.
         self.common.floats_out = self.common.floats_in.add_float(&info);
         self.rel_pos = self.common.floats_out.last_float_pos();
+
+        layout_abs_descendants(FloatFlow(self), ctx);
+    }
+
+    /// Returns the width of the margin box of this float, by which it is placed.
+    pub fn margin_box_width(&self) -> Au {
+        match self.box {
+            None => self.common.position.size.width,
+            Some(box) => {
+                do box.with_base |base| {
+                    base.position.size.width + base.model.margin.left + base.model.margin.right
+                }
+            }
+        }
     }
 
     pub fn build_display_list_float<E:ExtraDisplayListData>(@mut self,
@@ -226,6 +267,14 @@ impl FloatFlowData {
                                                             list: &Cell<DisplayList<E>>) {
 
         let offset = *offset + self.rel_pos;
+        let containing_block_size = match self.common.parent {
+            Some(parent) => parent.position().size,
+            None => self.common.position.size,
+        };
+        let offset = match self.box {
+            Some(box) => offset + box.relative_position(&containing_block_size),
+            None => offset,
+        };
         self.box.map(|&box| {
             box.build_display_list(builder, dirty, &offset, list)
         });
@@ -236,6 +285,10 @@ impl FloatFlowData {
         for flow.each_child |child| {
             flow.build_display_list_for_child(builder, child, dirty, &offset, list)
         }
+
+        // Absolutely positioned descendants are painted over the content of their containing
+        // block.
+        flow.build_display_list_for_abs_descendants(builder, dirty, &offset, list)
     }
 }
 
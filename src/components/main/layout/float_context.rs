@@ -6,6 +6,8 @@ use geom::point::Point2D;
 use geom::size::Size2D;
 use geom::rect::Rect;
 use gfx::geometry::{Au, max, min};
+use newcss::complete::CompleteStyle;
+use newcss::values::{CSSClearBoth, CSSClearLeft, CSSClearNone, CSSClearRight};
 use std::util::replace;
 use std::vec;
 
@@ -14,6 +16,13 @@ pub enum FloatType{
     FloatRight
 }
 
+/// The sides whose floats an element with `clear` must be placed below.
+pub enum ClearType {
+    ClearLeft,
+    ClearRight,
+    ClearBoth
+}
+
 struct FloatContextBase{
     float_data: ~[Option<FloatData>],
     floats_used: uint,
@@ -26,6 +35,16 @@ struct FloatData{
     f_type: FloatType
 }
 
+/// Returns the sides whose floats an element with the given style must be placed below, if any.
+pub fn clear_type(style: CompleteStyle) -> Option<ClearType> {
+    match style.clear() {
+        CSSClearNone => None,
+        CSSClearLeft => Some(ClearLeft),
+        CSSClearRight => Some(ClearRight),
+        CSSClearBoth => Some(ClearBoth),
+    }
+}
+
 /// All information necessary to place a float
 pub struct PlacementInfo{
     width: Au,      // The dimensions of the float
@@ -100,6 +119,13 @@ impl FloatContext {
             base.last_float_pos()
         }
     }
+
+    #[inline(always)]
+    pub fn lowest_float_bottom(&self, clear: ClearType) -> Option<Au> {
+        do self.with_base |base| {
+            base.lowest_float_bottom(clear)
+        }
+    }
 }
 
 impl FloatContextBase{
@@ -130,6 +156,28 @@ impl FloatContextBase{
         }
     }
 
+    /// Returns the bottom of the lowest float on the sides that `clear` names, if there is one.
+    fn lowest_float_bottom(&self, clear: ClearType) -> Option<Au> {
+        let mut bottom = None;
+        for self.float_data.each |float| {
+            match *float {
+                None => (),
+                Some(data) => {
+                    match (clear, data.f_type) {
+                        (ClearLeft, FloatRight) | (ClearRight, FloatLeft) => loop,
+                        _ => {}
+                    }
+                    let float_bottom = data.bounds.origin.y + data.bounds.size.height;
+                    bottom = match bottom {
+                        None => Some(float_bottom),
+                        Some(bottom) => Some(max(bottom, float_bottom))
+                    };
+                }
+            }
+        }
+        bottom.map(|bottom| *bottom + self.offset.y)
+    }
+
     /// Returns a rectangle that encloses the region from top to top + height,
     /// with width small enough that it doesn't collide with any floats. max_x
     /// is the x-coordinate beyond which floats have no effect (generally 
@@ -220,6 +268,8 @@ impl FloatContextBase{
             },
             f_type: info.f_type
         };
+        // A float may not be placed above any float that precedes it.
+        self.max_y = max(self.max_y, new_float.bounds.origin.y);
         self.float_data[self.floats_used] = Some(new_float);
         self.floats_used += 1;
     }
@@ -238,14 +288,24 @@ impl FloatContextBase{
             match maybe_location {
                 // If there are no floats blocking us, return the current location
                 // TODO(eatknson): integrate with overflow
-                None => return Point2D(Au(0), float_y),
+                None => {
+                    return match info.f_type {
+                        FloatLeft => Point2D(Au(0), float_y),
+                        FloatRight => Point2D(info.max_width - info.width, float_y)
+                    }
+                }
                 Some(rect) => {
                     assert!(rect.origin.y + rect.size.height != float_y, 
                             "Non-terminating float placement");
                     
                     // Place here if there is enough room
                     if (rect.size.width >= info.width) {
-                        return Point2D(rect.origin.x, float_y);
+                        return match info.f_type {
+                            FloatLeft => Point2D(rect.origin.x, float_y),
+                            FloatRight => {
+                                Point2D(rect.origin.x + rect.size.width - info.width, float_y)
+                            }
+                        }
                     }
 
                     // Try to place at the next-lowest location.
@@ -257,3 +317,30 @@ impl FloatContextBase{
     }
 }
 
+
+#[test]
+fn test_place_right_floats_and_clear() {
+    fn placement(width: int, height: int, f_type: FloatType) -> PlacementInfo {
+        PlacementInfo {
+            width: Au(width),
+            height: Au(height),
+            ceiling: Au(0),
+            max_width: Au(100),
+            f_type: f_type,
+        }
+    }
+
+    let mut floats = FloatContext::new(3);
+    floats = floats.add_float(&placement(30, 20, FloatLeft));
+    assert!(floats.last_float_pos() == Point2D(Au(0), Au(0)));
+    floats = floats.add_float(&placement(40, 50, FloatRight));
+    assert!(floats.last_float_pos() == Point2D(Au(60), Au(0)));
+
+    // There is no room left between the floats, so this one goes below the left float.
+    floats = floats.add_float(&placement(40, 10, FloatRight));
+    assert!(floats.last_float_pos() == Point2D(Au(20), Au(20)));
+
+    assert!(floats.lowest_float_bottom(ClearLeft) == Some(Au(20)));
+    assert!(floats.lowest_float_bottom(ClearRight) == Some(Au(50)));
+    assert!(floats.lowest_float_bottom(ClearBoth) == Some(Au(50)));
+}
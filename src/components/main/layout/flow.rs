@@ -24,6 +24,20 @@
 ///   boxes/flows that are subject to inline layout and line breaking and structs to represent
 ///   line breaks and mapping to CSS boxes, for the purpose of handling `getClientRects()` and
 ///   similar methods.
+///
+/// * `TableFlow`: A flow that lays out a table. Its children are its captions, which are block
+///   flows, and its `TableRowGroupFlow`s, whose children are `TableRowFlow`s, whose children are
+///   `TableCellFlow`s. The table positions all of these itself; each cell lays out its own
+///   children like a block.
+///
+/// * `InlineBlockFlow`: A flow that lays out the content of an inline-block as a block. It is a
+///   child of the inline flow whose line the inline-block is on, where an `InlineBlockRenderBox`
+///   stands in for it.
+///
+/// * `AbsoluteFlow`: A flow for an absolutely positioned or fixed element. It stays among the
+///   children of its parent flow, which only gives it its static position, and is also listed in
+///   the `abs_descendants` of the flow that establishes its containing block. That flow lays it
+///   out once its own size is known, and paints it over its own content.
 
 use layout::block::BlockFlowData;
 use layout::float::FloatFlowData;
@@ -31,6 +45,7 @@ use layout::box::RenderBox;
 use layout::context::LayoutContext;
 use layout::display_list_builder::{DisplayListBuilder, ExtraDisplayListData};
 use layout::inline::{InlineFlowData};
+use layout::table::TableFlowData;
 use layout::float_context::{FloatContext, Invalid};
 
 use std::cell::Cell;
@@ -45,12 +60,15 @@ use servo_util::tree::{TreeNode, TreeNodeRef, TreeUtils};
 /// The type of the formatting context and data specific to each context, such as line box
 /// structures or float lists.
 pub enum FlowContext {
-    AbsoluteFlow(@mut FlowData), 
+    AbsoluteFlow(@mut BlockFlowData),
     BlockFlow(@mut BlockFlowData),
     FloatFlow(@mut FloatFlowData),
-    InlineBlockFlow(@mut FlowData),
+    InlineBlockFlow(@mut BlockFlowData),
     InlineFlow(@mut InlineFlowData),
-    TableFlow(@mut FlowData),
+    TableFlow(@mut TableFlowData),
+    TableRowGroupFlow(@mut BlockFlowData),
+    TableRowFlow(@mut BlockFlowData),
+    TableCellFlow(@mut BlockFlowData),
 }
 
 pub enum FlowContextType {
@@ -60,7 +78,10 @@ pub enum FlowContextType {
     Flow_InlineBlock,
     Flow_Inline,
     Flow_Root,
-    Flow_Table
+    Flow_Table,
+    Flow_TableRowGroup,
+    Flow_TableRow,
+    Flow_TableCell,
 }
 
 impl Clone for FlowContext {
@@ -74,8 +95,11 @@ impl FlowContext {
         match *self {
           AbsoluteFlow(data) |
           InlineBlockFlow(data) |
+          BlockFlow(data) |
+          TableRowGroupFlow(data) |
+          TableRowFlow(data) |
+          TableCellFlow(data) => data.teardown(),
           TableFlow(data) => data.teardown(),
-          BlockFlow(data) => data.teardown(),
           FloatFlow(data) => data.teardown(),
           InlineFlow(data) => data.teardown()
         }
@@ -103,36 +127,41 @@ impl FlowData {
         self.next_sibling = None;
 
         self.prev_sibling = None;
+
+        // The absolutely positioned descendants are torn down with the rest of the tree.
+        self.abs_descendants = ~[];
     }
 }
 
 impl TreeNodeRef<FlowData> for FlowContext {
     fn with_base<R>(&self, callback: &fn(&FlowData) -> R) -> R {
         match *self {
-            AbsoluteFlow(info) => callback(info),
-            BlockFlow(info) => {
+            AbsoluteFlow(info) | BlockFlow(info) | InlineBlockFlow(info) => {
                 callback(&info.common)
             }
             FloatFlow(info) => callback(&info.common),
-            InlineBlockFlow(info) => callback(info),
             InlineFlow(info) => {
                 callback(&info.common)
             }
-            TableFlow(info) => callback(info)
+            TableFlow(info) => callback(&info.common),
+            TableRowGroupFlow(info) | TableRowFlow(info) | TableCellFlow(info) => {
+                callback(&info.common)
+            }
         }
     }
     fn with_mut_base<R>(&self, callback: &fn(&mut FlowData) -> R) -> R {
         match *self {
-            AbsoluteFlow(info) => callback(info),
-            BlockFlow(info) => {
+            AbsoluteFlow(info) | BlockFlow(info) | InlineBlockFlow(info) => {
                 callback(&mut info.common)
             }
             FloatFlow(info) => callback(&mut info.common),
-            InlineBlockFlow(info) => callback(info),
             InlineFlow(info) => {
                 callback(&mut info.common)
             }
-            TableFlow(info) => callback(info),
+            TableFlow(info) => callback(&mut info.common),
+            TableRowGroupFlow(info) | TableRowFlow(info) | TableCellFlow(info) => {
+                callback(&mut info.common)
+            }
         }
     }
 }
@@ -162,6 +191,14 @@ pub struct FlowData {
     floats_in: FloatContext,
     floats_out: FloatContext,
     num_floats: uint,
+
+    /// The height of the content box of the containing block of this flow, if it does not depend
+    /// on the content. Percentage heights refer to it. Set by the parent during width
+    /// assignment.
+    containing_block_height: Option<Au>,
+
+    /// The absolutely positioned flows whose containing block this flow establishes.
+    abs_descendants: ~[FlowContext],
 }
 
 impl TreeNode<FlowContext> for FlowData {
@@ -225,6 +262,8 @@ impl FlowData {
             floats_in: Invalid,
             floats_out: Invalid,
             num_floats: 0,
+            containing_block_height: None,
+            abs_descendants: ~[],
         }
     }
 }
@@ -269,29 +308,60 @@ impl<'self> FlowContext {
         }
     }
 
+    /// Whether this flow is taken out of the normal flow by `position: absolute` or `fixed`.
+    pub fn is_absolutely_positioned(&self) -> bool {
+        match *self {
+            AbsoluteFlow(*) => true,
+            _ => false
+        }
+    }
+
+    /// Whether this flow is positioned relative to the viewport by `position: fixed`.
+    pub fn is_fixed(&self) -> bool {
+        match *self {
+            AbsoluteFlow(info) => info.is_fixed(),
+            _ => false
+        }
+    }
+
     pub fn bubble_widths(&self, ctx: &mut LayoutContext) {
         match *self {
-            BlockFlow(info)  => info.bubble_widths_block(ctx),
+            BlockFlow(info) | InlineBlockFlow(info) => info.bubble_widths_block(ctx),
             InlineFlow(info) => info.bubble_widths_inline(ctx),
             FloatFlow(info)  => info.bubble_widths_float(ctx),
+            TableFlow(info)  => info.bubble_widths_table(ctx),
+            TableCellFlow(info) => info.bubble_widths_table_cell(ctx),
+            AbsoluteFlow(info) => info.bubble_widths_absolute(ctx),
+            // The table measures its columns from its cells itself.
+            TableRowGroupFlow(*) | TableRowFlow(*) => {}
             _ => fail!(fmt!("Tried to bubble_widths of flow: f%d", self.id()))
         }
     }
 
     pub fn assign_widths(&self, ctx: &mut LayoutContext) {
         match *self {
-            BlockFlow(info)  => info.assign_widths_block(ctx),
+            // The inline flow of an inline-block has given it its width.
+            BlockFlow(info) | InlineBlockFlow(info) => info.assign_widths_block(ctx),
             InlineFlow(info) => info.assign_widths_inline(ctx),
             FloatFlow(info)  => info.assign_widths_float(ctx),
+            TableFlow(info)  => info.assign_widths_table(ctx),
+            TableCellFlow(info) => info.assign_widths_table_cell(ctx),
+            AbsoluteFlow(info) => info.assign_widths_absolute(ctx),
+            // The table has already placed its row groups and rows across it.
+            TableRowGroupFlow(*) | TableRowFlow(*) => {}
             _ => fail!(fmt!("Tried to assign_widths of flow: f%d", self.id()))
         }
     }
 
     pub fn assign_height(&self, ctx: &mut LayoutContext) {
         match *self {
-            BlockFlow(info)  => info.assign_height_block(ctx),
+            BlockFlow(info) | InlineBlockFlow(info) => info.assign_height_block(ctx),
             InlineFlow(info) => info.assign_height_inline(ctx),
             FloatFlow(info)  => info.assign_height_float(ctx),
+            TableFlow(info)  => info.assign_height_table(ctx),
+            TableCellFlow(info) => info.assign_height_table_cell(ctx),
+            // The containing block lays out absolutely positioned flows once its size is known.
+            AbsoluteFlow(info) => info.assign_static_height_absolute(),
             _ => fail!(fmt!("Tried to assign_height of flow: f%d", self.id()))
         }
     }
@@ -309,6 +379,11 @@ impl<'self> FlowContext {
             BlockFlow(info)  => info.build_display_list_block(builder, dirty, offset, list),
             InlineFlow(info) => info.build_display_list_inline(builder, dirty, offset, list),
             FloatFlow(info)  => info.build_display_list_float(builder, dirty, offset, list),
+            TableFlow(info)  => info.build_display_list_table(builder, dirty, offset, list),
+            TableRowGroupFlow(info) | TableRowFlow(info) | TableCellFlow(info) |
+            AbsoluteFlow(info) | InlineBlockFlow(info) => {
+                info.build_display_list_block(builder, dirty, offset, list)
+            }
             _ => fail!(fmt!("Tried to build_display_list_recurse of flow: %?", self))
         }
     }
@@ -316,12 +391,19 @@ impl<'self> FlowContext {
     // Actual methods that do not require much flow-specific logic
     pub fn foldl_all_boxes<B:Copy>(&self, seed: B, cb: &fn(a: B, b: RenderBox) -> B) -> B {
         match *self {
-            BlockFlow(block) => {
+            BlockFlow(block) | TableRowGroupFlow(block) | TableRowFlow(block) |
+            TableCellFlow(block) | AbsoluteFlow(block) | InlineBlockFlow(block) => {
                 let block = &mut *block;
                 do block.box.map_default(copy seed) |box| {
                     cb(copy seed, *box)
                 }
             }
+            TableFlow(table) => {
+                let table = &mut *table;
+                do table.box.map_default(copy seed) |box| {
+                    cb(copy seed, *box)
+                }
+            }
             InlineFlow(inline) => {
                 let inline = &mut *inline;
                 do inline.boxes.foldl(seed) |acc, box| {
@@ -348,7 +430,8 @@ impl<'self> FlowContext {
 
     pub fn iter_all_boxes(&self, cb: &fn(RenderBox) -> bool) -> bool {
         match *self {
-            BlockFlow(block) => {
+            BlockFlow(block) | TableRowGroupFlow(block) | TableRowFlow(block) |
+            TableCellFlow(block) | AbsoluteFlow(block) | InlineBlockFlow(block) => {
                 let block = &mut *block;
                 for block.box.iter().advance |box| {
                     if !cb(*box) {
@@ -356,6 +439,14 @@ impl<'self> FlowContext {
                     }
                 }
             }
+            TableFlow(table) => {
+                let table = &mut *table;
+                for table.box.iter().advance |box| {
+                    if !cb(*box) {
+                        break;
+                    }
+                }
+            }
             InlineFlow(inline) => {
                 let inline = &mut *inline;
                 for inline.boxes.iter().advance |box| {
@@ -427,6 +518,35 @@ impl<'self> FlowContext {
                     None => ~"FloatFlow",
                 }
             },
+            InlineBlockFlow(inline_block) => {
+                match inline_block.box {
+                    Some(box) => fmt!("InlineBlockFlow(box=b%d)", box.id()),
+                    None => ~"InlineBlockFlow",
+                }
+            },
+            AbsoluteFlow(abs) => {
+                match abs.box {
+                    Some(box) => fmt!("AbsoluteFlow(box=b%d)", box.id()),
+                    None => ~"AbsoluteFlow",
+                }
+            },
+            TableFlow(table) => {
+                match table.box {
+                    Some(box) => fmt!("TableFlow(box=b%d)", box.id()),
+                    None => ~"TableFlow",
+                }
+            },
+            TableRowGroupFlow(part) | TableRowFlow(part) | TableCellFlow(part) => {
+                let name = match *self {
+                    TableRowGroupFlow(*) => "TableRowGroupFlow",
+                    TableRowFlow(*) => "TableRowFlow",
+                    _ => "TableCellFlow",
+                };
+                match part.box {
+                    Some(box) => fmt!("%s(box=b%d)", name, box.id()),
+                    None => name.to_owned(),
+                }
+            },
             _ => ~"(Unknown flow)"
         };
 
@@ -3,25 +3,41 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use std::cell::Cell;
-use layout::box::{CannotSplit, GenericRenderBoxClass, ImageRenderBoxClass, RenderBox};
+use css::node_style::StyledNode;
+use layout::absolute::layout_abs_descendants;
+use layout::box::{CannotSplit, GenericRenderBoxClass, ImageRenderBoxClass};
+use layout::box::{FloatPlaceholderRenderBoxClass, InlineBlockRenderBoxClass};
+use layout::box::{ListMarkerRenderBoxClass, RenderBox};
 use layout::box::{SplitDidFit, SplitDidNotFit, TextRenderBoxClass};
+use layout::box::inline_relative_position_of;
 use layout::context::LayoutContext;
 use layout::display_list_builder::{DisplayListBuilder, ExtraDisplayListData};
-use layout::flow::{FlowContext, FlowData, InlineFlow};
+use layout::display_list_builder::{FlowDisplayListBuilderMethods, ToGfxColor};
+use layout::flow::{FloatFlow, FlowContext, FlowData, InlineFlow};
 use layout::float_context::FloatContext;
+use layout::model::BoxModel;
 use layout::util::{ElementMapping};
 
+use std::cmp::ApproxEq;
+use std::num::Zero;
 use std::u16;
+use std::uint;
 use std::util;
+use std::vec;
 use geom::{Point2D, Rect, Size2D};
-use gfx::display_list::DisplayList;
+use gfx::display_list::{BaseDisplayItem, DisplayList, SolidColorDisplayItem};
+use gfx::display_list::{SolidColorDisplayItemClass};
 use gfx::geometry::Au;
+use gfx::text::bidi::reorder_visually;
 use newcss::values::{CSSTextAlignLeft, CSSTextAlignCenter, CSSTextAlignRight, CSSTextAlignJustify};
+use newcss;
 use newcss::units::{Em, Px, Pt};
 use newcss::values::{CSSLineHeightNormal, CSSLineHeightNumber, CSSLineHeightLength, CSSLineHeightPercentage};
+use script::dom::node::{AbstractNode, LayoutView};
 use servo_util::range::Range;
 use servo_util::tree::{TreeNodeRef, TreeUtils};
 use extra::deque::Deque;
+use extra::sort::merge_sort;
 
 /*
 Lineboxes are represented as offsets into the child list, rather than
@@ -47,7 +63,11 @@ hard to try out that alternative.
 
 struct PendingLine {
     range: Range,
-    bounds: Rect<Au>
+    /// The top left corner of the line and the width of the boxes on it. The height is that of
+    /// the first box, which the line was made room for among the floats.
+    bounds: Rect<Au>,
+    /// The width between the floats beside the line.
+    available_width: Au,
 }
 
 struct LineboxScanner {
@@ -56,34 +76,53 @@ struct LineboxScanner {
     work_list: @mut Deque<RenderBox>,
     pending_line: PendingLine,
     line_spans: ~[Range],
+    /// The tops of the lines, relative to the top of the flow.
+    line_tops: ~[Au],
+    /// The floats beside the lines, relative to the top of the flow.
+    floats: FloatContext,
+    /// The top of the next line.
+    cur_y: Au,
+    /// The floats met on the pending line that did not fit beside it, which go below it.
+    deferred_floats: ~[FlowContext],
 }
 
 impl LineboxScanner {
-    pub fn new(inline: FlowContext) -> LineboxScanner {
+    pub fn new(inline: FlowContext, floats: FloatContext) -> LineboxScanner {
         assert!(inline.starts_inline_flow());
 
         LineboxScanner {
             flow: inline,
             new_boxes: ~[],
             work_list: @mut Deque::new(),
-            pending_line: PendingLine {range: Range::empty(), bounds: Rect(Point2D(Au(0), Au(0)), Size2D(Au(0), Au(0)))},
+            pending_line: PendingLine {
+                range: Range::empty(),
+                bounds: Rect(Point2D(Au(0), Au(0)), Size2D(Au(0), Au(0))),
+                available_width: Au(0),
+            },
             line_spans: ~[],
+            line_tops: ~[],
+            floats: floats,
+            cur_y: Au(0),
+            deferred_floats: ~[],
         }
     }
 
     fn reset_scanner(&mut self) {
         debug!("Resetting line box scanner's state for flow f%d.", self.flow.id());
         self.line_spans = ~[];
+        self.line_tops = ~[];
         self.new_boxes = ~[];
+        self.cur_y = Au(0);
         self.reset_linebox();
     }
 
     fn reset_linebox(&mut self) {
         self.pending_line.range.reset(0,0);
         self.pending_line.bounds = Rect(Point2D(Au(0), Au(0)), Size2D(Au(0), Au(0)));
+        self.pending_line.available_width = Au(0);
     }
 
-    pub fn scan_for_lines(&mut self, ctx: &LayoutContext) {
+    pub fn scan_for_lines(&mut self, ctx: &mut LayoutContext) {
         self.reset_scanner();
 
         { // FIXME: manually control borrow length
@@ -105,11 +144,20 @@ impl LineboxScanner {
                     box
                 };
 
+                // Floats are placed among the lines, and take up no room on them.
+                match cur_box {
+                    FloatPlaceholderRenderBoxClass(placeholder_box) => {
+                        self.place_float(ctx, cur_box, placeholder_box.flow);
+                        loop
+                    }
+                    _ => {}
+                }
+
                 let box_was_appended = self.try_append_to_line(ctx, cur_box);
                 if !box_was_appended {
                     debug!("LineboxScanner: Box wasn't appended, because line %u was full.",
                            self.line_spans.len());
-                    self.flush_current_line();
+                    self.flush_current_line(ctx);
                 } else {
                     debug!("LineboxScanner: appended a box to line %u", self.line_spans.len());
                 }
@@ -118,7 +166,7 @@ impl LineboxScanner {
             if self.pending_line.range.length() > 0 {
                 debug!("LineboxScanner: Partially full linebox %u left at end of scanning.",
                        self.line_spans.len());
-                self.flush_current_line();
+                self.flush_current_line(ctx);
             }
         }
 
@@ -137,15 +185,92 @@ impl LineboxScanner {
         let inline: &mut InlineFlowData = self.flow.inline();
         util::swap(&mut inline.boxes, &mut self.new_boxes);
         util::swap(&mut inline.lines, &mut self.line_spans);
+        util::swap(&mut inline.line_tops, &mut self.line_tops);
+    }
+
+    /// Makes room for a line that starts with the given box, at the top of the next line or
+    /// below it: beside the floats there where the box fits between them, or else below them.
+    fn start_line(&mut self, ctx: &LayoutContext, first_box: RenderBox) {
+        let flow_width = self.flow.position().size.width;
+        let line_height = box_line_height(first_box);
+        let min_width = first_box.get_min_width(ctx);
+
+        let mut left = Au(0);
+        let mut available_width = flow_width;
+        loop {
+            match self.floats.available_rect(self.cur_y, line_height, flow_width) {
+                None => break,
+                Some(rect) if rect.size.width >= min_width => {
+                    left = rect.origin.x;
+                    available_width = rect.size.width;
+                    break
+                }
+                Some(rect) => self.cur_y = rect.origin.y + rect.size.height,
+            }
+        }
+
+        debug!("LineboxScanner: Starting line %u at %? with width %?",
+               self.line_spans.len(), self.cur_y, available_width);
+        self.pending_line.bounds.origin = Point2D(left, self.cur_y);
+        self.pending_line.bounds.size.height = line_height;
+        self.pending_line.available_width = available_width;
+    }
+
+    /// Places the float whose placeholder is the given box: at the top of the pending line if it
+    /// fits beside the boxes on it, or else below the line. The placeholder keeps the place of the
+    /// float among the boxes.
+    fn place_float(&mut self, ctx: &mut LayoutContext, box: RenderBox, flow: FlowContext) {
+        if self.pending_line.range.length() == 0 {
+            let top = self.cur_y;
+            self.lay_out_float(ctx, flow, top);
+            self.new_boxes.push(box);
+            return
+        }
+
+        let remaining_width = self.pending_line.available_width -
+            self.pending_line.bounds.size.width;
+        let float_width = match flow {
+            FloatFlow(float) => float.margin_box_width(),
+            _ => fail!(~"A float placeholder must stand in for a float flow."),
+        };
+        if float_width <= remaining_width {
+            // The boxes on the line move aside for the float.
+            let line_top = self.pending_line.bounds.origin.y;
+            self.lay_out_float(ctx, flow, line_top);
+            let flow_width = self.flow.position().size.width;
+            let line_height = self.pending_line.bounds.size.height;
+            let (left, available_width) =
+                match self.floats.available_rect(line_top, line_height, flow_width) {
+                    None => (Au(0), flow_width),
+                    Some(rect) => (rect.origin.x, rect.size.width),
+                };
+            self.pending_line.bounds.origin.x = left;
+            self.pending_line.available_width = available_width;
+        } else {
+            self.deferred_floats.push(flow);
+        }
+        self.push_box_to_line(box);
+    }
+
+    /// Lays out the given float, whose top can go no higher than `top`, and places it among the
+    /// floats beside the lines.
+    fn lay_out_float(&mut self, ctx: &mut LayoutContext, flow: FlowContext, top: Au) {
+        debug!("LineboxScanner: Placing float f%d at %?", flow.id(), top);
+        do flow.with_mut_base |base| {
+            base.position.origin.y = top;
+            base.floats_in = self.floats.translate(Point2D(Au(0), -top));
+        }
+        flow.assign_height(ctx);
+        self.floats = do flow.with_mut_base |base| {
+            base.floats_out.translate(Point2D(Au(0), top))
+        };
     }
 
-    fn flush_current_line(&mut self) {
+    fn flush_current_line(&mut self, ctx: &mut LayoutContext) {
         debug!("LineboxScanner: Flushing line %u: %?",
                self.line_spans.len(), self.pending_line);
         // set box horizontal offsets
         let line_range = self.pending_line.range;
-        let mut offset_x = Au(0);
-        // TODO(Issue #199): interpretation of CSS 'direction' will change how boxes are positioned.
         debug!("LineboxScanner: Setting horizontal offsets for boxes in line %u range: %?",
                self.line_spans.len(), line_range);
 
@@ -157,53 +282,99 @@ impl LineboxScanner {
             let first_box = self.new_boxes[self.pending_line.range.begin()];
             linebox_align = first_box.text_align();
         } else {
-            // Nothing to lay out, so assume left alignment.
+            // Nothing to lay out, so assume start alignment.
             linebox_align = CSSTextAlignLeft;
         }
 
-        let slack_width = self.flow.position().size.width - self.pending_line.bounds.size.width;
-        match linebox_align {
+        // The line is aligned between the floats beside it. Lines of right-to-left paragraphs
+        // start at the right.
+        // TODO: rust-css gives the initial value of `text-align` as `left`, so an explicit `left`
+        // in a right-to-left paragraph is taken as the start too.
+        let rtl = self.flow.inline().paragraph_level % 2 == 1;
+        let slack_width = self.pending_line.available_width - self.pending_line.bounds.size.width;
+        let mut offset_x = self.pending_line.bounds.origin.x + match linebox_align {
             // So sorry, but justified text is more complicated than shuffling linebox coordinates.
             // TODO(Issue #213): implement `text-align: justify`
-            CSSTextAlignLeft | CSSTextAlignJustify => {
-                for line_range.eachi |i| {
-                    do self.new_boxes[i].with_mut_base |base| {
-                        base.position.origin.x = offset_x;
-                        offset_x += base.position.size.width;
-                    }
-                }
-            },
-            CSSTextAlignCenter => {
-                offset_x = slack_width.scale_by(0.5f);
-                for line_range.eachi |i| {
-                    do self.new_boxes[i].with_mut_base |base| {
-                        base.position.origin.x = offset_x;
-                        offset_x += base.position.size.width;
-                    }
-                }
-            },
-            CSSTextAlignRight => {
-                offset_x = slack_width;
-                for line_range.eachi |i| {
+            CSSTextAlignLeft | CSSTextAlignJustify if rtl => slack_width,
+            CSSTextAlignLeft | CSSTextAlignJustify => Au(0),
+            CSSTextAlignCenter => slack_width.scale_by(0.5f),
+            CSSTextAlignRight => slack_width,
+        };
+
+        // Boxes keep their logical order in the box list, but are positioned in visual order.
+        let line_left = self.pending_line.bounds.origin.x;
+        for self.visual_order_of_line(line_range).iter().advance |&i| {
+            // A marker outside its list item hangs to the left of the line.
+            match self.new_boxes[i] {
+                ListMarkerRenderBoxClass(marker_box) if marker_box.outside => {
                     do self.new_boxes[i].with_mut_base |base| {
-                        base.position.origin.x = offset_x;
-                        offset_x += base.position.size.width;
+                        base.position.origin.x = line_left - base.position.size.width;
                     }
+                    loop
                 }
-            },
+                _ => {}
+            }
+            do self.new_boxes[i].with_mut_base |base| {
+                base.position.origin.x = offset_x;
+                offset_x += base.position.size.width;
+            }
         }
 
+        // The next line goes below this one.
+        let (line_height, _) = measure_line(self.new_boxes, line_range);
+        let line_top = self.pending_line.bounds.origin.y;
+        self.cur_y = line_top + line_height;
+
         // clear line and add line mapping
         debug!("LineboxScanner: Saving information for flushed line %u.", self.line_spans.len());
         self.line_spans.push(line_range);
+        self.line_tops.push(line_top);
         self.reset_linebox();
+
+        // The floats that did not fit beside the line go below it.
+        let deferred_floats = util::replace(&mut self.deferred_floats, ~[]);
+        for deferred_floats.iter().advance |&flow| {
+            let top = self.cur_y;
+            self.lay_out_float(ctx, flow, top);
+        }
+    }
+
+    /// Returns the indices of the boxes of the given line from left to right, as ordered by their
+    /// bidirectional embedding levels. Whitespace at the end of the line is at the paragraph
+    /// level.
+    fn visual_order_of_line(&self, line_range: Range) -> ~[uint] {
+        let mut levels = do vec::from_fn(line_range.length()) |i| {
+            self.new_boxes[line_range.begin() + i].bidi_level()
+        };
+
+        let paragraph_level = self.flow.inline().paragraph_level;
+        let mut i = levels.len();
+        while i > 0 && is_whitespace(self.new_boxes[line_range.begin() + i - 1]) {
+            i -= 1;
+            levels[i] = paragraph_level;
+        }
+
+        return reorder_visually(levels).map(|&i| line_range.begin() + i);
+
+        fn is_whitespace(box: RenderBox) -> bool {
+            match box {
+                TextRenderBoxClass(text_box) => {
+                    text_box.run.range_is_trimmable_whitespace(&text_box.range)
+                }
+                _ => false
+            }
+        }
     }
 
     // return value: whether any box was appended.
     fn try_append_to_line(&mut self, ctx: &LayoutContext, in_box: RenderBox) -> bool {
-        let remaining_width = self.flow.position().size.width - self.pending_line.bounds.size.width;
-        let in_box_width = in_box.position().size.width;
         let line_is_empty: bool = self.pending_line.range.length() == 0;
+        if line_is_empty {
+            self.start_line(ctx, in_box);
+        }
+        let remaining_width = self.pending_line.available_width -
+            self.pending_line.bounds.size.width;
+        let in_box_width = width_on_line(in_box);
 
         debug!("LineboxScanner: Trying to append box to line %u (box width: %?, remaining width: \
                 %?): %s",
@@ -228,7 +399,8 @@ impl LineboxScanner {
                 self.push_box_to_line(in_box);
                 return true;
             } else {
-                debug!("LineboxScanner: Case=box can't split, not appending.");
+                debug!("LineboxScanner: Case=box can't split, not appending and deferring it.");
+                self.work_list.add_front(in_box);
                 return false;
             }
         }
@@ -292,7 +464,7 @@ impl LineboxScanner {
             self.pending_line.range.reset(self.new_boxes.len(), 0);
         }
         self.pending_line.range.extend_by(1);
-        self.pending_line.bounds.size.width += box.position().size.width;
+        self.pending_line.bounds.size.width += width_on_line(box);
         self.new_boxes.push(box);
     }
 }
@@ -307,10 +479,19 @@ pub struct InlineFlowData {
     // vec of ranges into boxes that represents line positions.
     // these ranges are disjoint, and are the result of inline layout.
     lines: ~[Range],
+    /// The tops of the lines, relative to the top of the flow. Lines move down past floats that
+    /// leave no room for them.
+    line_tops: ~[Au],
     // vec of ranges into boxes that represent elements. These ranges
     // must be well-nested, and are only related to the content of
     // boxes (not lines). Ranges are only kept for non-leaf elements.
-    elems: ElementMapping
+    elems: ElementMapping,
+    /// The bidirectional embedding level of the flow's paragraph: 0 if its block container is
+    /// left-to-right, or 1 if it is right-to-left.
+    paragraph_level: u8,
+    /// The distance from the top of the flow to the baseline of its last line box, if it has
+    /// any lines. Inline-blocks are aligned on it.
+    last_line_baseline: Option<Au>,
 }
 
 impl InlineFlowData {
@@ -319,7 +500,10 @@ impl InlineFlowData {
             common: common,
             boxes: ~[],
             lines: ~[],
+            line_tops: ~[],
             elems: ElementMapping::new(),
+            paragraph_level: 0,
+            last_line_baseline: None,
         }
     }
 
@@ -386,50 +570,72 @@ impl InlineFlowData {
             for this.boxes.each |&box| {
                 match box {
                     ImageRenderBoxClass(image_box) => {
-                        let size = image_box.image.get_size();
-                        let width = Au::from_px(size.get_or_default(Size2D(0, 0)).width);
-                        image_box.base.position.size.width = width;
+                        // The height of an image follows from its width, so both are found here.
+                        let size = image_box.used_size(box.style(),
+                                                       this.common.position.size.width,
+                                                       this.common.containing_block_height);
+                        image_box.base.position.size = size;
                     }
                     TextRenderBoxClass(_) => {
                         // Text boxes are preinitialized.
                     }
                     GenericRenderBoxClass(generic_box) => {
-                        // TODO(#225): There will be different cases here for `inline-block` and
-                        // other replaced content.
+                        // TODO: There will be different cases here for other replaced content.
                         // FIXME(pcwalton): This seems clownshoes; can we remove?
                         generic_box.position.size.width = Au::from_px(45);
                     }
+                    InlineBlockRenderBoxClass(inline_block_box) => {
+                        // The box and the flow of the inline-block are the same width.
+                        let width = inline_block_box.margin_box_width(this.common.position.size.width);
+                        inline_block_box.base.position.size.width = width;
+                        do inline_block_box.flow.with_mut_base |base| {
+                            base.position.size.width = width;
+                            base.containing_block_height = this.common.containing_block_height;
+                        }
+                    }
+                    FloatPlaceholderRenderBoxClass(placeholder_box) => {
+                        // The float takes no room on the line, and finds its width within that
+                        // of the whole flow.
+                        do placeholder_box.flow.with_mut_base |base| {
+                            base.position.origin.x = Au(0);
+                            base.position.size.width = this.common.position.size.width;
+                            base.containing_block_height = this.common.containing_block_height;
+                        }
+                    }
+                    ListMarkerRenderBoxClass(marker_box) => {
+                        // Marker images are drawn at their intrinsic size, and marker text is
+                        // preinitialized.
+                        match marker_box.content {
+                            ImageRenderBoxClass(image_box) => {
+                                image_box.base.position.size = image_box.intrinsic_size();
+                            }
+                            _ => {}
+                        }
+                    }
                     // FIXME(pcwalton): This isn't very type safe!
                     _ => fail!(fmt!("Tried to assign width to unknown Box variant: %?", box)),
                 }
             } // End of for loop.
         }
 
-        for InlineFlow(self).each_child |kid| {
-            do kid.with_mut_base |base| {
-                base.position.size.width = self.common.position.size.width;
-            }
-        }
-        // There are no child contexts, so stop here.
-
-        // TODO(Issue #225): once there are 'inline-block' elements, this won't be
-        // true.  In that case, set the InlineBlockBox's width to the
-        // shrink-to-fit width, perform inline flow, and set the block
-        // flow context's width as the assigned width of the
-        // 'inline-block' box that created this flow before recursing.
+        // The child flows are those of the inline-blocks and floats, which were given their widths
+        // above, and absolutely positioned flows, which find their own.
     }
 
     pub fn assign_height_inline(@mut self, ctx: &mut LayoutContext) {
-
+        // The floats are laid out as the lines are formed around them.
         for InlineFlow(self).each_child |kid| {
-            kid.assign_height(ctx);
+            match kid {
+                FloatFlow(*) => {}
+                _ => kid.assign_height(ctx),
+            }
         }
 
-
-        // TODO(eatkinson): line boxes need to shrink if there are floats
-        let mut scanner = LineboxScanner::new(InlineFlow(self));
+        let floats = self.common.floats_in.clone();
+        let mut scanner = LineboxScanner::new(InlineFlow(self), floats);
         scanner.scan_for_lines(ctx);
-        self.common.floats_out = self.common.floats_in.clone();
+        self.common.floats_out = scanner.floats.clone();
+        self.last_line_baseline = None;
 
         // TODO(#226): Get the CSS `line-height` property from the containing block's style to
         // determine minimum linebox height.
@@ -437,86 +643,13 @@ impl InlineFlowData {
         // TODO(#226): Get the CSS `line-height` property from each non-replaced inline element to
         // determine its height for computing linebox height.
 
-        let mut cur_y = Au(0);
+        let mut height = Au(0);
 
         for self.lines.eachi |i, line_span| {
             debug!("assign_height_inline: processing line %u with box span: %?", i, line_span);
 
-            // These coordinates are relative to the left baseline.
-            let mut linebox_bounding_box = Au::zero_rect();
-            let mut linebox_height = Au(0);
-            let mut baseline_offset = Au(0);
-
-            for line_span.eachi |box_i| {
-                let cur_box = self.boxes[box_i];
-
-                // Compute the height and bounding box of each box.
-                let bounding_box = match cur_box {
-                    ImageRenderBoxClass(image_box) => {
-                        let size = image_box.image.get_size();
-                        let height = Au::from_px(size.get_or_default(Size2D(0, 0)).height);
-                        image_box.base.position.size.height = height;
-
-                        if height > linebox_height {
-                            linebox_height = height;
-                        }
-
-                        image_box.base.position.translate(&Point2D(Au(0), -height))
-                    }
-                    TextRenderBoxClass(text_box) => {
-
-                        let range = &text_box.range;
-                        let run = &text_box.run;
-                        
-                        // Compute the height based on the line-height and font size
-                        let text_bounds = run.metrics_for_range(range).bounding_box;
-                        let em_size = text_bounds.size.height;
-                        let line_height = match cur_box.line_height() {
-                            CSSLineHeightNormal => em_size.scale_by(1.14f),
-                            CSSLineHeightNumber(l) => em_size.scale_by(l),
-                            CSSLineHeightLength(Em(l)) => em_size.scale_by(l),
-                            CSSLineHeightLength(Px(l)) => Au::from_frac_px(l),
-                            CSSLineHeightLength(Pt(l)) => Au::from_pt(l),
-                            CSSLineHeightPercentage(p) => em_size.scale_by(p / 100.0f)
-                        };
-
-                        // If this is the current tallest box then use it for baseline
-                        // calculations.
-                        // TODO: this will need to take into account type of line-height
-                        // and the vertical-align value.
-                        if line_height > linebox_height {
-                            linebox_height = line_height;
-                            // Offset from the top of the linebox is 1/2 of the leading + ascent
-                            baseline_offset = text_box.run.font.metrics.ascent +
-                                    (linebox_height - em_size).scale_by(0.5f);
-                        }
-                        text_bounds.translate(&Point2D(text_box.base.position.origin.x, Au(0)))
-                    }
-                    GenericRenderBoxClass(generic_box) => {
-                        // TODO(Issue #225): There will be different cases here for `inline-block`
-                        // and other replaced content.
-                        // FIXME(pcwalton): This seems clownshoes; can we remove?
-                        generic_box.position.size.height = Au::from_px(30);
-                        if generic_box.position.size.height > linebox_height {
-                            linebox_height = generic_box.position.size.height;
-                        }
-                        generic_box.position
-                    }
-                    // FIXME(pcwalton): This isn't very type safe!
-                    _ => {
-                        fail!(fmt!("Tried to assign height to unknown Box variant: %s",
-                                   cur_box.debug_str()))
-                    }
-                };
-
-                debug!("assign_height_inline: bounding box for box b%d = %?",
-                       cur_box.id(),
-                       bounding_box);
-
-                linebox_bounding_box = linebox_bounding_box.union(&bounding_box);
-
-                debug!("assign_height_inline: linebox bounding box = %?", linebox_bounding_box);
-            }
+            let cur_y = self.line_tops[i];
+            let (linebox_height, baseline_offset) = measure_line(self.boxes, *line_span);
 
             // Now go back and adjust the Y coordinates to match the baseline we determined.
             for line_span.eachi |box_i| {
@@ -525,25 +658,156 @@ impl InlineFlowData {
                 // TODO(#226): This is completely wrong. We need to use the element's `line-height`
                 // when calculating line box height. Then we should go back over and set Y offsets
                 // according to the `vertical-align` property of the containing block.
-                let offset = match cur_box {
+                let offset = match unwrap_list_marker(cur_box) {
                     TextRenderBoxClass(text_box) => {
-                        baseline_offset - text_box.run.font.metrics.ascent
+                        baseline_offset - text_box.run.metrics_for_range(&text_box.range).ascent
                     },
+                    InlineBlockRenderBoxClass(inline_block_box) => {
+                        baseline_offset - inline_block_box.baseline()
+                    }
                     _ => Au(0),
                 };
 
                 do cur_box.with_mut_base |base| {
                     base.position.origin.y = offset + cur_y;
                 }
+
+                // The flow of an inline-block goes where its box went.
+                match cur_box {
+                    InlineBlockRenderBoxClass(inline_block_box) => {
+                        do inline_block_box.flow.with_mut_base |base| {
+                            base.position.origin = inline_block_box.base.position.origin;
+                        }
+                    }
+                    _ => {}
+                }
             }
 
-            cur_y += linebox_height;
+            self.last_line_baseline = Some(cur_y + baseline_offset);
+            height = cur_y + linebox_height;
         } // End of `lines.each` loop.
 
-        self.common.position.size.height = cur_y;
+        self.common.position.size.height = height;
+
+        layout_abs_descendants(InlineFlow(self), ctx);
     }
 
-    pub fn build_display_list_inline<E:ExtraDisplayListData>(&self,
+    /// Returns the rect that encloses the boxes of the given inline element, offset by the
+    /// relative positions of it and the inline elements around it. This is the containing block
+    /// of the absolutely positioned elements inside it, if it is positioned (CSS 2.1 § 10.1).
+    ///
+    /// TODO: The containing block should run from the start of the first box of the element to
+    /// the end of the last, rather than enclose every line the element is on.
+    pub fn element_rect(&self, node: AbstractNode<LayoutView>) -> Rect<Au> {
+        let mut rect: Option<Rect<Au>> = None;
+        for self.elems.each |node_range| {
+            if node_range.node != node {
+                loop;
+            }
+            for node_range.range.eachi |i| {
+                let box_rect = self.boxes[i].position();
+                rect = match rect {
+                    Some(rect) => Some(rect.union(&box_rect)),
+                    None => Some(box_rect),
+                };
+            }
+        }
+
+        let rect = rect.get_or_default(Rect(Au::zero_point(), Size2D(Au(0), Au(0))));
+        rect.translate(&inline_relative_position_of(node, &self.common.position.size))
+    }
+
+    /// Paints the backgrounds and borders of the inline elements in this flow, outer elements
+    /// first, as one rect around the boxes of each element on each of its lines (CSS 2.1 § 9.4.2).
+    /// The start edge of an element is only painted where it begins, and its end edge where it
+    /// ends: an element that blocks split has its start edge on its first part and its end edge
+    /// on its last.
+    ///
+    /// TODO: The borders of inline elements do not take up room on the line yet, so they are
+    /// painted around the boxes of the element, over whatever is beside them.
+    priv fn build_display_list_for_elements<E:ExtraDisplayListData>(&self,
+                                                                    dirty: &Rect<Au>,
+                                                                    offset: &Point2D<Au>,
+                                                                    list: &Cell<DisplayList<E>>) {
+        let mut node_ranges = ~[];
+        for self.elems.each |node_range| {
+            node_ranges.push(copy *node_range);
+        }
+        let node_ranges = do merge_sort(node_ranges) |a, b| {
+            a.range.begin() < b.range.begin() ||
+                (a.range.begin() == b.range.begin() && a.range.end() >= b.range.end())
+        };
+
+        let rtl = self.paragraph_level % 2 == 1;
+        let model: BoxModel = Zero::zero();
+        for node_ranges.iter().advance |node_range| {
+            let node = node_range.node;
+            let style = node.style();
+            let border_top = model.compute_border_width(style.border_top_width());
+            let border_right = model.compute_border_width(style.border_right_width());
+            let border_bottom = model.compute_border_width(style.border_bottom_width());
+            let border_left = model.compute_border_width(style.border_left_width());
+            let background_color = style.background_color();
+            let offset = *offset + inline_relative_position_of(node, &self.common.position.size);
+
+            // Find the rect around the boxes of the element on each line, and the first of them.
+            let mut parts = ~[];
+            for self.lines.iter().advance |line_span| {
+                let begin = uint::max(line_span.begin(), node_range.range.begin());
+                let end = uint::min(line_span.end(), node_range.range.end());
+                if begin >= end {
+                    loop;
+                }
+                let mut rect = self.boxes[begin].position();
+                for uint::range(begin + 1, end) |i| {
+                    rect = rect.union(&self.boxes[i].position());
+                }
+                parts.push((rect.translate(&offset), self.boxes[begin]));
+            }
+
+            for parts.iter().enumerate().advance |(i, &(rect, box))| {
+                let starts = node_range.first_part && i == 0;
+                let ends = node_range.last_part && i == parts.len() - 1;
+                let (left_edge, right_edge) = if rtl { (ends, starts) } else { (starts, ends) };
+                let left = if left_edge { border_left } else { Au(0) };
+                let right = if right_edge { border_right } else { Au(0) };
+
+                let bounds = Rect(Point2D(rect.origin.x - left, rect.origin.y - border_top),
+                                  Size2D(left + rect.size.width + right,
+                                         border_top + rect.size.height + border_bottom));
+                if !bounds.intersects(dirty) {
+                    loop;
+                }
+
+                // The background reaches under the borders.
+                push_solid_color(list, box, bounds, background_color);
+
+                let content_bottom = rect.origin.y + rect.size.height;
+                let content_right = rect.origin.x + rect.size.width;
+                push_solid_color(list,
+                                 box,
+                                 Rect(bounds.origin, Size2D(bounds.size.width, border_top)),
+                                 style.border_top_color());
+                push_solid_color(list,
+                                 box,
+                                 Rect(Point2D(content_right, rect.origin.y),
+                                      Size2D(right, rect.size.height)),
+                                 style.border_right_color());
+                push_solid_color(list,
+                                 box,
+                                 Rect(Point2D(bounds.origin.x, content_bottom),
+                                      Size2D(bounds.size.width, border_bottom)),
+                                 style.border_bottom_color());
+                push_solid_color(list,
+                                 box,
+                                 Rect(Point2D(bounds.origin.x, rect.origin.y),
+                                      Size2D(left, rect.size.height)),
+                                 style.border_left_color());
+            }
+        }
+    }
+
+    pub fn build_display_list_inline<E:ExtraDisplayListData>(@mut self,
                                                              builder: &DisplayListBuilder,
                                                              dirty: &Rect<Au>,
                                                              offset: &Point2D<Au>,
@@ -554,12 +818,180 @@ impl InlineFlowData {
                self.common.id,
                self.boxes.len());
 
+        self.build_display_list_for_elements(dirty, offset, list);
+
+        let containing_block_size = self.common.position.size;
         for self.boxes.each |box| {
-            box.build_display_list(builder, dirty, offset, list)
+            let offset = *offset + box.inline_relative_position(&containing_block_size);
+            box.build_display_list(builder, dirty, &offset, list)
+        }
+
+        // The rects of positioned inline elements, which contain the absolutely positioned
+        // descendants, already include their relative positions.
+        InlineFlow(self).build_display_list_for_abs_descendants(builder, dirty, offset, list)
+    }
+}
+
+
+/// Adds a rect of the given color to the display list on behalf of the given box, unless it is
+/// empty or transparent.
+fn push_solid_color<E:ExtraDisplayListData>(list: &Cell<DisplayList<E>>,
+                                            box: RenderBox,
+                                            bounds: Rect<Au>,
+                                            color: newcss::color::Color) {
+    if bounds.size.width <= Au(0) || bounds.size.height <= Au(0) || color.alpha.approx_eq(&0.0) {
+        return
+    }
+    do list.with_mut_ref |list| {
+        let solid_color_display_item = ~SolidColorDisplayItem {
+            base: BaseDisplayItem {
+                bounds: bounds,
+                extra: ExtraDisplayListData::new(box),
+            },
+            color: color.to_gfx_color(),
+        };
+        list.append_item(SolidColorDisplayItemClass(solid_color_display_item))
+    }
+}
+
+/// Measures the line of the given boxes that spans `line_span`, and returns its height and the
+/// distance from its top to its baseline.
+fn measure_line(boxes: &[RenderBox], line_span: Range) -> (Au, Au) {
+    // These coordinates are relative to the left baseline.
+    let mut linebox_bounding_box = Au::zero_rect();
+    let mut linebox_height = Au(0);
+    let mut baseline_offset = Au(0);
+
+    // How far the inline-blocks on the line reach above and below the baseline.
+    let mut inline_block_ascent = Au(0);
+    let mut inline_block_descent = Au(0);
+
+    for line_span.eachi |box_i| {
+        let cur_box = unwrap_list_marker(boxes[box_i]);
+
+        // Compute the height and bounding box of each box.
+        let bounding_box = match cur_box {
+            ImageRenderBoxClass(image_box) => {
+                let height = image_box.base.position.size.height;
+
+                if height > linebox_height {
+                    linebox_height = height;
+                }
+
+                image_box.base.position.translate(&Point2D(Au(0), -height))
+            }
+            TextRenderBoxClass(text_box) => {
+
+                let range = &text_box.range;
+                let run = &text_box.run;
+
+                // Compute the height based on the line-height and font size
+                let text_metrics = run.metrics_for_range(range);
+                let text_bounds = text_metrics.bounding_box;
+                let em_size = text_bounds.size.height;
+                let line_height = text_line_height(cur_box, em_size);
+
+                // If this is the current tallest box then use it for baseline
+                // calculations.
+                // TODO: this will need to take into account type of line-height
+                // and the vertical-align value.
+                if line_height > linebox_height {
+                    linebox_height = line_height;
+                    // Offset from the top of the linebox is 1/2 of the leading + ascent
+                    baseline_offset = text_metrics.ascent +
+                            (linebox_height - em_size).scale_by(0.5f);
+                }
+                text_bounds.translate(&Point2D(text_box.base.position.origin.x, Au(0)))
+            }
+            InlineBlockRenderBoxClass(inline_block_box) => {
+                // Inline-blocks are laid out already, and sit on the baseline.
+                let height = inline_block_box.flow.position().size.height;
+                let baseline = inline_block_box.baseline();
+                inline_block_box.base.position.size.height = height;
+
+                inline_block_ascent = Au::max(inline_block_ascent, baseline);
+                inline_block_descent = Au::max(inline_block_descent, height - baseline);
+
+                inline_block_box.base.position.translate(&Point2D(Au(0), -baseline))
+            }
+            GenericRenderBoxClass(generic_box) => {
+                // TODO: There will be different cases here for other replaced content.
+                // FIXME(pcwalton): This seems clownshoes; can we remove?
+                generic_box.position.size.height = Au::from_px(30);
+                if generic_box.position.size.height > linebox_height {
+                    linebox_height = generic_box.position.size.height;
+                }
+                generic_box.position
+            }
+            // Floats are beside the line rather than on it.
+            FloatPlaceholderRenderBoxClass(*) => Au::zero_rect(),
+            // FIXME(pcwalton): This isn't very type safe!
+            _ => {
+                fail!(fmt!("Tried to assign height to unknown Box variant: %s",
+                           cur_box.debug_str()))
+            }
+        };
+
+        debug!("measure_line: bounding box for box b%d = %?", cur_box.id(), bounding_box);
+
+        linebox_bounding_box = linebox_bounding_box.union(&bounding_box);
+
+        debug!("measure_line: linebox bounding box = %?", linebox_bounding_box);
+    }
+
+    // Inline-blocks that reach above the baseline of the text push the baseline, and the
+    // text, down.
+    if inline_block_ascent > baseline_offset {
+        linebox_height = linebox_height + inline_block_ascent - baseline_offset;
+        baseline_offset = inline_block_ascent;
+    }
+    linebox_height = Au::max(linebox_height, baseline_offset + inline_block_descent);
+
+    (linebox_height, baseline_offset)
+}
+
+/// Returns the height of a line that holds only the given box, as far as it is known before the
+/// line is measured.
+fn box_line_height(box: RenderBox) -> Au {
+    let box = unwrap_list_marker(box);
+    match box {
+        ImageRenderBoxClass(image_box) => image_box.base.position.size.height,
+        TextRenderBoxClass(text_box) => {
+            let text_metrics = text_box.run.metrics_for_range(&text_box.range);
+            text_line_height(box, text_metrics.bounding_box.size.height)
         }
+        InlineBlockRenderBoxClass(inline_block_box) => inline_block_box.flow.position().size.height,
+        GenericRenderBoxClass(*) => Au::from_px(30),
+        _ => Au(0),
+    }
+}
 
-        // TODO(#225): Should `inline-block` elements have flows as children of the inline flow or
-        // should the flow be nested inside the box somehow?
+/// Returns the room that the given box takes up on its line.
+fn width_on_line(box: RenderBox) -> Au {
+    match box {
+        ListMarkerRenderBoxClass(marker_box) => marker_box.width_on_line(),
+        _ => box.position().size.width,
     }
 }
 
+/// Returns the box that draws the given list marker, or else the given box. Markers are measured
+/// and placed on their lines as the text or images that they are.
+fn unwrap_list_marker(box: RenderBox) -> RenderBox {
+    match box {
+        ListMarkerRenderBoxClass(marker_box) => marker_box.content,
+        _ => box,
+    }
+}
+
+/// Returns the height that the `line-height` of the given text box gives it, if its glyphs are
+/// `em_size` tall.
+fn text_line_height(box: RenderBox, em_size: Au) -> Au {
+    match box.line_height() {
+        CSSLineHeightNormal => em_size.scale_by(1.14f),
+        CSSLineHeightNumber(l) => em_size.scale_by(l),
+        CSSLineHeightLength(Em(l)) => em_size.scale_by(l),
+        CSSLineHeightLength(Px(l)) => Au::from_frac_px(l),
+        CSSLineHeightLength(Pt(l)) => Au::from_pt(l),
+        CSSLineHeightPercentage(p) => em_size.scale_by(p / 100.0f)
+    }
+}
@@ -21,24 +21,27 @@ use geom::point::Point2D;
 use geom::rect::Rect;
 use geom::size::Size2D;
 use gfx::display_list::DisplayList;
-use gfx::font_context::FontContext;
+use gfx::font_context::{FontContext, WebFontFace, is_supported_web_font_format};
 use gfx::geometry::Au;
 use gfx::opts::Opts;
 use gfx::render_task::{RenderMsg, RenderChan, RenderLayer};
+use gfx::text::hyphenation::HyphenationDictionaries;
 use newcss::select::SelectCtx;
 use newcss::stylesheet::Stylesheet;
 use newcss::types::OriginAuthor;
 use script::dom::event::ReflowEvent;
-use script::dom::node::{AbstractNode, LayoutView};
+use script::dom::node::{AbstractNode, LayoutView, ScriptView};
 use script::layout_interface::{AddStylesheetMsg, ContentBoxQuery};
 use script::layout_interface::{HitTestQuery, ContentBoxResponse, HitTestResponse};
-use script::layout_interface::{ContentBoxesQuery, ContentBoxesResponse, ExitMsg, LayoutQuery};
-use script::layout_interface::{MatchSelectorsDocumentDamage, Msg};
+use script::layout_interface::{ContentBoxesQuery, ContentBoxesResponse, ExitMsg, LayoutChan};
+use script::layout_interface::{TextOffsetQuery, TextOffsetResponse};
+use script::layout_interface::{LayoutQuery, MatchSelectorsDocumentDamage, Msg};
 use script::layout_interface::{QueryMsg, RouteScriptMsg, Reflow, ReflowDocumentDamage};
-use script::layout_interface::{ReflowForDisplay, ReflowMsg};
+use script::layout_interface::{ReflowForDisplay, ReflowMsg, WebFontLoadedMsg};
 use script::script_task::{ReflowCompleteMsg, ScriptChan, ScriptMsg, SendEventMsg};
 use servo_net::image_cache_task::{ImageCacheTask, ImageResponseMsg};
 use servo_net::local_image_cache::LocalImageCache;
+use servo_net::resource_task::{ResourceTask, load_whole_resource};
 use servo_util::tree::{TreeNodeRef, TreeUtils};
 use servo_util::time::{ProfilerChan, profile};
 use servo_util::time;
@@ -46,11 +49,15 @@ use extra::net::url::Url;
 
 struct LayoutTask {
     port: Port<Msg>,
+    /// A channel to this task, for messages from the tasks it spawns.
+    chan: LayoutChan,
     script_chan: ScriptChan,
     render_chan: RenderChan,
     image_cache_task: ImageCacheTask,
+    resource_task: ResourceTask,
     local_image_cache: @mut LocalImageCache,
     font_ctx: @mut FontContext,
+    hyphenation: @mut HyphenationDictionaries,
     doc_url: Option<Url>,
     screen_size: Option<Size2D<Au>>,
 
@@ -63,17 +70,21 @@ struct LayoutTask {
 
 impl LayoutTask {
     pub fn create(port: Port<Msg>,
+                              chan: LayoutChan,
                               script_chan: ScriptChan,
                               render_chan: RenderChan,
                               img_cache_task: ImageCacheTask,
+                              resource_task: ResourceTask,
                               opts: Opts,
                               profiler_chan: ProfilerChan) {
         let port = Cell::new(port);
         do spawn {
             let mut layout = LayoutTask::new(port.take(),
+                                         chan.clone(),
                                          script_chan.clone(),
                                          render_chan.clone(),
                                          img_cache_task.clone(),
+                                         resource_task.clone(),
                                          &opts,
                                          profiler_chan.clone());
             layout.start();
@@ -81,26 +92,34 @@ impl LayoutTask {
     }
 
     fn new(port: Port<Msg>,
+           chan: LayoutChan,
            script_chan: ScriptChan,
            render_chan: RenderChan, 
            image_cache_task: ImageCacheTask,
+           resource_task: ResourceTask,
            opts: &Opts,
            profiler_chan: ProfilerChan)
            -> LayoutTask {
-        let fctx = @mut FontContext::new(opts.render_backend, true, profiler_chan.clone());
+        let fctx = @mut FontContext::new(opts.render_backend,
+                                         true,
+                                         copy opts.font_prefs,
+                                         profiler_chan.clone());
 
         LayoutTask {
             port: port,
+            chan: chan,
             script_chan: script_chan,
             render_chan: render_chan,
             image_cache_task: image_cache_task.clone(),
+            resource_task: resource_task,
             local_image_cache: @mut LocalImageCache(image_cache_task),
             font_ctx: fctx,
+            hyphenation: @mut HyphenationDictionaries::new(copy opts.hyphenation_dir),
             doc_url: None,
             screen_size: None,
             
             layout_refs: ~[],
-            css_select_ctx: @mut new_css_select_ctx(),
+            css_select_ctx: @mut new_css_select_ctx(copy opts.user_stylesheet),
             profiler_chan: profiler_chan,
         }
     }
@@ -120,13 +139,16 @@ impl LayoutTask {
         LayoutContext {
             image_cache: image_cache,
             font_ctx: font_ctx,
+            hyphenation: self.hyphenation,
             screen_size: Rect(Point2D(Au(0), Au(0)), screen_size),
         }
     }
 
     fn handle_request(&mut self) -> bool {
         match self.port.recv() {
-            AddStylesheetMsg(sheet) => self.handle_add_stylesheet(sheet),
+            AddStylesheetMsg(sheet, font_face_rules) => {
+                self.handle_add_stylesheet(sheet, font_face_rules)
+            }
             ReflowMsg(data) => {
                 let data = Cell::new(data);
 
@@ -144,6 +166,7 @@ impl LayoutTask {
                 debug!("layout: routing %? to script task", script_msg);
                 self.route_script_msg(script_msg);
             }
+            WebFontLoadedMsg(face, url, data) => self.handle_web_font_loaded(face, url, data),
             ExitMsg => {
                 debug!("layout: ExitMsg received");
                 return false
@@ -153,11 +176,73 @@ impl LayoutTask {
         true
     }
 
-    fn handle_add_stylesheet(&self, sheet: Stylesheet) {
+    fn handle_add_stylesheet(&self, sheet: Stylesheet, font_face_rules: ~[FontFaceRule]) {
+        for font_face_rules.iter().advance |rule| {
+            self.load_font_face(rule);
+        }
+
         let sheet = Cell::new(sheet);
         self.css_select_ctx.append_sheet(sheet.take(), OriginAuthor);
     }
 
+    /// Finds the font for an `@font-face` rule. Its sources are tried in order: a `local()`
+    /// source is used at once if the font is installed, and URLs in supported formats are
+    /// downloaded in the background. Until a download finishes, text in the rule's family falls
+    /// back to the other families of its `font-family`.
+    fn load_font_face(&self, rule: &FontFaceRule) {
+        let face = rule.face.clone();
+
+        let mut urls = ~[];
+        for rule.sources.iter().advance |source| {
+            match *source {
+                FontFaceSourceLocal(ref local_family) => {
+                    let local_family = local_family.as_slice();
+                    if urls.is_empty() && self.font_ctx.add_local_web_font(&face, local_family) {
+                        debug!("layout: using local font `%s` for @font-face family `%s`",
+                               local_family, face.family);
+                        return;
+                    }
+                }
+                FontFaceSourceUrl(ref url, ref format) => {
+                    let format = format.map(|format| format.as_slice());
+                    if is_supported_web_font_format(format) {
+                        urls.push(copy *url);
+                    }
+                }
+            }
+        }
+
+        if urls.is_empty() {
+            debug!("layout: no usable source for @font-face family `%s`", face.family);
+            return;
+        }
+
+        // Try each URL in turn until one loads.
+        let face = Cell::new(face);
+        let urls = Cell::new(urls);
+        let chan = self.chan.clone();
+        let resource_task = self.resource_task.clone();
+        do spawn {
+            let face = face.take();
+            for urls.take().consume_iter().advance |url| {
+                match load_whole_resource(&resource_task, copy url) {
+                    Ok(data) => {
+                        chan.send(WebFontLoadedMsg(face, url, data));
+                        return;
+                    }
+                    Err(()) => debug!("layout: failed to load web font from %s", url.to_str()),
+                }
+            }
+        }
+    }
+
+    /// Registers a downloaded web font and reflows, since text in its family was laid out with
+    /// fallback fonts while it was loading.
+    fn handle_web_font_loaded(&mut self, face: WebFontFace, url: Url, data: ~[u8]) {
+        self.font_ctx.add_downloaded_web_font(&face, url.to_str(), data);
+        self.script_chan.send(SendEventMsg(ReflowEvent));
+    }
+
     /// The high-level routine that performs layout tasks.
     fn handle_reflow(&mut self, data: &Reflow) {
         // FIXME: Isolate this transmutation into a "bridge" module.
@@ -243,13 +328,24 @@ impl LayoutTask {
                 // TODO: Be smarter about what needs painting.
                 layout_root.build_display_list(&builder, &layout_root.position(), display_list);
 
+                // Fixed content is painted separately, so that it can stay put while the rest of
+                // the page scrolls.
+                let fixed_display_list = @Cell::new(DisplayList::new());
+                layout_root.build_fixed_display_list(&builder,
+                                                     &layout_ctx.screen_size,
+                                                     fixed_display_list);
+
                 let root_size = do layout_root.with_base |base| {
                     base.position.size
                 };
+                let viewport_size = layout_ctx.screen_size.size;
 
                 let render_layer = RenderLayer {
                     display_list: display_list.take(),
-                    size: Size2D(root_size.width.to_px() as uint, root_size.height.to_px() as uint)
+                    size: Size2D(root_size.width.to_px() as uint, root_size.height.to_px() as uint),
+                    fixed_display_list: fixed_display_list.take(),
+                    viewport_size: Size2D(viewport_size.width.to_px() as uint,
+                                          viewport_size.height.to_px() as uint),
                 };
 
                 self.render_chan.send(RenderMsg(render_layer));
@@ -323,54 +419,87 @@ impl LayoutTask {
                 reply_chan.send(response)
             }
             HitTestQuery(node, point, reply_chan) => {
-                // FIXME: Isolate this transmutation into a single "bridge" module.
-                let node: AbstractNode<LayoutView> = unsafe {
-                    transmute(node)
-                };
-                let mut flow_node: AbstractNode<LayoutView> = node;
-                for node.traverse_preorder |node| {
-                    if node.layout_data().flow.is_some() {
-                        flow_node = node;
-                        break;
+                let point = Point2D(Au::from_frac_px(point.x as float),
+                                    Au::from_frac_px(point.y as float));
+                let mut resp = Err(());
+                match self.build_hit_test_display_list(node) {
+                    None => {}
+                    Some(display_list) => {
+                        match display_list.item_at(point) {
+                            Some(display_item) => {
+                                resp = Ok(HitTestResponse(display_item.base().extra.node()))
+                            }
+                            None => {}
+                        }
                     }
-                };
+                }
 
-                let response = match flow_node.layout_data().flow {
-                    None => {
-                        debug!("HitTestQuery: flow is None");
-                        Err(())
-                    }
-                    Some(flow) => {
-                        let layout_ctx = self.build_layout_context();
-                        let builder = DisplayListBuilder {
-                            ctx: &layout_ctx,
-                        };
-                        let display_list: @Cell<DisplayList<RenderBox>> =
-                            @Cell::new(DisplayList::new());
-                        flow.build_display_list(&builder,
-                                                &flow.position(),
-                                                display_list);
-                        let (x, y) = (Au::from_frac_px(point.x as float),
-                                      Au::from_frac_px(point.y as float));
-                        let mut resp = Err(());
-                        let display_list = &display_list.take().list;
-                        // iterate in reverse to ensure we have the most recently painted render box
-                        for display_list.rev_iter().advance |display_item| {
-                            let bounds = display_item.bounds();
-                            // TODO this check should really be performed by a method of DisplayItem
-                            if x <= bounds.origin.x + bounds.size.width &&
-                               bounds.origin.x <= x &&
-                               y < bounds.origin.y + bounds.size.height &&
-                               bounds.origin.y <  y {
-                                resp = Ok(HitTestResponse(display_item.base().extra.node()));
-                                break;
+                reply_chan.send(resp)
+            }
+            TextOffsetQuery(node, point, reply_chan) => {
+                let point = Point2D(Au::from_frac_px(point.x as float),
+                                    Au::from_frac_px(point.y as float));
+                let mut resp = Err(());
+                match self.build_hit_test_display_list(node) {
+                    None => {}
+                    Some(display_list) => {
+                        match display_list.item_at(point) {
+                            Some(display_item) => {
+                                let box = display_item.base().extra;
+                                let box_x = point.x - display_item.bounds().origin.x;
+                                match box.text_offset_at(box_x) {
+                                    Some(offset) => {
+                                        resp = Ok(TextOffsetResponse(box.node(), offset))
+                                    }
+                                    None => {}
+                                }
                             }
+                            None => {}
                         }
-                        resp
                     }
-                };
+                }
 
-                reply_chan.send(response)
+                reply_chan.send(resp)
+            }
+        }
+    }
+
+    /// Builds a display list of the flow tree that the given node or its first descendant with a
+    /// flow is the root of, whose items refer back to the render boxes that created them. Returns
+    /// None if there is no such flow.
+    fn build_hit_test_display_list(&self, node: AbstractNode<ScriptView>)
+                                   -> Option<DisplayList<RenderBox>> {
+        // FIXME: Isolate this transmutation into a single "bridge" module.
+        let node: AbstractNode<LayoutView> = unsafe {
+            transmute(node)
+        };
+        let mut flow_node: AbstractNode<LayoutView> = node;
+        for node.traverse_preorder |node| {
+            if node.layout_data().flow.is_some() {
+                flow_node = node;
+                break;
+            }
+        };
+
+        match flow_node.layout_data().flow {
+            None => {
+                debug!("build_hit_test_display_list: flow is None");
+                None
+            }
+            Some(flow) => {
+                let layout_ctx = self.build_layout_context();
+                let builder = DisplayListBuilder {
+                    ctx: &layout_ctx,
+                };
+                let display_list: @Cell<DisplayList<RenderBox>> =
+                    @Cell::new(DisplayList::new());
+                flow.build_display_list(&builder,
+                                        &flow.position(),
+                                        display_list);
+                // FIXME: Fixed content is hit in viewport coordinates, but the point is in page
+                // coordinates, so this is only right while the page is not scrolled.
+                flow.build_fixed_display_list(&builder, &layout_ctx.screen_size, display_list);
+                Some(display_list.take())
             }
         }
     }
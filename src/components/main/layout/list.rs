@@ -0,0 +1,177 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The markers of list items (CSS 2.1 § 12.5), and the numbering of the items of ordered lists
+//! (HTML § 4.4.5).
+
+use css::node_style::StyledNode;
+
+use std::int;
+use newcss::values::{CSSDisplayListItem, CSSListStyleType, CSSListStyleTypeCircle};
+use newcss::values::{CSSListStyleTypeDecimal, CSSListStyleTypeDisc};
+use newcss::values::{CSSListStyleTypeDisclosureClosed, CSSListStyleTypeDisclosureOpen};
+use newcss::values::{CSSListStyleTypeLowerAlpha, CSSListStyleTypeLowerRoman};
+use newcss::values::{CSSListStyleTypeNone, CSSListStyleTypeSquare, CSSListStyleTypeUpperAlpha};
+use newcss::values::{CSSListStyleTypeUpperRoman};
+use script::dom::node::{AbstractNode, LayoutView};
+use servo_util::tree::TreeUtils;
+
+static ROMAN_NUMERALS: [(int, &'static str), ..13] = [
+    (1000, "m"), (900, "cm"), (500, "d"), (400, "cd"), (100, "c"), (90, "xc"), (50, "l"),
+    (40, "xl"), (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i")
+];
+
+/// Numbers the items of a list in order as they are met (HTML § 4.4.5). The first item is
+/// numbered by the `start` attribute of the list, and each item after it counts on from the one
+/// before it, down if the list is `reversed` and up otherwise. Any item can be renumbered by its
+/// `value` attribute, and the ones after it count on from there.
+pub struct ListNumbering {
+    priv next: int,
+    priv step: int,
+}
+
+impl ListNumbering {
+    /// Starts numbering the list items among the children of the given node.
+    pub fn new(list: AbstractNode<LayoutView>) -> ListNumbering {
+        let reversed = list.is_element() && do list.with_imm_element |element| {
+            element.get_attr("reversed").is_some()
+        };
+        let step = if reversed { -1 } else { 1 };
+        let start = match integer_attribute(list, "start") {
+            Some(start) => start,
+            None if reversed => {
+                let mut item_count = 0;
+                for list.each_child |child| {
+                    if is_list_item(child) {
+                        item_count += 1;
+                    }
+                }
+                item_count
+            }
+            None => 1,
+        };
+        ListNumbering {
+            next: start,
+            step: step,
+        }
+    }
+
+    /// Returns the ordinal of the given item, which is the next item of the list.
+    pub fn number(&mut self, item: AbstractNode<LayoutView>) -> int {
+        let ordinal = integer_attribute(item, "value").get_or_default(self.next);
+        self.next = ordinal + self.step;
+        ordinal
+    }
+}
+
+/// Returns the text of the marker of the given list item, which has the given ordinal, if it has
+/// one.
+pub fn list_item_marker_text(item: AbstractNode<LayoutView>, ordinal: int) -> Option<~str> {
+    marker_text(item.style().list_style_type(), ordinal)
+}
+
+/// Returns the text of the marker of a list item with the given `list-style-type` and ordinal,
+/// followed by the space that separates it from the content of the item, or None if the item has
+/// no marker. Ordinals that an alphabetic or roman style cannot represent are given in decimal.
+fn marker_text(list_style_type: CSSListStyleType, ordinal: int) -> Option<~str> {
+    let numbered = |numbering: Option<~str>| {
+        fmt!("%s.", numbering.get_or_default(ordinal.to_str()))
+    };
+    let marker = match list_style_type {
+        CSSListStyleTypeNone => return None,
+        // A bullet, a white bullet and a black small square.
+        CSSListStyleTypeDisc => ~"\u2022",
+        CSSListStyleTypeCircle => ~"\u25e6",
+        CSSListStyleTypeSquare => ~"\u25aa",
+        CSSListStyleTypeDecimal => fmt!("%d.", ordinal),
+        CSSListStyleTypeLowerAlpha => numbered(alphabetic(ordinal)),
+        CSSListStyleTypeUpperAlpha => ascii_upper(numbered(alphabetic(ordinal))),
+        CSSListStyleTypeLowerRoman => numbered(roman(ordinal)),
+        CSSListStyleTypeUpperRoman => ascii_upper(numbered(roman(ordinal))),
+        // Small triangles pointing down and right.
+        CSSListStyleTypeDisclosureOpen => ~"\u25be",
+        CSSListStyleTypeDisclosureClosed => ~"\u25b8",
+    };
+    Some(marker + " ")
+}
+
+/// Whether the given node is an element displayed as a list item.
+pub fn is_list_item(node: AbstractNode<LayoutView>) -> bool {
+    node.is_element() && match node.style().display(false) {
+        CSSDisplayListItem => true,
+        _ => false,
+    }
+}
+
+/// Returns the value of the given integer attribute of an element, if it has a valid one.
+fn integer_attribute(node: AbstractNode<LayoutView>, name: &str) -> Option<int> {
+    element_attribute(node, name).chain(|value| int::from_str(value.trim()))
+}
+
+/// Returns the value of the given attribute of an element, if it has one.
+fn element_attribute(node: AbstractNode<LayoutView>, name: &str) -> Option<~str> {
+    if !node.is_element() {
+        return None
+    }
+    do node.with_imm_element |element| {
+        element.get_attr(name).map(|value| value.to_owned())
+    }
+}
+
+/// Returns the given positive ordinal in lowercase latin letters: a to z, then aa to zz, and so
+/// on.
+fn alphabetic(ordinal: int) -> Option<~str> {
+    if ordinal < 1 {
+        return None
+    }
+    let mut letters = ~"";
+    let mut n = ordinal;
+    while n > 0 {
+        n -= 1;
+        letters = fmt!("%c%s", ('a' as u8 + (n % 26) as u8) as char, letters);
+        n /= 26;
+    }
+    Some(letters)
+}
+
+/// Returns the given ordinal in lowercase roman numerals, if it is between 1 and 3999.
+fn roman(ordinal: int) -> Option<~str> {
+    if ordinal < 1 || ordinal > 3999 {
+        return None
+    }
+    let mut numerals = ~"";
+    let mut n = ordinal;
+    for ROMAN_NUMERALS.iter().advance |&(value, numeral)| {
+        while n >= value {
+            numerals.push_str(numeral);
+            n -= value;
+        }
+    }
+    Some(numerals)
+}
+
+/// Returns the given text with its latin letters in uppercase.
+fn ascii_upper(text: &str) -> ~str {
+    let mut upper = ~"";
+    for text.iter().advance |c| {
+        upper.push_char(if c >= 'a' && c <= 'z' { (c as u8 - 32) as char } else { c });
+    }
+    upper
+}
+
+#[test]
+fn test_marker_text() {
+    assert!(marker_text(CSSListStyleTypeNone, 1).is_none());
+    assert!(marker_text(CSSListStyleTypeDecimal, 3) == Some(~"3. "));
+    assert!(marker_text(CSSListStyleTypeDecimal, -2) == Some(~"-2. "));
+    assert!(marker_text(CSSListStyleTypeLowerAlpha, 1) == Some(~"a. "));
+    assert!(marker_text(CSSListStyleTypeLowerAlpha, 28) == Some(~"ab. "));
+    assert!(marker_text(CSSListStyleTypeUpperAlpha, 26) == Some(~"Z. "));
+    // Alphabetic numbering has no zero, so it falls back to decimal.
+    assert!(marker_text(CSSListStyleTypeUpperAlpha, 0) == Some(~"0. "));
+    assert!(marker_text(CSSListStyleTypeLowerRoman, 1994) == Some(~"mcmxciv. "));
+    assert!(marker_text(CSSListStyleTypeUpperRoman, 49) == Some(~"XLIX. "));
+    assert!(marker_text(CSSListStyleTypeUpperRoman, 4000) == Some(~"4000. "));
+    assert!(marker_text(CSSListStyleTypeDisclosureClosed, 1) == Some(~"\u25b8 "));
+}
@@ -15,14 +15,20 @@ use geom::size::Size2D;
 use geom::side_offsets::SideOffsets2D;
 use gfx::display_list::{BaseDisplayItem, BorderDisplayItem, BorderDisplayItemClass, DisplayList};
 use gfx::geometry::Au;
+use gfx::geometry;
 use newcss::complete::CompleteStyle;
 use newcss::units::{Em, Pt, Px};
 use newcss::values::{CSSBorderWidth, CSSBorderWidthLength, CSSBorderWidthMedium};
 use newcss::values::{CSSBorderWidthThick, CSSBorderWidthThin};
 use newcss::values::{CSSWidth, CSSWidthLength, CSSWidthPercentage, CSSWidthAuto};
 use newcss::values::{CSSHeight, CSSHeightLength, CSSHeightPercentage, CSSHeightAuto};
+use newcss::values::{CSSMinWidth, CSSMinWidthLength, CSSMinWidthPercentage};
+use newcss::values::{CSSMaxWidth, CSSMaxWidthLength, CSSMaxWidthPercentage, CSSMaxWidthNone};
+use newcss::values::{CSSMinHeight, CSSMinHeightLength, CSSMinHeightPercentage};
+use newcss::values::{CSSMaxHeight, CSSMaxHeightLength, CSSMaxHeightPercentage, CSSMaxHeightNone};
 use newcss::values::{CSSMargin, CSSMarginLength, CSSMarginPercentage, CSSMarginAuto};
 use newcss::values::{CSSPadding, CSSPaddingLength, CSSPaddingPercentage};
+use newcss::values::{CSSOffset, CSSOffsetLength, CSSOffsetPercentage, CSSOffsetAuto};
 /// Encapsulates the borders, padding, and margins, which we collectively call the "box model".
 pub struct BoxModel {
     border: SideOffsets2D<Au>,
@@ -62,10 +68,15 @@ impl MaybeAuto{
         }
     }
 
-    pub fn from_height(height: CSSHeight, cb_height: Au) -> MaybeAuto{
+    /// Percentages refer to the height of the containing block, and compute to `auto` when that
+    /// depends on the content (CSS 2.1 § 10.5).
+    pub fn from_height(height: CSSHeight, cb_height: Option<Au>) -> MaybeAuto{
         match height {
             CSSHeightAuto => Auto,
-            CSSHeightPercentage(percent) => Specified(cb_height.scale_by(percent/100.0)),
+            CSSHeightPercentage(percent) => match cb_height {
+                Some(cb_height) => Specified(cb_height.scale_by(percent/100.0)),
+                None => Auto,
+            },
             //FIXME(eatkinson): Compute pt and em values properly
             CSSHeightLength(Px(v)) | 
             CSSHeightLength(Pt(v)) | 
@@ -73,6 +84,19 @@ impl MaybeAuto{
         }
     }
 
+    /// Resolves one of the `top`, `right`, `bottom` or `left` offsets of a positioned element.
+    /// Percentages refer to the containing block's length along the same axis.
+    pub fn from_offset(offset: CSSOffset, cb_length: Au) -> MaybeAuto {
+        match offset {
+            CSSOffsetAuto => Auto,
+            CSSOffsetPercentage(percent) => Specified(cb_length.scale_by(percent/100.0)),
+            //FIXME(eatkinson): Compute pt and em values properly
+            CSSOffsetLength(Px(v)) |
+            CSSOffsetLength(Pt(v)) |
+            CSSOffsetLength(Em(v)) => Specified(Au::from_frac_px(v)),
+        }
+    }
+
     pub fn spec_or_default(&self, default: Au) -> Au{
         match *self{
             Auto => default,
@@ -81,6 +105,164 @@ impl MaybeAuto{
     }
 }
 
+/// The `min-width` and `max-width`, or `min-height` and `max-height`, that constrain a width or a
+/// height (CSS 2.1 §§ 10.4 and 10.7).
+pub struct SizeConstraint {
+    min: Au,
+    max: Option<Au>,
+}
+
+impl SizeConstraint {
+    /// A constraint that lets any size through.
+    pub fn none() -> SizeConstraint {
+        SizeConstraint {
+            min: Au(0),
+            max: None,
+        }
+    }
+
+    /// Resolves `min-width` and `max-width` against the width of the containing block.
+    pub fn for_width(style: CompleteStyle, cb_width: Au) -> SizeConstraint {
+        SizeConstraint::for_width_within(style, Some(cb_width))
+    }
+
+    /// Resolves `min-width` and `max-width` for the minimum and preferred widths of a box, which
+    /// are found before the width of the containing block is known. Percentages are ignored:
+    /// `min-width` becomes zero and `max-width` becomes `none`.
+    pub fn for_intrinsic_width(style: CompleteStyle) -> SizeConstraint {
+        SizeConstraint::for_width_within(style, None)
+    }
+
+    priv fn for_width_within(style: CompleteStyle, cb_width: Option<Au>) -> SizeConstraint {
+        let min = match style.min_width() {
+            CSSMinWidthPercentage(percent) => match cb_width {
+                Some(cb_width) => cb_width.scale_by(percent/100.0),
+                None => Au(0),
+            },
+            //FIXME(eatkinson): Compute pt and em values properly
+            CSSMinWidthLength(Px(v)) |
+            CSSMinWidthLength(Pt(v)) |
+            CSSMinWidthLength(Em(v)) => Au::from_frac_px(v),
+        };
+        let max = match style.max_width() {
+            CSSMaxWidthNone => None,
+            CSSMaxWidthPercentage(percent) => do cb_width.map |&cb_width| {
+                cb_width.scale_by(percent/100.0)
+            },
+            CSSMaxWidthLength(Px(v)) |
+            CSSMaxWidthLength(Pt(v)) |
+            CSSMaxWidthLength(Em(v)) => Some(Au::from_frac_px(v)),
+        };
+        SizeConstraint {
+            min: min,
+            max: max,
+        }
+    }
+
+    /// Resolves `min-height` and `max-height` against the height of the containing block. Where
+    /// that depends on the content, percentages are ignored: `min-height` becomes zero and
+    /// `max-height` becomes `none`.
+    pub fn for_height(style: CompleteStyle, cb_height: Option<Au>) -> SizeConstraint {
+        let min = match style.min_height() {
+            CSSMinHeightPercentage(percent) => match cb_height {
+                Some(cb_height) => cb_height.scale_by(percent/100.0),
+                None => Au(0),
+            },
+            CSSMinHeightLength(Px(v)) |
+            CSSMinHeightLength(Pt(v)) |
+            CSSMinHeightLength(Em(v)) => Au::from_frac_px(v),
+        };
+        let max = match style.max_height() {
+            CSSMaxHeightNone => None,
+            CSSMaxHeightPercentage(percent) => do cb_height.map |&cb_height| {
+                cb_height.scale_by(percent/100.0)
+            },
+            CSSMaxHeightLength(Px(v)) |
+            CSSMaxHeightLength(Pt(v)) |
+            CSSMaxHeightLength(Em(v)) => Some(Au::from_frac_px(v)),
+        };
+        SizeConstraint {
+            min: min,
+            max: max,
+        }
+    }
+
+    /// Limits a size to the maximum, and then raises it to the minimum, which wins if the two
+    /// conflict.
+    pub fn clamp(&self, size: Au) -> Au {
+        let size = match self.max {
+            Some(max) => geometry::min(size, max),
+            None => size,
+        };
+        geometry::max(size, self.min)
+    }
+
+    /// Applies the constraint to a size that is solved for along with other values, such as
+    /// margins. `solve` finds the used size, and whatever else depends on it, from a specified
+    /// or `auto` size. A tentative size above the maximum is solved for again with the maximum
+    /// as the specified size, and then one below the minimum with the minimum.
+    pub fn apply<T>(&self, size: MaybeAuto, solve: &fn(MaybeAuto) -> (Au, T)) -> (Au, T) {
+        let (used, rest) = solve(size);
+        let (used, rest) = match self.max {
+            Some(max) if used > max => solve(Specified(max)),
+            _ => (used, rest),
+        };
+        if used < self.min {
+            solve(Specified(self.min))
+        } else {
+            (used, rest)
+        }
+    }
+}
+
+/// Returns the height of the content box of an element when it does not depend on the content:
+/// its `height`, within its `min-height` and `max-height`. The children of the element resolve
+/// percentage heights against it.
+pub fn definite_height(style: CompleteStyle, cb_height: Option<Au>) -> Option<Au> {
+    match MaybeAuto::from_height(style.height(), cb_height) {
+        Auto => None,
+        Specified(height) => Some(SizeConstraint::for_height(style, cb_height).clamp(height)),
+    }
+}
+
+/// A set of adjoining vertical margins, which collapse into a single margin (CSS 2.1 § 8.3.1).
+/// The largest positive margin and the most negative margin are kept apart, since collapsing
+/// another margin into the set depends on both.
+pub struct AdjoiningMargins {
+    positive: Au,
+    negative: Au,
+}
+
+impl AdjoiningMargins {
+    pub fn new() -> AdjoiningMargins {
+        AdjoiningMargins {
+            positive: Au(0),
+            negative: Au(0),
+        }
+    }
+
+    pub fn from_margin(margin: Au) -> AdjoiningMargins {
+        if margin >= Au(0) {
+            AdjoiningMargins { positive: margin, negative: Au(0) }
+        } else {
+            AdjoiningMargins { positive: Au(0), negative: margin }
+        }
+    }
+
+    /// Returns the set containing the margins of both `self` and `other`.
+    pub fn union(&self, other: &AdjoiningMargins) -> AdjoiningMargins {
+        AdjoiningMargins {
+            positive: geometry::max(self.positive, other.positive),
+            negative: geometry::min(self.negative, other.negative),
+        }
+    }
+
+    /// The width of the collapsed margin: the largest positive margin plus the most negative one.
+    pub fn collapse(&self) -> Au {
+        self.positive + self.negative
+    }
+}
+
 impl Zero for BoxModel {
     fn zero() -> BoxModel {
         BoxModel {
@@ -209,3 +391,17 @@ impl RenderBox {
 
 }
 
+
+#[test]
+fn test_adjoining_margins_collapse() {
+    let margins = AdjoiningMargins::from_margin(Au(10));
+    let margins = margins.union(&AdjoiningMargins::from_margin(Au(20)));
+    assert!(margins.collapse() == Au(20));
+
+    let margins = margins.union(&AdjoiningMargins::from_margin(Au(-5)));
+    assert!(margins.collapse() == Au(15));
+
+    let margins = AdjoiningMargins::from_margin(Au(-5));
+    let margins = margins.union(&AdjoiningMargins::from_margin(Au(-8)));
+    assert!(margins.collapse() == Au(-8));
+}
@@ -0,0 +1,1271 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! CSS table layout (CSS 2.1 § 17).
+//!
+//! The children of a table flow are its captions, which are block flows, and its row groups. The
+//! children of a row group are its rows, and the children of a row are its cells. Row groups, rows
+//! and cells keep their data in a `BlockFlowData`. The table lays out its whole grid itself: the
+//! cells find their own widths and heights, but the table places and sizes its row groups, rows
+//! and cells.
+
+use css::node_style::StyledNode;
+use layout::absolute::layout_abs_descendants;
+use layout::block::{BlockFlowData, BlockLayout, stack_block_children};
+use layout::box::RenderBox;
+use layout::context::LayoutContext;
+use layout::display_list_builder::{DisplayListBuilder, ExtraDisplayListData};
+use layout::display_list_builder::{FlowDisplayListBuilderMethods};
+use layout::flow::{BlockFlow, FlowData, TableCellFlow, TableFlow, TableRowFlow};
+use layout::flow::{TableRowGroupFlow};
+use layout::float_context::FloatContext;
+use layout::inline::InlineLayout;
+use layout::model::{Auto, MaybeAuto, Specified};
+
+use std::cell::Cell;
+use std::num::Zero;
+use std::uint;
+use std::vec;
+use geom::point::Point2D;
+use geom::rect::Rect;
+use geom::side_offsets::SideOffsets2D;
+use gfx::display_list::DisplayList;
+use gfx::geometry::Au;
+use gfx::geometry;
+use newcss::units::{Em, Length, Pt, Px};
+use newcss::values::{CSSBorderCollapseCollapse, CSSBorderCollapseSeparate, CSSCaptionSideBottom};
+use newcss::values::{CSSDisplayTableColumn, CSSDisplayTableColumnGroup};
+use newcss::values::{CSSDisplayTableFooterGroup, CSSDisplayTableHeaderGroup};
+use newcss::values::{CSSDisplayTableRowGroup, CSSVerticalAlignBottom, CSSVerticalAlignMiddle};
+use newcss::values::{CSSTableLayoutAuto, CSSTableLayoutFixed, CSSWidth, CSSWidthAuto};
+use newcss::values::{CSSWidthPercentage};
+use script::dom::node::{AbstractNode, LayoutView};
+use servo_util::tree::{TreeNodeRef, TreeUtils};
+
+pub struct TableFlowData {
+    /// Data common to all flows.
+    common: FlowData,
+
+    /// The associated render box, or None if this is an anonymous table.
+    box: Option<RenderBox>,
+
+    /// The minimum width of each column, found while bubbling widths.
+    column_min_widths: ~[Au],
+
+    /// The preferred width of each column, found while bubbling widths.
+    column_pref_widths: ~[Au],
+
+    /// The percentage of the width of the grid that each column asks for, if any, found while
+    /// bubbling widths.
+    column_percentages: ~[Option<float>],
+
+    /// The widths that the column and column group elements of the table ask for, by column.
+    column_element_widths: ~[Option<SpecifiedWidth>],
+
+    /// The used width of each column.
+    column_widths: ~[Au],
+}
+
+/// A width that a cell or a column element asks for with its `width`.
+enum SpecifiedWidth {
+    /// A length. That of a cell includes its borders and padding.
+    FixedWidth(Au),
+    /// A percentage of the width of the table's grid.
+    PercentageWidth(float),
+}
+
+impl SpecifiedWidth {
+    /// Returns the width asked for in a grid of the given width.
+    fn resolve(&self, grid_width: Au) -> Au {
+        match *self {
+            FixedWidth(width) => width,
+            PercentageWidth(percentage) => grid_width.scale_by(percentage / 100.0),
+        }
+    }
+}
+
+/// A cell and the slots of its table's grid that it covers.
+struct CellSlot {
+    cell: @mut BlockFlowData,
+    /// The index of the cell's first row within its row group.
+    row: uint,
+    /// The index of the cell's first column.
+    column: uint,
+    colspan: uint,
+    rowspan: uint,
+}
+
+/// A row group with its rows and the slots of the cells in them. Cells never span rows of
+/// different row groups.
+struct RowGroupGrid {
+    group: @mut BlockFlowData,
+    rows: ~[@mut BlockFlowData],
+    cells: ~[CellSlot],
+}
+
+impl TableFlowData {
+    pub fn new(common: FlowData) -> TableFlowData {
+        TableFlowData {
+            common: common,
+            box: None,
+            column_min_widths: ~[],
+            column_pref_widths: ~[],
+            column_percentages: ~[],
+            column_element_widths: ~[],
+            column_widths: ~[],
+        }
+    }
+
+    pub fn teardown(&mut self) {
+        self.common.teardown();
+        for self.box.iter().advance |box| {
+            box.teardown();
+        }
+        self.box = None;
+    }
+
+    /// Finds the rows of each row group and the slots of their cells. The groups are returned in
+    /// the order they are displayed in, with header groups first and footer groups last (CSS 2.1
+    /// § 17.2), along with the number of columns of the grid, which includes those of the column
+    /// elements.
+    priv fn grid(@mut self) -> (~[RowGroupGrid], uint) {
+        let mut headers = ~[];
+        let mut bodies = ~[];
+        let mut footers = ~[];
+        let mut num_columns = 0;
+
+        for TableFlow(self).each_child |child| {
+            let group = match child {
+                TableRowGroupFlow(group) => group,
+                _ => loop,
+            };
+
+            let mut rows = ~[];
+            for child.each_child |row| {
+                match row {
+                    TableRowFlow(row) => rows.push(row),
+                    _ => {}
+                }
+            }
+
+            // The number of rows below the current one that each column is covered for by cells
+            // from the rows above.
+            let mut covered: ~[uint] = ~[];
+            let mut cells = ~[];
+            for rows.iter().enumerate().advance |(row_index, &row)| {
+                let rows_left = rows.len() - row_index;
+                let mut column = 0;
+                for TableRowFlow(row).each_child |cell| {
+                    let cell = match cell {
+                        TableCellFlow(cell) => cell,
+                        _ => loop,
+                    };
+                    while column < covered.len() && covered[column] > 0 {
+                        column += 1;
+                    }
+
+                    let colspan = match span_attribute(cell, "colspan") {
+                        Some(colspan) if colspan > 0 => uint::min(colspan, 1000),
+                        _ => 1,
+                    };
+                    // A row span of zero spans the rest of the row group.
+                    let rowspan = match span_attribute(cell, "rowspan") {
+                        Some(0) => rows_left,
+                        Some(rowspan) => uint::min(rowspan, rows_left),
+                        None => 1,
+                    };
+
+                    while covered.len() < column + colspan {
+                        covered.push(0);
+                    }
+                    for uint::range(column, column + colspan) |covered_column| {
+                        covered[covered_column] = rowspan;
+                    }
+
+                    cells.push(CellSlot {
+                        cell: cell,
+                        row: row_index,
+                        column: column,
+                        colspan: colspan,
+                        rowspan: rowspan,
+                    });
+                    column += colspan;
+                }
+
+                num_columns = uint::max(num_columns, covered.len());
+                for covered.mut_iter().advance |count| {
+                    if *count > 0 {
+                        *count -= 1;
+                    }
+                }
+            }
+
+            let grid = RowGroupGrid {
+                group: group,
+                rows: rows,
+                cells: cells,
+            };
+            let display = do group.box.map_default(CSSDisplayTableRowGroup) |box| {
+                box.style().display(false)
+            };
+            match display {
+                CSSDisplayTableHeaderGroup => headers.push(grid),
+                CSSDisplayTableFooterGroup => footers.push(grid),
+                _ => bodies.push(grid),
+            }
+        }
+
+        headers.push_all_move(bodies);
+        headers.push_all_move(footers);
+        (headers, uint::max(num_columns, self.column_element_widths.len()))
+    }
+
+    /// Finds the widths that the column and column group elements of the table ask for, by
+    /// column. Columns are not flows, so they are read from the document. A column element covers
+    /// as many columns as its `span` attribute says, and so does a column group element with no
+    /// column elements in it. Column elements without a `width` of their own take that of their
+    /// group.
+    priv fn find_column_element_widths(&self) -> ~[Option<SpecifiedWidth>] {
+        let mut widths = ~[];
+        if self.box.is_none() {
+            return widths
+        }
+        for self.common.node.each_child |child| {
+            if !child.is_element() {
+                loop;
+            }
+            match child.style().display(false) {
+                CSSDisplayTableColumn => push_column_widths(&mut widths, child, None),
+                CSSDisplayTableColumnGroup => {
+                    let group_width = specified_width(child.style().width());
+                    let mut has_columns = false;
+                    for child.each_child |column| {
+                        if !column.is_element() {
+                            loop;
+                        }
+                        match column.style().display(false) {
+                            CSSDisplayTableColumn => {
+                                push_column_widths(&mut widths, column, group_width);
+                                has_columns = true;
+                            }
+                            _ => {}
+                        }
+                    }
+                    if !has_columns {
+                        push_column_widths(&mut widths, child, None);
+                    }
+                }
+                _ => {}
+            }
+        }
+        widths
+    }
+
+    /// Returns whether the borders of this table collapse. Anonymous tables keep them separate.
+    pub fn collapses_borders(&self) -> bool {
+        match self.box {
+            None => false,
+            Some(box) => match box.style().border_collapse() {
+                CSSBorderCollapseCollapse => true,
+                CSSBorderCollapseSeparate => false,
+            },
+        }
+    }
+
+    /// Returns the horizontal and vertical spacing between the cells of this table. Tables whose
+    /// borders collapse have none, and neither do anonymous tables.
+    priv fn border_spacing(&self) -> (Au, Au) {
+        match self.box {
+            Some(box) if !self.collapses_borders() => {
+                let (horizontal, vertical) = box.style().border_spacing();
+                (length_to_au(horizontal), length_to_au(vertical))
+            }
+            _ => (Au(0), Au(0)),
+        }
+    }
+
+    /// Computes the borders and padding of the table box. Tables whose borders collapse have no
+    /// padding.
+    priv fn compute_borders_and_padding(&self, containing_width: Au) {
+        let collapse = self.collapses_borders();
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            do box.with_model |model| {
+                model.compute_borders(style);
+                if collapse {
+                    model.padding = Zero::zero();
+                } else {
+                    model.compute_padding(style, containing_width);
+                }
+            }
+        }
+    }
+
+    /// Resolves the borders that meet between the cells of a table whose borders collapse, and
+    /// between the cells and the table (CSS 2.1 § 17.6.2.1): the wider border wins, and the cells
+    /// on both sides take its width. The minimum and preferred widths of the cells are widened by
+    /// as much as their borders are.
+    // FIXME: Borders should also be compared by style, and the winning border's color drawn.
+    priv fn resolve_collapsed_borders(&self, groups: &[RowGroupGrid], num_columns: uint) {
+        // The cells that have borders, and the index into them of the cell covering each slot of
+        // the grid, row by row.
+        let mut cells = ~[];
+        let mut slots: ~[~[Option<uint>]] = ~[];
+        for groups.iter().advance |grid| {
+            let first_row = slots.len();
+            for grid.rows.len().times {
+                slots.push(vec::from_elem(num_columns, None));
+            }
+            for grid.cells.iter().advance |slot| {
+                if slot.cell.box.is_none() {
+                    loop;
+                }
+                let index = cells.len();
+                cells.push(slot.cell);
+                let rows = first_row + slot.row;
+                for uint::range(rows, rows + slot.rowspan) |row| {
+                    for uint::range(slot.column, slot.column + slot.colspan) |column| {
+                        slots[row][column] = Some(index);
+                    }
+                }
+            }
+        }
+
+        let borders = do cells.map |cell| {
+            cell.box.get().with_base(|base| base.model.border)
+        };
+        let insets = cells.map(|cell| cell.cell_insets());
+        let table_border = self.box.map_default(Zero::zero(), |box| {
+            box.with_base(|base| base.model.border)
+        });
+
+        let mut resolved = copy borders;
+        let num_rows = slots.len();
+        for uint::range(0, num_rows) |row| {
+            for uint::range(0, num_columns) |column| {
+                let cell = match slots[row][column] {
+                    Some(cell) => cell,
+                    None => loop,
+                };
+                if column + 1 < num_columns {
+                    match slots[row][column + 1] {
+                        Some(next) if next != cell => {
+                            let width = geometry::max(borders[cell].right, borders[next].left);
+                            resolved[cell].right = geometry::max(resolved[cell].right, width);
+                            resolved[next].left = geometry::max(resolved[next].left, width);
+                        }
+                        _ => {}
+                    }
+                }
+                if row + 1 < num_rows {
+                    match slots[row + 1][column] {
+                        Some(next) if next != cell => {
+                            let width = geometry::max(borders[cell].bottom, borders[next].top);
+                            resolved[cell].bottom = geometry::max(resolved[cell].bottom, width);
+                            resolved[next].top = geometry::max(resolved[next].top, width);
+                        }
+                        _ => {}
+                    }
+                }
+                if row == 0 {
+                    resolved[cell].top = geometry::max(resolved[cell].top, table_border.top);
+                }
+                if row + 1 == num_rows {
+                    resolved[cell].bottom = geometry::max(resolved[cell].bottom,
+                                                          table_border.bottom);
+                }
+                if column == 0 {
+                    resolved[cell].left = geometry::max(resolved[cell].left, table_border.left);
+                }
+                if column + 1 == num_columns {
+                    resolved[cell].right = geometry::max(resolved[cell].right,
+                                                         table_border.right);
+                }
+            }
+        }
+
+        for cells.iter().enumerate().advance |(index, &cell)| {
+            do cell.box.get().with_mut_base |base| {
+                base.model.border = resolved[index];
+            }
+            let new_insets = cell.cell_insets();
+            let extra_width = new_insets.left + new_insets.right - insets[index].left -
+                insets[index].right;
+            cell.common.min_width = cell.common.min_width + extra_width;
+            cell.common.pref_width = cell.common.pref_width + extra_width;
+        }
+    }
+
+    /// Determines the minimum and preferred widths of each column from the column elements and
+    /// cells in it, following the automatic table layout algorithm (CSS 2.1 § 17.5.2.2), and from
+    /// them those of the table.
+    pub fn bubble_widths_table(@mut self, _: &LayoutContext) {
+        self.column_element_widths = self.find_column_element_widths();
+        let (groups, num_columns) = self.grid();
+        if self.collapses_borders() {
+            self.resolve_collapsed_borders(groups, num_columns);
+        }
+        let (spacing, _) = self.border_spacing();
+
+        let mut min_widths = vec::from_elem(num_columns, Au(0));
+        let mut pref_widths = vec::from_elem(num_columns, Au(0));
+        let mut percentages = vec::from_elem(num_columns, None);
+
+        // Column elements and the cells in one column set its widths. Cells spanning several
+        // columns then widen them evenly if they need more room than the columns already have.
+        for self.column_element_widths.iter().enumerate().advance |(column, &width)| {
+            match width {
+                Some(FixedWidth(width)) => {
+                    min_widths[column] = width;
+                    pref_widths[column] = width;
+                }
+                Some(PercentageWidth(percentage)) => percentages[column] = Some(percentage),
+                None => {}
+            }
+        }
+        for groups.iter().advance |grid| {
+            for grid.cells.iter().advance |slot| {
+                if slot.colspan == 1 {
+                    let (min_width, pref_width) = cell_widths(slot.cell);
+                    min_widths[slot.column] = geometry::max(min_widths[slot.column], min_width);
+                    pref_widths[slot.column] = geometry::max(pref_widths[slot.column], pref_width);
+
+                    // FIXME: Percentage widths of cells spanning several columns are ignored.
+                    match slot.cell.cell_specified_width() {
+                        Some(PercentageWidth(percentage)) => {
+                            if percentage > percentages[slot.column].get_or_default(0.0) {
+                                percentages[slot.column] = Some(percentage);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        for groups.iter().advance |grid| {
+            for grid.cells.iter().advance |slot| {
+                if slot.colspan > 1 {
+                    let (min_width, pref_width) = cell_widths(slot.cell);
+                    let end = slot.column + slot.colspan;
+                    let inner_spacing = spacing.scale_by((slot.colspan - 1) as float);
+                    widen(min_widths.mut_slice(slot.column, end), min_width - inner_spacing);
+                    widen(pref_widths.mut_slice(slot.column, end), pref_width - inner_spacing);
+                }
+            }
+        }
+        for uint::range(0, num_columns) |column| {
+            pref_widths[column] = geometry::max(pref_widths[column], min_widths[column]);
+        }
+
+        let grid_spacing = spacing.scale_by((num_columns + 1) as float);
+        let mut min_width = sum(min_widths) + grid_spacing;
+        let mut pref_width = grid_pref_width(pref_widths, percentages) + grid_spacing;
+
+        self.compute_borders_and_padding(Au(0));
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            let noncontent_width = box.get_noncontent_width();
+            min_width = min_width + noncontent_width;
+            pref_width = pref_width + noncontent_width;
+
+            // Like other browsers, we take the `width` of a table to be that of its border box.
+            match MaybeAuto::from_width(style.width(), Au(0)) {
+                Specified(width) => {
+                    min_width = geometry::max(min_width, width);
+                    pref_width = geometry::max(pref_width, width);
+                }
+                Auto => {}
+            }
+
+            let margin_left = MaybeAuto::from_margin(style.margin_left(), Au(0));
+            let margin_right = MaybeAuto::from_margin(style.margin_right(), Au(0));
+            let margins = margin_left.spec_or_default(Au(0)) + margin_right.spec_or_default(Au(0));
+            min_width = min_width + margins;
+            pref_width = pref_width + margins;
+        }
+
+        // Captions are as wide as the table, so wide captions widen it.
+        for TableFlow(self).each_child |child| {
+            match child {
+                BlockFlow(caption) => {
+                    min_width = geometry::max(min_width, caption.common.min_width);
+                    pref_width = geometry::max(pref_width, caption.common.pref_width);
+                }
+                _ => {}
+            }
+        }
+
+        self.column_min_widths = min_widths;
+        self.column_pref_widths = pref_widths;
+        self.column_percentages = percentages;
+        self.common.min_width = min_width;
+        self.common.pref_width = pref_width;
+        // Floats inside a table don't affect anything outside it.
+        self.common.num_floats = 0;
+    }
+
+    /// Determines the widths of the table and its columns, and places the captions, row groups,
+    /// rows and cells across the table. When called on this context, the context has had its width
+    /// set by the parent context.
+    pub fn assign_widths_table(@mut self, _: &LayoutContext) {
+        debug!("assign_widths_table: assigning width for flow %?", self.common.id);
+
+        let available_width = self.common.position.size.width;
+        let (groups, num_columns) = self.grid();
+        let (spacing, _) = self.border_spacing();
+        let grid_spacing = spacing.scale_by((num_columns + 1) as float);
+
+        let mut width = Auto;
+        let mut margin_left = Specified(Au(0));
+        let mut margin_right = Specified(Au(0));
+        let mut fixed_layout = false;
+        self.compute_borders_and_padding(available_width);
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            width = MaybeAuto::from_width(style.width(), available_width);
+            margin_left = MaybeAuto::from_margin(style.margin_left(), available_width);
+            margin_right = MaybeAuto::from_margin(style.margin_right(), available_width);
+            fixed_layout = match style.table_layout() {
+                CSSTableLayoutAuto => false,
+                CSSTableLayoutFixed => true,
+            };
+        }
+        let noncontent_width = self.box.map_default(Au(0), |box| box.get_noncontent_width());
+
+        // Find the widths of the columns, and from them that of the table.
+        let column_widths = match width {
+            Specified(width) if fixed_layout => {
+                let grid_width = geometry::max(Au(0), width - noncontent_width - grid_spacing);
+                fixed_column_widths(groups,
+                                    self.column_element_widths,
+                                    num_columns,
+                                    grid_width,
+                                    spacing)
+            }
+            Specified(width) => {
+                let grid_width = width - noncontent_width - grid_spacing;
+                distribute_widths(self.column_min_widths,
+                                  self.column_pref_widths,
+                                  self.column_percentages,
+                                  grid_width)
+            }
+            Auto => {
+                let margins = margin_left.spec_or_default(Au(0)) +
+                    margin_right.spec_or_default(Au(0));
+                let grid_width = available_width - margins - noncontent_width - grid_spacing;
+                let grid_width = geometry::min(grid_width,
+                                               grid_pref_width(self.column_pref_widths,
+                                                               self.column_percentages));
+                distribute_widths(self.column_min_widths,
+                                  self.column_pref_widths,
+                                  self.column_percentages,
+                                  grid_width)
+            }
+        };
+        let table_width = geometry::max(sum(column_widths) + grid_spacing + noncontent_width,
+                                        width.spec_or_default(Au(0)));
+
+        // Auto margins center the table.
+        let (margin_left, margin_right) = match (margin_left, margin_right) {
+            (Auto, Auto) => {
+                let margin = geometry::max(Au(0), (available_width - table_width).scale_by(0.5));
+                (margin, margin)
+            }
+            (Auto, Specified(right)) => {
+                (geometry::max(Au(0), available_width - table_width - right), right)
+            }
+            (Specified(left), Auto) => {
+                (left, geometry::max(Au(0), available_width - table_width - left))
+            }
+            (Specified(left), Specified(right)) => (left, right),
+        };
+
+        let mut content_left = margin_left;
+        for self.box.iter().advance |&box| {
+            do box.with_mut_base |base| {
+                base.model.margin.left = margin_left;
+                base.model.margin.right = margin_right;
+                base.position.origin.x = margin_left;
+                base.position.size.width = table_width;
+                content_left = base.model.offset();
+            }
+        }
+
+        // Lay the columns out across the table.
+        let mut column_lefts = ~[];
+        let mut x = Au(0);
+        for column_widths.iter().advance |&column_width| {
+            column_lefts.push(x);
+            x = x + column_width + spacing;
+        }
+        let grid_width = geometry::max(Au(0), x - spacing);
+
+        for TableFlow(self).each_child |child| {
+            match child {
+                BlockFlow(caption) => {
+                    caption.common.position.origin.x = margin_left;
+                    caption.common.position.size.width = table_width;
+                }
+                _ => {}
+            }
+        }
+
+        for groups.iter().advance |grid| {
+            grid.group.common.position.origin.x = content_left + spacing;
+            set_part_width(grid.group, grid_width);
+            for grid.rows.iter().advance |&row| {
+                row.common.position.origin.x = Au(0);
+                set_part_width(row, grid_width);
+            }
+            for grid.cells.iter().advance |slot| {
+                let spanned_widths = column_widths.slice(slot.column, slot.column + slot.colspan);
+                let inner_spacing = spacing.scale_by((slot.colspan - 1) as float);
+                slot.cell.common.position.origin.x = column_lefts[slot.column];
+                slot.cell.common.position.size.width = sum(spanned_widths) + inner_spacing;
+            }
+        }
+
+        self.column_widths = column_widths;
+    }
+
+    /// Lays out the cells, rows and row groups of the table from top to bottom, with its captions
+    /// above or below it.
+    pub fn assign_height_table(@mut self, ctx: &mut LayoutContext) {
+        let (groups, _) = self.grid();
+        let (_, spacing) = self.border_spacing();
+
+        let mut margin_top = Au(0);
+        let mut margin_bottom = Au(0);
+        let mut specified_height = Au(0);
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            let containing_width = self.common.position.size.width;
+            margin_top = MaybeAuto::from_margin(style.margin_top(),
+                                                containing_width).spec_or_default(Au(0));
+            margin_bottom = MaybeAuto::from_margin(style.margin_bottom(),
+                                                   containing_width).spec_or_default(Au(0));
+            let cb_height = self.common.containing_block_height;
+            specified_height = MaybeAuto::from_height(style.height(),
+                                                      cb_height).spec_or_default(Au(0));
+        }
+
+        let mut cur_y = self.place_captions(ctx, true, margin_top);
+
+        let table_top = cur_y;
+        for self.box.iter().advance |&box| {
+            do box.with_base |base| {
+                cur_y = cur_y + base.model.border.top + base.model.padding.top;
+            }
+        }
+        cur_y = cur_y + spacing;
+        for groups.iter().advance |grid| {
+            grid.group.common.position.origin.y = cur_y;
+            if !grid.rows.is_empty() {
+                cur_y = cur_y + layout_row_group(ctx, grid, spacing) + spacing;
+            }
+        }
+        for self.box.iter().advance |&box| {
+            do box.with_mut_base |base| {
+                base.model.margin.top = margin_top;
+                base.model.margin.bottom = margin_bottom;
+                cur_y = cur_y + base.model.padding.bottom + base.model.border.bottom;
+
+                // The table's `height` is that of its border box, and only makes it taller.
+                let height = geometry::max(cur_y - table_top, specified_height);
+                base.position.origin.y = table_top;
+                base.position.size.height = height;
+                cur_y = table_top + height;
+            }
+        }
+
+        cur_y = self.place_captions(ctx, false, cur_y);
+
+        self.common.position.size.height = cur_y + margin_bottom;
+        self.common.floats_out = self.common.floats_in.clone();
+
+        layout_abs_descendants(TableFlow(self), ctx);
+    }
+
+    /// Lays out the captions on the given side of the table one above another, starting at the
+    /// given vertical position, and returns the position below them.
+    priv fn place_captions(@mut self, ctx: &mut LayoutContext, top: bool, y: Au) -> Au {
+        let mut y = y;
+        for TableFlow(self).each_child |child| {
+            let caption = match child {
+                BlockFlow(caption) => caption,
+                _ => loop,
+            };
+            let caption_on_top = match caption.box {
+                None => true,
+                Some(box) => match box.style().caption_side() {
+                    CSSCaptionSideBottom => false,
+                    _ => true,
+                },
+            };
+            if caption_on_top != top {
+                loop;
+            }
+
+            // Captions establish block formatting contexts of their own.
+            caption.common.floats_in = FloatContext::new(caption.common.num_floats);
+            child.assign_height(ctx);
+            caption.common.position.origin.y = y;
+            y = y + caption.common.position.size.height;
+        }
+        y
+    }
+
+    pub fn build_display_list_table<E:ExtraDisplayListData>(@mut self,
+                                                            builder: &DisplayListBuilder,
+                                                            dirty: &Rect<Au>,
+                                                            offset: &Point2D<Au>,
+                                                            list: &Cell<DisplayList<E>>) {
+        // A relatively positioned table is painted, with its captions and cells, away from its
+        // place in the flow.
+        let containing_block_size = match self.common.parent {
+            Some(parent) => parent.position().size,
+            None => self.common.position.size,
+        };
+        let offset = match self.box {
+            Some(box) => *offset + box.relative_position(&containing_block_size),
+            None => *offset,
+        };
+        let offset = &offset;
+
+        // Paint the table's background and borders under those of its rows and cells.
+        self.box.map(|&box| {
+            box.build_display_list(builder, dirty, offset, list)
+        });
+
+        let flow = TableFlow(self);
+        for flow.each_child |child| {
+            flow.build_display_list_for_child(builder, child, dirty, offset, list)
+        }
+
+        flow.build_display_list_for_abs_descendants(builder, dirty, offset, list)
+    }
+}
+
+impl BlockFlowData {
+    /// Returns whether the borders of the table this cell is in collapse.
+    priv fn in_collapsing_table(&self) -> bool {
+        let mut flow = self.common.parent;
+        loop {
+            match flow {
+                Some(TableFlow(table)) => return table.collapses_borders(),
+                Some(parent) => flow = parent.with_base(|base| base.parent),
+                None => return false,
+            }
+        }
+    }
+
+    /// Returns the widths of the borders and padding of this cell that lie inside its slots of
+    /// the grid. When the table's borders collapse, the outer half of each border lies outside
+    /// the slots, over the grid line.
+    priv fn cell_insets(&self) -> SideOffsets2D<Au> {
+        match self.box {
+            None => Zero::zero(),
+            Some(box) => {
+                let collapse = self.in_collapsing_table();
+                fn inner_border(border: Au, collapse: bool) -> Au {
+                    if collapse { border - border.scale_by(0.5) } else { border }
+                }
+
+                do box.with_base |base| {
+                    let border = base.model.border;
+                    let padding = base.model.padding;
+                    SideOffsets2D {
+                        top: inner_border(border.top, collapse) + padding.top,
+                        right: inner_border(border.right, collapse) + padding.right,
+                        bottom: inner_border(border.bottom, collapse) + padding.bottom,
+                        left: inner_border(border.left, collapse) + padding.left,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the width of this cell's slots that its `width` asks for, if any.
+    priv fn cell_specified_width(&self) -> Option<SpecifiedWidth> {
+        match self.box {
+            None => None,
+            Some(box) => match specified_width(box.style().width()) {
+                Some(FixedWidth(width)) => {
+                    let insets = self.cell_insets();
+                    Some(FixedWidth(width + insets.left + insets.right))
+                }
+                width => width,
+            },
+        }
+    }
+
+    /// Determines the minimum and preferred widths of a table cell's slots from its content. The
+    /// table combines them with the cell's `width` to find the widths of its columns.
+    pub fn bubble_widths_table_cell(@mut self, _: &LayoutContext) {
+        let mut min_width = Au(0);
+        let mut pref_width = Au(0);
+        let mut num_floats = 0;
+
+        for TableCellFlow(self).each_child |child_ctx| {
+            assert!(child_ctx.starts_block_flow() || child_ctx.starts_inline_flow());
+
+            do child_ctx.with_base |child_node| {
+                min_width = geometry::max(min_width, child_node.min_width);
+                pref_width = geometry::max(pref_width, child_node.pref_width);
+                num_floats = num_floats + child_node.num_floats;
+            }
+        }
+
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            do box.with_model |model| {
+                model.compute_borders(style);
+                model.compute_padding(style, Au(0));
+            }
+        }
+
+        let insets = self.cell_insets();
+        self.common.min_width = min_width + insets.left + insets.right;
+        self.common.pref_width = pref_width + insets.left + insets.right;
+        self.common.num_floats = num_floats;
+    }
+
+    /// Assigns the width of a table cell's content from the width of its slots, which the table
+    /// has set. A cell's `width` and margins have no effect here.
+    pub fn assign_widths_table_cell(@mut self, _: &LayoutContext) {
+        let width = self.common.position.size.width;
+
+        for self.box.iter().advance |&box| {
+            let style = box.style();
+            do box.with_model |model| {
+                model.compute_padding(style, width);
+                model.margin = Zero::zero();
+            }
+        }
+
+        let insets = self.cell_insets();
+        let content_width = geometry::max(Au(0), width - insets.left - insets.right);
+        for self.box.iter().advance |&box| {
+            do box.with_mut_base |base| {
+                let left = base.model.border.left + base.model.padding.left;
+                let right = base.model.border.right + base.model.padding.right;
+                base.position.origin.x = insets.left - left;
+                base.position.size.width = content_width + left + right;
+            }
+        }
+
+        for TableCellFlow(self).each_child |kid| {
+            do kid.with_mut_base |child_node| {
+                child_node.position.origin.x = insets.left;
+                child_node.position.size.width = content_width;
+            }
+        }
+    }
+
+    /// Lays out a table cell's content and finds the height its slots need. The table then
+    /// stretches the cell to the height of the rows it spans.
+    pub fn assign_height_table_cell(@mut self, ctx: &mut LayoutContext) {
+        let insets = self.cell_insets();
+
+        let floats = self.common.floats_in.translate(Point2D(-insets.left, -insets.top));
+        let mut stacked = stack_block_children(TableCellFlow(self), ctx, floats, insets.top, false);
+        let mut height = insets.top + stacked.height + stacked.bottom_margins.collapse() +
+            insets.bottom;
+        for self.box.iter().advance |&box| {
+            match MaybeAuto::from_height(box.style().height(), None) {
+                Specified(content_height) => {
+                    height = geometry::max(height, content_height + insets.top + insets.bottom)
+                }
+                Auto => {}
+            }
+        }
+
+        self.common.position.size.height = height;
+        self.common.floats_out = stacked.floats_out.translate(Point2D(insets.left, insets.top));
+    }
+
+    /// Stretches a table cell, whose content has been laid out, to the given height, and moves the
+    /// content within it according to the cell's `vertical-align`.
+    priv fn stretch_table_cell(@mut self, height: Au) {
+        let extra_height = height - self.common.position.size.height;
+        let shift = match self.box {
+            None => Au(0),
+            Some(box) => match box.style().vertical_align() {
+                CSSVerticalAlignMiddle => extra_height.scale_by(0.5),
+                CSSVerticalAlignBottom => extra_height,
+                // FIXME: Cells aligned on their baselines should line up their first lines, but
+                // we don't know where those are yet.
+                _ => Au(0),
+            },
+        };
+        for TableCellFlow(self).each_child |kid| {
+            do kid.with_mut_base |child_node| {
+                child_node.position.origin.y = child_node.position.origin.y + shift;
+            }
+        }
+
+        let insets = self.cell_insets();
+        for self.box.iter().advance |&box| {
+            do box.with_mut_base |base| {
+                let top = base.model.border.top + base.model.padding.top;
+                let bottom = base.model.border.bottom + base.model.padding.bottom;
+                base.position.origin.y = insets.top - top;
+                base.position.size.height = height - insets.top - insets.bottom + top + bottom;
+            }
+        }
+        self.common.position.size.height = height;
+    }
+}
+
+/// Lays out the cells and rows of a row group and returns its height. Rows are as tall as their
+/// `height` and the cells in them that span one row, and cells that span several rows make those
+/// rows taller evenly if they need more room.
+fn layout_row_group(ctx: &mut LayoutContext, grid: &RowGroupGrid, spacing: Au) -> Au {
+    for grid.cells.iter().advance |slot| {
+        // Cells establish block formatting contexts of their own.
+        slot.cell.common.floats_in = FloatContext::new(slot.cell.common.num_floats);
+        TableCellFlow(slot.cell).assign_height(ctx);
+    }
+
+    let num_rows = grid.rows.len();
+    let mut heights = do vec::from_fn(num_rows) |row_index| {
+        let row = grid.rows[row_index];
+        do row.box.map_default(Au(0)) |box| {
+            MaybeAuto::from_height(box.style().height(), None).spec_or_default(Au(0))
+        }
+    };
+    for grid.cells.iter().advance |slot| {
+        if slot.rowspan == 1 {
+            let cell_height = slot.cell.common.position.size.height;
+            heights[slot.row] = geometry::max(heights[slot.row], cell_height);
+        }
+    }
+    for grid.cells.iter().advance |slot| {
+        if slot.rowspan > 1 {
+            let inner_spacing = spacing.scale_by((slot.rowspan - 1) as float);
+            widen(heights.mut_slice(slot.row, slot.row + slot.rowspan),
+                  slot.cell.common.position.size.height - inner_spacing);
+        }
+    }
+
+    let mut row_tops = ~[];
+    let mut y = Au(0);
+    for grid.rows.iter().enumerate().advance |(row_index, &row)| {
+        row_tops.push(y);
+        row.common.position.origin.y = y;
+        set_part_height(row, heights[row_index]);
+        y = y + heights[row_index] + spacing;
+    }
+    let group_height = y - spacing;
+
+    for grid.cells.iter().advance |slot| {
+        let last_row = slot.row + slot.rowspan - 1;
+        let height = row_tops[last_row] + heights[last_row] - row_tops[slot.row];
+        slot.cell.common.position.origin.y = Au(0);
+        slot.cell.stretch_table_cell(height);
+
+        // A cell spanning several rows hangs below the first; the flow of that row covers the
+        // cell, so that it is painted wherever it is dirty.
+        let row = grid.rows[slot.row];
+        row.common.position.size.height = geometry::max(row.common.position.size.height, height);
+    }
+
+    set_part_height(grid.group, group_height);
+    group_height
+}
+
+/// Sets the width of a row group or row flow, and of its box if it has one. These boxes only paint
+/// backgrounds: row groups and rows have no borders in the separated borders model, and we don't
+/// collapse their borders into those of the cells yet.
+fn set_part_width(part: &mut BlockFlowData, width: Au) {
+    part.common.position.size.width = width;
+    for part.box.iter().advance |&box| {
+        do box.with_mut_base |base| {
+            base.position.origin.x = Au(0);
+            base.position.size.width = width;
+        }
+    }
+}
+
+/// Sets the height of a row group or row flow, and of its box if it has one.
+fn set_part_height(part: &mut BlockFlowData, height: Au) {
+    part.common.position.size.height = height;
+    for part.box.iter().advance |&box| {
+        do box.with_mut_base |base| {
+            base.position.origin.y = Au(0);
+            base.position.size.height = height;
+        }
+    }
+}
+
+/// Returns the value of the given attribute of the given node, if it is an element.
+fn element_attribute(node: AbstractNode<LayoutView>, name: &str) -> Option<~str> {
+    if !node.is_element() {
+        return None
+    }
+    do node.with_imm_element |element| {
+        element.get_attr(name).map(|value| value.to_owned())
+    }
+}
+
+/// Returns the value of the given span attribute of the element that a cell was made for. Anonymous
+/// cells have no spans.
+fn span_attribute(cell: &BlockFlowData, name: &str) -> Option<uint> {
+    if cell.box.is_none() {
+        return None
+    }
+    element_attribute(cell.common.node, name).chain(|value| uint::from_str(value.trim()))
+}
+
+/// Returns the width that the given `width` asks for, if it is not `auto`.
+fn specified_width(width: CSSWidth) -> Option<SpecifiedWidth> {
+    match width {
+        CSSWidthAuto => None,
+        CSSWidthPercentage(percentage) => Some(PercentageWidth(percentage)),
+        length => Some(FixedWidth(MaybeAuto::from_width(length, Au(0)).spec_or_default(Au(0)))),
+    }
+}
+
+/// Adds the width that a column element or column group element asks for, or else the given
+/// width, once for each column that its `span` attribute says it covers.
+fn push_column_widths(widths: &mut ~[Option<SpecifiedWidth>],
+                      node: AbstractNode<LayoutView>,
+                      default_width: Option<SpecifiedWidth>) {
+    let span = match element_attribute(node, "span").chain(|value| uint::from_str(value.trim())) {
+        Some(span) if span > 0 => uint::min(span, 1000),
+        _ => 1,
+    };
+    let width = match specified_width(node.style().width()) {
+        None => default_width,
+        width => width,
+    };
+    for span.times {
+        widths.push(width);
+    }
+}
+
+/// Returns the minimum and preferred widths of the slots of a cell. A `width` wider than the
+/// content of the cell widens both.
+fn cell_widths(cell: &BlockFlowData) -> (Au, Au) {
+    let min_width = cell.common.min_width;
+    let pref_width = cell.common.pref_width;
+    match cell.cell_specified_width() {
+        Some(FixedWidth(width)) => {
+            (geometry::max(min_width, width), geometry::max(min_width, width))
+        }
+        _ => (min_width, pref_width),
+    }
+}
+
+fn length_to_au(length: Length) -> Au {
+    match length {
+        // FIXME: Handle `em` and `pt` correctly.
+        Px(v) | Pt(v) | Em(v) => Au::from_frac_px(v),
+    }
+}
+
+fn sum(widths: &[Au]) -> Au {
+    let mut total = Au(0);
+    for widths.iter().advance |&width| {
+        total = total + width;
+    }
+    total
+}
+
+/// Widens the given widths evenly so that they add up to at least the given total.
+fn widen(widths: &mut [Au], total: Au) {
+    let current = sum(widths);
+    if total > current && widths.len() > 0 {
+        let extra = (total - current).scale_by(1.0 / (widths.len() as float));
+        for widths.mut_iter().advance |width| {
+            *width = *width + extra;
+        }
+    }
+}
+
+/// Returns the width that the grid of a table needs to give each column its preferred width, and
+/// each column that asks for a percentage of the grid at least its preferred width.
+fn grid_pref_width(pref_widths: &[Au], percentages: &[Option<float>]) -> Au {
+    let mut width = sum(pref_widths);
+    let mut total_percentage = 0.0;
+    let mut other_width = Au(0);
+    for pref_widths.iter().zip(percentages.iter()).advance |(&pref_width, &percentage)| {
+        match percentage {
+            Some(percentage) if percentage > 0.0 => {
+                width = geometry::max(width, pref_width.scale_by(100.0 / percentage));
+                total_percentage += percentage;
+            }
+            _ => other_width = other_width + pref_width,
+        }
+    }
+    if total_percentage > 0.0 && total_percentage < 100.0 {
+        width = geometry::max(width, other_width.scale_by(100.0 / (100.0 - total_percentage)));
+    }
+    width
+}
+
+/// Divides the given width between columns with the given minimum and preferred widths and
+/// percentages (CSS 2.1 § 17.5.2.2). The columns with percentages get them first, as far as that
+/// leaves room for the minimum widths of the others, which then share the rest. If there are no
+/// others, the columns with percentages share it in proportion to their widths.
+fn distribute_widths(min_widths: &[Au],
+                     pref_widths: &[Au],
+                     percentages: &[Option<float>],
+                     width: Au)
+                     -> ~[Au] {
+    let mut percentage_columns = ~[];
+    let mut other_columns = ~[];
+    for uint::range(0, min_widths.len()) |column| {
+        if percentages[column].is_some() {
+            percentage_columns.push(column);
+        } else {
+            other_columns.push(column);
+        }
+    }
+
+    let mut widths = min_widths.to_owned();
+    let mut room = width - sum(other_columns.map(|&column| min_widths[column]));
+    for percentage_columns.iter().advance |&column| {
+        let percentage_width = width.scale_by(percentages[column].get() / 100.0);
+        widths[column] = geometry::max(min_widths[column], geometry::min(percentage_width, room));
+        room = room - widths[column];
+    }
+    let percentage_widths = percentage_columns.map(|&column| widths[column]);
+
+    let (columns, shared_widths) = if other_columns.is_empty() {
+        (percentage_columns,
+         share_widths(percentage_widths, percentage_widths, width))
+    } else {
+        let other_min_widths = other_columns.map(|&column| min_widths[column]);
+        let other_pref_widths = other_columns.map(|&column| pref_widths[column]);
+        let rest = width - sum(percentage_widths);
+        (other_columns, share_widths(other_min_widths, other_pref_widths, rest))
+    };
+    for columns.iter().zip(shared_widths.iter()).advance |(&column, &shared_width)| {
+        widths[column] = shared_width;
+    }
+    widths
+}
+
+/// Divides the given width between columns with the given minimum and preferred widths (CSS 2.1
+/// § 17.5.2.2). If there is room for every preferred width, the room to spare is shared in
+/// proportion to them; otherwise each column gets as much more than its minimum width as there is
+/// room for, in proportion to how much more it would prefer. Columns never get less than their
+/// minimum widths.
+fn share_widths(min_widths: &[Au], pref_widths: &[Au], width: Au) -> ~[Au] {
+    let num_columns = min_widths.len();
+    let min_total = sum(min_widths);
+    let pref_total = sum(pref_widths);
+
+    if width >= pref_total {
+        if pref_total > Au(0) {
+            let factor = (*width as float) / (*pref_total as float);
+            pref_widths.map(|&pref_width| pref_width.scale_by(factor))
+        } else if num_columns > 0 {
+            let share = width.scale_by(1.0 / (num_columns as float));
+            vec::from_elem(num_columns, share)
+        } else {
+            ~[]
+        }
+    } else if width > min_total {
+        let factor = ((*width - *min_total) as float) / ((*pref_total - *min_total) as float);
+        do vec::from_fn(num_columns) |column| {
+            min_widths[column] + (pref_widths[column] - min_widths[column]).scale_by(factor)
+        }
+    } else {
+        min_widths.to_owned()
+    }
+}
+
+/// Finds the widths of the columns of a table with `table-layout: fixed` from the `width` of its
+/// column elements, and of the cells in its first row for the columns whose elements have none
+/// (CSS 2.1 § 17.5.2.1). The columns whose widths that leaves unknown share the rest of the given
+/// width equally.
+fn fixed_column_widths(groups: &[RowGroupGrid],
+                       column_element_widths: &[Option<SpecifiedWidth>],
+                       num_columns: uint,
+                       width: Au,
+                       spacing: Au)
+                       -> ~[Au] {
+    let mut widths: ~[Option<Au>] = vec::from_elem(num_columns, None);
+    for column_element_widths.iter().enumerate().advance |(column, element_width)| {
+        widths[column] = element_width.map(|element_width| element_width.resolve(width));
+    }
+    for groups.iter().advance |grid| {
+        if grid.rows.is_empty() {
+            loop;
+        }
+        for grid.cells.iter().advance |slot| {
+            if slot.row != 0 {
+                loop;
+            }
+            for slot.cell.cell_specified_width().iter().advance |cell_width| {
+                let inner_spacing = spacing.scale_by((slot.colspan - 1) as float);
+                let share = (cell_width.resolve(width) - inner_spacing).scale_by(
+                    1.0 / (slot.colspan as float));
+                for uint::range(slot.column, slot.column + slot.colspan) |column| {
+                    if widths[column].is_none() {
+                        widths[column] = Some(geometry::max(Au(0), share));
+                    }
+                }
+            }
+        }
+        break;
+    }
+
+    let mut known_width = Au(0);
+    let mut num_unknown = 0;
+    for widths.iter().advance |column_width| {
+        match *column_width {
+            Some(column_width) => known_width = known_width + column_width,
+            None => num_unknown += 1,
+        }
+    }
+
+    let rest = geometry::max(Au(0), width - known_width);
+    if num_unknown > 0 {
+        let share = rest.scale_by(1.0 / (num_unknown as float));
+        do widths.map |column_width| {
+            match *column_width {
+                Some(column_width) => column_width,
+                None => share,
+            }
+        }
+    } else if num_columns > 0 {
+        let extra = rest.scale_by(1.0 / (num_columns as float));
+        widths.map(|column_width| column_width.get() + extra)
+    } else {
+        ~[]
+    }
+}
+
+#[test]
+fn test_share_widths() {
+    let min_widths = [Au(10), Au(20)];
+    let pref_widths = [Au(30), Au(20)];
+    // Room to spare goes to the columns in proportion to their preferred widths.
+    assert!(share_widths(min_widths, pref_widths, Au(100)) == ~[Au(60), Au(40)]);
+    // Less room than preferred goes to the columns that would prefer more.
+    assert!(share_widths(min_widths, pref_widths, Au(40)) == ~[Au(20), Au(20)]);
+    assert!(share_widths(min_widths, pref_widths, Au(0)) == ~[Au(10), Au(20)]);
+}
+
+#[test]
+fn test_distribute_percentage_widths() {
+    let min_widths = [Au(10), Au(20), Au(10)];
+    let pref_widths = [Au(30), Au(20), Au(10)];
+    let percentages = [None, Some(50.0), None];
+    // A column with a percentage gets it, and the others share the rest.
+    assert!(distribute_widths(min_widths, pref_widths, percentages, Au(120)) ==
+            ~[Au(45), Au(60), Au(15)]);
+    // It gets no more than leaves the others their minimum widths, and no less than its own.
+    assert!(distribute_widths(min_widths, pref_widths, percentages, Au(40)) ==
+            ~[Au(10), Au(20), Au(10)]);
+    // Columns that all have percentages share the room to spare.
+    assert!(distribute_widths([Au(10)], [Au(10)], [Some(50.0)], Au(100)) == ~[Au(100)]);
+
+    // The grid is wide enough for each percentage of it.
+    assert!(grid_pref_width(pref_widths, percentages) == Au(80));
+    assert!(grid_pref_width([Au(30), Au(10)], [Some(25.0), None]) == Au(120));
+}
@@ -4,22 +4,37 @@
 
 //! Text layout.
 
+use std::str;
 use std::uint;
 use std::vec;
+use extra::sort::tim_sort;
 
+use css::node_style::StyledNode;
+use gfx::text::bidi::{LRE, LRO, PDF, RLE, RLO, is_rtl, resolve_levels};
+use gfx::text::hyphenation::Hyphenator;
 use gfx::text::text_run::TextRun;
-use gfx::text::util::{CompressWhitespaceNewline, transform_text};
+use gfx::text::util::{CompressWhitespaceNewline, SkipChars, transform_text};
 use layout::box::{RenderBox, RenderBoxBase, TextRenderBox};
 use layout::box::{TextRenderBoxClass, UnscannedTextRenderBoxClass};
 use layout::context::LayoutContext;
 use layout::flow::FlowContext;
 use layout::util::{NodeRange};
-use newcss::values::{CSSTextDecoration, CSSTextDecorationUnderline};
+use newcss::values::{CSSDirectionLtr, CSSDirectionRtl, CSSHyphensAuto, CSSHyphensManual};
+use newcss::values::{CSSHyphensNone, CSSTextDecoration};
+use newcss::values::{CSSTextDecorationUnderline, CSSUnicodeBidiEmbed, CSSUnicodeBidiNormal};
+use newcss::values::{CSSUnicodeBidiOverride};
+use script::dom::node::{AbstractNode, LayoutView};
 use servo_util::range::Range;
 
 
-/// Creates a TextRenderBox from a range and a text run.
-pub fn adapt_textbox_with_range(mut base: RenderBoxBase, run: @TextRun, range: Range)
+/// Creates a TextRenderBox from a range and a text run. `skip_chars` records which characters of
+/// the text of the box's node were kept in its transformed text, and `run_offset` is the index
+/// in that transformed text of the first character of the run.
+pub fn adapt_textbox_with_range(mut base: RenderBoxBase,
+                                run: @TextRun,
+                                range: Range,
+                                skip_chars: @SkipChars,
+                                run_offset: int)
                                 -> TextRenderBox {
     debug!("Creating textbox with span: (strlen=%u, off=%u, len=%u) of textrun (%s) (len=%u)",
            run.char_len(),
@@ -39,6 +54,9 @@ pub fn adapt_textbox_with_range(mut base: RenderBoxBase, run: @TextRun, range: R
         base: base,
         run: run,
         range: range,
+        hyphenated: false,
+        skip_chars: skip_chars,
+        run_offset: run_offset,
     }
 }
 
@@ -56,15 +74,38 @@ impl UnscannedMethods for RenderBox {
     }
 }
 
+/// The character that stands in for a box that is not text in the text of a paragraph.
+static OBJECT_REPLACEMENT_CHARACTER: char = '\uFFFC';
+
+/// The invisible character that marks where a word may be hyphenated.
+static SOFT_HYPHEN: char = '\u00AD';
+
 /// A stack-allocated object for scanning an inline flow into `TextRun`-containing `TextBox`es.
 struct TextRunScanner {
     clump: Range,
+    /// The text of each box of the flow after whitespace compression and the removal of soft
+    /// hyphens. Boxes that are not text have no text.
+    transformed_text: ~[~str],
+    /// Which characters of the text of each box were kept in its transformed text.
+    skip_chars: ~[@SkipChars],
+    /// The character indices in the transformed text of each box before which a word may be
+    /// hyphenated, in increasing order.
+    hyphen_breaks: ~[~[uint]],
+    /// The bidirectional embedding level of each character of the flow's paragraph.
+    levels: ~[u8],
+    /// The index into `levels` of the first character of each box.
+    level_offsets: ~[uint],
 }
 
 impl TextRunScanner {
     pub fn new() -> TextRunScanner {
         TextRunScanner {
             clump: Range::empty(),
+            transformed_text: ~[],
+            skip_chars: ~[],
+            hyphen_breaks: ~[],
+            levels: ~[],
+            level_offsets: ~[],
         }
     }
 
@@ -73,18 +114,20 @@ impl TextRunScanner {
         assert!(inline.boxes.len() > 0);
         debug!("TextRunScanner: scanning %u boxes for text runs...", inline.boxes.len());
 
-        let mut last_whitespace = true;
+        self.transform_text_of_boxes(ctx, inline.boxes);
+        self.resolve_bidi_levels(flow);
+
         let mut out_boxes = ~[];
         for uint::range(0, flow.inline().boxes.len()) |box_i| {
             debug!("TextRunScanner: considering box: %?", flow.inline().boxes[box_i].debug_str());
             if box_i > 0 && !can_coalesce_text_nodes(flow.inline().boxes, box_i-1, box_i) {
-                last_whitespace = self.flush_clump_to_list(ctx, flow, last_whitespace, &mut out_boxes);
+                self.flush_clump_to_list(ctx, flow, &mut out_boxes);
             }
             self.clump.extend_by(1);
         }
         // handle remaining clumps
         if self.clump.length() > 0 {
-            self.flush_clump_to_list(ctx, flow, last_whitespace, &mut out_boxes);
+            self.flush_clump_to_list(ctx, flow, &mut out_boxes);
         }
 
         debug!("TextRunScanner: swapping out boxes.");
@@ -108,6 +151,115 @@ impl TextRunScanner {
         }
     }
 
+    /// Compresses the whitespace in the text of each box, treating the text of all of the boxes
+    /// as one string, and removes its soft hyphens. Finds where the words of each box may be
+    /// hyphenated: at soft hyphens unless it is styled `hyphens: none`, and, if it is styled
+    /// `hyphens: auto`, wherever the hyphenation dictionary of its language allows in words
+    /// without soft hyphens.
+    fn transform_text_of_boxes(&mut self, ctx: &LayoutContext, boxes: &[RenderBox]) {
+        // TODO(#115): Use the actual CSS `white-space` property of the relevant style.
+        let compression = CompressWhitespaceNewline;
+
+        let mut last_whitespace = true;
+        self.transformed_text = ~[];
+        self.skip_chars = ~[];
+        self.hyphen_breaks = ~[];
+        for boxes.iter().advance |box| {
+            match *box {
+                UnscannedTextRenderBoxClass(*) => {
+                    let raw_text = box.raw_text();
+                    let mut skip_chars = SkipChars::new();
+                    let (new_str, new_whitespace) = transform_text(raw_text,
+                                                                   compression,
+                                                                   last_whitespace,
+                                                                   &mut skip_chars);
+                    last_whitespace = new_whitespace;
+
+                    let soft_hyphen_breaks = soft_hyphen_breaks(raw_text, &skip_chars);
+                    let breaks = match box.hyphens() {
+                        CSSHyphensNone => ~[],
+                        CSSHyphensManual => soft_hyphen_breaks,
+                        CSSHyphensAuto => {
+                            let hyphenator = match box.language() {
+                                Some(lang) => ctx.hyphenation.get(lang),
+                                None => None,
+                            };
+                            match hyphenator {
+                                Some(hyphenator) => {
+                                    hyphenate_words(hyphenator, new_str, soft_hyphen_breaks)
+                                }
+                                None => soft_hyphen_breaks,
+                            }
+                        }
+                    };
+
+                    self.transformed_text.push(new_str);
+                    self.skip_chars.push(@skip_chars);
+                    self.hyphen_breaks.push(breaks);
+                }
+                _ => {
+                    self.transformed_text.push(~"");
+                    self.skip_chars.push(@SkipChars::new());
+                    self.hyphen_breaks.push(~[]);
+                }
+            }
+        }
+    }
+
+    /// Resolves the bidirectional embedding levels of the flow's paragraph. In the text of the
+    /// paragraph, each box that is not text is represented by an object replacement character,
+    /// and the embeddings and overrides of inline elements by explicit directional formatting
+    /// characters.
+    fn resolve_bidi_levels(&mut self, flow: FlowContext) {
+        let inline = flow.inline();
+        let container = block_container_of(flow);
+        let paragraph_level = match container.style().direction() {
+            CSSDirectionLtr => 0,
+            CSSDirectionRtl => 1,
+        };
+        inline.paragraph_level = paragraph_level;
+
+        let mut paragraph = ~"";
+        let mut char_count = 0;
+        let mut open_embeddings = ~[];
+        self.level_offsets = ~[];
+        for uint::range(0, inline.boxes.len()) |box_i| {
+            let box = inline.boxes[box_i];
+
+            // Close the embeddings that this box is outside of, and open the ones it is newly
+            // inside.
+            let embeddings = bidi_embeddings_of(box.node(), container);
+            let mut common = 0;
+            while common < open_embeddings.len() && common < embeddings.len() &&
+                    open_embeddings[common] == embeddings[common] {
+                common += 1;
+            }
+            for uint::range(common, open_embeddings.len()) |_| {
+                paragraph.push_char(PDF);
+                char_count += 1;
+            }
+            for uint::range(common, embeddings.len()) |i| {
+                paragraph.push_char(embeddings[i].second());
+                char_count += 1;
+            }
+            open_embeddings = embeddings;
+
+            self.level_offsets.push(char_count);
+            match box {
+                UnscannedTextRenderBoxClass(*) => {
+                    paragraph.push_str(self.transformed_text[box_i]);
+                    char_count += self.transformed_text[box_i].char_len();
+                }
+                _ => {
+                    paragraph.push_char(OBJECT_REPLACEMENT_CHARACTER);
+                    char_count += 1;
+                }
+            }
+        }
+
+        self.levels = resolve_levels(paragraph, paragraph_level);
+    }
+
     /// A "clump" is a range of inline flow leaves that can be merged together into a single
     /// `RenderBox`. Adjacent text with the same style can be merged, and nothing else can.
     ///
@@ -115,14 +267,16 @@ impl TextRunScanner {
     /// necessary for correct painting order. Since we compress several leaf `RenderBox`es here,
     /// the mapping must be adjusted.
     ///
+    /// Text is split into one run for each sequence of characters at the same embedding level,
+    /// so a text box may become several boxes.
+    ///
     /// N.B. `in_boxes` is passed by reference, since the old code used a `DVec`. The caller is
     /// responsible for swapping out the list. It is not clear to me (pcwalton) that this is still
     /// necessary.
     pub fn flush_clump_to_list(&mut self,
                                ctx: &LayoutContext,
                                flow: FlowContext,
-                               last_whitespace: bool,
-                               out_boxes: &mut ~[RenderBox]) -> bool {
+                               out_boxes: &mut ~[RenderBox]) {
         let inline = &mut *flow.inline();
         let in_boxes = &inline.boxes;
 
@@ -142,108 +296,93 @@ impl TextRunScanner {
             _ => false
         };
 
-        let mut new_whitespace = last_whitespace;
-
         match (is_singleton, is_text_clump) {
             (false, false) => {
                 fail!(~"WAT: can't coalesce non-text nodes in flush_clump_to_list()!")
             }
             (true, false) => {
                 debug!("TextRunScanner: pushing single non-text box in range: %?", self.clump);
-                out_boxes.push(in_boxes[self.clump.begin()]);
-            },
-            (true, true)  => {
-                let old_box = in_boxes[self.clump.begin()];
-                let text = old_box.raw_text();
-                let font_style = old_box.font_style();
-                let underline = has_underline(old_box.text_decoration());
-
-                // TODO(#115): Use the actual CSS `white-space` property of the relevant style.
-                let compression = CompressWhitespaceNewline;
-
-                let (transformed_text, whitespace) = transform_text(text, compression, last_whitespace);
-                new_whitespace = whitespace;
-
-                if transformed_text.len() > 0 {
-                    // TODO(#177): Text run creation must account for the renderability of text by
-                    // font group fonts. This is probably achieved by creating the font group above
-                    // and then letting `FontGroup` decide which `Font` to stick into the text run.
-                    let fontgroup = ctx.font_ctx.get_resolved_font_for_style(&font_style);
-                    let run = @fontgroup.create_textrun(transformed_text, underline);
-
-                    debug!("TextRunScanner: pushing single text box in range: %? (%?)", self.clump, text);
-                    let new_box = do old_box.with_base |old_box_base| {
-                        let range = Range::new(0, run.char_len());
-                        @mut adapt_textbox_with_range(*old_box_base, run, range)
-                    };
-
-                    out_boxes.push(TextRenderBoxClass(new_box));
+                let box = in_boxes[self.clump.begin()];
+                let level = self.levels[self.level_offsets[self.clump.begin()]];
+                do box.with_mut_base |base| {
+                    base.bidi_level = level;
                 }
+                out_boxes.push(box);
             },
-            (false, true) => {
-                // TODO(#115): Use the actual CSS `white-space` property of the relevant style.
-                let compression = CompressWhitespaceNewline;
-
-                // First, transform/compress text of all the nodes.
-                let mut last_whitespace_in_clump = new_whitespace;
-                let transformed_strs: ~[~str] = do vec::from_fn(self.clump.length()) |i| {
-                    // TODO(#113): We should be passing the compression context between calls to
-                    // `transform_text`, so that boxes starting and/or ending with whitespace can
-                    // be compressed correctly with respect to the text run.
-                    let idx = i + self.clump.begin();
-                    let (new_str, new_whitespace) = transform_text(in_boxes[idx].raw_text(),
-                                                                   compression,
-                                                                   last_whitespace_in_clump);
-                    last_whitespace_in_clump = new_whitespace;
-                    new_str
-                };
-                new_whitespace = last_whitespace_in_clump;
-
-                // Next, concatenate all of the transformed strings together, saving the new
-                // character indices.
+            (_, true) => {
+                // Concatenate all of the transformed strings together, saving the new character
+                // indices, the embedding level of each character, and where words may be
+                // hyphenated.
                 let mut run_str: ~str = ~"";
+                let mut run_levels: ~[u8] = ~[];
+                let mut run_hyphen_breaks: ~[uint] = ~[];
                 let mut new_ranges: ~[Range] = ~[];
                 let mut char_total = 0;
-                for uint::range(0, transformed_strs.len()) |i| {
-                    let added_chars = transformed_strs[i].char_len();
+                for self.clump.eachi |i| {
+                    let added_chars = self.transformed_text[i].char_len();
                     new_ranges.push(Range::new(char_total, added_chars));
-                    run_str.push_str(transformed_strs[i]);
+                    run_str.push_str(self.transformed_text[i]);
+                    for self.hyphen_breaks[i].iter().advance |&hyphen_break| {
+                        run_hyphen_breaks.push(char_total + hyphen_break);
+                    }
+                    let level_offset = self.level_offsets[i];
+                    let levels = self.levels.slice(level_offset, level_offset + added_chars);
+                    run_levels.push_all(levels);
                     char_total += added_chars;
                 }
 
-                // Now create the run.
-                //
-                // TODO(#177): Text run creation must account for the renderability of text by
-                // font group fonts. This is probably achieved by creating the font group above
-                // and then letting `FontGroup` decide which `Font` to stick into the text run.
                 let font_style = in_boxes[self.clump.begin()].font_style();
                 let fontgroup = ctx.font_ctx.get_resolved_font_for_style(&font_style);
                 let underline = has_underline(in_boxes[self.clump.begin()].text_decoration());
+                let features = in_boxes[self.clump.begin()].font_features();
 
-                // TextRuns contain a cycle which is usually resolved by the teardown
-                // sequence. If no clump takes ownership, however, it will leak.
-                let clump = self.clump;
-                let run = if clump.length() != 0 && run_str.len() > 0 {
-                    Some(@TextRun::new(fontgroup.fonts[0], run_str, underline))
-                } else {
-                    None
-                };
-
-                // Make new boxes with the run and adjusted text indices.
+                // Create a run for each sequence of characters at the same embedding level, and
+                // new boxes with the run and adjusted text indices for each box that overlaps it.
                 debug!("TextRunScanner: pushing box(es) in range: %?", self.clump);
-                for clump.eachi |i| {
-                    let range = new_ranges[i - self.clump.begin()];
-                    if range.length() == 0 {
-                        debug!("Elided an `UnscannedTextbox` because it was zero-length after \
-                                compression; %s",
-                               in_boxes[i].debug_str());
-                        loop
+                let mut level_start = 0;
+                while level_start < char_total {
+                    let level = run_levels[level_start];
+                    let mut level_end = level_start + 1;
+                    while level_end < char_total && run_levels[level_end] == level {
+                        level_end += 1;
                     }
-
-                    do in_boxes[i].with_base |base| {
-                        let new_box = @mut adapt_textbox_with_range(*base, run.get(), range);
-                        out_boxes.push(TextRenderBoxClass(new_box));
+                    let level_range = Range::new(level_start, level_end - level_start);
+
+                    let text = run_str.slice_chars(level_start, level_end).to_owned();
+                    let mut hyphen_breaks = ~[];
+                    for run_hyphen_breaks.iter().advance |&hyphen_break| {
+                        if hyphen_break > level_start && hyphen_break < level_end {
+                            hyphen_breaks.push(hyphen_break - level_start);
+                        }
+                    }
+                    let run = @fontgroup.create_textrun(ctx.font_ctx,
+                                                        text,
+                                                        underline,
+                                                        is_rtl(level),
+                                                        features,
+                                                        hyphen_breaks);
+                    for self.clump.eachi |i| {
+                        let range = new_ranges[i - self.clump.begin()].intersect(&level_range);
+                        if range.length() == 0 {
+                            loop
+                        }
+
+                        let range = Range::new(range.begin() - level_start, range.length());
+                        let skip_chars = self.skip_chars[i];
+                        let run_offset = level_start as int -
+                            new_ranges[i - self.clump.begin()].begin() as int;
+                        do in_boxes[i].with_base |base| {
+                            let new_box = @mut adapt_textbox_with_range(*base,
+                                                                        run,
+                                                                        range,
+                                                                        skip_chars,
+                                                                        run_offset);
+                            new_box.base.bidi_level = level;
+                            out_boxes.push(TextRenderBoxClass(new_box));
+                        }
                     }
+
+                    level_start = level_end;
                 }
             }
         } // End of match.
@@ -268,7 +407,106 @@ impl TextRunScanner {
 
         let end = self.clump.end(); // FIXME: borrow checker workaround
         self.clump.reset(end, 0);
-
-        new_whitespace
     } // End of `flush_clump_to_list`.
 }
+
+/// Returns the character index in the transformed text of each place a soft hyphen was removed
+/// from the original text, given which characters of the original text were kept.
+fn soft_hyphen_breaks(text: &str, skip_chars: &SkipChars) -> ~[uint] {
+    let mut breaks = ~[];
+    for text.iter().enumerate().advance |(i, ch)| {
+        if ch != SOFT_HYPHEN {
+            loop;
+        }
+        // Several soft hyphens in a row only allow one break.
+        let hyphen_break = skip_chars.original_to_transformed(i);
+        if breaks.last_opt() != Some(&hyphen_break) {
+            breaks.push(hyphen_break);
+        }
+    }
+    breaks
+}
+
+/// Returns the character indices, in increasing order, before which the words of the given text
+/// may be hyphenated: the given breaks at soft hyphens, and the places the hyphenator finds in
+/// the words that have no soft hyphens.
+fn hyphenate_words(hyphenator: @Hyphenator, text: &str, soft_hyphen_breaks: &[uint]) -> ~[uint] {
+    let chars: ~[char] = text.iter().collect();
+    let mut breaks = soft_hyphen_breaks.to_owned();
+    let mut word_start = 0;
+    for uint::range(0, chars.len() + 1) |i| {
+        if i < chars.len() && chars[i].is_alphabetic() {
+            loop;
+        }
+        let word_has_soft_hyphen = soft_hyphen_breaks.iter().any_(|&hyphen_break| {
+            hyphen_break > word_start && hyphen_break < i
+        });
+        if i > word_start && !word_has_soft_hyphen {
+            let word = str::from_chars(chars.slice(word_start, i));
+            for hyphenator.hyphenate(word).iter().advance |&hyphen_break| {
+                breaks.push(word_start + hyphen_break);
+            }
+        }
+        word_start = i + 1;
+    }
+    tim_sort(breaks);
+    breaks
+}
+
+/// Returns the element whose content an inline flow lays out: the parent of the node the flow
+/// was created for.
+fn block_container_of(flow: FlowContext) -> AbstractNode<LayoutView> {
+    let node = flow.with_base(|base| base.node);
+    match node.parent_node() {
+        Some(parent) => parent,
+        None => node,
+    }
+}
+
+/// Returns the elements from the given node up to, but not including, its block container that
+/// open a bidirectional embedding or override, outermost first, each with the explicit
+/// directional formatting character that opens it.
+fn bidi_embeddings_of(node: AbstractNode<LayoutView>, container: AbstractNode<LayoutView>)
+                      -> ~[(AbstractNode<LayoutView>, char)] {
+    let mut embeddings = ~[];
+    let mut node = node;
+    while node != container {
+        if node.is_element() {
+            let style = node.style();
+            let rtl = match style.direction() {
+                CSSDirectionLtr => false,
+                CSSDirectionRtl => true,
+            };
+            match style.unicode_bidi() {
+                CSSUnicodeBidiNormal => {}
+                CSSUnicodeBidiEmbed => embeddings.push((node, if rtl { RLE } else { LRE })),
+                CSSUnicodeBidiOverride => embeddings.push((node, if rtl { RLO } else { LRO })),
+            }
+        }
+        match node.parent_node() {
+            Some(parent) => node = parent,
+            None => break,
+        }
+    }
+    vec::reverse(embeddings);
+    embeddings
+}
+
+#[test]
+fn test_soft_hyphen_breaks() {
+    let text = "hy\u00ADphen\u00AD\u00ADat  ion";
+    let mut skip_chars = SkipChars::new();
+    let (transformed, _) = transform_text(text, CompressWhitespaceNewline, true, &mut skip_chars);
+    assert!(transformed == ~"hyphenat ion");
+    // The two soft hyphens in a row allow only one break.
+    assert!(soft_hyphen_breaks(text, &skip_chars) == ~[2, 6]);
+}
+
+#[test]
+fn test_hyphenate_words() {
+    let hyphenator = @Hyphenator::parse("hy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n\n").unwrap();
+    assert!(hyphenate_words(hyphenator, "hyphenation", []) == ~[2, 6]);
+    // Words with soft hyphens are only hyphenated there.
+    assert!(hyphenate_words(hyphenator, "hyphenation, hyphenation", [17]) == ~[2, 6, 17]);
+    assert!(hyphenate_words(hyphenator, "", []).is_empty());
+}
@@ -9,11 +9,27 @@ use servo_util::range::Range;
 pub struct NodeRange {
     node: AbstractNode<LayoutView>,
     range: Range,
+    /// Whether these boxes begin the element, rather than carry on from an earlier part of it
+    /// that a block split off into another flow.
+    first_part: bool,
+    /// Whether these boxes end the element, rather than stop before a block that splits it.
+    last_part: bool,
 }
 
 impl NodeRange {
     pub fn new(node: AbstractNode<LayoutView>, range: &Range) -> NodeRange {
-        NodeRange { node: node, range: copy *range }
+        NodeRange::new_part(node, range, true, true)
+    }
+
+    pub fn new_part(node: AbstractNode<LayoutView>, range: &Range, first_part: bool,
+                    last_part: bool)
+                    -> NodeRange {
+        NodeRange {
+            node: node,
+            range: copy *range,
+            first_part: first_part,
+            last_part: last_part,
+        }
     }
 }
 
@@ -30,6 +46,15 @@ impl ElementMapping {
         self.entries.push(NodeRange::new(node, range))
     }
 
+    /// Maps the given boxes to one part of an element that blocks split across several flows.
+    pub fn add_part_mapping(&mut self,
+                            node: AbstractNode<LayoutView>,
+                            range: &Range,
+                            first_part: bool,
+                            last_part: bool) {
+        self.entries.push(NodeRange::new_part(node, range, first_part, last_part))
+    }
+
     pub fn each(&self, callback: &fn(nr: &NodeRange) -> bool) -> bool {
         for self.entries.each |nr| {
             if !callback(nr) {
@@ -47,14 +47,16 @@ impl Pipeline {
                            render_port,
                            compositor_chan.clone(),
                            copy opts,
-                           profiler_chan.clone());
+                           profiler_chan.for_task(~"Render", Some(id)));
 
         LayoutTask::create(layout_port,
+                           layout_chan.clone(),
                            script_chan.clone(),
                            render_chan.clone(),
                            image_cache_task.clone(),
+                           resource_task.clone(),
                            copy opts,
-                           profiler_chan);
+                           profiler_chan.for_task(~"Layout", Some(id)));
 
         ScriptTask::create(id,
                            compositor_chan,
@@ -63,7 +65,8 @@ impl Pipeline {
                            script_chan.clone(),
                            constellation_chan,
                            resource_task,
-                           image_cache_task);
+                           image_cache_task,
+                           profiler_chan.for_task(~"Script", Some(id)));
 
         Pipeline::new(id,
                       script_chan,
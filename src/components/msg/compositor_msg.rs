@@ -26,7 +26,9 @@ pub struct LayerBuffer {
 /// A set of layer buffers. This is an atomic unit used to switch between the front and back
 /// buffers.
 pub struct LayerBufferSet {
-    buffers: ~[LayerBuffer]
+    buffers: ~[LayerBuffer],
+    /// The buffers of the content that stays fixed to the viewport while the page scrolls.
+    fixed_buffers: ~[LayerBuffer],
 }
 
 /// The status of the renderer.
@@ -5,6 +5,7 @@
 use image::base::{Image, load_from_memory};
 use resource_task;
 use resource_task::ResourceTask;
+use servo_util::time::{ImageDecodingCategory, ProfilerChan, profile};
 use servo_util::url::{UrlMap, url_map};
 
 use std::cell::Cell;
@@ -83,10 +84,18 @@ pub type ImageCacheTask = SharedChan<Msg>;
 type DecoderFactory = ~fn() -> ~fn(&[u8]) -> Option<Image>;
 
 pub fn ImageCacheTask(resource_task: ResourceTask) -> ImageCacheTask {
-    ImageCacheTask_(resource_task, default_decoder_factory)
+    ImageCacheTask_(resource_task, default_decoder_factory, None)
 }
 
-pub fn ImageCacheTask_(resource_task: ResourceTask, decoder_factory: DecoderFactory)
+/// Creates an image cache task that reports how long each image decode takes to the profiler.
+pub fn ProfiledImageCacheTask(resource_task: ResourceTask, profiler_chan: ProfilerChan)
+                              -> ImageCacheTask {
+    ImageCacheTask_(resource_task, default_decoder_factory, Some(profiler_chan))
+}
+
+pub fn ImageCacheTask_(resource_task: ResourceTask,
+                       decoder_factory: DecoderFactory,
+                       profiler_chan: Option<ProfilerChan>)
                        -> ImageCacheTask {
     // FIXME: Doing some dancing to avoid copying decoder_factory, our test
     // version of which contains an uncopyable type which rust will currently
@@ -97,11 +106,13 @@ pub fn ImageCacheTask_(resource_task: ResourceTask, decoder_factory: DecoderFact
     let chan = SharedChan::new(chan);
     let port_cell = Cell::new(port);
     let chan_cell = Cell::new(chan.clone());
+    let profiler_chan_cell = Cell::new(profiler_chan);
 
     do spawn {
         let mut cache = ImageCache {
             resource_task: resource_task.clone(),
             decoder_factory: decoder_factory_cell.take(),
+            profiler_chan: profiler_chan_cell.take(),
             port: port_cell.take(),
             chan: chan_cell.take(),
             state_map: url_map(),
@@ -146,6 +157,8 @@ struct ImageCache {
     resource_task: ResourceTask,
     /// Creates image decoders
     decoder_factory: DecoderFactory,
+    /// A channel to the profiler, if decode times should be reported
+    profiler_chan: Option<ProfilerChan>,
     /// The port on which we'll receive client requests
     port: Port<Msg>,
     /// A copy of the shared chan to give to child tasks
@@ -322,11 +335,17 @@ impl ImageCache {
                 let to_cache = self.chan.clone();
                 let url_cell = Cell::new(copy url);
                 let decode = (self.decoder_factory)();
+                let profiler_chan = self.profiler_chan.clone();
 
                 do spawn {
                     let url = url_cell.take();
                     debug!("image_cache_task: started image decode for %s", url.to_str());
-                    let image = decode(data);
+                    let image = match profiler_chan {
+                        Some(ref profiler_chan) => {
+                            profile(ImageDecodingCategory, profiler_chan.clone(), || decode(data))
+                        }
+                        None => decode(data),
+                    };
                     let image = if image.is_some() {
                         Some(ARC(~image.unwrap()))
                     } else {
@@ -866,7 +885,7 @@ fn should_return_not_ready_if_image_is_still_decoding() {
         }
     };
 
-    let image_cache_task = ImageCacheTask_(mock_resource_task, decoder_factory);
+    let image_cache_task = ImageCacheTask_(mock_resource_task, decoder_factory, None);
     let url = make_url(~"file", None);
 
     let wait_for_prefetech = comm::Port();
@@ -7,9 +7,12 @@
 use file_loader;
 use http_loader;
 
+use servo_util::time::{ProfilerChan, ResourceLoadingCategory};
 use std::cell::Cell;
-use std::comm::{Chan, Port, SharedChan};
+use std::comm::{Chan, Port, SharedChan, stream};
+use std::task::spawn;
 use extra::net::url::{Url, to_str};
+use extra::time::precise_time_ns;
 use util::spawn_listener;
 
 pub enum ControlMsg {
@@ -42,20 +45,47 @@ pub type LoaderTask = ~fn(url: Url, Chan<ProgressMsg>);
 
 /// Create a ResourceTask with the default loaders
 pub fn ResourceTask() -> ResourceTask {
+    create_resource_task_with_loaders(default_loaders(), None)
+}
+
+/// Create a ResourceTask with the default loaders that reports how long each load takes to the
+/// profiler
+pub fn ProfiledResourceTask(profiler_chan: ProfilerChan) -> ResourceTask {
+    create_resource_task_with_loaders(default_loaders(), Some(profiler_chan))
+}
+
+/// Loads the whole resource at the given URL, blocking until it has all arrived.
+pub fn load_whole_resource(resource_task: &ResourceTask, url: Url) -> Result<~[u8], ()> {
+    let (response_port, response_chan) = stream();
+    resource_task.send(Load(url, response_chan));
+
+    let mut data = ~[];
+    loop {
+        match response_port.recv() {
+            Payload(payload) => data.push_all_move(payload),
+            Done(Ok(())) => return Ok(data),
+            Done(Err(())) => return Err(()),
+        }
+    }
+}
+
+fn default_loaders() -> ~[(~str, LoaderTaskFactory)] {
     let file_loader_factory: LoaderTaskFactory = file_loader::factory;
     let http_loader_factory: LoaderTaskFactory = http_loader::factory;
-    let loaders = ~[
+    ~[
         (~"file", file_loader_factory),
         (~"http", http_loader_factory)
-    ];
-    create_resource_task_with_loaders(loaders)
+    ]
 }
 
-fn create_resource_task_with_loaders(loaders: ~[(~str, LoaderTaskFactory)]) -> ResourceTask {
+fn create_resource_task_with_loaders(loaders: ~[(~str, LoaderTaskFactory)],
+                                     profiler_chan: Option<ProfilerChan>)
+                                     -> ResourceTask {
     let loaders_cell = Cell::new(loaders);
+    let profiler_chan_cell = Cell::new(profiler_chan);
     let chan = do spawn_listener |from_client| {
         // TODO: change copy to move once we can move out of closures
-        ResourceManager(from_client, loaders_cell.take()).start()
+        ResourceManager(from_client, loaders_cell.take(), profiler_chan_cell.take()).start()
     };
     SharedChan::new(chan)
 }
@@ -64,14 +94,18 @@ pub struct ResourceManager {
     from_client: Port<ControlMsg>,
     /// Per-scheme resource loaders
     loaders: ~[(~str, LoaderTaskFactory)],
+    /// A channel to the profiler, if load times should be reported
+    profiler_chan: Option<ProfilerChan>,
 }
 
 
 pub fn ResourceManager(from_client: Port<ControlMsg>, 
-                       loaders: ~[(~str, LoaderTaskFactory)]) -> ResourceManager {
+                       loaders: ~[(~str, LoaderTaskFactory)],
+                       profiler_chan: Option<ProfilerChan>) -> ResourceManager {
     ResourceManager {
         from_client : from_client,
         loaders : loaders,
+        profiler_chan : profiler_chan,
     }
 }
 
@@ -95,6 +129,11 @@ impl ResourceManager {
         match self.get_loader_factory(&url) {
             Some(loader_factory) => {
                 debug!("resource_task: loading url: %s", to_str(&url));
+                let progress_chan = match self.profiler_chan {
+                    Some(ref profiler_chan) => timed_progress_chan(profiler_chan.clone(),
+                                                                   progress_chan),
+                    None => progress_chan,
+                };
                 loader_factory(url, progress_chan);
             }
             None => {
@@ -118,6 +157,33 @@ impl ResourceManager {
     }
 }
 
+/// Returns a channel that forwards progress messages to `progress_chan`, reporting the time from
+/// now until the load is done to the profiler.
+fn timed_progress_chan(profiler_chan: ProfilerChan, progress_chan: Chan<ProgressMsg>)
+                       -> Chan<ProgressMsg> {
+    let (timed_port, timed_chan) = stream();
+    let timed_port = Cell::new(timed_port);
+    let progress_chan = Cell::new(progress_chan);
+    let start_time = precise_time_ns();
+    do spawn {
+        let timed_port = timed_port.take();
+        let progress_chan = progress_chan.take();
+        loop {
+            let msg = timed_port.recv();
+            let done = match msg {
+                Done(_) => true,
+                Payload(_) => false,
+            };
+            progress_chan.send(msg);
+            if done {
+                profiler_chan.send_span(ResourceLoadingCategory, start_time, precise_time_ns());
+                break;
+            }
+        }
+    }
+    timed_chan
+}
+
 #[test]
 fn test_exit() {
     let resource_task = ResourceTask();
@@ -146,7 +212,7 @@ fn should_delegate_to_scheme_loader() {
         progress_chan.send(Done(Ok(())));
     };
     let loader_factories = ~[(~"snicklefritz", loader_factory)];
-    let resource_task = create_resource_task_with_loaders(loader_factories);
+    let resource_task = create_resource_task_with_loaders(loader_factories, None);
     let progress = Port();
     resource_task.send(Load(url::from_str(~"snicklefritz://heya").get(), progress.chan()));
     assert!(progress.recv() == Payload(payload));
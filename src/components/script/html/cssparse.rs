@@ -7,7 +7,9 @@
 use std::cell::Cell;
 use std::comm;
 use std::comm::Port;
+use std::str;
 use std::task;
+use html::font_face::{FontFaceRule, parse_font_face_rules};
 use newcss::stylesheet::Stylesheet;
 use newcss::util::DataStream;
 use servo_net::resource_task::{ResourceTask, ProgressMsg, Load, Payload, Done};
@@ -19,9 +21,11 @@ pub enum StylesheetProvenance {
     InlineProvenance(Url, ~str),
 }
 
+/// Parses a style sheet on a new task. The returned port receives the sheet along with its
+/// `@font-face` rules, which the CSS parser drops.
 pub fn spawn_css_parser(provenance: StylesheetProvenance,
                         resource_task: ResourceTask)
-                     -> Port<Stylesheet> {
+                     -> Port<(Stylesheet, ~[FontFaceRule])> {
     let (result_port, result_chan) = comm::stream();
 
     let provenance_cell = Cell::new(provenance);
@@ -33,9 +37,25 @@ pub fn spawn_css_parser(provenance: StylesheetProvenance,
             }
         };
 
-        let sheet = Stylesheet::new(url, data_stream(provenance_cell.take(),
-                                                     resource_task.clone()));
-        result_chan.send(sheet);
+        // Keep the source as the CSS parser reads it, to find the `@font-face` rules in.
+        let source = @mut ~[];
+        let stream = data_stream(provenance_cell.take(), resource_task.clone());
+        let sheet = Stylesheet::new(copy url, || {
+            let data = stream();
+            match data {
+                Some(ref data) => source.push_all(*data),
+                None => {}
+            }
+            data
+        });
+
+        let font_face_rules = if str::is_utf8(*source) {
+            parse_font_face_rules(str::from_bytes(*source), &url)
+        } else {
+            debug!("cssparse: not looking for @font-face rules in a sheet that isn't UTF-8");
+            ~[]
+        };
+        result_chan.send((sheet, font_face_rules));
     }
 
     return result_port;
@@ -0,0 +1,370 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Collects the `@font-face` rules of a style sheet, which the CSS parser drops, so that layout
+//! can load the fonts they declare.
+
+use std::uint;
+use gfx::font_context::WebFontFace;
+use servo_util::url::make_url;
+use extra::net::url::Url;
+
+/// A font declared by an `@font-face` rule.
+pub struct FontFaceRule {
+    face: WebFontFace,
+    /// The places the rule's `src` descriptor gives to get the font from, in order of preference.
+    sources: ~[FontFaceSource],
+}
+
+/// A place to get the font of an `@font-face` rule from.
+pub enum FontFaceSource {
+    /// An installed font, by family name.
+    FontFaceSourceLocal(~str),
+    /// A font to download, with its format hint if it has one.
+    FontFaceSourceUrl(Url, Option<~str>),
+}
+
+/// Returns the `@font-face` rules in the given style sheet source, in order, with their URLs
+/// resolved against `base_url`. Rules inside other at-rules such as `@media` are included. A rule
+/// without a `font-family` or a valid `src` declares no font and is dropped.
+pub fn parse_font_face_rules(css: &str, base_url: &Url) -> ~[FontFaceRule] {
+    let css = strip_comments(css);
+    let css = css.as_slice();
+
+    let mut rules = ~[];
+    let mut i = 0;
+    while i < css.len() {
+        match css[i] as char {
+            '"' | '\'' => i = skip_string(css, i),
+            '@' => {
+                let name_end = ident_end(css, i + 1);
+                let open = skip_whitespace(css, name_end);
+                if css.slice(i + 1, name_end).eq_ignore_ascii_case("font-face") &&
+                        open < css.len() && css[open] as char == '{' {
+                    let close = block_end(css, open);
+                    match parse_font_face(css.slice(open + 1, close), base_url) {
+                        Some(rule) => rules.push(rule),
+                        None => debug!("font_face: dropping an @font-face rule without a font"),
+                    }
+                    i = close;
+                } else {
+                    i = name_end;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    rules
+}
+
+/// Parses the declarations of an `@font-face` rule.
+fn parse_font_face(block: &str, base_url: &Url) -> Option<FontFaceRule> {
+    let mut family = None;
+    let mut weight = 400;
+    let mut italic = false;
+    let mut sources = ~[];
+
+    for split_top_level(block, ';').iter().advance |declaration| {
+        let colon = match declaration.find(':') {
+            None => loop,
+            Some(colon) => colon,
+        };
+        let value = declaration.slice_from(colon + 1).trim();
+        match declaration.slice_to(colon).trim().to_ascii_lower() {
+            ~"font-family" => family = parse_family_name(value),
+            ~"font-weight" => {
+                match parse_weight(value) {
+                    Some(value) => weight = value,
+                    None => {}
+                }
+            }
+            ~"font-style" => {
+                match value.to_ascii_lower() {
+                    ~"normal" => italic = false,
+                    ~"italic" | ~"oblique" => italic = true,
+                    _ => {}
+                }
+            }
+            ~"src" => sources = parse_sources(value, base_url),
+            _ => {}
+        }
+    }
+
+    let family = match family {
+        None => return None,
+        Some(family) => family,
+    };
+    if sources.is_empty() {
+        return None;
+    }
+    Some(FontFaceRule {
+        face: WebFontFace {
+            family: family,
+            weight: weight,
+            italic: italic,
+        },
+        sources: sources,
+    })
+}
+
+/// Parses a single family name: a string, or a sequence of identifiers.
+fn parse_family_name(value: &str) -> Option<~str> {
+    match unquote(value) {
+        Some(name) => return Some(name.to_owned()),
+        None => {}
+    }
+    let words: ~[&str] = value.word_iter().collect();
+    if words.is_empty() || value.contains_char(',') || value.contains_char('"') ||
+            value.contains_char('\'') {
+        return None;
+    }
+    Some(words.connect(" "))
+}
+
+/// Parses a `font-weight` descriptor into a weight from 100 to 900.
+fn parse_weight(value: &str) -> Option<uint> {
+    match value.to_ascii_lower() {
+        ~"normal" => Some(400),
+        ~"bold" => Some(700),
+        value => {
+            match uint::from_str(value.as_slice()) {
+                Some(weight) if weight >= 100 && weight <= 900 && weight % 100 == 0 => {
+                    Some(weight)
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Parses the comma-separated list of a `src` descriptor. Invalid entries are skipped.
+fn parse_sources(value: &str, base_url: &Url) -> ~[FontFaceSource] {
+    let mut sources = ~[];
+    for split_top_level(value, ',').iter().advance |source| {
+        let source = source.trim();
+        match function_call(source, "url") {
+            Some((argument, rest)) => {
+                let url = match unquote(argument) {
+                    Some(url) => url,
+                    None => argument,
+                };
+                let format = match function_call(rest, "format") {
+                    Some((format, _)) => unquote(format).map(|format| format.to_ascii_lower()),
+                    None => None,
+                };
+                sources.push(FontFaceSourceUrl(make_url(url.to_owned(), Some(copy *base_url)),
+                                               format));
+                loop
+            }
+            None => {}
+        }
+        match function_call(source, "local") {
+            Some((argument, _)) => {
+                match parse_family_name(argument) {
+                    Some(name) => sources.push(FontFaceSourceLocal(name)),
+                    None => {}
+                }
+            }
+            None => debug!("font_face: skipping the invalid source `%s`", source),
+        }
+    }
+    sources
+}
+
+/// If `value` starts with a call to the function `name`, returns the trimmed argument of the call
+/// and the trimmed text after it.
+fn function_call<'a>(value: &'a str, name: &str) -> Option<(&'a str, &'a str)> {
+    let open = name.len();
+    if value.len() <= open || !value.slice_to(open).eq_ignore_ascii_case(name) ||
+            value[open] as char != '(' {
+        return None;
+    }
+    let mut i = open + 1;
+    while i < value.len() {
+        match value[i] as char {
+            '"' | '\'' => i = skip_string(value, i),
+            ')' => return Some((value.slice(open + 1, i).trim(), value.slice_from(i + 1).trim())),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Returns the contents of a quoted string, or None if `value` is not one.
+fn unquote<'a>(value: &'a str) -> Option<&'a str> {
+    if value.len() < 2 {
+        return None;
+    }
+    let quote = value[0] as char;
+    if (quote != '"' && quote != '\'') || value[value.len() - 1] as char != quote {
+        return None;
+    }
+    Some(value.slice(1, value.len() - 1))
+}
+
+/// Splits `css` at each `separator` that is outside strings, parentheses and blocks.
+fn split_top_level<'a>(css: &'a str, separator: char) -> ~[&'a str] {
+    let mut parts = ~[];
+    let mut depth = 0;
+    let mut start = 0;
+    let mut i = 0;
+    while i < css.len() {
+        match css[i] as char {
+            '"' | '\'' => {
+                i = skip_string(css, i);
+                loop
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth > 0 => depth -= 1,
+            c if c == separator && depth == 0 => {
+                parts.push(css.slice(start, i));
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    parts.push(css.slice_from(start));
+    parts
+}
+
+/// Removes the comments of a style sheet, leaving a space in place of each.
+fn strip_comments(css: &str) -> ~str {
+    let mut result = ~"";
+    let mut start = 0;
+    let mut i = 0;
+    while i < css.len() {
+        match css[i] as char {
+            '"' | '\'' => i = skip_string(css, i),
+            '/' if i + 1 < css.len() && css[i + 1] as char == '*' => {
+                result.push_str(css.slice(start, i));
+                result.push_char(' ');
+                i = match css.slice_from(i + 2).find_str("*/") {
+                    Some(end) => i + 2 + end + 2,
+                    None => css.len(),
+                };
+                start = i;
+            }
+            _ => i += 1,
+        }
+    }
+    result.push_str(css.slice_from(start));
+    result
+}
+
+/// Returns the index just past the string that starts at `start`. An unterminated string ends at
+/// the end of its line.
+fn skip_string(css: &str, start: uint) -> uint {
+    let quote = css[start];
+    let mut i = start + 1;
+    while i < css.len() {
+        if css[i] == quote {
+            return i + 1;
+        }
+        match css[i] as char {
+            '\\' => i += 2,
+            '\n' => return i,
+            _ => i += 1,
+        }
+    }
+    css.len()
+}
+
+/// Returns the index just past the identifier that starts at `start`.
+fn ident_end(css: &str, start: uint) -> uint {
+    let mut i = start;
+    while i < css.len() && (css[i] >= 0x80 || (css[i] as char).is_alphanumeric() ||
+                            css[i] as char == '-' || css[i] as char == '_') {
+        i += 1;
+    }
+    i
+}
+
+fn skip_whitespace(css: &str, start: uint) -> uint {
+    let mut i = start;
+    while i < css.len() && (css[i] as char).is_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+/// Returns the index of the `}` that closes the block opened at `open`, or the end of the sheet if
+/// the block is never closed.
+fn block_end(css: &str, open: uint) -> uint {
+    let mut depth = 0;
+    let mut i = open;
+    while i < css.len() {
+        match css[i] as char {
+            '"' | '\'' => {
+                i = skip_string(css, i);
+                loop
+            }
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    css.len()
+}
+
+#[test]
+fn test_parse_font_face_rules() {
+    let base_url = make_url(~"http://example.com/style/main.css", None);
+    let css = "/* @font-face { font-family: Commented; src: local(Commented) } */
+               p { font-family: 'My Font'; }
+               @FONT-FACE {
+                   font-family: 'My Font';
+                   font-weight: bold;
+                   font-style: italic;
+                   src: local(My  Font Bold), url('fonts/my-font.ttf') format(\"TrueType\"),
+                        url(/my-font.woff) format('woff'), bogus(x);
+               }
+               @media print {
+                   @font-face { font-family: Other Font; src: url(\"/a;b.otf\") }
+               }
+               @font-face { font-family: No Source; }
+               @font-face { src: local(No Family) }";
+    let rules = parse_font_face_rules(css, &base_url);
+    assert!(rules.len() == 2);
+
+    let rule = &rules[0];
+    assert!(rule.face.family == ~"My Font");
+    assert!(rule.face.weight == 700);
+    assert!(rule.face.italic);
+    assert!(rule.sources.len() == 3);
+    match rule.sources[0] {
+        FontFaceSourceLocal(ref name) => assert!(*name == ~"My Font Bold"),
+        _ => fail!(),
+    }
+    match rule.sources[1] {
+        FontFaceSourceUrl(ref url, ref format) => {
+            assert!(url.host == ~"example.com");
+            assert!(url.path == ~"/style/fonts/my-font.ttf");
+            assert!(*format == Some(~"truetype"));
+        }
+        _ => fail!(),
+    }
+    match rule.sources[2] {
+        FontFaceSourceUrl(ref url, ref format) => {
+            assert!(url.path == ~"/my-font.woff");
+            assert!(*format == Some(~"woff"));
+        }
+        _ => fail!(),
+    }
+
+    let rule = &rules[1];
+    assert!(rule.face.family == ~"Other Font");
+    assert!(rule.face.weight == 400);
+    assert!(!rule.face.italic);
+    match rule.sources[0] {
+        FontFaceSourceUrl(_, ref format) => assert!(format.is_none()),
+        _ => fail!(),
+    }
+}
@@ -6,6 +6,7 @@ use dom::element::*;
 use dom::node::{AbstractNode, Comment, Doctype, Element, ElementNodeTypeId, Node, ScriptView};
 use dom::node::{Text};
 use html::cssparse::{InlineProvenance, StylesheetProvenance, UrlProvenance, spawn_css_parser};
+use html::font_face::FontFaceRule;
 use newcss::stylesheet::Stylesheet;
 
 use std::cast;
@@ -54,7 +55,7 @@ enum JSMessage {
 
 struct HtmlParserResult {
     root: AbstractNode<ScriptView>,
-    style_port: Port<Option<Stylesheet>>,
+    style_port: Port<Option<(Stylesheet, ~[FontFaceRule])>>,
     js_port: Port<JSResult>,
 }
 
@@ -87,7 +88,7 @@ spawned, collates them, and sends them to the given result channel.
 * `from_parent` - A port on which to receive new links.
 
 */
-fn css_link_listener(to_parent: Chan<Option<Stylesheet>>,
+fn css_link_listener(to_parent: Chan<Option<(Stylesheet, ~[FontFaceRule])>>,
                      from_parent: Port<CSSMessage>,
                      resource_task: ResourceTask) {
     let mut result_vec = ~[];
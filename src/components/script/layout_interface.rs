@@ -7,12 +7,14 @@
 /// from layout.
 
 use dom::node::{AbstractNode, ScriptView, LayoutView};
+use html::font_face::FontFaceRule;
 use script_task::{ScriptMsg, ScriptChan};
 
 use std::comm::{Chan, SharedChan};
 use geom::rect::Rect;
 use geom::size::Size2D;
 use geom::point::Point2D;
+use gfx::font_context::WebFontFace;
 use gfx::geometry::Au;
 use newcss::stylesheet::Stylesheet;
 use extra::net::url::Url;
@@ -21,8 +23,8 @@ use extra::net::url::Url;
 ///
 /// FIXME(pcwalton): I think this should probably be merged with `LayoutQuery` below.
 pub enum Msg {
-    /// Adds the given stylesheet to the document.
-    AddStylesheetMsg(Stylesheet),
+    /// Adds the given stylesheet to the document, along with its `@font-face` rules.
+    AddStylesheetMsg(Stylesheet, ~[FontFaceRule]),
 
     /// Requests a reflow.
     ReflowMsg(~Reflow),
@@ -35,6 +37,10 @@ pub enum Msg {
     /// Routes a message (usually from the compositor) to the appropriate script task
     RouteScriptMsg(ScriptMsg),
 
+    /// Registers the font downloaded from the given URL for an `@font-face` rule. Layout sends
+    /// this to itself when the download finishes.
+    WebFontLoadedMsg(WebFontFace, Url, ~[u8]),
+
     /// Requests that the layout task shut down and exit.
     ExitMsg,
 }
@@ -47,11 +53,18 @@ pub enum LayoutQuery {
     ContentBoxesQuery(AbstractNode<ScriptView>, Chan<Result<ContentBoxesResponse, ()>>),
     /// Requests the node containing the point of interest
     HitTestQuery(AbstractNode<ScriptView>, Point2D<f32>, Chan<Result<HitTestResponse, ()>>),
+    /// Requests the text node under the point of interest and the offset in its text of the
+    /// character boundary nearest the point, in UTF-16 code units, for placing a caret or a
+    /// selection.
+    TextOffsetQuery(AbstractNode<ScriptView>,
+                    Point2D<f32>,
+                    Chan<Result<TextOffsetResponse, ()>>),
 }
 
 pub struct ContentBoxResponse(Rect<Au>);
 pub struct ContentBoxesResponse(~[Rect<Au>]);
 pub struct HitTestResponse(AbstractNode<LayoutView>);
+pub struct TextOffsetResponse(AbstractNode<LayoutView>, uint);
 
 /// Determines which part of the 
 pub enum DocumentDamageLevel {
@@ -22,6 +22,7 @@ use layout_interface;
 use servo_msg::constellation_msg::{ConstellationChan, LoadUrlMsg, NavigationDirection};
 use servo_msg::constellation_msg::RendererReadyMsg;
 use servo_msg::constellation_msg;
+use servo_util::time::{HtmlParsingCategory, ProfilerChan, profile};
 
 use std::cast::transmute;
 use std::cell::Cell;
@@ -106,6 +107,8 @@ pub struct ScriptTask {
     image_cache_task: ImageCacheTask,
     /// A handle to the resource task.
     resource_task: ResourceTask,
+    /// A channel to the profiler.
+    profiler_chan: ProfilerChan,
 
     /// The port that we will use to join layout. If this is `None`, then layout is not currently
     /// running.
@@ -183,7 +186,8 @@ impl ScriptTask {
                script_chan: ScriptChan,
                constellation_chan: ConstellationChan,
                resource_task: ResourceTask,
-               img_cache_task: ImageCacheTask)
+               img_cache_task: ImageCacheTask,
+               profiler_chan: ProfilerChan)
                -> @mut ScriptTask {
         let js_runtime = js::rust::rt();
         let js_context = js_runtime.cx();
@@ -203,6 +207,7 @@ impl ScriptTask {
             layout_chan: layout_chan,
             image_cache_task: img_cache_task,
             resource_task: resource_task,
+            profiler_chan: profiler_chan,
 
             layout_join_port: None,
             script_port: script_port,
@@ -253,7 +258,8 @@ impl ScriptTask {
                   script_chan: ScriptChan,
                   constellation_chan: ConstellationChan,
                   resource_task: ResourceTask,
-                  image_cache_task: ImageCacheTask) {
+                  image_cache_task: ImageCacheTask,
+                  profiler_chan: ProfilerChan) {
         let compositor = Cell::new(compositor);
         let script_port = Cell::new(script_port);
         // FIXME: rust#6399
@@ -267,7 +273,8 @@ impl ScriptTask {
                                               script_chan.clone(),
                                               constellation_chan.clone(),
                                               resource_task.clone(),
-                                              image_cache_task.clone());
+                                              image_cache_task.clone(),
+                                              profiler_chan.clone());
             script_task.start();
         }
     }
@@ -368,9 +375,11 @@ impl ScriptTask {
         // Parse HTML.
         //
         // Note: We can parse the next document in parallel with any previous documents.
-        let html_parsing_result = hubbub_html_parser::parse_html(url.clone(),
-                                                                 self.resource_task.clone(),
-                                                                 self.image_cache_task.clone());
+        let html_parsing_result = do profile(HtmlParsingCategory, self.profiler_chan.clone()) {
+            hubbub_html_parser::parse_html(url.clone(),
+                                           self.resource_task.clone(),
+                                           self.image_cache_task.clone())
+        };
 
         let root_node = html_parsing_result.root;
 
@@ -380,7 +389,9 @@ impl ScriptTask {
         // in the script task.
         loop {
               match html_parsing_result.style_port.recv() {
-                  Some(sheet) => self.layout_chan.send(AddStylesheetMsg(sheet)),
+                  Some((sheet, font_face_rules)) => {
+                      self.layout_chan.send(AddStylesheetMsg(sheet, font_face_rules))
+                  }
                   None => break,
               }
         }
@@ -3,6 +3,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use std::hashmap::HashMap;
+use std::util::replace;
 
 pub trait Cache<K: Eq, V: Clone> {
     fn insert(&mut self, key: K, value: V);
@@ -198,3 +199,338 @@ fn test_lru_cache() {
     assert!(cache.find(&3).is_none()); // (4, 1) (no change)
     assert!(cache.find(&4).is_some()); // (1, 4)
 }
+
+/// Counters describing how well a cache is doing.
+#[deriving(Clone, Eq)]
+pub struct CacheStats {
+    hits: uint,
+    misses: uint,
+    evictions: uint,
+}
+
+impl CacheStats {
+    pub fn new() -> CacheStats {
+        CacheStats {
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    /// The fraction of lookups that were hits, or 0 if there have been no lookups.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 { 0f64 } else { (self.hits as f64) / (lookups as f64) }
+    }
+}
+
+/// A weight function that counts every entry as 1, making the budget an entry count.
+pub fn unit_weight<K, V>(_key: &K, _value: &V) -> uint {
+    1
+}
+
+struct WeightedLRUNode<K, V> {
+    key: K,
+    value: V,
+    weight: uint,
+    /// The next more recently used entry.
+    prev: Option<uint>,
+    /// The next less recently used entry.
+    next: Option<uint>,
+}
+
+/// An LRU cache bounded by the total weight of its entries (typically their size in bytes), as
+/// measured by a weight function. Lookups, insertions and evictions are all O(1). Entries are
+/// kept in a doubly-linked recency list threaded through a vector of nodes, with a hash map from
+/// keys to node indices.
+pub struct WeightedLRUCache<K, V> {
+    priv index: HashMap<K, uint>,
+    priv nodes: ~[Option<WeightedLRUNode<K, V>>],
+    priv free_nodes: ~[uint],
+    /// The most recently used entry.
+    priv head: Option<uint>,
+    /// The least recently used entry; the next to be evicted.
+    priv tail: Option<uint>,
+    priv weigher: extern fn(&K, &V) -> uint,
+    priv weight: uint,
+    priv budget: uint,
+    priv on_evict: Option<~fn(&K, &V)>,
+    priv stats: CacheStats,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> WeightedLRUCache<K,V> {
+    pub fn new(budget: uint, weigher: extern fn(&K, &V) -> uint) -> WeightedLRUCache<K,V> {
+        WeightedLRUCache {
+            index: HashMap::new(),
+            nodes: ~[],
+            free_nodes: ~[],
+            head: None,
+            tail: None,
+            weigher: weigher,
+            weight: 0,
+            budget: budget,
+            on_evict: None,
+            stats: CacheStats::new(),
+        }
+    }
+
+    /// Sets a function to be called with every entry that is evicted to stay within budget.
+    pub fn set_eviction_callback(&mut self, callback: ~fn(&K, &V)) {
+        self.on_evict = Some(callback);
+    }
+
+    /// The total weight of all entries currently in the cache.
+    pub fn weight(&self) -> uint {
+        self.weight
+    }
+
+    pub fn budget(&self) -> uint {
+        self.budget
+    }
+
+    /// Changes the budget, evicting entries if the cache is now over it.
+    pub fn set_budget(&mut self, budget: uint) {
+        self.budget = budget;
+        self.evict_to_budget();
+    }
+
+    pub fn len(&self) -> uint {
+        self.index.len()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::new();
+    }
+
+    /// Removes an entry without counting it as an eviction, returning its value.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        match self.index.pop(key) {
+            None => None,
+            Some(idx) => {
+                self.unlink(idx);
+                let node = self.take_node(idx);
+                Some(node.value)
+            }
+        }
+    }
+
+    priv fn node<'a>(&'a mut self, idx: uint) -> &'a mut WeightedLRUNode<K,V> {
+        match self.nodes[idx] {
+            Some(ref mut node) => node,
+            None => fail!("dangling cache node index"),
+        }
+    }
+
+    /// Removes the node at `idx` from the recency list, leaving it in place.
+    priv fn unlink(&mut self, idx: uint) {
+        let (prev, next) = {
+            let node = self.node(idx);
+            let links = (node.prev, node.next);
+            node.prev = None;
+            node.next = None;
+            links
+        };
+        match prev {
+            Some(prev) => self.node(prev).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.node(next).prev = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    /// Makes the node at `idx`, which must be unlinked, the most recently used.
+    priv fn push_front(&mut self, idx: uint) {
+        let old_head = self.head;
+        {
+            let node = self.node(idx);
+            node.prev = None;
+            node.next = old_head;
+        }
+        match old_head {
+            Some(old_head) => self.node(old_head).prev = Some(idx),
+            None => self.tail = Some(idx),
+        }
+        self.head = Some(idx);
+    }
+
+    /// Frees the node at `idx`, which must be unlinked and unindexed, and returns it.
+    priv fn take_node(&mut self, idx: uint) -> WeightedLRUNode<K,V> {
+        let node = replace(&mut self.nodes[idx], None).unwrap();
+        self.free_nodes.push(idx);
+        self.weight -= node.weight;
+        node
+    }
+
+    priv fn evict_to_budget(&mut self) {
+        while self.weight > self.budget {
+            let idx = match self.tail {
+                Some(idx) => idx,
+                None => break,
+            };
+            self.unlink(idx);
+            let node = self.take_node(idx);
+            self.index.remove(&node.key);
+            self.stats.evictions += 1;
+            match self.on_evict {
+                Some(ref callback) => (*callback)(&node.key, &node.value),
+                None => {}
+            }
+        }
+    }
+
+    priv fn touch(&mut self, idx: uint) -> V {
+        if self.head != Some(idx) {
+            self.unlink(idx);
+            self.push_front(idx);
+        }
+        self.node(idx).value.clone()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Cache<K,V> for WeightedLRUCache<K,V> {
+    fn insert(&mut self, key: K, value: V) {
+        self.remove(&key);
+
+        // An entry that could never fit is not worth evicting everything else for.
+        let weight = (self.weigher)(&key, &value);
+        if weight > self.budget {
+            return;
+        }
+
+        let node = WeightedLRUNode {
+            key: key.clone(),
+            value: value,
+            weight: weight,
+            prev: None,
+            next: None,
+        };
+        let idx = if self.free_nodes.is_empty() {
+            self.nodes.push(Some(node));
+            self.nodes.len() - 1
+        } else {
+            let idx = self.free_nodes.pop();
+            self.nodes[idx] = Some(node);
+            idx
+        };
+        self.index.insert(key, idx);
+        self.weight += weight;
+        self.push_front(idx);
+        self.evict_to_budget();
+    }
+
+    fn find(&mut self, key: &K) -> Option<V> {
+        match self.index.find_copy(key) {
+            Some(idx) => {
+                self.stats.hits += 1;
+                Some(self.touch(idx))
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn find_or_create(&mut self, key: &K, blk: &fn(&K) -> V) -> V {
+        match self.find(key) {
+            Some(value) => value,
+            None => {
+                let value = blk(key);
+                self.insert(key.clone(), value.clone());
+                value
+            }
+        }
+    }
+
+    fn evict_all(&mut self) {
+        self.index.clear();
+        self.nodes.clear();
+        self.free_nodes.clear();
+        self.head = None;
+        self.tail = None;
+        self.weight = 0;
+    }
+}
+
+#[cfg(test)]
+fn str_weight(_key: &uint, value: &@str) -> uint {
+    value.len()
+}
+
+#[test]
+fn test_weighted_lru_cache() {
+    let one = @"one";
+    let two = @"two";
+    let three = @"three";
+
+    // Budget for exactly "one" and "two".
+    let mut cache = WeightedLRUCache::new(6, str_weight);
+    cache.insert(1, one);                   // (1)
+    cache.insert(2, two);                   // (1, 2)
+    assert!(cache.weight() == 6);
+
+    // Touch 1 so that 2 is evicted to make room for "three".
+    assert!(cache.find(&1).is_some());      // (2, 1)
+    cache.insert(3, three);                 // (3), since "three" and "one" don't fit together
+    assert!(cache.find(&1).is_none());
+    assert!(cache.find(&2).is_none());
+    assert!(cache.find(&3).is_some());
+    assert!(cache.weight() == 5);
+    assert!(cache.len() == 1);
+
+    // Entries heavier than the whole budget aren't cached at all.
+    cache.insert(4, @"seventeen");
+    assert!(cache.find(&4).is_none());
+    assert!(cache.find(&3).is_some());
+
+    let stats = cache.stats();
+    assert!(stats.hits == 3);
+    assert!(stats.misses == 3);
+    assert!(stats.evictions == 2);
+}
+
+#[test]
+fn test_weighted_lru_cache_unit_weight() {
+    let mut cache = WeightedLRUCache::new(2, unit_weight);
+    cache.insert(1, @"one");    // (1)
+    cache.insert(2, @"two");    // (1, 2)
+    cache.insert(1, @"uno");    // (2, 1), replacing the old value
+    cache.insert(3, @"three");  // (1, 3)
+
+    assert!(cache.find(&2).is_none());
+    assert!(cache.find(&1) == Some(@"uno"));
+    assert!(cache.find(&3).is_some());
+    assert!(cache.stats().evictions == 1);
+
+    do cache.find_or_create(&4) |_| { @"four" };  // (3, 4)
+    assert!(cache.find(&1).is_none());
+    assert!(cache.len() == 2);
+
+    cache.evict_all();
+    assert!(cache.len() == 0);
+    assert!(cache.weight() == 0);
+    assert!(cache.find(&3).is_none());
+}
+
+#[test]
+fn test_weighted_lru_cache_eviction_callback() {
+    let (port, chan) = std::comm::stream();
+    let mut cache = WeightedLRUCache::new(1, unit_weight);
+    do cache.set_eviction_callback |key: &uint, _value: &uint| {
+        chan.send(*key);
+    }
+    cache.insert(1, 10);
+    cache.insert(2, 20);
+    assert!(port.recv() == 1);
+
+    // Explicit removal is not an eviction.
+    assert!(cache.remove(&2) == Some(20));
+    assert!(cache.stats().evictions == 1);
+    assert!(!port.peek());
+}
@@ -6,23 +6,64 @@
 use extra::time::precise_time_ns;
 use std::cell::Cell;
 use std::comm::{Port, SharedChan};
+use std::io;
+use std::io::WriterUtil;
+use std::vec;
 use extra::sort::tim_sort;
 
 // front-end representation of the profiler used to communicate with the profiler
 #[deriving(Clone)]
 pub struct ProfilerChan {
     chan: SharedChan<ProfilerMsg>,
+    /// The name of the task this channel reports from, used to label trace events.
+    task_name: ~str,
+    /// The pipeline this channel reports on behalf of, if any.
+    pipeline_id: Option<uint>,
+    /// Whether the profiler is writing a trace, and so wants spans rather than bare times.
+    tracing: bool,
 }
 
 impl ProfilerChan {
-    pub fn new(chan: Chan<ProfilerMsg>) -> ProfilerChan {
+    pub fn new(chan: Chan<ProfilerMsg>, tracing: bool) -> ProfilerChan {
         ProfilerChan {
             chan: SharedChan::new(chan),
+            task_name: ~"Main",
+            pipeline_id: None,
+            tracing: tracing,
         }
     }
+
+    /// Returns a clone of this channel whose spans are attributed to the given task and
+    /// pipeline. Tasks should call this once when they start up.
+    pub fn for_task(&self, task_name: ~str, pipeline_id: Option<uint>) -> ProfilerChan {
+        ProfilerChan {
+            chan: self.chan.clone(),
+            task_name: task_name,
+            pipeline_id: pipeline_id,
+            tracing: self.tracing,
+        }
+    }
+
     pub fn send(&self, msg: ProfilerMsg) {
         self.chan.send(msg);
     }
+
+    /// Reports a span that was timed by hand, for work that doesn't fit in a single `profile()`
+    /// callback (for example, a load that completes asynchronously). Unless the profiler is
+    /// tracing, only the duration of the span is sent.
+    pub fn send_span(&self, category: ProfilerCategory, start_ns: u64, end_ns: u64) {
+        if !self.tracing {
+            self.send(TimeMsg(category, (end_ns - start_ns) as f64 / 1000000f64));
+            return
+        }
+        self.send(SpanMsg(ProfilerSpan {
+            category: category,
+            start_ns: start_ns,
+            end_ns: end_ns,
+            task_name: copy self.task_name,
+            pipeline_id: self.pipeline_id,
+        }));
+    }
 }
 
 #[deriving(Eq)]
@@ -35,29 +76,142 @@ pub enum ProfilerCategory {
     LayoutTreeBuilderCategory,
     LayoutMainCategory,
     LayoutShapingCategory,
+    LayoutShapeCacheCategory,
     LayoutDispListBuildCategory,
     GfxRegenAvailableFontsCategory,
     RenderingDrawingCategory,
     RenderingPrepBuffCategory,
     RenderingCategory,
+    HtmlParsingCategory,
+    ResourceLoadingCategory,
+    ImageDecodingCategory,
     // hackish but helps prevent errors when adding new categories
     NUM_BUCKETS,
 }
 // FIXME(#5873) this should be initialized by a NUM_BUCKETS cast,
-static BUCKETS: uint = 13;
+static BUCKETS: uint = 17;
+
+/// A single timed `profile()` call, as recorded for trace output.
+pub struct ProfilerSpan {
+    category: ProfilerCategory,
+    start_ns: u64,
+    end_ns: u64,
+    task_name: ~str,
+    pipeline_id: Option<uint>,
+}
+
+/// Summary statistics over all of the times recorded for one category, in milliseconds.
+pub struct ProfilerStats {
+    count: uint,
+    total: f64,
+    mean: f64,
+    min: f64,
+    p50: f64,
+    p90: f64,
+    p99: f64,
+    max: f64,
+}
+
+impl ProfilerStats {
+    /// Computes statistics over `data`, which must already be sorted. Returns `None` if there is
+    /// no data.
+    pub fn from_sorted(data: &[f64]) -> Option<ProfilerStats> {
+        if data.is_empty() {
+            return None;
+        }
+        let total = data.iter().fold(0f64, |a, b| a + *b);
+        Some(ProfilerStats {
+            count: data.len(),
+            total: total,
+            mean: total / (data.len() as f64),
+            min: data[0],
+            p50: percentile(data, 50),
+            p90: percentile(data, 90),
+            p99: percentile(data, 99),
+            max: data[data.len() - 1],
+        })
+    }
+}
+
+/// Returns the `pct`th percentile of the sorted, non-empty `data`, using the nearest-rank method.
+pub fn percentile(data: &[f64], pct: uint) -> f64 {
+    assert!(!data.is_empty());
+    let rank = (pct * data.len() + 99) / 100;
+    data[if rank == 0 { 0 } else { rank - 1 }]
+}
+
+/// Escapes a string for inclusion in a JSON string literal.
+fn json_escape(s: &str) -> ~str {
+    let mut result = ~"";
+    for s.iter().advance |c| {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            c if (c as uint) < 0x20 => result.push_str(fmt!("\\u%04x", c as uint)),
+            c => result.push_char(c),
+        }
+    }
+    result
+}
+
+/// Returns the fraction of cache lookups that hit, given at least one lookup.
+fn hit_rate(hits: uint, misses: uint) -> f64 {
+    (hits as f64) / ((hits + misses) as f64)
+}
+
+impl ProfilerSpan {
+    pub fn duration_ms(&self) -> f64 {
+        (self.end_ns - self.start_ns) as f64 / 1000000f64
+    }
+}
 
 pub enum ProfilerMsg {
     // Normal message used for reporting time
     TimeMsg(ProfilerCategory, f64),
+    // Message used for reporting a timed span along with where and when it ran
+    SpanMsg(ProfilerSpan),
+    // Message used for reporting whether a cache lookup hit
+    CacheLookupMsg(ProfilerCategory, bool),
     // Message used to force print the profiling metrics
     PrintMsg,
+    // Message sent on shutdown so that any output files can be written
+    ExitMsg(Chan<()>),
+}
+
+/// The statistics and the cache hits and misses of one category, as reported on exit.
+type ReportRow = (ProfilerCategory, Option<ProfilerStats>, Option<(uint, uint)>);
+
+/// The formats that `Profiler` can write its end-of-run report in.
+pub enum ReportFormat {
+    JsonReport,
+    CsvReport,
+}
+
+impl ReportFormat {
+    /// Picks a report format from the extension of the output file name, defaulting to JSON.
+    pub fn from_file_name(name: &str) -> ReportFormat {
+        if name.ends_with(".csv") { CsvReport } else { JsonReport }
+    }
 }
 
 // back end of the profiler that handles data aggregation and performance metrics
 pub struct Profiler {
     port: Port<ProfilerMsg>,
     buckets: ~[(ProfilerCategory, ~[f64])],
+    /// The number of cache hits and misses reported for each category.
+    cache_lookups: ~[(uint, uint)],
     last_msg: Option<ProfilerMsg>,
+    /// The file to write Chrome trace events to on exit, if tracing is enabled.
+    trace_file: Option<~str>,
+    /// Every span received so far. Only populated when tracing is enabled.
+    spans: ~[ProfilerSpan],
+    /// The time the profiler was started; trace timestamps are relative to this.
+    start_ns: u64,
+    /// The file to write per-category statistics to on exit, if any.
+    report_file: Option<~str>,
+    /// A label, typically the URL that was loaded, to tag the report with.
+    report_tag: Option<~str>,
 }
 
 impl ProfilerCategory {
@@ -79,11 +233,15 @@ impl ProfilerCategory {
         vec.push((LayoutTreeBuilderCategory, ~[]));
         vec.push((LayoutMainCategory, ~[]));
         vec.push((LayoutShapingCategory, ~[]));
+        vec.push((LayoutShapeCacheCategory, ~[]));
         vec.push((LayoutDispListBuildCategory, ~[]));
         vec.push((GfxRegenAvailableFontsCategory, ~[]));
         vec.push((RenderingDrawingCategory, ~[]));
         vec.push((RenderingPrepBuffCategory, ~[]));
         vec.push((RenderingCategory, ~[]));
+        vec.push((HtmlParsingCategory, ~[]));
+        vec.push((ResourceLoadingCategory, ~[]));
+        vec.push((ImageDecodingCategory, ~[]));
 
         ProfilerCategory::check_order(vec);
         vec
@@ -102,27 +260,63 @@ impl ProfilerCategory {
     pub fn format(self) -> ~str {
         let padding = match self {
             LayoutAuxInitCategory | LayoutSelectorMatchCategory | LayoutTreeBuilderCategory |
-            LayoutMainCategory | LayoutDispListBuildCategory | LayoutShapingCategory=> " - ",
+            LayoutMainCategory | LayoutDispListBuildCategory | LayoutShapingCategory |
+            LayoutShapeCacheCategory => " - ",
             _ => ""
         };
         fmt!("%s%?", padding, self)
     }
+
+    // the coarse subsystem a category belongs to, used as the trace event category
+    pub fn group(self) -> &'static str {
+        match self {
+            CompositingCategory => "compositing",
+            LayoutQueryCategory | LayoutPerformCategory | LayoutAuxInitCategory |
+            LayoutSelectorMatchCategory | LayoutTreeBuilderCategory | LayoutMainCategory |
+            LayoutShapingCategory | LayoutShapeCacheCategory |
+            LayoutDispListBuildCategory => "layout",
+            GfxRegenAvailableFontsCategory => "gfx",
+            RenderingDrawingCategory | RenderingPrepBuffCategory | RenderingCategory => "rendering",
+            HtmlParsingCategory => "script",
+            ResourceLoadingCategory | ImageDecodingCategory => "net",
+            NUM_BUCKETS => fail!("NUM_BUCKETS is not a real category"),
+        }
+    }
 }
 
 impl Profiler {
-    pub fn create(port: Port<ProfilerMsg>) {
+    pub fn create(port: Port<ProfilerMsg>,
+                  trace_file: Option<~str>,
+                  report_file: Option<~str>,
+                  report_tag: Option<~str>) {
         let port = Cell::new(port);
+        let trace_file = Cell::new(trace_file);
+        let report_file = Cell::new(report_file);
+        let report_tag = Cell::new(report_tag);
         do spawn {
-            let mut profiler = Profiler::new(port.take());
+            let mut profiler = Profiler::new(port.take(),
+                                             trace_file.take(),
+                                             report_file.take(),
+                                             report_tag.take());
             profiler.start();
         }
     }
 
-    pub fn new(port: Port<ProfilerMsg>) -> Profiler {
+    pub fn new(port: Port<ProfilerMsg>,
+               trace_file: Option<~str>,
+               report_file: Option<~str>,
+               report_tag: Option<~str>)
+               -> Profiler {
         Profiler {
             port: port,
             buckets: ProfilerCategory::empty_buckets(),
+            cache_lookups: vec::from_elem(ProfilerCategory::num_buckets(), (0u, 0u)),
             last_msg: None,
+            trace_file: trace_file,
+            spans: ~[],
+            start_ns: precise_time_ns(),
+            report_file: report_file,
+            report_tag: report_tag,
         }
     }
 
@@ -135,47 +329,244 @@ impl Profiler {
 
     priv fn handle_msg(&mut self, msg: ProfilerMsg) {
         match msg {
-            TimeMsg(category, t) => match self.buckets[category as uint] {
-                // FIXME(#3874): this should be a let (cat, ref mut bucket) = ...,
-                // not a match
-                (_, ref mut data) => {
-                    data.push(t);
+            TimeMsg(category, t) => self.record_time(category, t),
+            SpanMsg(span) => {
+                let (category, t) = (span.category, span.duration_ms());
+                self.record_time(category, t);
+                if self.trace_file.is_some() {
+                    self.spans.push(span);
                 }
-            },
+                // The span itself has been moved out; record it as a plain time message so
+                // that `PrintMsg` knows there is new data to print.
+                self.last_msg = Some(TimeMsg(category, t));
+                return;
+            }
+            CacheLookupMsg(category, hit) => {
+                let (hits, misses) = self.cache_lookups[category as uint];
+                self.cache_lookups[category as uint] = if hit {
+                    (hits + 1, misses)
+                } else {
+                    (hits, misses + 1)
+                };
+                // Lookups carry no timings, so they must not hide the last time message from
+                // `PrintMsg`.
+                return;
+            }
             PrintMsg => match self.last_msg {
                 Some(TimeMsg(*)) => self.print_buckets(),
                 _ => {}
             },
+            ExitMsg(ref response_chan) => {
+                self.write_trace();
+                self.write_report();
+                response_chan.send(());
+            }
         };
         self.last_msg = Some(msg);
     }
 
-    priv fn print_buckets(&mut self) {
-        println(fmt!("%31s %15s %15s %15s %15s %15s",
-                         "_category_", "_mean (ms)_", "_median (ms)_",
-                         "_min (ms)_", "_max (ms)_", "_bucket size_"));
+    priv fn record_time(&mut self, category: ProfilerCategory, t: f64) {
+        match self.buckets[category as uint] {
+            // FIXME(#3874): this should be a let (cat, ref mut bucket) = ...,
+            // not a match
+            (_, ref mut data) => {
+                data.push(t);
+            }
+        }
+    }
+
+    /// Writes every recorded span to the trace file in the Chrome trace-event JSON format,
+    /// which can be loaded into `chrome://tracing` or any compatible viewer.
+    priv fn write_trace(&mut self) {
+        let path = match self.trace_file {
+            None => return,
+            Some(ref path) => copy *path,
+        };
+        let writer = match io::file_writer(&Path(path), [io::Create, io::Truncate]) {
+            Ok(writer) => writer,
+            Err(e) => {
+                error!("profiler: couldn't open trace file %s: %s", path, e);
+                return;
+            }
+        };
+
+        // Each distinct task/pipeline pair becomes a thread in the trace viewer.
+        let mut threads: ~[(~str, Option<uint>)] = ~[];
+        let mut events: ~[~str] = ~[];
+        for self.spans.iter().advance |span| {
+            let thread = (copy span.task_name, span.pipeline_id);
+            let tid = match threads.position(|t| *t == thread) {
+                Some(tid) => tid,
+                None => {
+                    threads.push(thread);
+                    threads.len() - 1
+                }
+            };
+            let pipeline = match span.pipeline_id {
+                Some(id) => fmt!("%u", id),
+                None => ~"null",
+            };
+            events.push(fmt!("{\"name\":\"%?\",\"cat\":\"%s\",\"ph\":\"X\",\"ts\":%.3f,\
+                              \"dur\":%.3f,\"pid\":0,\"tid\":%u,\"args\":{\"pipeline\":%s}}",
+                             span.category, span.category.group(),
+                             self.trace_offset_ns(span.start_ns) as f64 / 1000f64,
+                             (span.end_ns - span.start_ns) as f64 / 1000f64,
+                             tid, pipeline));
+        }
+        for threads.iter().enumerate().advance |(tid, &(ref name, pipeline_id))| {
+            let name = match pipeline_id {
+                Some(id) => fmt!("%s (pipeline %u)", *name, id),
+                None => copy *name,
+            };
+            events.push(fmt!("{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":%u,\
+                              \"args\":{\"name\":\"%s\"}}", tid, json_escape(name)));
+        }
+
+        writer.write_str("{\"traceEvents\":[\n");
+        writer.write_str(events.connect(",\n"));
+        writer.write_str("\n]}\n");
+
+        // Only write the trace once, even if we are asked to exit again.
+        self.trace_file = None;
+        self.spans = ~[];
+    }
+
+    /// Returns the time of the given timestamp since the profiler started, or zero if it was
+    /// taken before then, as spans timed by hand can be.
+    priv fn trace_offset_ns(&self, timestamp_ns: u64) -> u64 {
+        if timestamp_ns > self.start_ns { timestamp_ns - self.start_ns } else { 0 }
+    }
+
+    /// Writes per-category statistics to the report file, in the format implied by its name.
+    priv fn write_report(&mut self) {
+        let path = match self.report_file {
+            None => return,
+            Some(ref path) => copy *path,
+        };
+        let writer = match io::file_writer(&Path(copy path), [io::Create, io::Truncate]) {
+            Ok(writer) => writer,
+            Err(e) => {
+                error!("profiler: couldn't open report file %s: %s", path, e);
+                return;
+            }
+        };
+
+        let rows = self.compute_report_rows();
+        match ReportFormat::from_file_name(path) {
+            JsonReport => {
+                let tag = match self.report_tag {
+                    Some(ref tag) => fmt!("\"%s\"", json_escape(*tag)),
+                    None => ~"null",
+                };
+                let categories = do rows.map |&(category, ref stats, ref lookups)| {
+                    let mut fields = fmt!("\"category\":\"%?\"", category);
+                    for stats.iter().advance |stats| {
+                        fields.push_str(fmt!(",\"count\":%u,\"total\":%f,\"mean\":%f,\"min\":%f,\
+                                              \"p50\":%f,\"p90\":%f,\"p99\":%f,\"max\":%f",
+                                             stats.count, stats.total, stats.mean, stats.min,
+                                             stats.p50, stats.p90, stats.p99, stats.max));
+                    }
+                    for lookups.iter().advance |&(hits, misses)| {
+                        fields.push_str(fmt!(",\"cache_hits\":%u,\"cache_misses\":%u,\
+                                              \"cache_hit_rate\":%f",
+                                             hits, misses, hit_rate(hits, misses)));
+                    }
+                    fmt!("    {%s}", fields)
+                };
+                writer.write_str(fmt!("{\"url\":%s,\"units\":\"ms\",\"categories\":[\n", tag));
+                writer.write_str(categories.connect(",\n"));
+                writer.write_str("\n]}\n");
+            }
+            CsvReport => {
+                let tag = match self.report_tag {
+                    Some(ref tag) => fmt!("\"%s\"", tag.replace("\"", "\"\"")),
+                    None => ~"",
+                };
+                writer.write_line("url,category,count,total_ms,mean_ms,min_ms,p50_ms,p90_ms,\
+                                   p99_ms,max_ms,cache_hits,cache_misses,cache_hit_rate");
+                for rows.iter().advance |&(category, ref stats, ref lookups)| {
+                    // Categories without timings or without lookups leave those columns empty.
+                    let times = match *stats {
+                        Some(ref stats) => fmt!("%u,%f,%f,%f,%f,%f,%f,%f",
+                                                stats.count, stats.total, stats.mean, stats.min,
+                                                stats.p50, stats.p90, stats.p99, stats.max),
+                        None => ~",,,,,,,",
+                    };
+                    let lookups = match *lookups {
+                        Some((hits, misses)) => fmt!("%u,%u,%f",
+                                                     hits, misses, hit_rate(hits, misses)),
+                        None => ~",,",
+                    };
+                    writer.write_line(fmt!("%s,%?,%s,%s", tag, category, times, lookups));
+                }
+            }
+        }
+
+        self.report_file = None;
+    }
+
+    /// Sorts each bucket and computes its statistics, skipping empty buckets.
+    priv fn compute_stats(&mut self) -> ~[(ProfilerCategory, ProfilerStats)] {
+        let mut result = ~[];
         for self.buckets.mut_iter().advance |bucket| {
             match *bucket {
                 (category, ref mut data) => {
                     tim_sort(*data);
-                    let data_len = data.len();
-                    if data_len > 0 {
-                        let (mean, median, min, max) =
-                            (data.foldl(0f64, |a, b| a + *b) / (data_len as f64),
-                             data[data_len / 2],
-                             data.iter().min(),
-                             data.iter().max());
-                        println(fmt!("%-30s: %15.4? %15.4? %15.4? %15.4? %15u",
-                                     category.format(), mean, median, min, max, data_len));
+                    match ProfilerStats::from_sorted(*data) {
+                        Some(stats) => result.push((category, stats)),
+                        None => {}
                     }
                 }
             }
         }
+        result
+    }
+
+    /// Pairs the statistics of each category with its cache hits and misses, skipping
+    /// categories that have neither.
+    priv fn compute_report_rows(&mut self) -> ~[ReportRow] {
+        let mut stats = self.compute_stats();
+        let mut rows = ~[];
+        for self.buckets.iter().enumerate().advance |(i, &(category, _))| {
+            let category_stats = match stats.iter().position(|&(c, _)| c as uint == i) {
+                Some(index) => {
+                    let (_, category_stats) = stats.remove(index);
+                    Some(category_stats)
+                }
+                None => None,
+            };
+            let lookups = match self.cache_lookups[i] {
+                (0, 0) => None,
+                lookups => Some(lookups),
+            };
+            if category_stats.is_some() || lookups.is_some() {
+                rows.push((category, category_stats, lookups));
+            }
+        }
+        rows
+    }
+
+    priv fn print_buckets(&mut self) {
+        println(fmt!("%31s %15s %15s %15s %15s %15s %15s %15s",
+                         "_category_", "_mean (ms)_", "_median (ms)_", "_90% (ms)_",
+                         "_99% (ms)_", "_min (ms)_", "_max (ms)_", "_bucket size_"));
+        for self.compute_stats().iter().advance |&(category, ref stats)| {
+            println(fmt!("%-30s: %15.4f %15.4f %15.4f %15.4f %15.4f %15.4f %15u",
+                         category.format(), stats.mean, stats.p50, stats.p90, stats.p99,
+                         stats.min, stats.max, stats.count));
+        }
+        for self.cache_lookups.iter().enumerate().advance |(i, &(hits, misses))| {
+            if hits + misses > 0 {
+                let (category, _) = self.buckets[i];
+                println(fmt!("%-30s: %14.2f%% hit rate (%u of %u lookups)",
+                             category.format(), 100f64 * hit_rate(hits, misses),
+                             hits, hits + misses));
+            }
+        }
         println("");
     }
 }
 
-
 pub fn profile<T>(category: ProfilerCategory, 
                   profiler_chan: ProfilerChan,
                   callback: &fn() -> T)
@@ -183,8 +574,7 @@ pub fn profile<T>(category: ProfilerCategory,
     let start_time = precise_time_ns();
     let val = callback();
     let end_time = precise_time_ns();
-    let ms = ((end_time - start_time) as f64 / 1000000f64);
-    profiler_chan.send(TimeMsg(category, ms));
+    profiler_chan.send_span(category, start_time, end_time);
     return val;
 }
 
@@ -200,3 +590,26 @@ pub fn time<T>(msg: &str, callback: &fn() -> T) -> T{
 }
 
 
+
+#[test]
+fn test_percentile() {
+    let data = [1f64, 2f64, 3f64, 4f64, 5f64, 6f64, 7f64, 8f64, 9f64, 10f64];
+    assert!(percentile(data, 50) == 5f64);
+    assert!(percentile(data, 90) == 9f64);
+    assert!(percentile(data, 99) == 10f64);
+    assert!(percentile(data, 100) == 10f64);
+    assert!(percentile([42f64], 50) == 42f64);
+}
+
+#[test]
+fn test_profiler_stats() {
+    assert!(ProfilerStats::from_sorted([]).is_none());
+
+    let stats = ProfilerStats::from_sorted([1f64, 2f64, 3f64, 10f64]).unwrap();
+    assert!(stats.count == 4);
+    assert!(stats.total == 16f64);
+    assert!(stats.mean == 4f64);
+    assert!(stats.min == 1f64);
+    assert!(stats.p50 == 2f64);
+    assert!(stats.max == 10f64);
+}
@@ -4,6 +4,13 @@
 
 //! Helper functions for garbage collected doubly-linked trees.
 
+use extra::arc::{ARC, MutexARC};
+use std::cell::Cell;
+use std::comm::{SharedChan, stream};
+use std::task;
+use std::uint;
+use std::vec;
+
 /// The basic trait. This function is meant to encapsulate a clonable reference to a tree node.
 pub trait TreeNodeRef<N> : Clone {
     /// Borrows this node as immutable.
@@ -171,3 +178,490 @@ impl<NR:TreeNodeRef<N>,N:TreeNode<NR>> TreeUtils for NR {
     }
 }
 
+
+/// A tree node reference that can hand out a handle to its node that may be sent to the workers
+/// of a parallel traversal. The handle must be `Owned`, so only trees whose nodes are shared
+/// through ARCs can be traversed in parallel; managed trees such as the DOM and the flow tree
+/// cannot, and are traversed sequentially.
+pub trait SendableTreeNodeRef<H> {
+    fn to_sendable(&self) -> H;
+}
+
+/// An operation to be run on every node of a tree during a parallel traversal. A copy of the
+/// traversal is handed to each worker task, so any state it shares between nodes must be
+/// thread-safe.
+pub trait ParallelTraversal<H> : Owned + Clone {
+    fn process(&self, node: H);
+}
+
+/// A unit of work: the preorder index of a node.
+type WorkUnit = uint;
+
+/// The queues of one traversal.
+struct QueueState {
+    /// One deque per worker. Workers push and pop their own deque at the back and steal from the
+    /// front of everyone else's.
+    deques: ~[~[WorkUnit]],
+    /// The number of units that have been queued but not yet finished processing.
+    outstanding: uint,
+}
+
+impl QueueState {
+    /// Takes the most recently pushed unit from this worker's own deque, or steals the oldest unit
+    /// from another worker's.
+    fn pop_or_steal(&mut self, worker: uint) -> Option<WorkUnit> {
+        let n_workers = self.deques.len();
+        for uint::range(0, n_workers) |offset| {
+            let victim = (worker + offset) % n_workers;
+            if self.deques[victim].is_empty() {
+                loop
+            }
+            return Some(if victim == worker {
+                self.deques[victim].pop()
+            } else {
+                self.deques[victim].shift()
+            })
+        }
+        None
+    }
+}
+
+/// The queues of one traversal, shared by all of its workers under one lock. A worker that finds
+/// no work waits on the lock's condition until more is queued or every unit is finished.
+struct WorkQueues {
+    state: MutexARC<QueueState>,
+}
+
+impl Clone for WorkQueues {
+    fn clone(&self) -> WorkQueues {
+        WorkQueues {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl WorkQueues {
+    fn new(n_workers: uint) -> WorkQueues {
+        WorkQueues {
+            state: MutexARC(QueueState {
+                deques: do vec::from_fn(n_workers) |_| { ~[] },
+                outstanding: 0,
+            }),
+        }
+    }
+
+    /// Queues `units` on the given worker's deque and marks `n_finished` units as finished. Wakes
+    /// a waiting worker for each new unit, or every worker once nothing is outstanding so that
+    /// they can exit.
+    fn push_and_finish(&self, worker: uint, units: &[WorkUnit], n_finished: uint) {
+        unsafe {
+            do self.state.access_cond |state, cond| {
+                state.deques[worker].push_all(units);
+                state.outstanding = state.outstanding + units.len() - n_finished;
+                if state.outstanding == 0 {
+                    cond.broadcast();
+                } else {
+                    for units.len().times {
+                        cond.signal();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the next unit for the given worker, waiting until there is one, or None once every
+    /// unit is finished.
+    fn next_unit(&self, worker: uint) -> Option<WorkUnit> {
+        unsafe {
+            do self.state.access_cond |state, cond| {
+                let mut unit = None;
+                while unit.is_none() && state.outstanding > 0 {
+                    unit = state.pop_or_steal(worker);
+                    if unit.is_none() {
+                        cond.wait();
+                    }
+                }
+                unit
+            }
+        }
+    }
+}
+
+/// Runs `n_workers` tasks that pull units from `queues` until every queued unit is finished.
+/// `run_unit` processes a unit and returns the units that became ready as a result.
+fn run_workers<S: Owned + Clone>(queues: &WorkQueues,
+                                 n_workers: uint,
+                                 state: &S,
+                                 run_unit: extern fn(&S, WorkUnit) -> ~[WorkUnit]) {
+    let (done_port, done_chan) = stream();
+    let done_chan = SharedChan::new(done_chan);
+    for uint::range(0, n_workers) |worker| {
+        let queues = Cell::new(queues.clone());
+        let state = Cell::new(state.clone());
+        let done_chan = done_chan.clone();
+        do task::spawn {
+            let queues = queues.take();
+            let state = state.take();
+            loop {
+                match queues.next_unit(worker) {
+                    None => break,
+                    Some(unit) => {
+                        let ready = run_unit(&state, unit);
+                        queues.push_and_finish(worker, ready, 1);
+                    }
+                }
+            }
+            done_chan.send(());
+        }
+    }
+    for n_workers.times {
+        done_port.recv();
+    }
+}
+
+/// The shape of a tree, worked out before a traversal so that the workers never have to follow
+/// the references of the tree itself.
+struct TreeShape<H> {
+    /// The sendable handle of each node, by preorder index.
+    handles: ~[H],
+    /// The preorder index of each node's parent.
+    parents: ~[Option<uint>],
+    /// The preorder indices of each node's children.
+    children: ~[~[uint]],
+}
+
+impl<H> TreeShape<H> {
+    fn new<NR: TreeNodeRef<N> + SendableTreeNodeRef<H>, N: TreeNode<NR>>(root: &NR)
+                                                                        -> TreeShape<H> {
+        let mut shape = TreeShape {
+            handles: ~[],
+            parents: ~[],
+            children: ~[],
+        };
+        shape.number_subtree(root, None);
+        shape
+    }
+
+    fn number_subtree<NR: TreeNodeRef<N> + SendableTreeNodeRef<H>,
+                      N: TreeNode<NR>>(&mut self, node: &NR, parent_index: Option<uint>) {
+        let index = self.handles.len();
+        self.handles.push(node.to_sendable());
+        self.parents.push(parent_index);
+        self.children.push(~[]);
+        for node.each_child |kid| {
+            self.children[index].push(self.handles.len());
+            self.number_subtree(&kid, Some(index));
+        }
+    }
+}
+
+/// The per-traversal state for `parallel_traverse_postorder`.
+struct PostorderState<H, T> {
+    traversal: T,
+    handles: ~[H],
+    parents: ARC<~[Option<uint>]>,
+    /// The number of children of each node that haven't been processed yet.
+    pending_children: MutexARC<~[uint]>,
+}
+
+impl<H: Clone, T: Clone> Clone for PostorderState<H, T> {
+    fn clone(&self) -> PostorderState<H, T> {
+        PostorderState {
+            traversal: self.traversal.clone(),
+            handles: self.handles.clone(),
+            parents: self.parents.clone(),
+            pending_children: self.pending_children.clone(),
+        }
+    }
+}
+
+fn run_postorder_unit<H: Owned + Clone, T: ParallelTraversal<H>>(state: &PostorderState<H, T>,
+                                                                  index: WorkUnit)
+                                                                  -> ~[WorkUnit] {
+    state.traversal.process(state.handles[index].clone());
+
+    // If this was the last child of its parent to finish, the parent is now ready.
+    let parent_index = match state.parents.get()[index] {
+        None => return ~[],
+        Some(parent_index) => parent_index,
+    };
+    let parent_ready = unsafe {
+        do state.pending_children.access |pending_children| {
+            pending_children[parent_index] -= 1;
+            pending_children[parent_index] == 0
+        }
+    };
+    if parent_ready { ~[parent_index] } else { ~[] }
+}
+
+/// The per-traversal state for `parallel_traverse_preorder`.
+struct PreorderState<H, T> {
+    traversal: T,
+    handles: ~[H],
+    children: ARC<~[~[uint]]>,
+}
+
+impl<H: Clone, T: Clone> Clone for PreorderState<H, T> {
+    fn clone(&self) -> PreorderState<H, T> {
+        PreorderState {
+            traversal: self.traversal.clone(),
+            handles: self.handles.clone(),
+            children: self.children.clone(),
+        }
+    }
+}
+
+fn run_preorder_unit<H: Owned + Clone, T: ParallelTraversal<H>>(state: &PreorderState<H, T>,
+                                                                 index: WorkUnit)
+                                                                 -> ~[WorkUnit] {
+    state.traversal.process(state.handles[index].clone());
+
+    // Fan out: every child can now be processed, independently of its siblings.
+    copy state.children.get()[index]
+}
+
+/// Processes every node of the tree rooted at `root` bottom-up on `n_workers` tasks, handing the
+/// traversal each node's sendable handle. A node is processed only once all of its children have
+/// been, so this is a parallel equivalent of `traverse_postorder`. Siblings and cousins may be
+/// processed concurrently.
+pub fn parallel_traverse_postorder<NR: TreeNodeRef<N> + SendableTreeNodeRef<H>,
+                                   N: TreeNode<NR>,
+                                   H: Owned + Clone,
+                                   T: ParallelTraversal<H>>(root: &NR,
+                                                            n_workers: uint,
+                                                            traversal: T) {
+    assert!(n_workers > 0);
+
+    // Workers tell when a node's children are all done by counting them down. The leaves are the
+    // initial work.
+    let TreeShape { handles, parents, children } = TreeShape::new(root);
+    let pending_children = children.map(|kids| kids.len());
+    let queues = WorkQueues::new(n_workers);
+    let mut leaf_count = 0;
+    for pending_children.iter().enumerate().advance |(index, &pending)| {
+        if pending == 0 {
+            queues.push_and_finish(leaf_count % n_workers, [index], 0);
+            leaf_count += 1;
+        }
+    }
+
+    let state = PostorderState {
+        traversal: traversal,
+        handles: handles,
+        parents: ARC(parents),
+        pending_children: MutexARC(pending_children),
+    };
+    run_workers(&queues, n_workers, &state, run_postorder_unit::<H, T>);
+}
+
+/// Processes every node of the tree rooted at `root` top-down on `n_workers` tasks, handing the
+/// traversal each node's sendable handle. A node is processed only after its parent has been, so
+/// this is a parallel equivalent of `traverse_preorder`. Siblings and cousins may be processed
+/// concurrently.
+pub fn parallel_traverse_preorder<NR: TreeNodeRef<N> + SendableTreeNodeRef<H>,
+                                  N: TreeNode<NR>,
+                                  H: Owned + Clone,
+                                  T: ParallelTraversal<H>>(root: &NR,
+                                                           n_workers: uint,
+                                                           traversal: T) {
+    assert!(n_workers > 0);
+
+    let TreeShape { handles, parents: _, children } = TreeShape::new(root);
+    let queues = WorkQueues::new(n_workers);
+    queues.push_and_finish(0, [0], 0);
+
+    let state = PreorderState {
+        traversal: traversal,
+        handles: handles,
+        children: ARC(children),
+    };
+    run_workers(&queues, n_workers, &state, run_preorder_unit::<H, T>);
+}
+
+/// A thread-safe tree for testing the parallel traversals.
+#[cfg(test)]
+struct TestNode {
+    parent: Option<TestNodeRef>,
+    first_child: Option<TestNodeRef>,
+    last_child: Option<TestNodeRef>,
+    prev_sibling: Option<TestNodeRef>,
+    next_sibling: Option<TestNodeRef>,
+    /// Computed bottom-up: the number of nodes in this subtree.
+    subtree_size: uint,
+    /// Computed top-down: the distance from the root.
+    depth: uint,
+}
+
+#[cfg(test)]
+#[deriving(Clone)]
+struct TestNodeRef {
+    node: MutexARC<TestNode>,
+}
+
+#[cfg(test)]
+impl TreeNodeRef<TestNode> for TestNodeRef {
+    fn with_base<R>(&self, callback: &fn(&TestNode) -> R) -> R {
+        unsafe {
+            self.node.access(|node| callback(node))
+        }
+    }
+
+    fn with_mut_base<R>(&self, callback: &fn(&mut TestNode) -> R) -> R {
+        unsafe {
+            self.node.access(|node| callback(node))
+        }
+    }
+}
+
+#[cfg(test)]
+impl SendableTreeNodeRef<TestNodeRef> for TestNodeRef {
+    fn to_sendable(&self) -> TestNodeRef {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+impl TreeNode<TestNodeRef> for TestNode {
+    fn parent_node(&self) -> Option<TestNodeRef> { self.parent.clone() }
+    fn first_child(&self) -> Option<TestNodeRef> { self.first_child.clone() }
+    fn last_child(&self) -> Option<TestNodeRef> { self.last_child.clone() }
+    fn prev_sibling(&self) -> Option<TestNodeRef> { self.prev_sibling.clone() }
+    fn next_sibling(&self) -> Option<TestNodeRef> { self.next_sibling.clone() }
+    fn set_parent_node(&mut self, new_parent: Option<TestNodeRef>) {
+        self.parent = new_parent
+    }
+    fn set_first_child(&mut self, new_first_child: Option<TestNodeRef>) {
+        self.first_child = new_first_child
+    }
+    fn set_last_child(&mut self, new_last_child: Option<TestNodeRef>) {
+        self.last_child = new_last_child
+    }
+    fn set_prev_sibling(&mut self, new_prev_sibling: Option<TestNodeRef>) {
+        self.prev_sibling = new_prev_sibling
+    }
+    fn set_next_sibling(&mut self, new_next_sibling: Option<TestNodeRef>) {
+        self.next_sibling = new_next_sibling
+    }
+}
+
+/// Builds a tree in which every node above `depth` 0 has `fan_out` children.
+#[cfg(test)]
+fn build_test_tree(depth: uint, fan_out: uint) -> TestNodeRef {
+    let node = TestNodeRef {
+        node: MutexARC(TestNode {
+            parent: None,
+            first_child: None,
+            last_child: None,
+            prev_sibling: None,
+            next_sibling: None,
+            subtree_size: 0,
+            depth: 0,
+        })
+    };
+    if depth > 0 {
+        for fan_out.times {
+            node.add_child(build_test_tree(depth - 1, fan_out));
+        }
+    }
+    node
+}
+
+/// Unlinks every node of a test tree, which would otherwise keep each other alive through the
+/// reference cycles between parents and children.
+#[cfg(test)]
+fn destroy_test_tree(root: TestNodeRef) {
+    let mut nodes = ~[];
+    for root.traverse_preorder |node| {
+        nodes.push(node);
+    }
+    for nodes.iter().advance |node| {
+        do node.with_mut_base |n| {
+            n.parent = None;
+            n.first_child = None;
+            n.last_child = None;
+            n.prev_sibling = None;
+            n.next_sibling = None;
+        }
+    }
+}
+
+#[cfg(test)]
+#[deriving(Clone)]
+struct ComputeSubtreeSize;
+
+#[cfg(test)]
+impl ParallelTraversal<TestNodeRef> for ComputeSubtreeSize {
+    fn process(&self, node: TestNodeRef) {
+        let mut size = 1;
+        for node.each_child |kid| {
+            let kid_size = kid.with_base(|k| k.subtree_size);
+            // Every child must have been processed before its parent.
+            assert!(kid_size > 0);
+            size += kid_size;
+        }
+        node.with_mut_base(|n| n.subtree_size = size);
+    }
+}
+
+#[cfg(test)]
+#[deriving(Clone)]
+struct ComputeDepth;
+
+#[cfg(test)]
+impl ParallelTraversal<TestNodeRef> for ComputeDepth {
+    fn process(&self, node: TestNodeRef) {
+        let depth = match node.with_base(|n| n.parent_node()) {
+            None => 0,
+            Some(parent) => {
+                // The parent must have been processed before its children.
+                let parent_depth = parent.with_base(|p| p.depth);
+                assert!(parent.with_base(|p| p.parent_node()).is_none() || parent_depth > 0);
+                parent_depth + 1
+            }
+        };
+        node.with_mut_base(|n| n.depth = depth);
+    }
+}
+
+#[test]
+fn test_parallel_traverse_postorder() {
+    // 1 + 3 + 9 + 27 + 81 nodes.
+    let root = build_test_tree(4, 3);
+    parallel_traverse_postorder(&root, 4, ComputeSubtreeSize);
+    assert!(root.with_base(|n| n.subtree_size) == 121);
+
+    for root.traverse_postorder |node| {
+        let mut expected = 1;
+        for node.each_child |kid| {
+            expected += kid.with_base(|k| k.subtree_size);
+        }
+        assert!(node.with_base(|n| n.subtree_size) == expected);
+    }
+    destroy_test_tree(root);
+}
+
+#[test]
+fn test_parallel_traverse_postorder_single_node() {
+    let root = build_test_tree(0, 0);
+    parallel_traverse_postorder(&root, 2, ComputeSubtreeSize);
+    assert!(root.with_base(|n| n.subtree_size) == 1);
+    destroy_test_tree(root);
+}
+
+#[test]
+fn test_parallel_traverse_preorder() {
+    let root = build_test_tree(5, 2);
+    parallel_traverse_preorder(&root, 4, ComputeDepth);
+
+    let mut count = 0;
+    for root.traverse_preorder |node| {
+        let expected = match node.with_base(|n| n.parent_node()) {
+            None => 0,
+            Some(parent) => parent.with_base(|p| p.depth) + 1,
+        };
+        assert!(node.with_base(|n| n.depth) == expected);
+        count += 1;
+    }
+    assert!(count == 63);
+    destroy_test_tree(root);
+}